@@ -0,0 +1,84 @@
+use quote::quote;
+use syn::{Expr, Token};
+use syn::__private::TokenStream2;
+use syn::parse::{Parse, ParseStream};
+
+/// The input of the macro ensure, a condition and the error which is returned if it does not hold, like
+///
+/// ensure!(line < lines.len(), MyError::InvalidLine { line })
+pub struct EnsureInput {
+    condition: Expr,
+    error: Expr,
+}
+
+impl Parse for EnsureInput {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let condition = input.parse()?;
+        input.parse::<Token![,]>()?;
+        let error = input.parse()?;
+        // allow a trailing comma, like in assert!
+        input.parse::<Option<Token![,]>>()?;
+
+        Ok(EnsureInput { condition, error })
+    }
+}
+
+/// Create an early return of the given error. The error is converted with From, so it can be returned
+/// from functions with another error type it converts into (like an enum with a variant for it).
+/// The paths start at core, so the macro also works in no_std crates.
+pub fn implement_bail(error: &Expr) -> TokenStream2 {
+    quote! {
+        return ::core::result::Result::Err(::core::convert::From::from(#error))
+    }
+}
+
+/// Create an early return of the error of the given input if its condition does not hold (see implement_bail).
+/// The error is only created if it is returned.
+pub fn implement_ensure(input: &EnsureInput) -> TokenStream2 {
+    let condition = &input.condition;
+    let bail = implement_bail(&input.error);
+
+    quote! {
+        if !(#condition) {
+            #bail;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use quote::quote;
+    use syn::{Expr, parse_quote};
+
+    use crate::bail::{EnsureInput, implement_bail, implement_ensure};
+    use crate::test_helper::assert_tokens_are_equal;
+
+    #[test]
+    fn bail() {
+        let error: Expr = parse_quote!(MyError::NotFound { path });
+
+        let expected = quote! {
+            return ::core::result::Result::Err(::core::convert::From::from(MyError::NotFound { path }))
+        };
+
+        assert_tokens_are_equal(implement_bail(&error).to_string(), expected.to_string())
+    }
+
+    #[test]
+    fn ensure() {
+        let input: EnsureInput = parse_quote!(line < lines.len(), MyError::InvalidLine { line },);
+
+        let expected = quote! {
+            if !(line < lines.len()) {
+                return ::core::result::Result::Err(::core::convert::From::from(MyError::InvalidLine { line }));
+            }
+        };
+
+        assert_tokens_are_equal(implement_ensure(&input).to_string(), expected.to_string())
+    }
+
+    #[test]
+    fn ensure_without_error_fails() {
+        assert!(syn::parse_str::<EnsureInput>("line < lines.len()").is_err())
+    }
+}
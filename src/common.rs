@@ -1,12 +1,466 @@
-use syn::{Attribute, Path};
+use quote::{format_ident, quote, quote_spanned, ToTokens};
+use syn::{Attribute, Error, Field, Fields, Ident, Index, ItemEnum, Member, Path, Token, Type, Variant};
+use syn::parse::Parser;
+use syn::punctuated::Punctuated;
+use syn::__private::{Span, TokenStream2};
 
-use crate::parameters::ERROR_ATTRIBUTE;
+use crate::parameters::{DEBUG_PLACEHOLDER, DERIVES, ERROR_ATTRIBUTE, ERROR_ATTRIBUTE_ALIAS, FROM, HELPER_DERIVE, NO_STD, Parameters, REDACT, SKIP, SOURCE_TYPE};
+use crate::validator::{ParameterLocation, Validator};
+
+pub type FieldWithParams<'a> = (&'a Field, Option<Parameters>);
+
+/// The text which replaces the value of a field marked with REDACT.
+pub const REDACTED: &str = "[REDACTED]";
+
+/// The name of variants of non exhaustive enums which are unknown to the generated code.
+pub const UNKNOWN_VARIANT_NAME: &str = "unknown";
+
+/// The environment variable which contains further names of the error attribute, like "app_error, api_error", for crates
+/// which re-export the macro under their own name. Set it for a whole workspace with the [env] section of '.cargo/config.toml'.
+const ATTRIBUTE_NAMES_VARIABLE: &str = "ERROR_GENERATOR_ATTRIBUTES";
 
 /// Convert a syn::Path to a name (as String)
-pub fn path_to_name(path: &Path) -> String {
-    path.get_ident().map(|ident| ident.to_string()).expect("The given path was not an identifier.")
+pub fn path_to_name(path: &Path) -> syn::Result<String> {
+    path.get_ident()
+        .map(|ident| ident.to_string())
+        .ok_or_else(|| Error::new_spanned(path, "Expected an identifier"))
 }
 
+/// Check if the given attribute is an error attribute, which is named ERROR_ATTRIBUTE, ERROR_ATTRIBUTE_ALIAS
+/// or one of the names in ERROR_GENERATOR_ATTRIBUTES.
 pub fn attribute_is_error(attribute: &Attribute) -> bool {
-    path_to_name(&attribute.path) == ERROR_ATTRIBUTE
-}
\ No newline at end of file
+    attribute.path.is_ident(ERROR_ATTRIBUTE)
+        || attribute.path.is_ident(ERROR_ATTRIBUTE_ALIAS)
+        || std::env::var(ATTRIBUTE_NAMES_VARIABLE).map(|names| is_listed_name(&attribute.path, &names)).unwrap_or(false)
+}
+
+/// Create a constant which reads ERROR_GENERATOR_ATTRIBUTES with option_env!, so the compiler records the variable as
+/// dependency of the crate and cargo expands the macro again when the names change. Reading the variable in the macro
+/// itself is not tracked.
+pub fn track_attribute_names() -> TokenStream2 {
+    quote! {
+        const _: ::core::option::Option<&str> = ::core::option_env!(#ATTRIBUTE_NAMES_VARIABLE);
+    }
+}
+
+/// Check if the given path is one of the given comma separated names.
+fn is_listed_name(path: &Path, names: &str) -> bool {
+    names
+        .split(',')
+        .map(str::trim)
+        .any(|name| !name.is_empty() && path.is_ident(name))
+}
+
+/// Return the merged parameters of all error attributes of the given attributes, or None if there is no error attribute.
+/// Splitting the parameters into several attributes keeps long parameter lists readable.
+///
+/// Fails if an attribute is invalid or a parameter is set in more than one attribute.
+pub fn error_attribute_parameters(attributes: &[Attribute]) -> syn::Result<Option<Parameters>> {
+    let mut error_attributes = attributes.iter().filter(|att| attribute_is_error(att));
+
+    match error_attributes.next() {
+        Some(first) => error_attributes
+            .try_fold(Parameters::from_attribute(first)?, Parameters::merge_attribute)
+            .map(Some),
+        None => Ok(None)
+    }
+}
+
+/// Add the parameters of all error attributes in the given attributes to the given parameters of the macro and remove the attributes.
+/// Other attributes of the item (like '#[error(impl_from)]' after '#[error(message = "...")]') are not expanded
+/// by the compiler a second time, as they belong to the same error.
+///
+/// Fails if an attribute is invalid or a parameter is set in more than one attribute.
+pub fn merge_error_attributes(parameters: Parameters, attributes: &mut Vec<Attribute>) -> syn::Result<Parameters> {
+    let merged = attributes
+        .iter()
+        .filter(|att| attribute_is_error(att))
+        .try_fold(parameters, Parameters::merge_attribute)?;
+    remove_error_attributes(attributes);
+    Ok(merged)
+}
+
+/// Remove all error attributes from the given attributes.
+//  Attributes on non items seem to be only allowed as helper attributes in custom derives
+//  (https://doc.rust-lang.org/reference/procedural-macros.html#derive-macro-helper-attributes). proc_macro_attributes on the other hand are only allowed
+//  on items (https://doc.rust-lang.org/reference/items.html) and need to be removed manually.
+pub fn remove_error_attributes(attributes: &mut Vec<Attribute>) {
+    attributes.retain(|att| !attribute_is_error(att))
+}
+
+/// Pair every field with the parameters of its error attribute (if it has one).
+/// The parameters are validated for fields.
+pub fn to_fields_with_parameters(fields: &Fields) -> syn::Result<Vec<FieldWithParams<'_>>> {
+    fields
+        .iter()
+        .map(|field| match error_attribute_parameters(&field.attrs)? {
+            Some(parameters) => {
+                Validator::new(ParameterLocation::Field).validate(&parameters)?;
+                Ok((field, Some(parameters)))
+            }
+            None => Ok((field, None))
+        })
+        .collect()
+}
+
+/// Return the parameters of the error attribute of the given field, if it has one.
+/// The parameters of the field must already be validated.
+pub fn field_parameters(field: &Field) -> Option<Parameters> {
+    error_attribute_parameters(&field.attrs).ok().flatten()
+}
+
+/// Check if the given field is marked with the parameter SKIP, which excludes it from generated
+/// match patterns and From implementations.
+pub fn field_is_skipped(field: &Field) -> bool {
+    field_parameters(field)
+        .map(|p| p.bool_for_name(SKIP))
+        .unwrap_or(false)
+}
+
+/// Check if the given field has the type &'static std::panic::Location (or core::panic::Location),
+/// which is filled with the location of the caller by generated From implementations.
+///
+/// The path needs the segment 'panic' (like 'panic::Location' after 'use std::panic'), so user types which
+/// are just named Location are not mistaken for it.
+pub fn field_is_location(field: &Field) -> bool {
+    let path = match &field.ty {
+        Type::Reference(reference) if reference.mutability.is_none() && reference.lifetime.as_ref().map(|l| l.ident == "static").unwrap_or(false) => match reference.elem.as_ref() {
+            Type::Path(type_path) if type_path.qself.is_none() => &type_path.path,
+            _ => return false
+        },
+        _ => return false
+    };
+
+    let segments = path.segments.iter().map(|s| s.ident.to_string()).collect::<Vec<_>>();
+    matches!(segments.iter().map(String::as_str).collect::<Vec<_>>()[..], ["panic", "Location"] | ["std", "panic", "Location"] | ["core", "panic", "Location"])
+}
+
+/// Check if the given field has the type std::marker::PhantomData (or core::marker::PhantomData), a marker for
+/// generic parameters without a value. Such fields are never converted from, bound in match arms or passed to constructors.
+pub fn field_is_phantom(field: &Field) -> bool {
+    let path = match &field.ty {
+        Type::Path(type_path) if type_path.qself.is_none() => &type_path.path,
+        _ => return false
+    };
+
+    let segments = path.segments.iter().map(|s| s.ident.to_string()).collect::<Vec<_>>();
+    matches!(segments.iter().map(String::as_str).collect::<Vec<_>>()[..], ["PhantomData"] | ["marker", "PhantomData"] | ["std", "marker", "PhantomData"] | ["core", "marker", "PhantomData"])
+}
+
+/// Check if the given field can be used in messages. Skipped fields and phantom markers are not used.
+pub fn field_is_usable(field: &Field) -> bool {
+    !field_is_skipped(field) && !field_is_phantom(field)
+}
+
+/// Check if the given field is marked with the parameter SOURCE_TYPE, which makes generated From
+/// implementations store the name of the converted type in it.
+pub fn field_is_source_type(field: &Field) -> bool {
+    field_parameters(field)
+        .map(|p| p.bool_for_name(SOURCE_TYPE))
+        .unwrap_or(false)
+}
+
+/// Check if the given field is marked with the parameter DEBUG_PLACEHOLDER, which formats it
+/// with Debug instead of Display in messages.
+pub fn field_uses_debug_placeholder(field: &Field) -> bool {
+    field_parameters(field)
+        .map(|p| p.bool_for_name(DEBUG_PLACEHOLDER))
+        .unwrap_or(false)
+}
+
+/// Check if the given field is marked with the parameter REDACT, which hides its value in messages.
+pub fn field_is_redacted(field: &Field) -> bool {
+    field_parameters(field)
+        .map(|p| p.bool_for_name(REDACT))
+        .unwrap_or(false)
+}
+
+/// Return the fields a From implementation could convert from.
+///
+/// If any field is marked with FROM, these are the marked fields. Otherwise, all fields which
+/// are neither marked with SKIP or SOURCE_TYPE nor caller locations (see field_is_location) or phantom markers
+/// (see field_is_phantom) are returned.
+pub fn from_source_fields(fields: &Fields) -> Vec<&Field> {
+    let marked_fields = fields
+        .iter()
+        .filter(|field| field_parameters(field).map(|p| p.bool_for_name(FROM)).unwrap_or(false))
+        .collect::<Vec<_>>();
+
+    match marked_fields.is_empty() {
+        true => fields.iter().filter(|field| !field_is_skipped(field) && !field_is_source_type(field) && !field_is_location(field) && !field_is_phantom(field)).collect(),
+        false => marked_fields
+    }
+}
+
+/// Return the single field a From implementation could convert from (see from_source_fields), together with
+/// the member to access it (like 'inner' or '0'). Returns None if there is not exactly one such field.
+pub fn single_source_field(fields: &Fields) -> Option<(Member, &Field)> {
+    let field = match from_source_fields(fields)[..] {
+        [field] => field,
+        _ => return None
+    };
+
+    let member = match &field.ident {
+        Some(ident) => Member::Named(ident.clone()),
+        None => Member::Unnamed(Index::from(fields.iter().position(|f| std::ptr::eq(f, field)).unwrap()))
+    };
+    Some((member, field))
+}
+
+/// Remove the error attributes from all given fields.
+pub fn remove_field_attributes(fields: &mut Fields) {
+    fields
+        .iter_mut()
+        .for_each(|field| remove_error_attributes(&mut field.attrs))
+}
+
+/// Return the root of the paths to the error, fmt and convert modules in generated code.
+/// This is '::core' if the parameter NO_STD is set, otherwise '::std', so a local module named std does not shadow it.
+pub fn std_root(parameters: &Parameters) -> TokenStream2 {
+    match parameters.bool_for_name(NO_STD) {
+        true => quote!(::core),
+        false => quote!(::std)
+    }
+}
+
+/// Return the attributes of generated trait implementations. They mark the implementation as generated and
+/// allow lints in it (see allow_lints_attribute).
+pub fn trait_impl_attributes() -> TokenStream2 {
+    let allow_lints = allow_lints_attribute();
+
+    quote! {
+        #[automatically_derived]
+        #allow_lints
+    }
+}
+
+/// Return the attribute which allows lints in generated code, so crates with '#![deny(warnings)]' or strict
+/// clippy configs don't fail on code they did not write.
+///
+/// Generated Debug implementations only get this attribute: the compiler ignores field reads in automatically
+/// derived Debug implementations, so '#[automatically_derived]' would report fields as never read.
+pub fn allow_lints_attribute() -> TokenStream2 {
+    quote!(#[allow(unused_qualifications, clippy::all)])
+}
+
+/// Create a constant which uses a deprecated item with the given name, so the compiler emits the given text as warning
+/// at the given span. Procedural macros have no other way to emit warnings on stable Rust.
+pub fn implement_warning(name: &str, text: &str, span: Span) -> TokenStream2 {
+    let name = format_ident!("{}", name);
+    let usage = quote_spanned! {span=> #name};
+
+    quote! {
+        const _: () = {
+            #[deprecated(note = #text)]
+            #[allow(non_upper_case_globals)]
+            const #name: () = ();
+            #usage
+        };
+    }
+}
+
+/// Return the derive attribute for helper types generated next to the error (like the memoize wrapper),
+/// created from the comma separated paths in HELPER_DERIVE. Returns an empty token stream if it is not set.
+///
+/// Fails if the value is not a list of paths.
+pub fn helper_derive(parameters: &Parameters) -> syn::Result<TokenStream2> {
+    let paths = derive_paths(parameters, HELPER_DERIVE)?;
+
+    match paths.is_empty() {
+        true => Ok(quote! {}),
+        false => Ok(quote! {#[derive(#(#paths),*)]})
+    }
+}
+
+/// Return the derives for the error itself from the comma separated paths in DERIVES. They are added to the
+/// derive attribute of Debug, so Debug itself must not be listed.
+///
+/// Fails if the value is not a list of paths or contains Debug.
+pub fn derives(parameters: &Parameters) -> syn::Result<Vec<Path>> {
+    let paths = derive_paths(parameters, DERIVES)?;
+
+    match (paths.iter().any(|p| p.segments.last().map(|s| s.ident == "Debug").unwrap_or(false)), parameters.span_for_name(DERIVES)) {
+        (true, Some(span)) => Err(Error::new(span, format!("Debug is derived or implemented automatically and must not be listed in '{}'.", DERIVES))),
+        _ => Ok(paths)
+    }
+}
+
+/// Parse the comma separated paths of the parameter with the given name, like "Clone, serde::Serialize".
+fn derive_paths(parameters: &Parameters, name: &str) -> syn::Result<Vec<Path>> {
+    let (derives, span) = match (parameters.string_for_name(name), parameters.span_for_name(name)) {
+        (Some(derives), Some(span)) => (derives, span),
+        _ => return Ok(vec![])
+    };
+
+    Punctuated::<Path, Token![,]>::parse_terminated
+        .parse_str(derives)
+        .map(|paths| paths.into_iter().collect())
+        .map_err(|_| Error::new(span, format!("The value of '{}' must be a comma separated list of paths, like \"Clone, serde::Serialize\".", name)))
+}
+
+/// Return the cfg attributes of the given variant, like '#[cfg(feature = "io")]'. Everything generated for the variant
+/// (like match arms, From implementations and constructors) gets them as well, so it only exists if the variant does.
+pub fn cfg_attributes(variant: &Variant) -> TokenStream2 {
+    let attributes = variant.attrs
+        .iter()
+        .filter(|a| a.path.is_ident("cfg"));
+
+    quote! {#(#attributes)*}
+}
+
+/// Create an expression which evaluates to the name of the variant of self, like
+/// 'match self { E::A { .. } => "A", E::B { .. } => "B" }'. Non exhaustive enums (see enum_is_non_exhaustive)
+/// evaluate to UNKNOWN_VARIANT_NAME for variants added in the future.
+pub fn implement_variant_name(item_enum: &ItemEnum) -> TokenStream2 {
+    let ident = &item_enum.ident;
+    let match_arms = item_enum.variants
+        .iter()
+        .map(|v| {
+            let variant_ident = &v.ident;
+            let name = variant_ident.to_string();
+            let cfg = cfg_attributes(v);
+            quote! {#cfg #ident::#variant_ident { .. } => #name}
+        });
+    let fallback_arm = implement_fallback_arm(item_enum, quote! {#UNKNOWN_VARIANT_NAME});
+
+    quote! {
+        match self {
+            #(#match_arms,)*
+            #fallback_arm
+        }
+    }
+}
+
+/// Check if the given enum is marked with '#[non_exhaustive]' (which is added by the parameter NON_EXHAUSTIVE),
+/// so variants can be added without breaking other crates.
+pub fn enum_is_non_exhaustive(item_enum: &ItemEnum) -> bool {
+    item_enum.attrs
+        .iter()
+        .any(|a| a.path.is_ident("non_exhaustive"))
+}
+
+/// Create a wildcard match arm with the given value for matches on self if the given enum is non exhaustive, so the
+/// match keeps compiling when variants are added. Otherwise, return an empty token stream. The arm is unreachable
+/// inside the defining crate, so the lint is allowed.
+pub fn implement_fallback_arm(item_enum: &ItemEnum, value: TokenStream2) -> TokenStream2 {
+    match enum_is_non_exhaustive(item_enum) {
+        true => quote! {
+            #[allow(unreachable_patterns)]
+            _ => #value
+        },
+        false => quote! {}
+    }
+}
+
+/// Convert anything that can be turned into tokens (like a type) to a readable string.
+/// Unlike TokenStream::to_string, whitespace is only kept between words and after commas,
+/// so "std :: io :: Error" becomes "std::io::Error".
+pub fn tokens_to_readable_string<T: ToTokens>(tokens: &T) -> String {
+    let string = tokens.to_token_stream().to_string();
+    let chars = string.chars().collect::<Vec<_>>();
+
+    chars.iter()
+        .enumerate()
+        .filter(|(i, c)| match c {
+            ' ' => {
+                let previous = chars[i - 1];
+                let next = chars[i + 1];
+                previous == ',' || (is_word_char(previous) && is_word_char(next))
+            }
+            _ => true
+        })
+        .map(|(_, c)| c)
+        .collect()
+}
+
+/// Convert an identifier in UpperCamelCase (like enum and variant names) to snake_case.
+pub fn to_snake_case(name: &str) -> String {
+    let mut result = String::new();
+
+    for (i, c) in name.chars().enumerate() {
+        if c.is_uppercase() && i > 0 {
+            result.push('_');
+        }
+        result.extend(c.to_lowercase());
+    }
+
+    result
+}
+
+/// Create the identifier of a generated function with the given name. Keywords (like 'type' for the variant 'Type')
+/// become raw identifiers like 'r#type'. Return None for the keywords which cannot be raw identifiers, like 'crate' or 'super'.
+pub fn function_ident(name: &str, span: Span) -> Option<Ident> {
+    match syn::parse_str::<Ident>(name) {
+        Ok(_) => Some(Ident::new(name, span)),
+        Err(_) if matches!(name, "crate" | "self" | "super" | "Self" | "_") => None,
+        Err(_) => Some(Ident::new_raw(name, span))
+    }
+}
+
+fn is_word_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_'
+}
+
+#[cfg(test)]
+mod tests {
+    use syn::{Field, parse_quote, Path, Type};
+    use syn::parse::Parser;
+    use syn::__private::{Span, TokenStream2};
+
+    use crate::common::{field_is_location, function_ident, is_listed_name, to_snake_case, tokens_to_readable_string, track_attribute_names};
+
+    #[test]
+    fn tokens_to_readable_string_works() {
+        let ty: Type = parse_quote!(std::collections::HashMap<&'static str, Vec<u8> >);
+        assert_eq!(tokens_to_readable_string(&ty), "std::collections::HashMap<&'static str, Vec<u8>>")
+    }
+
+    #[test]
+    fn is_listed_name_works() {
+        let path: Path = parse_quote!(app_error);
+        assert!(is_listed_name(&path, "app_error"));
+        assert!(is_listed_name(&path, "api_error, app_error"));
+        assert!(!is_listed_name(&path, "api_error,"));
+        assert!(!is_listed_name(&path, ""));
+
+        let path: Path = parse_quote!(app::app_error);
+        assert!(!is_listed_name(&path, "app_error"));
+    }
+
+    #[test]
+    fn to_snake_case_works() {
+        assert_eq!(to_snake_case("MyError"), "my_error");
+        assert_eq!(to_snake_case("ReadFileFailed"), "read_file_failed");
+        assert_eq!(to_snake_case("E"), "e");
+    }
+
+    #[test]
+    fn function_ident_works() {
+        assert_eq!(function_ident("parsing_failed", Span::call_site()).unwrap().to_string(), "parsing_failed");
+        assert_eq!(function_ident("type", Span::call_site()).unwrap().to_string(), "r#type");
+        assert_eq!(function_ident("match", Span::call_site()).unwrap().to_string(), "r#match");
+        assert!(function_ident("crate", Span::call_site()).is_none());
+        assert!(function_ident("super", Span::call_site()).is_none());
+    }
+
+    #[test]
+    fn field_is_location_works() {
+        let is_location = |field: TokenStream2| field_is_location(&Field::parse_named.parse2(field).unwrap());
+
+        assert!(is_location(quote::quote!(location: &'static std::panic::Location<'static>)));
+        assert!(is_location(quote::quote!(location: &'static ::core::panic::Location<'static>)));
+        assert!(is_location(quote::quote!(location: &'static panic::Location<'static>)));
+        assert!(!is_location(quote::quote!(location: &'static Location<'static>)));
+        assert!(!is_location(quote::quote!(location: &'static geo::Location)));
+        assert!(!is_location(quote::quote!(location: &'a std::panic::Location<'a>)));
+        assert!(!is_location(quote::quote!(location: std::panic::Location<'static>)));
+    }
+
+    #[test]
+    fn track_attribute_names_works() {
+        assert_eq!(
+            track_attribute_names().to_string(),
+            quote::quote!(const _: ::core::option::Option<&str> = ::core::option_env!("ERROR_GENERATOR_ATTRIBUTES");).to_string()
+        )
+    }
+}
@@ -0,0 +1,179 @@
+use proc_macro2::{Delimiter, Spacing, TokenTree};
+use syn::__private::TokenStream2;
+use syn::Ident;
+
+use crate::parameters::{DEBUG_OUTPUT, Parameters};
+
+/// The text used to indent nested blocks in the debug output.
+const INDENTATION: &str = "    ";
+
+/// Print the generated code of the error with the given ident to stderr if DEBUG_OUTPUT is set.
+/// The compiler shows the output of procedural macros while building, so the code behind an unexpected
+/// compile error can be inspected without tools like cargo-expand.
+pub fn print_debug_output(ident: &Ident, parameters: &Parameters, expansion: &TokenStream2) {
+    if parameters.bool_for_name(DEBUG_OUTPUT) {
+        eprintln!("note: code generated by error_generator for '{}':\n{}", ident, format_tokens(expansion))
+    }
+}
+
+/// Format the given tokens as readable code. Blocks are indented and statements, fields, match arms
+/// and outer attributes are put on their own lines. Everything else is kept on one line.
+pub fn format_tokens(tokens: &TokenStream2) -> String {
+    let mut formatter = Formatter::default();
+    formatter.write_stream(tokens.clone(), true);
+    formatter.output.trim_end().to_string()
+}
+
+/// Keywords which are followed by a space, even if a path like '::std::fmt::Display' follows.
+const KEYWORDS: &[&str] = &["impl", "for", "dyn", "as", "in", "return", "mut", "const", "static", "let", "where", "pub", "match", "if", "else", "move"];
+
+#[derive(Default)]
+struct Formatter {
+    output: String,
+    depth: usize,
+    previous: String,
+}
+
+impl Formatter {
+    /// Write the given tokens. In blocks, commas end a line (like after fields and match arms).
+    fn write_stream(&mut self, tokens: TokenStream2, in_block: bool) {
+        let mut tokens = tokens.into_iter();
+
+        while let Some(token) = tokens.next() {
+            match token {
+                TokenTree::Group(group) => match group.delimiter() {
+                    Delimiter::Brace if group.stream().is_empty() => {
+                        self.write_word("{}");
+                        self.new_line();
+                    }
+                    Delimiter::Brace => {
+                        self.write_word("{");
+                        self.depth += 1;
+                        self.new_line();
+                        self.write_stream(group.stream(), true);
+                        self.depth -= 1;
+                        self.new_line();
+                        self.write_word("}");
+                        self.new_line();
+                    }
+                    Delimiter::Parenthesis => self.write_group("(", group.stream(), ")"),
+                    Delimiter::Bracket => self.write_group("[", group.stream(), "]"),
+                    Delimiter::None => self.write_stream(group.stream(), in_block)
+                },
+                TokenTree::Punct(punct) => {
+                    // joint punctuation belongs to the next token, like in '::', '->' or the lifetime ''a'
+                    let mut word = punct.as_char().to_string();
+                    let mut spacing = punct.spacing();
+                    while spacing == Spacing::Joint {
+                        match tokens.next() {
+                            Some(TokenTree::Punct(next)) => {
+                                word.push(next.as_char());
+                                spacing = next.spacing();
+                            }
+                            Some(other) => {
+                                word.push_str(&other.to_string());
+                                break;
+                            }
+                            None => break
+                        }
+                    }
+
+                    let attribute_start = word == "#" && in_block && self.previous.is_empty();
+                    self.write_word(&word);
+
+                    if attribute_start {
+                        if let Some(TokenTree::Group(attribute)) = tokens.next() {
+                            self.write_group("[", attribute.stream(), "]");
+                        }
+                        self.new_line();
+                    } else if in_block && (word == ";" || word == ",") {
+                        self.new_line();
+                    }
+                }
+                other => self.write_word(&other.to_string())
+            }
+        }
+    }
+
+    fn write_group(&mut self, open: &str, tokens: TokenStream2, close: &str) {
+        self.write_word(open);
+        self.write_stream(tokens, false);
+        self.write_word(close);
+    }
+
+    /// Write the given word, separated with a space from the previous one unless they belong together.
+    fn write_word(&mut self, word: &str) {
+        if self.previous.is_empty() {
+            self.output.push_str(&INDENTATION.repeat(self.depth));
+        } else if needs_space(&self.previous, word) {
+            self.output.push(' ');
+        }
+
+        self.output.push_str(word);
+        self.previous = word.to_string();
+    }
+
+    /// End the current line, unless it is empty.
+    fn new_line(&mut self) {
+        if !self.previous.is_empty() {
+            self.output.push('\n');
+            self.previous.clear();
+        }
+    }
+}
+
+/// Check if a space is needed between the given words, like between 'fn' and 'fmt', but not between 'fmt' and '('.
+fn needs_space(previous: &str, word: &str) -> bool {
+    let previous_is_name = previous.chars().all(|c| c.is_alphanumeric() || c == '_') && !KEYWORDS.contains(&previous);
+
+    match (previous, word) {
+        (_, "," | ";" | "." | "?" | ")" | "]" | ">" | ">>" | ":") => false,
+        ("(" | "[" | "." | "&" | "!" | "<" | "#" | "::", _) => false,
+        ("impl", "<") => false,
+        (_, "::") => !previous_is_name,
+        (_, "(" | "[" | "<" | "!") => !previous_is_name && previous != ">",
+        _ => true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use quote::quote;
+
+    use crate::debug_output::format_tokens;
+
+    #[test]
+    fn format_tokens_works() {
+        let tokens = quote! {
+            #[derive(Debug)]
+            struct S {
+                value: usize,
+            }
+
+            impl<T> ::std::error::Error for S<T> {}
+
+            impl ::std::fmt::Display for S {
+                fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+                    match self.value {
+                        0 => f.write_str("zero"),
+                        _ => ::std::write!(f, "{}", self.value)
+                    }
+                }
+            }
+        };
+
+        assert_eq!(format_tokens(&tokens), r#"#[derive(Debug)]
+struct S {
+    value: usize,
+}
+impl<T> ::std::error::Error for S<T> {}
+impl ::std::fmt::Display for S {
+    fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+        match self.value {
+            0 => f.write_str("zero"),
+            _ => ::std::write!(f, "{}", self.value)
+        }
+    }
+}"#)
+    }
+}
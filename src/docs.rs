@@ -0,0 +1,39 @@
+use quote::quote;
+use syn::__private::TokenStream2;
+
+/// Create doc attributes for the given (possibly multiline) text, so generated items can
+/// be documented in rustdoc.
+///
+/// Every line becomes its own #[doc = "..."] attribute with a leading space, just like the
+/// compiler lowers '///' comments. This way, generated docs look the same as handwritten ones.
+pub fn implement_doc_comment(text: &str) -> TokenStream2 {
+    let lines = text
+        .lines()
+        .map(|line| match line.is_empty() {
+            true => String::new(),
+            false => format!(" {}", line)
+        });
+
+    quote! {
+        #(#[doc = #lines])*
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use quote::quote;
+
+    use crate::docs::implement_doc_comment;
+    use crate::test_helper::assert_tokens_are_equal;
+
+    #[test]
+    fn implement_doc_comment_works() {
+        let ts = implement_doc_comment("first line\n\nsecond line").to_string();
+        let expected = quote! {
+            #[doc = " first line"]
+            #[doc = ""]
+            #[doc = " second line"]
+        }.to_string();
+        assert_tokens_are_equal(ts, expected)
+    }
+}
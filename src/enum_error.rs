@@ -1,11 +1,49 @@
 use quote::quote;
-use syn::{Attribute, AttributeArgs, ItemEnum, Variant};
+use syn::{AttributeArgs, ItemEnum, parse_quote, Variant};
 use syn::__private::TokenStream2;
 
 use crate::common::*;
+use crate::impl_assert_macro::AssertMacroImplementor;
+use crate::debug_output::print_debug_output;
+use crate::impl_aggregate::AggregateImplementor;
+use crate::impl_chain::ChainImplementor;
+use crate::impl_boxed::BoxedImplementor;
+use crate::impl_errno::ErrnoImplementor;
+use crate::impl_exit_code::ExitCodeImplementor;
+use crate::impl_severity::SeverityImplementor;
+use crate::impl_category::CategoryImplementor;
+use crate::impl_grpc_status::GrpcStatusImplementor;
+use crate::impl_ffi::FfiImplementor;
+use crate::impl_into_response::IntoResponseImplementor;
+use crate::impl_retry_after::RetryAfterImplementor;
+use crate::impl_retryable::RetryableImplementor;
+use crate::impl_io_kind::IoKindImplementor;
+use crate::impl_json::JsonImplementor;
+use crate::impl_wire::WireImplementor;
+use crate::impl_matcher_macro::MatcherMacroImplementor;
+use crate::impl_constructors::ConstructorsImplementor;
+use crate::impl_predicates::PredicatesImplementor;
+use crate::impl_name::NameImplementor;
+use crate::impl_getters::{generated_methods, GettersImplementor};
+use crate::impl_into_inner::IntoInnerImplementor;
+use crate::impl_kind::KindImplementor;
+use crate::impl_variant_structs::VariantStructsImplementor;
+use crate::impl_duplicate_messages::{DuplicateMessagesImplementor, NamedMessage};
+use crate::impl_message_docs::add_message_docs_to_enum;
+use crate::impl_strict::StrictImplementor;
+use crate::impl_catalog::CatalogImplementor;
+use crate::impl_debug::enums::EnumDebugImplementor;
+use crate::impl_serialize::SerializeImplementor;
+use crate::impl_wrap_map::WrapMap;
+use crate::impl_includes::add_included_variants;
+use crate::impl_display::bounds::{error_bounds, with_bounds};
 use crate::impl_display::enums::EnumDisplayImplementor;
+use crate::impl_display::memoize::MemoizeImplementor;
+use crate::impl_display::render_into::RenderIntoImplementor;
+use crate::impl_from::FromContext;
 use crate::impl_from::enums::EnumFromImplementer;
-use crate::parameters::Parameters;
+use crate::parameters::{ALLOC, CATEGORY, CHAIN, CONSTRUCTORS, DYN_ERROR, ERRNO, EXIT_CODE, INTO_INNER, JSON, KIND, MEMOIZE_DISPLAY, MESSAGE, NON_EXHAUSTIVE, OTHER, Parameters, PREDICATES, RENDER_INTO, RETRY_AFTER, RETRYABLE, RUNTIME_VERBOSITY, SEVERITY, VARIANT_NAME, WIRE};
+use crate::validator::{ParameterLocation, Validator};
 
 pub type VariantWithParams<'a> = (&'a Variant, Option<Parameters>);
 
@@ -15,80 +53,229 @@ pub type VariantWithParams<'a> = (&'a Variant, Option<Parameters>);
 /// - std::error::Error is implemented
 /// - std::fmt::Debug and Display are implemented
 /// - std::convert::From is implemented (if possible) to allow the usage of the ?-operator
-pub fn implement(attr_args: AttributeArgs, mut item_enum: ItemEnum) -> TokenStream2 {
-    let enum_parameters = Parameters::from_attribute_args(attr_args);
+///
+/// Fails if the parameters are invalid or an implementation cannot be created.
+pub fn implement(attr_args: AttributeArgs, mut item_enum: ItemEnum) -> syn::Result<TokenStream2> {
+    let enum_parameters = merge_error_attributes(Parameters::from_attribute_args(attr_args)?, &mut item_enum.attrs)?;
+    Validator::new(ParameterLocation::Enum).validate(&enum_parameters)?;
+    add_included_variants(&mut item_enum, &enum_parameters)?;
+    add_non_exhaustive_attribute(&mut item_enum, &enum_parameters);
 
     let variants_with_parameters = item_enum.variants
         .iter()
         .map(to_variant_with_parameters)
+        .collect::<syn::Result<Vec<_>>>()?;
+    let helper_derive = helper_derive(&enum_parameters)?;
+    let wrap_map = WrapMap::parse(&item_enum, &enum_parameters, &variants_with_parameters)?;
+
+    let display_implementation = EnumDisplayImplementor::new(&item_enum, &enum_parameters, &variants_with_parameters, &wrap_map).implement()?;
+    let defmt_implementation = EnumDisplayImplementor::new(&item_enum, &enum_parameters, &variants_with_parameters, &wrap_map).implement_defmt()?;
+    let memoize_implementation = MemoizeImplementor::new(&item_enum.ident, &item_enum.vis, &item_enum.generics, &enum_parameters, &helper_derive).implement(!display_implementation.is_empty())?;
+    let aggregate_implementation = AggregateImplementor::new(&item_enum.ident, &item_enum.vis, &item_enum.generics, &enum_parameters, &helper_derive).implement(!display_implementation.is_empty())?;
+    let render_into_implementation = RenderIntoImplementor::new(&item_enum.ident, &item_enum.generics, &enum_parameters).implement(!display_implementation.is_empty())?;
+    let chain_implementation = ChainImplementor::new(&item_enum.ident, &item_enum.generics, &enum_parameters).implement(!display_implementation.is_empty())?;
+    let from_context = FromContext::new(&item_enum.ident, &enum_parameters, !display_implementation.is_empty())?;
+    let from_implementations = EnumFromImplementer::new(&item_enum, &enum_parameters, &variants_with_parameters, &from_context, &wrap_map).implement()?;
+    let assert_macro = AssertMacroImplementor::new(&item_enum, &enum_parameters).implement();
+    let matcher_macro = MatcherMacroImplementor::new(&item_enum, &enum_parameters).implement(!display_implementation.is_empty());
+    let constructors = ConstructorsImplementor::new(&item_enum, &enum_parameters, &variants_with_parameters, &from_context).implement()?;
+    let predicates = PredicatesImplementor::new(&item_enum, &enum_parameters).implement();
+    let name_implementation = NameImplementor::new(&item_enum, &enum_parameters).implement();
+    let into_inner = IntoInnerImplementor::new(&enum_parameters).implement_for_enum(&item_enum, &wrap_map);
+    let kind = KindImplementor::new(&item_enum, &enum_parameters, &variants_with_parameters, &wrap_map).implement();
+    let variant_structs = VariantStructsImplementor::new(&item_enum, &enum_parameters, &helper_derive).implement()?;
+    let catalog = CatalogImplementor::new(&item_enum, &enum_parameters, &variants_with_parameters, &wrap_map).implement();
+    let boxed_implementation = BoxedImplementor::new(&item_enum.ident, &item_enum.generics, &enum_parameters).implement();
+    let io_kind_implementation = IoKindImplementor::new(&item_enum).implement();
+    let exit_code_implementation = ExitCodeImplementor::new(&item_enum.ident, &item_enum.generics, &enum_parameters).implement_for_enum(&variants_with_parameters)?;
+    let severity_implementation = SeverityImplementor::new(&item_enum.ident, &item_enum.vis, &item_enum.generics, &enum_parameters).implement_for_enum(&variants_with_parameters)?;
+    let category_implementation = CategoryImplementor::new(&item_enum.ident, &item_enum.generics, &enum_parameters).implement_for_enum(&variants_with_parameters)?;
+    let errno_implementation = ErrnoImplementor::new(&item_enum.ident, &item_enum.generics, &enum_parameters).implement_for_enum(&variants_with_parameters)?;
+    let retry_after_implementation = RetryAfterImplementor::new(&item_enum.ident, &item_enum.generics, &enum_parameters).implement_for_enum(&variants_with_parameters);
+    let retryable_implementation = RetryableImplementor::new(&item_enum.ident, &item_enum.generics, &enum_parameters).implement_for_enum(&variants_with_parameters);
+    let into_response_implementation = IntoResponseImplementor::new(&item_enum.ident, &item_enum.generics, &enum_parameters).implement_for_enum(&variants_with_parameters, !display_implementation.is_empty(), !retry_after_implementation.is_empty())?;
+    let grpc_status_implementation = GrpcStatusImplementor::new(&item_enum.ident, &item_enum.generics, &enum_parameters).implement_for_enum(&variants_with_parameters, !display_implementation.is_empty())?;
+    let ffi_implementation = FfiImplementor::new(&item_enum, &enum_parameters).implement(!display_implementation.is_empty())?;
+    let serialize_implementation = SerializeImplementor::new(&item_enum.ident, &item_enum.generics, &enum_parameters).implement_for_enum(&item_enum, !display_implementation.is_empty(), !errno_implementation.is_empty())?;
+    let json_implementation = JsonImplementor::new(&item_enum.ident, &item_enum.generics, &enum_parameters).implement_for_enum(&item_enum, !display_implementation.is_empty(), !errno_implementation.is_empty())?;
+    let wire_implementation = WireImplementor::new(&item_enum.ident, &item_enum.vis, &item_enum.generics, &enum_parameters).implement(!display_implementation.is_empty(), !errno_implementation.is_empty())?;
+    let duplicate_message_warnings = DuplicateMessagesImplementor::new(item_enum.ident.to_string(), variant_messages(&item_enum, &variants_with_parameters)).implement();
+    let unused_fields_warnings = StrictImplementor::new(&enum_parameters).implement_for_enum(&item_enum, &variants_with_parameters, &wrap_map);
+
+    let debug_implementor = EnumDebugImplementor::new(&item_enum, &enum_parameters);
+    let debug_derive = debug_implementor.implement_derive(&derives(&enum_parameters)?)?;
+    let debug_implementation = debug_implementor.implement(!display_implementation.is_empty())?;
+
+    let root = std_root(&enum_parameters);
+    let source_implementation = wrap_map.implement_source(&root);
+    let wrap_implementation = wrap_map.implement_wrap(&root);
+    let source_bounds = wrap_map.source_bounds(&root);
+
+    let variant_methods = |prefix: &str, suffix: &str| item_enum.variants
+        .iter()
+        .map(|v| format!("{}{}{}", prefix, to_snake_case(&v.ident.to_string()), suffix))
         .collect::<Vec<_>>();
-
-    let display_implementation = match EnumDisplayImplementor::new(&item_enum, &enum_parameters, &variants_with_parameters).implement() {
-        Ok(implementation) => implementation,
-        Err(e) => panic!("{}", e)
-    };
-    let from_implementations = match EnumFromImplementer::new(&item_enum, &enum_parameters, &variants_with_parameters).implement() {
-        Ok(implementation) => implementation,
-        Err(e) => panic!("{}", e)
+    let methods = |names: &[&str]| names.iter().map(|n| n.to_string()).collect::<Vec<_>>();
+    let boxed_parameter = match enum_parameters.bool_for_name(DYN_ERROR) {
+        true => DYN_ERROR,
+        false => ALLOC
     };
-
+    let generated_methods = vec![
+        (&constructors, [variant_methods("", ""), variant_methods("", "_with")].concat(), CONSTRUCTORS),
+        (&predicates, variant_methods("is_", ""), PREDICATES),
+        (&into_inner, [variant_methods("into_", ""), methods(&["into_source"])].concat(), INTO_INNER),
+        (&name_implementation, methods(&["name"]), VARIANT_NAME),
+        (&kind, methods(&["kind"]), KIND),
+        (&display_implementation, methods(&["set_error_verbosity", "error_verbosity"]), RUNTIME_VERBOSITY),
+        (&memoize_implementation, methods(&["memoized"]), MEMOIZE_DISPLAY),
+        (&render_into_implementation, methods(&["render_into"]), RENDER_INTO),
+        (&chain_implementation, methods(&["chain", "render_chain"]), CHAIN),
+        (&boxed_implementation, methods(&["boxed", "as_dyn_error"]), boxed_parameter),
+        (&exit_code_implementation, methods(&["exit_code"]), EXIT_CODE),
+        (&severity_implementation, methods(&["severity"]), SEVERITY),
+        (&category_implementation, methods(&["category"]), CATEGORY),
+        (&errno_implementation, methods(&["errno", "to_errno"]), ERRNO),
+        (&retry_after_implementation, methods(&["retry_after"]), RETRY_AFTER),
+        (&retryable_implementation, methods(&["is_retryable"]), RETRYABLE),
+        (&json_implementation, methods(&["to_json"]), JSON),
+        (&wire_implementation, methods(&["to_wire", "from_wire"]), WIRE),
+        (&wrap_implementation, methods(&["wrap"]), OTHER),
+    ]
+        .into_iter()
+        .flat_map(|(implementation, candidates, parameter)| generated_methods(implementation, candidates, &format!("the parameter '{}'", parameter)))
+        .chain(generated_methods(&io_kind_implementation, methods(&["io_kind"]), "the variants wrapping a std::io::Error"))
+        .collect();
+    let getters = GettersImplementor::new(&item_enum, &enum_parameters).with_generated_methods(generated_methods).implement()?;
+
+    add_message_docs_to_enum(&mut item_enum, &enum_parameters)?;
     remove_variant_attributes(&mut item_enum);
 
     let ident = &item_enum.ident;
-    let generics = &item_enum.generics;
+    let error_bounds = error_bounds(&item_enum.generics, &root).into_iter().chain(source_bounds).collect::<Vec<_>>();
+    let generics = with_bounds(&item_enum.generics, &error_bounds);
     let (impl_generics, type_generics, where_clause) = generics.split_for_impl();
 
-    quote! {
-        #[derive(Debug)] #item_enum
-        impl #impl_generics std::error::Error for #ident #type_generics #where_clause {}
+    let impl_attributes = trait_impl_attributes();
+
+    let expansion = quote! {
+        #debug_derive #item_enum
+        #debug_implementation
+
+        #impl_attributes
+        impl #impl_generics #root::error::Error for #ident #type_generics #where_clause {
+            #source_implementation
+        }
 
         #from_implementations
 
+        #wrap_implementation
+
         #display_implementation
-    }
+
+        #defmt_implementation
+
+        #memoize_implementation
+        #render_into_implementation
+
+        #aggregate_implementation
+
+        #chain_implementation
+
+        #assert_macro
+
+        #matcher_macro
+
+        #constructors
+
+        #predicates
+
+        #name_implementation
+
+        #getters
+
+        #into_inner
+
+        #kind
+
+        #variant_structs
+
+        #catalog
+
+        #boxed_implementation
+
+        #io_kind_implementation
+
+        #exit_code_implementation
+
+        #severity_implementation
+
+        #category_implementation
+
+        #errno_implementation
+
+        #retry_after_implementation
+
+        #retryable_implementation
+
+        #into_response_implementation
+
+        #grpc_status_implementation
+
+        #ffi_implementation
+
+        #serialize_implementation
+
+        #json_implementation
+
+        #wire_implementation
+
+        #duplicate_message_warnings
+
+        #unused_fields_warnings
+    };
+
+    print_debug_output(&item_enum.ident, &enum_parameters, &expansion);
+    Ok(expansion)
+}
+
+/// Return the messages set on the variants for the detection of duplicate messages. The message of the enum
+/// is shared by all variants without a message, so it is no duplicate.
+fn variant_messages(item_enum: &ItemEnum, variants_with_parameters: &[VariantWithParams]) -> Vec<NamedMessage> {
+    variants_with_parameters
+        .iter()
+        .filter_map(|(v, p_opt)| {
+            let parameters = p_opt.as_ref()?;
+            Some((format!("{}::{}", item_enum.ident, v.ident), parameters.string_for_name(MESSAGE)?.to_string(), parameters.span_for_name(MESSAGE)?))
+        })
+        .collect()
 }
 
-fn to_variant_with_parameters(variant: &Variant) -> VariantWithParams {
-    match get_error_attribute(&variant.attrs) {
-        Some(attr) => (variant, Some(Parameters::from_attribute(attr))),
-        None => (variant, None)
+fn to_variant_with_parameters(variant: &Variant) -> syn::Result<VariantWithParams<'_>> {
+    to_fields_with_parameters(&variant.fields)?;
+
+    match error_attribute_parameters(&variant.attrs)? {
+        Some(parameters) => {
+            Validator::new(ParameterLocation::Variant).validate(&parameters)?;
+            Ok((variant, Some(parameters)))
+        }
+        None => Ok((variant, None))
     }
 }
 
-fn get_error_attribute(attributes: &Vec<Attribute>) -> Option<&Attribute> {
-    let index = attributes
-        .iter()
-        .enumerate()
-        .find_map(|(i, att)| match attribute_is_error(att) {
-            true => Some(i),
-            false => None
-        })?;
-    attributes.get(index)
+/// Mark the enum with '#[non_exhaustive]' if NON_EXHAUSTIVE is set and it is not marked already.
+fn add_non_exhaustive_attribute(item_enum: &mut ItemEnum, enum_parameters: &Parameters) {
+    if enum_parameters.bool_for_name(NON_EXHAUSTIVE) && !enum_is_non_exhaustive(item_enum) {
+        item_enum.attrs.push(parse_quote!(#[non_exhaustive]))
+    }
 }
 
-//  Attributes on non items seem to be only allowed as helper attributes in custom derives
-//  (https://doc.rust-lang.org/reference/procedural-macros.html#derive-macro-helper-attributes). proc_macro_aatributes on the other hand are only allowed
-//  on items (https://doc.rust-lang.org/reference/items.html) and need to be removed manually.
 fn remove_variant_attributes(item_enum: &mut ItemEnum) {
     item_enum.variants
         .iter_mut()
-        .for_each(remove_error_attribute_from_variant)
-}
-
-/// Search the index of the error attribute in the given variants attributes.
-/// If the index could be found, remove the entry from the variants attributes.
-fn remove_error_attribute_from_variant(variant: &mut Variant) {
-    let index_opt = variant.attrs
-        .iter()
-        .enumerate()
-        .find_map(|(i, att)| match attribute_is_error(att) {
-            true => Some(i),
-            false => None
-        });
-
-    if let Some(i) = index_opt {
-        variant.attrs.remove(i);
-    }
+        .for_each(|variant| {
+            remove_error_attributes(&mut variant.attrs);
+            remove_field_attributes(&mut variant.fields)
+        })
 }
 
 #[cfg(test)]
@@ -115,7 +302,9 @@ mod tests {
                     Unit
                 }
 
-                impl std::error::Error for E {}
+                #[automatically_derived]
+                #[allow(unused_qualifications, clippy::all)]
+                impl ::std::error::Error for E {}
             }
         )
     }
@@ -140,7 +329,9 @@ mod tests {
                     Unit
                 }
 
-                impl std::error::Error for E {}
+                #[automatically_derived]
+                #[allow(unused_qualifications, clippy::all)]
+                impl ::std::error::Error for E {}
             }
         )
     }
@@ -165,7 +356,69 @@ mod tests {
                     Unit
                 }
 
-                impl std::error::Error for E {}
+                #[automatically_derived]
+                #[allow(unused_qualifications, clippy::all)]
+                impl ::std::error::Error for E {}
+            }
+        )
+    }
+
+    #[test]
+    fn multiple_error_attributes() {
+        assert_enum_implementation_as_expected!(
+            item: {
+                #[error(message = "Something went wrong")]
+                #[derive(Clone)]
+                #[error(no_std)]
+                enum E {
+                    #[error(no_from)]
+                    #[error(message = "The foo value: {foo}")]
+                    Named {foo: usize},
+                    Unit
+                }
+            }
+
+            expected: {
+                #[derive(Debug)]
+                #[derive(Clone)]
+                enum E {
+                    Named {foo: usize},
+                    Unit
+                }
+
+                #[automatically_derived]
+                #[allow(unused_qualifications, clippy::all)]
+                impl ::core::error::Error for E {}
+
+                #[automatically_derived]
+                #[allow(unused_qualifications, clippy::all)]
+                impl ::core::fmt::Display for E {
+                    fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+                        match self {
+                            E::Named {foo,} => ::core::write!(f, "The foo value: {}", foo),
+                            _ => ::core::write!(f, "Something went wrong")
+                        }
+                    }
+                }
+            }
+        )
+    }
+
+    #[test]
+    #[should_panic(expected = "The parameter 'impl_from' is set in more than one error attribute")]
+    fn parameter_in_multiple_variant_error_attributes_should_panic() {
+        assert_enum_implementation_as_expected!(
+            item: {
+                #[error]
+                enum E {
+                    #[error(impl_from)]
+                    #[error(impl_from = false)]
+                    Unnamed(usize)
+                }
+            }
+
+            expected: {
+                should panic
             }
         )
     }
@@ -186,7 +439,9 @@ mod tests {
                     Unnamed(&'a usize)
                 }
 
-                impl<'a> std::error::Error for E<'a> {}
+                #[automatically_derived]
+                #[allow(unused_qualifications, clippy::all)]
+                impl<'a> ::std::error::Error for E<'a> {}
             }
         )
     }
@@ -207,7 +462,50 @@ mod tests {
                     Unnamed(T)
                 }
 
-                impl<T> std::error::Error for E<T> {}
+                #[automatically_derived]
+                #[allow(unused_qualifications, clippy::all)]
+                impl<T> ::std::error::Error for E<T> where Self: ::std::fmt::Debug + ::std::fmt::Display {}
+            }
+        )
+    }
+
+    #[test]
+    fn display_bounds_of_generic_fields() {
+        assert_enum_implementation_as_expected!(
+            item: {
+                #[error(message = "unknown")]
+                enum E<T> {
+                    #[error(message = "invalid value {_0}")]
+                    Unnamed(T),
+                    #[error(message = "invalid values {values:?}")]
+                    Named {values: Vec<T>},
+                    Other(T)
+                }
+            }
+
+            expected: {
+                #[derive(Debug)]
+                enum E<T> {
+                    Unnamed(T),
+                    Named {values: Vec<T>},
+                    Other(T)
+                }
+
+                #[automatically_derived]
+                #[allow(unused_qualifications, clippy::all)]
+                impl<T> ::std::error::Error for E<T> where Self: ::std::fmt::Debug + ::std::fmt::Display {}
+
+                #[automatically_derived]
+                #[allow(unused_qualifications, clippy::all)]
+                impl<T> ::std::fmt::Display for E<T> where T: ::std::fmt::Display, Vec<T>: ::std::fmt::Debug {
+                    fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+                        match self {
+                            E::Unnamed(_0,) => ::std::write!(f, "invalid value {}", _0),
+                            E::Named {values,} => ::std::write!(f, "invalid values {:?}", values),
+                            _ => ::std::write!(f, "unknown")
+                        }
+                    }
+                }
             }
         )
     }
@@ -228,7 +526,9 @@ mod tests {
                     Unnamed(C)
                 }
 
-                impl<const C: usize> std::error::Error for E<C> {}
+                #[automatically_derived]
+                #[allow(unused_qualifications, clippy::all)]
+                impl<const C: usize> ::std::error::Error for E<C> {}
             }
         )
     }
@@ -255,19 +555,48 @@ mod tests {
                     Unit
                 }
 
-                impl std::error::Error for E {}
-
-                impl std::convert::From<usize> for E {
+                #[automatically_derived]
+                #[allow(unused_qualifications, clippy::all)]
+                impl ::std::error::Error for E {}
+
+                #[doc = " Converts a `usize` into a `E`, which allows using the ?-operator like"]
+                #[doc = ""]
+                #[doc = " ```ignore"]
+                #[doc = " fn example(result: Result<(), usize>) -> Result<(), E> {"]
+                #[doc = "     result?;"]
+                #[doc = "     Ok(())"]
+                #[doc = " }"]
+                #[doc = " ```"]
+                #[automatically_derived]
+                #[allow(unused_qualifications, clippy::all)]
+                impl ::std::convert::From<usize> for E {
+                    #[track_caller]
                     fn from(val: usize) -> Self {
                         E::Named {foo: val}
                     }
                 }
 
-                impl std::convert::From<f32> for E {
+                #[doc = " Converts a `f32` into a `E`, which allows using the ?-operator like"]
+                #[doc = ""]
+                #[doc = " ```ignore"]
+                #[doc = " fn example(result: Result<(), f32>) -> Result<(), E> {"]
+                #[doc = "     result?;"]
+                #[doc = "     Ok(())"]
+                #[doc = " }"]
+                #[doc = " ```"]
+                #[automatically_derived]
+                #[allow(unused_qualifications, clippy::all)]
+                impl ::std::convert::From<f32> for E {
+                    #[track_caller]
                     fn from(val: f32) -> Self {
                         E::Unnamed(val)
                     }
                 }
+
+                impl E {
+                    #[doc = "The types this error can be created from with std::convert::From (and therefore the ?-operator)."]
+                    pub const FROM_TYPES: &'static [&'static str] = &["usize", "f32"];
+                }
             }
         )
     }
@@ -290,92 +619,199 @@ mod tests {
                     Unnamed(f32),
                 }
 
-                impl std::error::Error for E {}
-
-                impl std::convert::From<usize> for E {
+                #[automatically_derived]
+                #[allow(unused_qualifications, clippy::all)]
+                impl ::std::error::Error for E {}
+
+                #[doc = " Converts a `usize` into a `E`, which allows using the ?-operator like"]
+                #[doc = ""]
+                #[doc = " ```ignore"]
+                #[doc = " fn example(result: Result<(), usize>) -> Result<(), E> {"]
+                #[doc = "     result?;"]
+                #[doc = "     Ok(())"]
+                #[doc = " }"]
+                #[doc = " ```"]
+                #[automatically_derived]
+                #[allow(unused_qualifications, clippy::all)]
+                impl ::std::convert::From<usize> for E {
+                    #[track_caller]
                     fn from(val: usize) -> Self {
                         E::Named {foo: val}
                     }
                 }
 
-                impl std::convert::From<f32> for E {
+                #[doc = " Converts a `f32` into a `E`, which allows using the ?-operator like"]
+                #[doc = ""]
+                #[doc = " ```ignore"]
+                #[doc = " fn example(result: Result<(), f32>) -> Result<(), E> {"]
+                #[doc = "     result?;"]
+                #[doc = "     Ok(())"]
+                #[doc = " }"]
+                #[doc = " ```"]
+                #[automatically_derived]
+                #[allow(unused_qualifications, clippy::all)]
+                impl ::std::convert::From<f32> for E {
+                    #[track_caller]
                     fn from(val: f32) -> Self {
                         E::Unnamed(val)
                     }
                 }
+
+                impl E {
+                    #[doc = "The types this error can be created from with std::convert::From (and therefore the ?-operator)."]
+                    pub const FROM_TYPES: &'static [&'static str] = &["usize", "f32"];
+                }
             }
         )
     }
 
     #[test]
-    #[should_panic(expected = "'std::convert::From' cannot be implemented for enum 'E'. The following variants don't have exactly one field: Unit")]
-    fn unit_impl_from_should_panic() {
+    fn global_impl_from_excluded_variants() {
         assert_enum_implementation_as_expected!(
             item: {
-                #[error]
+                #[error(impl_from)]
                 enum E {
-                    #[error(impl_from)]
+                    Unnamed(f32),
+                    #[error(no_from)]
+                    Multiple(usize, usize),
+                    #[error(no_from)]
                     Unit
                 }
             }
 
             expected: {
-                should panic
+                #[derive(Debug)]
+                enum E {
+                    Unnamed(f32),
+                    Multiple(usize, usize),
+                    Unit
+                }
+
+                #[automatically_derived]
+                #[allow(unused_qualifications, clippy::all)]
+                impl ::std::error::Error for E {}
+
+                #[doc = " Converts a `f32` into a `E`, which allows using the ?-operator like"]
+                #[doc = ""]
+                #[doc = " ```ignore"]
+                #[doc = " fn example(result: Result<(), f32>) -> Result<(), E> {"]
+                #[doc = "     result?;"]
+                #[doc = "     Ok(())"]
+                #[doc = " }"]
+                #[doc = " ```"]
+                #[automatically_derived]
+                #[allow(unused_qualifications, clippy::all)]
+                impl ::std::convert::From<f32> for E {
+                    #[track_caller]
+                    fn from(val: f32) -> Self {
+                        E::Unnamed(val)
+                    }
+                }
+
+                impl E {
+                    #[doc = "The types this error can be created from with std::convert::From (and therefore the ?-operator)."]
+                    pub const FROM_TYPES: &'static [&'static str] = &["f32"];
+                }
             }
         )
     }
 
     #[test]
-    #[should_panic(expected = "'std::convert::From' cannot be implemented for enum 'E'. The following variants don't have exactly one field: Named,Unnamed,Unit")]
-    fn impl_from_multiple_errors_should_panic() {
+    fn no_std_impl_from_and_display() {
         assert_enum_implementation_as_expected!(
             item: {
-                #[error]
+                #[error(message = "failed", impl_from, no_from_docs, no_std)]
                 enum E {
-                    #[error(impl_from)]
-                    Named {one: usize, two: usize},
-                    #[error(impl_from)]
-                    Unnamed(f32, f32),
-                    #[error(impl_from)]
-                    Unit
+                    Value(usize)
                 }
             }
 
             expected: {
-                should panic
+                #[derive(Debug)]
+                enum E {
+                    Value(usize)
+                }
+
+                #[automatically_derived]
+                #[allow(unused_qualifications, clippy::all)]
+                impl ::core::error::Error for E {}
+
+                #[automatically_derived]
+                #[allow(unused_qualifications, clippy::all)]
+                impl ::core::convert::From<usize> for E {
+                    #[track_caller]
+                    fn from(val: usize) -> Self {
+                        E::Value(val)
+                    }
+                }
+
+                impl E {
+                    #[doc = "The types this error can be created from with std::convert::From (and therefore the ?-operator)."]
+                    pub const FROM_TYPES: &'static [&'static str] = &["usize"];
+                }
+
+                #[automatically_derived]
+                #[allow(unused_qualifications, clippy::all)]
+                impl ::core::fmt::Display for E {
+                    fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+                        match self {
+                            _ => ::core::write!(f, "failed")
+                        }
+                    }
+                }
             }
         )
     }
 
     #[test]
-    #[should_panic(expected = "'std::convert::From' cannot be implemented for enum 'E'. The following variants don't have exactly one field: Named,Unnamed,Unit")]
-    fn impl_from_global_multiple_errors_should_panic() {
+    fn variant_impl_from_into() {
         assert_enum_implementation_as_expected!(
             item: {
-                #[error(impl_from)]
+                #[error(no_from_docs)]
                 enum E {
-                    Named {one: usize, two: usize},
-                    Unnamed(f32, f32),
+                    #[error(impl_from = "into")]
+                    Message(String),
                     Unit
                 }
             }
 
             expected: {
-                should panic
+                #[derive(Debug)]
+                enum E {
+                    Message(String),
+                    Unit
+                }
+
+                #[automatically_derived]
+                #[allow(unused_qualifications, clippy::all)]
+                impl ::std::error::Error for E {}
+
+                #[automatically_derived]
+                #[allow(unused_qualifications, clippy::all)]
+                impl<__T: ::std::convert::Into<String> > ::std::convert::From<__T> for E {
+                    #[track_caller]
+                    fn from(val: __T) -> Self {
+                        E::Message(::std::convert::Into::into(val))
+                    }
+                }
+
+                impl E {
+                    #[doc = "The types this error can be created from with std::convert::From (and therefore the ?-operator)."]
+                    pub const FROM_TYPES: &'static [&'static str] = &["String"];
+                }
             }
         )
     }
 
     #[test]
-    #[should_panic(expected = "The 'impl_from' parameter was set on enum 'E' and at least one of its variants. Choose only one (enum or variants).")]
-    fn impl_from_on_variant_and_global_should_panic() {
+    #[should_panic(expected = "'impl_from = \"into\"' requires that only one From implementation is created for enum 'E', as the implementations would conflict.")]
+    fn impl_from_into_with_other_from_implementations_should_panic() {
         assert_enum_implementation_as_expected!(
             item: {
-                #[error(impl_from)]
+                #[error(impl_from = "into")]
                 enum E {
-                    #[error(impl_from)]
-                    Named {foo: usize},
-                    Unnamed(f32),
+                    Message(String),
+                    Code(usize)
                 }
             }
 
@@ -386,93 +822,100 @@ mod tests {
     }
 
     #[test]
-    fn impl_display() {
+    fn variant_impl_from_multiple_fields() {
         assert_enum_implementation_as_expected!(
             item: {
-                #[error]
+                #[error(no_from_docs)]
                 enum E {
-                    #[error(message = "The foo value: {foo}")]
-                    Named {foo: usize},
-                    #[error(message = "The first value: {_0}")]
-                    Unnamed(f32),
-                    #[error(message = "Something went wrong")]
-                    Unit
+                    #[error(impl_from)]
+                    Parse {
+                        #[error(from)]
+                        source: std::num::ParseIntError,
+                        line: usize,
+                        #[error(default = "String::from(\"unknown\")")]
+                        file: String
+                    }
                 }
             }
 
             expected: {
                 #[derive(Debug)]
                 enum E {
-                    Named {foo: usize},
-                    Unnamed(f32),
-                    Unit
+                    Parse {
+                        source: std::num::ParseIntError,
+                        line: usize,
+                        file: String
+                    }
                 }
 
-                impl std::error::Error for E {}
+                #[automatically_derived]
+                #[allow(unused_qualifications, clippy::all)]
+                impl ::std::error::Error for E {}
 
-                impl std::fmt::Display for E {
-                    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-                        match self {
-                            E::Named {foo,} => write!(f, "The foo value: {}", foo),
-                            E::Unnamed (_0,) => write!(f, "The first value: {}", _0),
-                            E::Unit => write!(f, "Something went wrong"),
-                        }
+                #[automatically_derived]
+                #[allow(unused_qualifications, clippy::all)]
+                impl ::std::convert::From<std::num::ParseIntError> for E {
+                    #[track_caller]
+                    fn from(val: std::num::ParseIntError) -> Self {
+                        E::Parse { source: val, line: ::std::default::Default::default(), file: String::from("unknown") }
                     }
                 }
+
+                impl E {
+                    #[doc = "The types this error can be created from with std::convert::From (and therefore the ?-operator)."]
+                    pub const FROM_TYPES: &'static [&'static str] = &["std::num::ParseIntError"];
+                }
             }
         )
     }
 
     #[test]
-    fn impl_display_default() {
+    #[should_panic(expected = "'1 +' is not a valid default expression.")]
+    fn variant_impl_from_invalid_default_should_panic() {
         assert_enum_implementation_as_expected!(
             item: {
-                #[error(message = "Something went wrong")]
+                #[error]
                 enum E {
-                    #[error(message = "The foo value: {foo}")]
-                    Named {foo: usize},
-                    #[error(message = "The first value: {_0}")]
-                    Unnamed(f32),
-                    Unit
+                    #[error(impl_from)]
+                    Parse(#[error(from)] std::num::ParseIntError, #[error(default = "1 +")] usize)
                 }
             }
 
             expected: {
-                #[derive(Debug)]
+                should panic
+            }
+        )
+    }
+
+    #[test]
+    #[should_panic(expected = "'std::convert::From<std::io::Error>' would be implemented twice for enum 'E', by the variants 'Read' and 'Write'. Exclude 'Write' with 'no_from'.")]
+    fn global_impl_from_duplicate_types_should_panic() {
+        assert_enum_implementation_as_expected!(
+            item: {
+                #[error(impl_from)]
                 enum E {
-                    Named {foo: usize},
-                    Unnamed(f32),
-                    Unit
+                    Read(std::io::Error),
+                    Write(std::io::Error)
                 }
+            }
 
-                impl std::error::Error for E {}
-
-                impl std::fmt::Display for E {
-                    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-                        match self {
-                            E::Named {foo,} => write!(f, "The foo value: {}", foo),
-                            E::Unnamed (_0,) => write!(f, "The first value: {}", _0),
-                            _ => write!(f, "Something went wrong")
-                        }
-                    }
-                }
+            expected: {
+                should panic
             }
         )
     }
 
     #[test]
-    #[should_panic(expected = "All variants for enum 'E' have a Display message, but a default was provided anyways. Please remove the default.")]
-    fn impl_display_unnecessary_default_should_panic() {
+    #[should_panic(expected = "'std::convert::From<std::io::Error>' would be implemented twice for enum 'E', by the variants 'Read' and 'Write'. Remove 'impl_from' from one of them.")]
+    fn variant_impl_from_duplicate_types_should_panic() {
         assert_enum_implementation_as_expected!(
             item: {
-                #[error(message = "Some default")]
+                #[error(message = "failed")]
                 enum E {
-                    #[error(message = "The foo value: {foo}")]
-                    Named {foo: usize},
-                    #[error(message = "The first value: {_0}")]
-                    Unnamed(f32),
-                    #[error(message = "Something went wrong")]
-                    Unit
+                    #[error(impl_from)]
+                    Read(std::io::Error),
+                    #[error(impl_from)]
+                    Write(std::io::Error)
                 }
             }
 
@@ -483,51 +926,2755 @@ mod tests {
     }
 
     #[test]
-    fn impl_from_and_display() {
+    #[should_panic(expected = "'std::convert::From<std::io::Error>' would be implemented twice for enum 'E', by the variants 'Read' and 'Write'. List the type only once in 'wrap_map' and 'includes'.")]
+    fn wrap_map_duplicate_types_should_panic() {
         assert_enum_implementation_as_expected!(
             item: {
-                #[error]
+                #[error(wrap_map = "std::io::Error => Read, std::io::Error => Write")]
                 enum E {
-                    #[error(message = "The foo value: {foo}", impl_from)]
-                    Named {foo: usize},
-                    #[error(message = "The first value: {_0}", impl_from)]
-                    Unnamed(f32),
-                    #[error(message = "Something went wrong")]
-                    Unit
+                    Read(std::io::Error),
+                    Write(std::io::Error)
+                }
+            }
+
+            expected: {
+                should panic
+            }
+        )
+    }
+
+    #[test]
+    #[should_panic(expected = "'std::convert::From<std::io::Error>' would be implemented twice for enum 'E', by the variants 'Read' and 'Io'. Remove 'impl_from' from 'Read'.")]
+    fn wrap_map_and_variant_impl_from_duplicate_types_should_panic() {
+        assert_enum_implementation_as_expected!(
+            item: {
+                #[error(wrap_map = "std::io::Error => Io")]
+                enum E {
+                    Io(std::io::Error),
+                    #[error(message = "reading failed", impl_from)]
+                    Read(std::io::Error)
+                }
+            }
+
+            expected: {
+                should panic
+            }
+        )
+    }
+
+    #[test]
+    fn variant_impl_from_for_concrete_types() {
+        assert_enum_implementation_as_expected!(
+            item: {
+                #[error(no_from_docs)]
+                enum E<'a, T: std::fmt::Display> where T: Clone {
+                    #[error(impl_from_for = "String, &'a str")]
+                    Value(T),
+                    Other(&'a usize)
+                }
+            }
+
+            expected: {
+                #[derive(Debug)]
+                enum E<'a, T: std::fmt::Display> where T: Clone {
+                    Value(T),
+                    Other(&'a usize)
+                }
+
+                #[automatically_derived]
+                #[allow(unused_qualifications, clippy::all)]
+                impl<'a, T: std::fmt::Display> ::std::error::Error for E<'a, T> where T: Clone, Self: ::std::fmt::Debug + ::std::fmt::Display {}
+
+                #[automatically_derived]
+                #[allow(unused_qualifications, clippy::all)]
+                impl<'a> ::std::convert::From<String> for E<'a, String> where String: Clone, String: std::fmt::Display {
+                    #[track_caller]
+                    fn from(val: String) -> Self {
+                        E::Value(val)
+                    }
+                }
+
+                #[automatically_derived]
+                #[allow(unused_qualifications, clippy::all)]
+                impl<'a> ::std::convert::From<&'a str> for E<'a, &'a str> where &'a str: Clone, &'a str: std::fmt::Display {
+                    #[track_caller]
+                    fn from(val: &'a str) -> Self {
+                        E::Value(val)
+                    }
+                }
+            }
+        )
+    }
+
+    #[test]
+    #[should_panic(expected = "'impl_from_for' requires the single field of variant 'Value' to have a type parameter of the enum as type.")]
+    fn variant_impl_from_for_without_generic_field_should_panic() {
+        assert_enum_implementation_as_expected!(
+            item: {
+                #[error]
+                enum E {
+                    #[error(impl_from_for = "String")]
+                    Value(usize)
+                }
+            }
+
+            expected: {
+                should panic
+            }
+        )
+    }
+
+    #[test]
+    fn exit_codes() {
+        assert_enum_implementation_as_expected!(
+            item: {
+                #[error(exit_code = 70)]
+                enum E {
+                    #[error(exit_code = 66)]
+                    NoInput,
+                    Software(usize)
+                }
+            }
+
+            expected: {
+                #[derive(Debug)]
+                enum E {
+                    NoInput,
+                    Software(usize)
+                }
+
+                #[automatically_derived]
+                #[allow(unused_qualifications, clippy::all)]
+                impl ::std::error::Error for E {}
+
+                impl E {
+                    #[doc = " Return the exit code a binary should exit with because of this error."]
+                    pub fn exit_code(&self) -> ::std::process::ExitCode {
+                        match self {
+                            E::NoInput { .. } => ::std::process::ExitCode::from(66u8),
+                            E::Software { .. } => ::std::process::ExitCode::from(70u8),
+                        }
+                    }
+                }
+            }
+        )
+    }
+
+    #[test]
+    fn severities() {
+        assert_enum_implementation_as_expected!(
+            item: {
+                #[error(severity = "warning")]
+                pub enum E {
+                    #[error(severity = "fatal")]
+                    Corrupted,
+                    Retrying(usize)
+                }
+            }
+
+            expected: {
+                #[derive(Debug)]
+                pub enum E {
+                    Corrupted,
+                    Retrying(usize)
+                }
+
+                #[automatically_derived]
+                #[allow(unused_qualifications, clippy::all)]
+                impl ::std::error::Error for E {}
+
+                #[doc = " The severity of 'E', see 'E::severity'. Ordered from Warning to Fatal."]
+                #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+                pub enum ESeverity {
+                    Warning,
+                    Error,
+                    Fatal
+                }
+
+                impl E {
+                    #[doc = " Return the severity of this error."]
+                    pub fn severity(&self) -> ESeverity {
+                        match self {
+                            E::Corrupted { .. } => ESeverity::Fatal,
+                            E::Retrying { .. } => ESeverity::Warning,
+                        }
+                    }
+                }
+            }
+        )
+    }
+
+    #[test]
+    fn categories() {
+        assert_enum_implementation_as_expected!(
+            item: {
+                #[error(category = "io")]
+                enum E {
+                    #[error(category = "validation")]
+                    InvalidInput(String),
+                    NotFound
+                }
+            }
+
+            expected: {
+                #[derive(Debug)]
+                enum E {
+                    InvalidInput(String),
+                    NotFound
+                }
+
+                #[automatically_derived]
+                #[allow(unused_qualifications, clippy::all)]
+                impl ::std::error::Error for E {}
+
+                impl E {
+                    #[doc = " Return the category of this error, to group it with similar errors."]
+                    pub fn category(&self) -> &'static str {
+                        match self {
+                            E::InvalidInput { .. } => "validation",
+                            E::NotFound { .. } => "io",
+                        }
+                    }
+                }
+            }
+        )
+    }
+
+    #[test]
+    #[should_panic(expected = "The following variants of enum 'E' have no 'category' value and the enum has no default: NotFound")]
+    fn missing_category_should_panic() {
+        assert_enum_implementation_as_expected!(
+            item: {
+                #[error]
+                enum E {
+                    #[error(category = "validation")]
+                    InvalidInput(String),
+                    NotFound
+                }
+            }
+
+            expected: {
+                should panic
+            }
+        )
+    }
+
+    #[test]
+    fn errno_values() {
+        assert_enum_implementation_as_expected!(
+            item: {
+                #[error]
+                enum E {
+                    #[error(errno = 2)]
+                    NotFound,
+                    #[error(errno = "EACCES")]
+                    PermissionDenied(String)
+                }
+            }
+
+            expected: {
+                #[derive(Debug)]
+                enum E {
+                    NotFound,
+                    PermissionDenied(String)
+                }
+
+                #[automatically_derived]
+                #[allow(unused_qualifications, clippy::all)]
+                impl ::std::error::Error for E {}
+
+                impl E {
+                    #[doc = " Return the errno value which describes this error."]
+                    pub fn to_errno(&self) -> i32 {
+                        match self {
+                            E::NotFound { .. } => 2i32,
+                            E::PermissionDenied { .. } => libc::EACCES,
+                        }
+                    }
+
+                    #[doc = " Return the errno value which describes this error, the same as 'to_errno'."]
+                    pub fn errno(&self) -> i32 {
+                        self.to_errno()
+                    }
+                }
+
+                #[automatically_derived]
+                #[allow(unused_qualifications, clippy::all)]
+                impl ::std::convert::From<E> for i32 {
+                    fn from(e: E) -> i32 {
+                        e.to_errno()
+                    }
+                }
+
+                #[automatically_derived]
+                #[allow(unused_qualifications, clippy::all)]
+                impl ::std::convert::From<E> for ::std::io::Error {
+                    fn from(e: E) -> ::std::io::Error {
+                        ::std::io::Error::from_raw_os_error(e.to_errno())
+                    }
+                }
+            }
+        )
+    }
+
+    #[test]
+    #[should_panic(expected = "The following variants of enum 'E' have no 'errno' value and the enum has no default: Other")]
+    fn errno_missing_should_panic() {
+        assert_enum_implementation_as_expected!(
+            item: {
+                #[error]
+                enum E {
+                    #[error(errno = 2)]
+                    NotFound,
+                    Other
+                }
+            }
+
+            expected: {
+                should panic
+            }
+        )
+    }
+
+    #[test]
+    fn debug_placeholder() {
+        assert_enum_implementation_as_expected!(
+            item: {
+                #[error]
+                enum E {
+                    #[error(message = "invalid payload {payload}")]
+                    Named {
+                        #[error(debug_placeholder)]
+                        payload: Vec<u8>
+                    },
+                    #[error(message = "invalid payload {_0} in {_1}")]
+                    Unnamed(#[error(debug_placeholder)] Vec<u8>, String)
+                }
+            }
+
+            expected: {
+                #[derive(Debug)]
+                enum E {
+                    Named {
+                        payload: Vec<u8>
+                    },
+                    Unnamed(Vec<u8>, String)
+                }
+
+                #[automatically_derived]
+                #[allow(unused_qualifications, clippy::all)]
+                impl ::std::error::Error for E {}
+
+                #[automatically_derived]
+                #[allow(unused_qualifications, clippy::all)]
+                impl ::std::fmt::Display for E {
+                    fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+                        match self {
+                            E::Named {payload,} => ::std::write!(f, "invalid payload {:?}", payload),
+                            E::Unnamed (_0, _1,) => ::std::write!(f, "invalid payload {:?} in {}", _0, _1),
+                        }
+                    }
+                }
+            }
+        )
+    }
+
+    #[test]
+    fn derives_with_manual_debug() {
+        assert_enum_implementation_as_expected!(
+            item: {
+                #[error(message = "failed", redact_debug, derives = "Clone")]
+                enum E {
+                    Unnamed(#[error(redact)] String)
+                }
+            }
+
+            expected: {
+                #[derive(Clone)]
+                enum E {
+                    Unnamed(String)
+                }
+                #[allow(unused_qualifications, clippy::all)]
+                impl ::std::fmt::Debug for E {
+                    fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+                        match self {
+                            E::Unnamed(_) => f.debug_tuple("Unnamed").field(&::std::format_args!("[REDACTED]")).finish()
+                        }
+                    }
+                }
+
+                #[automatically_derived]
+                #[allow(unused_qualifications, clippy::all)]
+                impl ::std::error::Error for E {}
+
+                #[automatically_derived]
+                #[allow(unused_qualifications, clippy::all)]
+                impl ::std::fmt::Display for E {
+                    fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+                        match self {
+                            _ => ::std::write!(f, "failed")
+                        }
+                    }
+                }
+            }
+        )
+    }
+
+    #[test]
+    fn redacted_fields() {
+        assert_enum_implementation_as_expected!(
+            item: {
+                #[error(redact_debug)]
+                enum E {
+                    #[error(message = "login of {user} with {e.password} failed")]
+                    Named {
+                        user: String,
+                        #[error(redact)]
+                        password: String
+                    },
+                    #[error(message = "invalid token {_0.len()}")]
+                    Unnamed(#[error(redact)] String),
+                    #[error(message = "unit")]
+                    Unit
+                }
+            }
+
+            expected: {
+                enum E {
+                    Named {
+                        user: String,
+                        password: String
+                    },
+                    Unnamed(String),
+                    Unit
+                }
+                #[allow(unused_qualifications, clippy::all)]
+                impl ::std::fmt::Debug for E {
+                    fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+                        match self {
+                            E::Named { user, .. } => f.debug_struct("Named").field("user", user).field("password", &::std::format_args!("[REDACTED]")).finish(),
+                            E::Unnamed(_) => f.debug_tuple("Unnamed").field(&::std::format_args!("[REDACTED]")).finish(),
+                            E::Unit => f.write_str("Unit")
+                        }
+                    }
+                }
+
+                #[automatically_derived]
+                #[allow(unused_qualifications, clippy::all)]
+                impl ::std::error::Error for E {}
+
+                #[automatically_derived]
+                #[allow(unused_qualifications, clippy::all)]
+                impl ::std::fmt::Display for E {
+                    fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+                        match self {
+                            E::Named {user, ..} => ::std::write!(f, "login of {} with [REDACTED] failed", user),
+                            E::Unnamed (_,) => ::std::write!(f, "invalid token [REDACTED]"),
+                            E::Unit => ::std::write!(f, "unit"),
+                        }
+                    }
+                }
+            }
+        )
+    }
+
+    #[test]
+    #[should_panic(expected = "The message key 'errors-login' requires a message, which is displayed if the key cannot be looked up.")]
+    fn msg_key_without_message_should_panic() {
+        assert_enum_implementation_as_expected!(
+            item: {
+                #[error(message = "failed")]
+                enum E {
+                    #[error(msg_key = "errors-login")]
+                    Login
+                }
+            }
+
+            expected: {
+                should panic
+            }
+        )
+    }
+
+    #[test]
+    fn skipped_fields() {
+        assert_enum_implementation_as_expected!(
+            item: {
+                #[error(impl_from, no_from_docs)]
+                enum E {
+                    #[error(message = "{code}")]
+                    Named {code: usize, #[error(skip)] buffer: Vec<u8>},
+                    #[error(message = "{_1}")]
+                    Unnamed(#[error(skip)] Vec<u8>, f32)
+                }
+            }
+
+            expected: {
+                #[derive(Debug)]
+                enum E {
+                    Named {code: usize, buffer: Vec<u8>},
+                    Unnamed(Vec<u8>, f32)
+                }
+
+                #[automatically_derived]
+                #[allow(unused_qualifications, clippy::all)]
+                impl ::std::error::Error for E {}
+
+                #[automatically_derived]
+                #[allow(unused_qualifications, clippy::all)]
+                impl ::std::convert::From<usize> for E {
+                    #[track_caller]
+                    fn from(val: usize) -> Self {
+                        E::Named { code: val, buffer: ::std::default::Default::default() }
+                    }
+                }
+
+                #[automatically_derived]
+                #[allow(unused_qualifications, clippy::all)]
+                impl ::std::convert::From<f32> for E {
+                    #[track_caller]
+                    fn from(val: f32) -> Self {
+                        E::Unnamed(::std::default::Default::default(), val)
+                    }
+                }
+
+                impl E {
+                    #[doc = "The types this error can be created from with std::convert::From (and therefore the ?-operator)."]
+                    pub const FROM_TYPES: &'static [&'static str] = &["usize", "f32"];
+                }
+
+                #[automatically_derived]
+                #[allow(unused_qualifications, clippy::all)]
+                impl ::std::fmt::Display for E {
+                    fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+                        match self {
+                            E::Named { code, .. } => ::std::write!(f, "{}", code),
+                            E::Unnamed(_, _1,) => ::std::write!(f, "{}", _1),
+                        }
+                    }
+                }
+            }
+        )
+    }
+
+    #[test]
+    fn auto_from_types() {
+        assert_enum_implementation_as_expected!(
+            item: {
+                #[error(auto_from_types = "std::io::Error, Vec<u8>")]
+                enum E {
+                    Io(std::io::Error),
+                    Bytes { bytes: Vec<u8> },
+                    Other(usize)
+                }
+            }
+
+            expected: {
+                #[derive(Debug)]
+                enum E {
+                    Io(std::io::Error),
+                    Bytes { bytes: Vec<u8> },
+                    Other(usize)
+                }
+
+                #[automatically_derived]
+                #[allow(unused_qualifications, clippy::all)]
+                impl ::std::error::Error for E {}
+
+                #[doc = " Converts a `std::io::Error` into a `E`, which allows using the ?-operator like"]
+                #[doc = ""]
+                #[doc = " ```ignore"]
+                #[doc = " fn example(result: Result<(), std::io::Error>) -> Result<(), E> {"]
+                #[doc = "     result?;"]
+                #[doc = "     Ok(())"]
+                #[doc = " }"]
+                #[doc = " ```"]
+                #[automatically_derived]
+                #[allow(unused_qualifications, clippy::all)]
+                impl ::std::convert::From<std::io::Error> for E {
+                    #[track_caller]
+                    fn from(val: std::io::Error) -> Self {
+                        E::Io(val)
+                    }
+                }
+
+                #[doc = " Converts a `Vec<u8>` into a `E`, which allows using the ?-operator like"]
+                #[doc = ""]
+                #[doc = " ```ignore"]
+                #[doc = " fn example(result: Result<(), Vec<u8>>) -> Result<(), E> {"]
+                #[doc = "     result?;"]
+                #[doc = "     Ok(())"]
+                #[doc = " }"]
+                #[doc = " ```"]
+                #[automatically_derived]
+                #[allow(unused_qualifications, clippy::all)]
+                impl ::std::convert::From<Vec<u8> > for E {
+                    #[track_caller]
+                    fn from(val: Vec<u8>) -> Self {
+                        E::Bytes {bytes: val}
+                    }
+                }
+
+                impl E {
+                    #[doc = "The types this error can be created from with std::convert::From (and therefore the ?-operator)."]
+                    pub const FROM_TYPES: &'static [&'static str] = &["std::io::Error", "Vec<u8>"];
+                }
+
+                impl E {
+                    #[doc = " Return the kind of the wrapped std::io::Error, if this error wraps one."]
+                    pub fn io_kind(&self) -> ::std::option::Option<::std::io::ErrorKind> {
+                        match self {
+                            E::Io { 0: e, .. } => ::std::option::Option::Some(e.kind()),
+                            _ => ::std::option::Option::None
+                        }
+                    }
+                }
+            }
+        )
+    }
+
+    #[test]
+    #[should_panic(expected = "The type 'String' from 'auto_from_types' does not match the single field of any variant of enum 'E'.")]
+    fn auto_from_types_without_variant_should_panic() {
+        assert_enum_implementation_as_expected!(
+            item: {
+                #[error(auto_from_types = "String")]
+                enum E {
+                    Other(usize)
+                }
+            }
+
+            expected: {
+                should panic
+            }
+        )
+    }
+
+    #[test]
+    #[should_panic(expected = "The type 'usize' from 'auto_from_types' matches multiple variants of enum 'E': One,Two")]
+    fn auto_from_types_multiple_variants_should_panic() {
+        assert_enum_implementation_as_expected!(
+            item: {
+                #[error(auto_from_types = "usize")]
+                enum E {
+                    One(usize),
+                    Two { val: usize }
+                }
+            }
+
+            expected: {
+                should panic
+            }
+        )
+    }
+
+    #[test]
+    #[should_panic(expected = "'std::convert::From' cannot be implemented for enum 'E'. The following variants don't have exactly one field: Unit")]
+    fn unit_impl_from_should_panic() {
+        assert_enum_implementation_as_expected!(
+            item: {
+                #[error]
+                enum E {
+                    #[error(impl_from)]
+                    Unit
+                }
+            }
+
+            expected: {
+                should panic
+            }
+        )
+    }
+
+    #[test]
+    #[should_panic(expected = "'std::convert::From' cannot be implemented for enum 'E'. The following variants don't have exactly one field: Named,Unnamed,Unit")]
+    fn impl_from_multiple_errors_should_panic() {
+        assert_enum_implementation_as_expected!(
+            item: {
+                #[error]
+                enum E {
+                    #[error(impl_from)]
+                    Named {one: usize, two: usize},
+                    #[error(impl_from)]
+                    Unnamed(f32, f32),
+                    #[error(impl_from)]
+                    Unit
+                }
+            }
+
+            expected: {
+                should panic
+            }
+        )
+    }
+
+    #[test]
+    #[should_panic(expected = "'std::convert::From' cannot be implemented for enum 'E'. The following variants don't have exactly one field: Named,Unnamed,Unit")]
+    fn impl_from_global_multiple_errors_should_panic() {
+        assert_enum_implementation_as_expected!(
+            item: {
+                #[error(impl_from)]
+                enum E {
+                    Named {one: usize, two: usize},
+                    Unnamed(f32, f32),
+                    Unit
+                }
+            }
+
+            expected: {
+                should panic
+            }
+        )
+    }
+
+    #[test]
+    #[should_panic(expected = "'impl_from' requires payload-carrying variants, but enum 'E' has none. Consider removing the 'impl_from' parameter.")]
+    fn impl_from_global_only_unit_variants_should_panic() {
+        assert_enum_implementation_as_expected!(
+            item: {
+                #[error(impl_from)]
+                enum E {
+                    Foo,
+                    Bar
+                }
+            }
+
+            expected: {
+                should panic
+            }
+        )
+    }
+
+    #[test]
+    #[should_panic(expected = "The 'impl_from' parameter was set on enum 'E' and at least one of its variants. Choose only one (enum or variants).")]
+    fn impl_from_on_variant_and_global_should_panic() {
+        assert_enum_implementation_as_expected!(
+            item: {
+                #[error(impl_from)]
+                enum E {
+                    #[error(impl_from)]
+                    Named {foo: usize},
+                    Unnamed(f32),
+                }
+            }
+
+            expected: {
+                should panic
+            }
+        )
+    }
+
+    #[test]
+    #[should_panic(expected = "The parameter 'no_from' is not allowed on enums.")]
+    fn misplaced_parameter_should_panic() {
+        assert_enum_implementation_as_expected!(
+            item: {
+                #[error(no_from)]
+                enum E {
+                    Unit
+                }
+            }
+
+            expected: {
+                should panic
+            }
+        )
+    }
+
+    #[test]
+    #[should_panic(expected = "Unknown parameter 'impl_frm'. Valid parameters on enum variants are: ")]
+    fn unknown_variant_parameter_should_panic() {
+        assert_enum_implementation_as_expected!(
+            item: {
+                #[error]
+                enum E {
+                    #[error(impl_frm)]
+                    Unnamed(usize)
+                }
+            }
+
+            expected: {
+                should panic
+            }
+        )
+    }
+
+    #[test]
+    fn impl_display() {
+        assert_enum_implementation_as_expected!(
+            item: {
+                #[error]
+                enum E {
+                    #[error(message = "The foo value: {foo}")]
+                    Named {foo: usize},
+                    #[error(message = "The first value: {_0}")]
+                    Unnamed(f32),
+                    #[error(message = "Something went wrong")]
+                    Unit
+                }
+            }
+
+            expected: {
+                #[derive(Debug)]
+                enum E {
+                    Named {foo: usize},
+                    Unnamed(f32),
+                    Unit
+                }
+
+                #[automatically_derived]
+                #[allow(unused_qualifications, clippy::all)]
+                impl ::std::error::Error for E {}
+
+                #[automatically_derived]
+                #[allow(unused_qualifications, clippy::all)]
+                impl ::std::fmt::Display for E {
+                    fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+                        match self {
+                            E::Named {foo,} => ::std::write!(f, "The foo value: {}", foo),
+                            E::Unnamed (_0,) => ::std::write!(f, "The first value: {}", _0),
+                            E::Unit => ::std::write!(f, "Something went wrong"),
+                        }
+                    }
+                }
+            }
+        )
+    }
+
+    #[test]
+    fn impl_display_default() {
+        assert_enum_implementation_as_expected!(
+            item: {
+                #[error(message = "Something went wrong")]
+                enum E {
+                    #[error(message = "The foo value: {foo}")]
+                    Named {foo: usize},
+                    #[error(message = "The first value: {_0}")]
+                    Unnamed(f32),
+                    Unit
+                }
+            }
+
+            expected: {
+                #[derive(Debug)]
+                enum E {
+                    Named {foo: usize},
+                    Unnamed(f32),
+                    Unit
+                }
+
+                #[automatically_derived]
+                #[allow(unused_qualifications, clippy::all)]
+                impl ::std::error::Error for E {}
+
+                #[automatically_derived]
+                #[allow(unused_qualifications, clippy::all)]
+                impl ::std::fmt::Display for E {
+                    fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+                        match self {
+                            E::Named {foo,} => ::std::write!(f, "The foo value: {}", foo),
+                            E::Unnamed (_0,) => ::std::write!(f, "The first value: {}", _0),
+                            _ => ::std::write!(f, "Something went wrong")
+                        }
+                    }
+                }
+            }
+        )
+    }
+
+    #[test]
+    fn impl_display_prefix_and_suffix() {
+        assert_enum_implementation_as_expected!(
+            item: {
+                #[error(message = "Something went wrong", prefix = "config: ", suffix = ".")]
+                enum E {
+                    #[error(message = "The foo value: {foo}")]
+                    Named {foo: usize},
+                    Unit
+                }
+            }
+
+            expected: {
+                #[derive(Debug)]
+                enum E {
+                    Named {foo: usize},
+                    Unit
+                }
+
+                #[automatically_derived]
+                #[allow(unused_qualifications, clippy::all)]
+                impl ::std::error::Error for E {}
+
+                #[automatically_derived]
+                #[allow(unused_qualifications, clippy::all)]
+                impl ::std::fmt::Display for E {
+                    fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+                        {
+                            f.write_str("config: ")?;
+                            (match self {
+                                E::Named {foo,} => ::std::write!(f, "The foo value: {}", foo),
+                                _ => ::std::write!(f, "Something went wrong")
+                            })?;
+                            f.write_str(".")
+                        }
+                    }
+                }
+            }
+        )
+    }
+
+    #[test]
+    #[should_panic(expected = "The parameter 'prefix' requires a message for 'E', as it is added to the message.")]
+    fn impl_display_prefix_without_message_should_panic() {
+        assert_enum_implementation_as_expected!(
+            item: {
+                #[error(prefix = "config: ")]
+                enum E {
+                    Unit
+                }
+            }
+
+            expected: {
+                should panic
+            }
+        )
+    }
+
+    #[test]
+    #[should_panic(expected = "All variants for enum 'E' have a Display message, but a default was provided anyways. Please remove the default.")]
+    fn impl_display_unnecessary_default_should_panic() {
+        assert_enum_implementation_as_expected!(
+            item: {
+                #[error(message = "Some default")]
+                enum E {
+                    #[error(message = "The foo value: {foo}")]
+                    Named {foo: usize},
+                    #[error(message = "The first value: {_0}")]
+                    Unnamed(f32),
+                    #[error(message = "Something went wrong")]
+                    Unit
+                }
+            }
+
+            expected: {
+                should panic
+            }
+        )
+    }
+
+    #[test]
+    fn impl_display_custom_formatter() {
+        assert_enum_implementation_as_expected!(
+            item: {
+                #[error(fmt = "format_e")]
+                enum E {
+                    Named {foo: usize},
+                    Unit
+                }
+            }
+
+            expected: {
+                #[derive(Debug)]
+                enum E {
+                    Named {foo: usize},
+                    Unit
+                }
+
+                #[automatically_derived]
+                #[allow(unused_qualifications, clippy::all)]
+                impl ::std::error::Error for E {}
+
+                #[automatically_derived]
+                #[allow(unused_qualifications, clippy::all)]
+                impl ::std::fmt::Display for E {
+                    fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+                        format_e(self, f)
+                    }
+                }
+            }
+        )
+    }
+
+    #[test]
+    #[should_panic(expected = "'E' has both a message and a custom formatter function. Choose only one.")]
+    fn impl_display_custom_formatter_and_variant_message_should_panic() {
+        assert_enum_implementation_as_expected!(
+            item: {
+                #[error(fmt = "format_e")]
+                enum E {
+                    #[error(message = "foo")]
+                    Unit
+                }
+            }
+
+            expected: {
+                should panic
+            }
+        )
+    }
+
+    #[test]
+    fn impl_from_and_display() {
+        assert_enum_implementation_as_expected!(
+            item: {
+                #[error]
+                enum E {
+                    #[error(message = "The foo value: {foo}", impl_from)]
+                    Named {foo: usize},
+                    #[error(message = "The first value: {_0}", impl_from)]
+                    Unnamed(f32),
+                    #[error(message = "Something went wrong")]
+                    Unit
+                }
+            }
+
+            expected: {
+                #[derive(Debug)]
+                enum E {
+                    Named {foo: usize},
+                    Unnamed(f32),
+                    Unit
+                }
+
+                #[automatically_derived]
+                #[allow(unused_qualifications, clippy::all)]
+                impl ::std::error::Error for E {}
+
+                #[doc = " Converts a `usize` into a `E`, which allows using the ?-operator like"]
+                #[doc = ""]
+                #[doc = " ```ignore"]
+                #[doc = " fn example(result: Result<(), usize>) -> Result<(), E> {"]
+                #[doc = "     result?;"]
+                #[doc = "     Ok(())"]
+                #[doc = " }"]
+                #[doc = " ```"]
+                #[automatically_derived]
+                #[allow(unused_qualifications, clippy::all)]
+                impl ::std::convert::From<usize> for E {
+                    #[track_caller]
+                    fn from(val: usize) -> Self {
+                        E::Named {foo: val}
+                    }
+                }
+
+                #[doc = " Converts a `f32` into a `E`, which allows using the ?-operator like"]
+                #[doc = ""]
+                #[doc = " ```ignore"]
+                #[doc = " fn example(result: Result<(), f32>) -> Result<(), E> {"]
+                #[doc = "     result?;"]
+                #[doc = "     Ok(())"]
+                #[doc = " }"]
+                #[doc = " ```"]
+                #[automatically_derived]
+                #[allow(unused_qualifications, clippy::all)]
+                impl ::std::convert::From<f32> for E {
+                    #[track_caller]
+                    fn from(val: f32) -> Self {
+                        E::Unnamed(val)
+                    }
+                }
+
+                impl E {
+                    #[doc = "The types this error can be created from with std::convert::From (and therefore the ?-operator)."]
+                    pub const FROM_TYPES: &'static [&'static str] = &["usize", "f32"];
+                }
+
+                #[automatically_derived]
+                #[allow(unused_qualifications, clippy::all)]
+                impl ::std::fmt::Display for E {
+                    fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+                        match self {
+                            E::Named {foo,} => ::std::write!(f, "The foo value: {}", foo),
+                            E::Unnamed (_0,) => ::std::write!(f, "The first value: {}", _0),
+                            E::Unit => ::std::write!(f, "Something went wrong"),
+                        }
+                    }
+                }
+            }
+        )
+    }
+
+    #[test]
+    fn assert_macro() {
+        assert_enum_implementation_as_expected!(
+            item: {
+                #[error(assert_macro)]
+                enum MyError<T> {
+                    Named {foo: T},
+                    Unit
+                }
+            }
+
+            expected: {
+                #[derive(Debug)]
+                enum MyError<T> {
+                    Named {foo: T},
+                    Unit
+                }
+
+                #[automatically_derived]
+                #[allow(unused_qualifications, clippy::all)]
+                impl<T> ::std::error::Error for MyError<T> where Self: ::std::fmt::Debug + ::std::fmt::Display {}
+
+                #[allow(unused_macros)]
+                macro_rules! assert_handles_all_my_error {
+                    ($($variant:ident),* $(,)?) => {
+                        const _: () = {
+                            #[allow(dead_code)]
+                            fn assert_handles_all<T>(e: &MyError<T>) {
+                                match e {
+                                    $(MyError::$variant { .. } => {})*
+                                }
+                            }
+                        };
+                    };
+                }
+            }
+        )
+    }
+
+    #[test]
+    fn matcher_macro() {
+        assert_enum_implementation_as_expected!(
+            item: {
+                #[error(matcher_macro)]
+                enum MyError {
+                    Named {foo: usize},
+                    Unit
+                }
+            }
+
+            expected: {
+                #[derive(Debug)]
+                enum MyError {
+                    Named {foo: usize},
+                    Unit
+                }
+
+                #[automatically_derived]
+                #[allow(unused_qualifications, clippy::all)]
+                impl ::std::error::Error for MyError {}
+
+                #[allow(unused_macros)]
+                macro_rules! assert_my_error {
+                    ($result:expr, $variant:ident $($fields:tt)?) => {
+                        let error = match $result {
+                            Ok(_) => panic!("expected {}::{}, but the result is Ok", "MyError", stringify!($variant)),
+                            Err(e) => e
+                        };
+                        let MyError::$variant $($fields)? = error else {
+                            panic!(concat!("expected {}::{}, but got: ", "{:?}"), "MyError", stringify!($variant), error)
+                        };
+                    };
+                }
+            }
+        )
+    }
+
+    #[test]
+    fn constructors() {
+        assert_enum_implementation_as_expected!(
+            item: {
+                #[error(constructors)]
+                enum MyError {
+                    ParsingFailed {line: usize, location: &'static std::panic::Location<'static>},
+                    Unnamed(usize, String),
+                    Unit
+                }
+            }
+
+            expected: {
+                #[derive(Debug)]
+                enum MyError {
+                    ParsingFailed {line: usize, location: &'static std::panic::Location<'static>},
+                    Unnamed(usize, String),
+                    Unit
+                }
+
+                #[automatically_derived]
+                #[allow(unused_qualifications, clippy::all)]
+                impl ::std::error::Error for MyError {}
+
+                impl MyError {
+                    #[doc = " Create the variant 'MyError::ParsingFailed'."]
+                    #[track_caller]
+                    pub fn parsing_failed(line: usize) -> Self {
+                        MyError::ParsingFailed { line: line, location: ::std::panic::Location::caller() }
+                    }
+
+                    #[doc = " Create the variant 'MyError::ParsingFailed' lazily. The fields are only computed when the returned closure is called."]
+                    #[track_caller]
+                    pub fn parsing_failed_with(line: impl FnOnce() -> usize) -> impl FnOnce() -> Self {
+                        let __location = ::std::panic::Location::caller();
+                        move || { MyError::ParsingFailed { line: line(), location: __location } }
+                    }
+
+                    #[doc = " Create the variant 'MyError::Unnamed'."]
+                    pub fn unnamed(_0: usize, _1: String) -> Self {
+                        MyError::Unnamed(_0, _1)
+                    }
+
+                    #[doc = " Create the variant 'MyError::Unnamed' lazily. The fields are only computed when the returned closure is called."]
+                    pub fn unnamed_with(_0: impl FnOnce() -> usize, _1: impl FnOnce() -> String) -> impl FnOnce() -> Self {
+                        move || { MyError::Unnamed(_0(), _1()) }
+                    }
+
+                    #[doc = " Create the variant 'MyError::Unit'."]
+                    pub fn unit() -> Self {
+                        MyError::Unit
+                    }
+                }
+            }
+        )
+    }
+
+    #[test]
+    fn constructors_of_keyword_variants() {
+        assert_enum_implementation_as_expected!(
+            item: {
+                #[error(constructors)]
+                enum MyError {
+                    Type(String),
+                    Match
+                }
+            }
+
+            expected: {
+                #[derive(Debug)]
+                enum MyError {
+                    Type(String),
+                    Match
+                }
+
+                #[automatically_derived]
+                #[allow(unused_qualifications, clippy::all)]
+                impl ::std::error::Error for MyError {}
+
+                impl MyError {
+                    #[doc = " Create the variant 'MyError::Type'."]
+                    pub fn r#type(_0: String) -> Self {
+                        MyError::Type(_0)
+                    }
+
+                    #[doc = " Create the variant 'MyError::Type' lazily. The fields are only computed when the returned closure is called."]
+                    pub fn type_with(_0: impl FnOnce() -> String) -> impl FnOnce() -> Self {
+                        move || { MyError::Type(_0()) }
+                    }
+
+                    #[doc = " Create the variant 'MyError::Match'."]
+                    pub fn r#match() -> Self {
+                        MyError::Match
+                    }
+                }
+            }
+        )
+    }
+
+    #[test]
+    #[should_panic(expected = "The parameter 'constructors' cannot create a constructor for the variant 'Crate', as 'crate' is a keyword which cannot be used as function name.")]
+    fn constructors_of_reserved_names_should_panic() {
+        assert_enum_implementation_as_expected!(
+            item: {
+                #[error(constructors)]
+                enum MyError {
+                    Crate
+                }
+            }
+
+            expected: {
+                should panic
+            }
+        )
+    }
+
+    #[test]
+    fn predicates() {
+        assert_enum_implementation_as_expected!(
+            item: {
+                #[error(predicates)]
+                enum MyError {
+                    ParsingFailed {line: usize},
+                    Unnamed(usize),
+                    Unit
+                }
+            }
+
+            expected: {
+                #[derive(Debug)]
+                enum MyError {
+                    ParsingFailed {line: usize},
+                    Unnamed(usize),
+                    Unit
+                }
+
+                #[automatically_derived]
+                #[allow(unused_qualifications, clippy::all)]
+                impl ::std::error::Error for MyError {}
+
+                impl MyError {
+                    #[doc = " Return true if this error is the variant 'MyError::ParsingFailed'."]
+                    pub fn is_parsing_failed(&self) -> bool {
+                        matches!(self, MyError::ParsingFailed { .. })
+                    }
+
+                    #[doc = " Return true if this error is the variant 'MyError::Unnamed'."]
+                    pub fn is_unnamed(&self) -> bool {
+                        matches!(self, MyError::Unnamed { .. })
+                    }
+
+                    #[doc = " Return true if this error is the variant 'MyError::Unit'."]
+                    pub fn is_unit(&self) -> bool {
+                        matches!(self, MyError::Unit { .. })
+                    }
+                }
+            }
+        )
+    }
+
+    #[test]
+    fn variant_name() {
+        assert_enum_implementation_as_expected!(
+            item: {
+                #[error(variant_name)]
+                enum E {
+                    ReadFileFailed(String),
+                    Unit
+                }
+            }
+
+            expected: {
+                #[derive(Debug)]
+                enum E {
+                    ReadFileFailed(String),
+                    Unit
+                }
+
+                #[automatically_derived]
+                #[allow(unused_qualifications, clippy::all)]
+                impl ::std::error::Error for E {}
+
+                impl E {
+                    #[doc = " Return the name of the variant of this error."]
+                    pub fn name(&self) -> &'static str {
+                        match self {
+                            E::ReadFileFailed { .. } => "ReadFileFailed",
+                            E::Unit { .. } => "Unit",
+                        }
+                    }
+                }
+            }
+        )
+    }
+
+    #[test]
+    fn non_exhaustive() {
+        assert_enum_implementation_as_expected!(
+            item: {
+                #[error(variant_name, non_exhaustive)]
+                enum E {
+                    #[error(message = "reading {_0} failed")]
+                    ReadFileFailed(String),
+                    #[error(message = "unit")]
+                    Unit
+                }
+            }
+
+            expected: {
+                #[derive(Debug)]
+                #[non_exhaustive]
+                enum E {
+                    ReadFileFailed(String),
+                    Unit
+                }
+
+                #[automatically_derived]
+                #[allow(unused_qualifications, clippy::all)]
+                impl ::std::error::Error for E {}
+
+                #[automatically_derived]
+                #[allow(unused_qualifications, clippy::all)]
+                impl ::std::fmt::Display for E {
+                    fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+                        match self {
+                            E::ReadFileFailed(_0,) => ::std::write!(f, "reading {} failed", _0),
+                            E::Unit => ::std::write!(f, "unit"),
+                            #[allow(unreachable_patterns)]
+                            _ => f.write_str("unknown")
+                        }
+                    }
+                }
+
+                impl E {
+                    #[doc = " Return the name of the variant of this error."]
+                    pub fn name(&self) -> &'static str {
+                        match self {
+                            E::ReadFileFailed { .. } => "ReadFileFailed",
+                            E::Unit { .. } => "Unit",
+                            #[allow(unreachable_patterns)]
+                            _ => "unknown"
+                        }
+                    }
+                }
+            }
+        )
+    }
+
+    #[test]
+    fn cfg_attributes_of_variants() {
+        assert_enum_implementation_as_expected!(
+            item: {
+                #[error(variant_name, constructors)]
+                enum E {
+                    #[cfg(feature = "parse")]
+                    #[error(message = "parsing failed", impl_from)]
+                    Parse(std::num::ParseIntError),
+                    #[error(message = "unit")]
+                    Unit
+                }
+            }
+
+            expected: {
+                #[derive(Debug)]
+                enum E {
+                    #[cfg(feature = "parse")]
+                    Parse(std::num::ParseIntError),
+                    Unit
+                }
+
+                #[automatically_derived]
+                #[allow(unused_qualifications, clippy::all)]
+                impl ::std::error::Error for E {}
+
+                #[cfg(feature = "parse")]
+                #[doc = " Converts a `std::num::ParseIntError` into a `E`, which allows using the ?-operator like"]
+                #[doc = ""]
+                #[doc = " ```ignore"]
+                #[doc = " fn example(result: Result<(), std::num::ParseIntError>) -> Result<(), E> {"]
+                #[doc = "     result?;"]
+                #[doc = "     Ok(())"]
+                #[doc = " }"]
+                #[doc = " ```"]
+                #[automatically_derived]
+                #[allow(unused_qualifications, clippy::all)]
+                impl ::std::convert::From<std::num::ParseIntError> for E {
+                    #[track_caller]
+                    fn from(val: std::num::ParseIntError) -> Self {
+                        E::Parse(val)
+                    }
+                }
+
+                impl E {
+                    #[doc = "The types this error can be created from with std::convert::From (and therefore the ?-operator)."]
+                    pub const FROM_TYPES: &'static [&'static str] = &[#[cfg(feature = "parse")] "std::num::ParseIntError"];
+                }
+
+                #[automatically_derived]
+                #[allow(unused_qualifications, clippy::all)]
+                impl ::std::fmt::Display for E {
+                    fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+                        match self {
+                            #[cfg(feature = "parse")]
+                            E::Parse(_0,) => ::std::write!(f, "parsing failed"),
+                            E::Unit => ::std::write!(f, "unit"),
+                        }
+                    }
+                }
+
+                impl E {
+                    #[cfg(feature = "parse")]
+                    #[doc = " Create the variant 'E::Parse'."]
+                    pub fn parse(_0: std::num::ParseIntError) -> Self {
+                        E::Parse(_0)
+                    }
+
+                    #[cfg(feature = "parse")]
+                    #[doc = " Create the variant 'E::Parse' lazily. The fields are only computed when the returned closure is called."]
+                    pub fn parse_with(_0: impl FnOnce() -> std::num::ParseIntError) -> impl FnOnce() -> Self {
+                        move || { E::Parse(_0()) }
+                    }
+
+                    #[doc = " Create the variant 'E::Unit'."]
+                    pub fn unit() -> Self {
+                        E::Unit
+                    }
+                }
+
+                impl E {
+                    #[doc = " Return the name of the variant of this error."]
+                    pub fn name(&self) -> &'static str {
+                        match self {
+                            #[cfg(feature = "parse")]
+                            E::Parse { .. } => "Parse",
+                            E::Unit { .. } => "Unit",
+                        }
+                    }
+                }
+            }
+        )
+    }
+
+    #[test]
+    fn getters() {
+        assert_enum_implementation_as_expected!(
+            item: {
+                #[error(getters)]
+                enum E {
+                    ParsingFailed {line: usize, file: String},
+                    Missing {file: String},
+                    Unit
+                }
+            }
+
+            expected: {
+                #[derive(Debug)]
+                enum E {
+                    ParsingFailed {line: usize, file: String},
+                    Missing {file: String},
+                    Unit
+                }
+
+                #[automatically_derived]
+                #[allow(unused_qualifications, clippy::all)]
+                impl ::std::error::Error for E {}
+
+                impl E {
+                    #[doc = " Return the field 'line' if the variant of this error has it."]
+                    pub fn line(&self) -> ::std::option::Option<&usize> {
+                        match self {
+                            E::ParsingFailed { line, .. } => ::std::option::Option::Some(line),
+                            _ => ::std::option::Option::None
+                        }
+                    }
+
+                    #[doc = " Return the field 'file' if the variant of this error has it."]
+                    pub fn file(&self) -> ::std::option::Option<&String> {
+                        match self {
+                            E::ParsingFailed { file, .. } => ::std::option::Option::Some(file),
+                            E::Missing { file, .. } => ::std::option::Option::Some(file),
+                            _ => ::std::option::Option::None
+                        }
+                    }
+                }
+            }
+        )
+    }
+
+    #[test]
+    #[should_panic(expected = "The parameter 'getters' requires all fields named 'line' to have the same type, but found 'usize' and 'u32'. This includes variants under mutually exclusive '#[cfg]' attributes, so rename one of the fields.")]
+    fn getters_conflicting_types_should_panic() {
+        assert_enum_implementation_as_expected!(
+            item: {
+                #[error(getters)]
+                enum E {
+                    First {line: usize},
+                    Second {line: u32}
+                }
+            }
+
+            expected: {
+                should panic
+            }
+        )
+    }
+
+    #[test]
+    #[should_panic(expected = "The parameter 'getters' creates the method 'path' for the field 'path', but the method is already created by the parameter 'constructors'. Rename the field or remove one of the parameters.")]
+    fn getters_conflicting_with_constructors_should_panic() {
+        assert_enum_implementation_as_expected!(
+            item: {
+                #[error(message = "bad", constructors, getters)]
+                enum E {
+                    Path {path: String},
+                    Other
+                }
+            }
+
+            expected: {
+                should panic
+            }
+        )
+    }
+
+    #[test]
+    #[should_panic(expected = "The parameter 'getters' creates the method 'name' for the field 'name', but the method is already created by the parameter 'variant_name'. Rename the field or remove one of the parameters.")]
+    fn getters_conflicting_with_variant_name_should_panic() {
+        assert_enum_implementation_as_expected!(
+            item: {
+                #[error(variant_name, getters)]
+                enum E {
+                    Unknown {name: String}
+                }
+            }
+
+            expected: {
+                should panic
+            }
+        )
+    }
+
+    #[test]
+    fn into_inner() {
+        assert_enum_implementation_as_expected!(
+            item: {
+                #[error(into_inner)]
+                enum E {
+                    Fmt(std::fmt::Error),
+                    ParsingFailed {line: usize},
+                    Missing {file: String, #[error(skip)] attempts: usize},
+                    Unit
+                }
+            }
+
+            expected: {
+                #[derive(Debug)]
+                enum E {
+                    Fmt(std::fmt::Error),
+                    ParsingFailed {line: usize},
+                    Missing {file: String, attempts: usize},
+                    Unit
+                }
+
+                #[automatically_derived]
+                #[allow(unused_qualifications, clippy::all)]
+                impl ::std::error::Error for E {}
+
+                impl E {
+                    #[doc = " Return the wrapped value if this error is 'E::Fmt', otherwise the error itself."]
+                    pub fn into_fmt(self) -> Result<std::fmt::Error, Self> {
+                        match self {
+                            E::Fmt { 0: inner, .. } => Ok(inner),
+                            _ => Err(self)
+                        }
+                    }
+
+                    #[doc = " Return the wrapped value if this error is 'E::ParsingFailed', otherwise the error itself."]
+                    pub fn into_parsing_failed(self) -> Result<usize, Self> {
+                        match self {
+                            E::ParsingFailed { line: inner, .. } => Ok(inner),
+                            _ => Err(self)
+                        }
+                    }
+
+                    #[doc = " Return the wrapped value if this error is 'E::Missing', otherwise the error itself."]
+                    pub fn into_missing(self) -> Result<String, Self> {
+                        match self {
+                            E::Missing { file: inner, .. } => Ok(inner),
+                            _ => Err(self)
+                        }
+                    }
+                }
+            }
+        )
+    }
+
+    #[test]
+    fn kind() {
+        assert_enum_implementation_as_expected!(
+            item: {
+                #[error(message = "Something went wrong", kind)]
+                pub enum E {
+                    #[error(message = "The foo value: {foo}")]
+                    Named {foo: usize},
+                    Unnamed(f32),
+                    Unit
+                }
+            }
+
+            expected: {
+                #[derive(Debug)]
+                pub enum E {
+                    Named {foo: usize},
+                    Unnamed(f32),
+                    Unit
+                }
+
+                #[automatically_derived]
+                #[allow(unused_qualifications, clippy::all)]
+                impl ::std::error::Error for E {}
+
+                #[automatically_derived]
+                #[allow(unused_qualifications, clippy::all)]
+                impl ::std::fmt::Display for E {
+                    fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+                        match self {
+                            E::Named {foo,} => ::std::write!(f, "The foo value: {}", foo),
+                            _ => ::std::write!(f, "Something went wrong")
+                        }
+                    }
+                }
+
+                #[doc = " The variants of 'E' without their fields, see 'E::kind'."]
+                #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+                pub enum EKind {
+                    Named,
+                    Unnamed,
+                    Unit
+                }
+
+                impl EKind {
+                    #[doc = " Return the message of this kind with the placeholders of the fields unresolved, if it has one."]
+                    pub fn message_template(&self) -> Option<&'static str> {
+                        match self {
+                            EKind::Named => Some("The foo value: {foo}"),
+                            EKind::Unnamed => Some("Something went wrong"),
+                            EKind::Unit => Some("Something went wrong"),
+                        }
+                    }
+                }
+
+                impl E {
+                    #[doc = " Return the kind of this error."]
+                    pub fn kind(&self) -> EKind {
+                        match self {
+                            E::Named { .. } => EKind::Named,
+                            E::Unnamed { .. } => EKind::Unnamed,
+                            E::Unit { .. } => EKind::Unit,
+                        }
+                    }
+                }
+
+                #[automatically_derived]
+                #[allow(unused_qualifications, clippy::all)]
+                impl ::std::convert::From<&E> for EKind {
+                    fn from(e: &E) -> Self {
+                        e.kind()
+                    }
+                }
+            }
+        )
+    }
+
+    #[test]
+    fn deprecated_from() {
+        assert_enum_implementation_as_expected!(
+            item: {
+                #[error(no_from_docs)]
+                enum E {
+                    #[error(impl_from, deprecated_from = "use ParseFailed instead")]
+                    Legacy(std::num::ParseIntError),
+                    ParseFailed { line: usize }
+                }
+            }
+
+            expected: {
+                #[derive(Debug)]
+                enum E {
+                    Legacy(std::num::ParseIntError),
+                    ParseFailed { line: usize }
+                }
+
+                #[automatically_derived]
+                #[allow(unused_qualifications, clippy::all)]
+                impl ::std::error::Error for E {}
+
+                #[doc = ""]
+                #[doc = " # Deprecated"]
+                #[doc = ""]
+                #[doc = " use ParseFailed instead"]
+                #[automatically_derived]
+                #[allow(unused_qualifications, clippy::all)]
+                impl ::std::convert::From<std::num::ParseIntError> for E {
+                    #[track_caller]
+                    fn from(val: std::num::ParseIntError) -> Self {
+                        E::Legacy(val)
+                    }
+                }
+
+                impl E {
+                    #[doc = "The types this error can be created from with std::convert::From (and therefore the ?-operator)."]
+                    pub const FROM_TYPES: &'static [&'static str] = &["std::num::ParseIntError"];
+                }
+            }
+        )
+    }
+
+    #[test]
+    #[should_panic(expected = "The parameter 'deprecated_from' was set on variant 'Legacy', but it has no From implementation which could be deprecated.")]
+    fn deprecated_from_without_from_should_panic() {
+        assert_enum_implementation_as_expected!(
+            item: {
+                #[error]
+                enum E {
+                    #[error(deprecated_from = "use ParseFailed instead")]
+                    Legacy(std::num::ParseIntError)
+                }
+            }
+
+            expected: {
+                should panic
+            }
+        )
+    }
+
+    #[test]
+    fn retry_after() {
+        assert_enum_implementation_as_expected!(
+            item: {
+                #[error(retry_after = 30)]
+                enum E {
+                    #[error(retry_after = 5)]
+                    Busy,
+                    Unavailable
+                }
+            }
+
+            expected: {
+                #[derive(Debug)]
+                enum E {
+                    Busy,
+                    Unavailable
+                }
+
+                #[automatically_derived]
+                #[allow(unused_qualifications, clippy::all)]
+                impl ::std::error::Error for E {}
+
+                impl E {
+                    #[doc = " Return the time after which the failed operation should be retried, if it should be retried at all."]
+                    pub fn retry_after(&self) -> Option<::std::time::Duration> {
+                        match self {
+                            E::Busy { .. } => Some(::std::time::Duration::from_secs(5u64)),
+                            E::Unavailable { .. } => Some(::std::time::Duration::from_secs(30u64)),
+                        }
+                    }
+                }
+            }
+        )
+    }
+
+    #[test]
+    fn retryable() {
+        assert_enum_implementation_as_expected!(
+            item: {
+                #[error]
+                enum E {
+                    #[error(retryable)]
+                    Timeout,
+                    #[error(retry_after = 5)]
+                    Busy,
+                    #[error(retry_after = 5, retryable = false)]
+                    Quota,
+                    InvalidRequest
+                }
+            }
+
+            expected: {
+                #[derive(Debug)]
+                enum E {
+                    Timeout,
+                    Busy,
+                    Quota,
+                    InvalidRequest
+                }
+
+                #[automatically_derived]
+                #[allow(unused_qualifications, clippy::all)]
+                impl ::std::error::Error for E {}
+
+                impl E {
+                    #[doc = " Return the time after which the failed operation should be retried, if it should be retried at all."]
+                    pub fn retry_after(&self) -> Option<::std::time::Duration> {
+                        match self {
+                            E::Timeout { .. } => None,
+                            E::Busy { .. } => Some(::std::time::Duration::from_secs(5u64)),
+                            E::Quota { .. } => Some(::std::time::Duration::from_secs(5u64)),
+                            E::InvalidRequest { .. } => None,
+                        }
+                    }
+                }
+
+                impl E {
+                    #[doc = " Return true if the failed operation can be retried."]
+                    pub fn is_retryable(&self) -> bool {
+                        match self {
+                            E::Timeout { .. } => true,
+                            E::Busy { .. } => true,
+                            E::Quota { .. } => false,
+                            E::InvalidRequest { .. } => false,
+                        }
+                    }
+                }
+            }
+        )
+    }
+
+    #[test]
+    fn catalog() {
+        assert_enum_implementation_as_expected!(
+            item: {
+                #[error(catalog, exit_code = 1)]
+                pub enum E {
+                    #[error(message = "invalid usage of {_0}", exit_code = 2, errno = 22)]
+                    Usage(String),
+                    #[error(message = "failed", errno = 1)]
+                    Other
+                }
+            }
+
+            expected: {
+                #[derive(Debug)]
+                pub enum E {
+                    Usage(String),
+                    Other
+                }
+
+                #[automatically_derived]
+                #[allow(unused_qualifications, clippy::all)]
+                impl ::std::error::Error for E {}
+
+                #[automatically_derived]
+                #[allow(unused_qualifications, clippy::all)]
+                impl ::std::fmt::Display for E {
+                    fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+                        match self {
+                            E::Usage (_0,) => ::std::write!(f, "invalid usage of {}", _0),
+                            E::Other => ::std::write!(f, "failed"),
+                        }
+                    }
+                }
+
+                #[doc = " Describes a variant of 'E', see 'E::CATALOG'."]
+                #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+                pub struct EInfo {
+                    #[doc = " The name of the variant."]
+                    pub name: &'static str,
+                    #[doc = " The message of the variant with the placeholders of the fields unresolved, if it has one."]
+                    pub message: Option<&'static str>,
+                    #[doc = " The HTTP status of the variant, if it has one."]
+                    pub status: Option<u16>,
+                    #[doc = " The exit code of the variant, if it has one."]
+                    pub exit_code: Option<u8>,
+                    #[doc = " The errno value of the variant, if it has one."]
+                    pub errno: Option<i32>,
+                    #[doc = " The severity of the variant ('warning', 'error' or 'fatal'), if it has one."]
+                    pub severity: Option<&'static str>,
+                    #[doc = " True if the failed operation of the variant can be retried."]
+                    pub retryable: bool,
+                    #[doc = " The time after which the failed operation of the variant should be retried, if it has one."]
+                    pub retry_after: Option<::std::time::Duration>,
+                }
+
+                impl E {
+                    #[doc = " The names, messages and behavior of all variants."]
+                    pub const CATALOG: &'static [EInfo] = &[
+                        EInfo { name: "Usage", message: Some("invalid usage of {_0}"), status: None, exit_code: Some(2u8), errno: Some(22i32), severity: None, retryable: false, retry_after: None, },
+                        EInfo { name: "Other", message: Some("failed"), status: None, exit_code: Some(1u8), errno: Some(1i32), severity: None, retryable: false, retry_after: None, }
+                    ];
+                }
+
+                impl E {
+                    #[doc = " Return the exit code a binary should exit with because of this error."]
+                    pub fn exit_code(&self) -> ::std::process::ExitCode {
+                        match self {
+                            E::Usage { .. } => ::std::process::ExitCode::from(2u8),
+                            E::Other { .. } => ::std::process::ExitCode::from(1u8),
+                        }
+                    }
+                }
+
+                impl E {
+                    #[doc = " Return the errno value which describes this error."]
+                    pub fn to_errno(&self) -> i32 {
+                        match self {
+                            E::Usage { .. } => 22i32,
+                            E::Other { .. } => 1i32,
+                        }
+                    }
+
+                    #[doc = " Return the errno value which describes this error, the same as 'to_errno'."]
+                    pub fn errno(&self) -> i32 {
+                        self.to_errno()
+                    }
+                }
+
+                #[automatically_derived]
+                #[allow(unused_qualifications, clippy::all)]
+                impl ::std::convert::From<E> for i32 {
+                    fn from(e: E) -> i32 {
+                        e.to_errno()
+                    }
+                }
+
+                #[automatically_derived]
+                #[allow(unused_qualifications, clippy::all)]
+                impl ::std::convert::From<E> for ::std::io::Error {
+                    fn from(e: E) -> ::std::io::Error {
+                        ::std::io::Error::from_raw_os_error(e.to_errno())
+                    }
+                }
+            }
+        )
+    }
+
+    #[test]
+    fn generate_from_tests() {
+        assert_enum_implementation_as_expected!(
+            item: {
+                #[error(no_from_docs, generate_from_tests)]
+                enum MyError {
+                    #[error(impl_from)]
+                    Text(String)
+                }
+            }
+
+            expected: {
+                #[derive(Debug)]
+                enum MyError {
+                    Text(String)
+                }
+
+                #[automatically_derived]
+                #[allow(unused_qualifications, clippy::all)]
+                impl ::std::error::Error for MyError {}
+
+                #[automatically_derived]
+                #[allow(unused_qualifications, clippy::all)]
+                impl ::std::convert::From<String> for MyError {
+                    #[track_caller]
+                    fn from(val: String) -> Self {
+                        MyError::Text(val)
+                    }
+                }
+
+                impl MyError {
+                    #[doc = "The types this error can be created from with std::convert::From (and therefore the ?-operator)."]
+                    pub const FROM_TYPES: &'static [&'static str] = &["String"];
+                }
+
+                #[cfg(test)]
+                mod __my_error_from_tests {
+                    use super::*;
+
+                    struct Probe<T>(::std::marker::PhantomData<T>);
+
+                    trait WithDefault<T> {
+                        fn source(&self) -> Option<T>;
+                    }
+
+                    impl<T: Default> WithDefault<T> for Probe<T> {
+                        fn source(&self) -> Option<T> {
+                            Some(T::default())
+                        }
+                    }
+
+                    trait WithoutDefault<T> {
+                        fn source(&self) -> Option<T>;
+                    }
+
+                    impl<T> WithoutDefault<T> for &Probe<T> {
+                        fn source(&self) -> Option<T> {
+                            None
+                        }
+                    }
+
+                    #[test]
+                    fn from_text() {
+                        if let Some(source) = (&Probe::<String>(::std::marker::PhantomData)).source() {
+                            assert!(matches!(MyError::from(source), MyError::Text { .. }), "converting String should create MyError::Text");
+                        }
+                    }
+                }
+            }
+        )
+    }
+
+    #[test]
+    #[should_panic(expected = "The parameter 'generate_from_tests' cannot be used on the generic enum 'E'.")]
+    fn generate_from_tests_generic_should_panic() {
+        assert_enum_implementation_as_expected!(
+            item: {
+                #[error(impl_from, generate_from_tests)]
+                enum E<T> {
+                    Value(T)
+                }
+            }
+
+            expected: {
+                should panic
+            }
+        )
+    }
+
+    #[test]
+    fn impl_from_with_source_type() {
+        assert_enum_implementation_as_expected!(
+            item: {
+                #[error(no_from_docs)]
+                enum E {
+                    #[error(impl_from)]
+                    Io {
+                        source: std::io::Error,
+                        #[error(source_type)]
+                        converted_from: &'static str
+                    }
+                }
+            }
+
+            expected: {
+                #[derive(Debug)]
+                enum E {
+                    Io {
+                        source: std::io::Error,
+                        converted_from: &'static str
+                    }
+                }
+
+                #[automatically_derived]
+                #[allow(unused_qualifications, clippy::all)]
+                impl ::std::error::Error for E {}
+
+                #[automatically_derived]
+                #[allow(unused_qualifications, clippy::all)]
+                impl ::std::convert::From<std::io::Error> for E {
+                    #[track_caller]
+                    fn from(val: std::io::Error) -> Self {
+                        E::Io { source: val, converted_from: "std::io::Error" }
+                    }
+                }
+
+                impl E {
+                    #[doc = "The types this error can be created from with std::convert::From (and therefore the ?-operator)."]
+                    pub const FROM_TYPES: &'static [&'static str] = &["std::io::Error"];
+                }
+
+                impl E {
+                    #[doc = " Return the kind of the wrapped std::io::Error, if this error wraps one."]
+                    pub fn io_kind(&self) -> ::std::option::Option<::std::io::ErrorKind> {
+                        match self {
+                            E::Io { source: e, .. } => ::std::option::Option::Some(e.kind()),
+                        }
+                    }
+                }
+            }
+        )
+    }
+
+    #[test]
+    fn wrap_map() {
+        assert_enum_implementation_as_expected!(
+            item: {
+                #[error(wrap_map = "std::num::ParseIntError => Parse, std::fmt::Error => Fmt")]
+                enum E {
+                    Parse(std::num::ParseIntError),
+                    Fmt { source: std::fmt::Error },
+                    #[error(message = "Something else went wrong")]
+                    Other
+                }
+            }
+
+            expected: {
+                #[derive(Debug)]
+                enum E {
+                    Parse(std::num::ParseIntError),
+                    Fmt { source: std::fmt::Error },
+                    Other
+                }
+
+                #[automatically_derived]
+                #[allow(unused_qualifications, clippy::all)]
+                impl ::std::error::Error for E {
+                    fn source(&self) -> ::std::option::Option<&(dyn ::std::error::Error + 'static)> {
+                        match self {
+                            E::Parse { 0: e, .. } => ::std::error::Error::source(e),
+                            E::Fmt { source: e, .. } => ::std::error::Error::source(e),
+                            _ => ::std::option::Option::None
+                        }
+                    }
+                }
+
+                #[doc = " Converts a `std::num::ParseIntError` into a `E`, which allows using the ?-operator like"]
+                #[doc = ""]
+                #[doc = " ```ignore"]
+                #[doc = " fn example(result: Result<(), std::num::ParseIntError>) -> Result<(), E> {"]
+                #[doc = "     result?;"]
+                #[doc = "     Ok(())"]
+                #[doc = " }"]
+                #[doc = " ```"]
+                #[automatically_derived]
+                #[allow(unused_qualifications, clippy::all)]
+                impl ::std::convert::From<std::num::ParseIntError> for E {
+                    #[track_caller]
+                    fn from(val: std::num::ParseIntError) -> Self {
+                        E::Parse(val)
+                    }
+                }
+
+                #[doc = " Converts a `std::fmt::Error` into a `E`, which allows using the ?-operator like"]
+                #[doc = ""]
+                #[doc = " ```ignore"]
+                #[doc = " fn example(result: Result<(), std::fmt::Error>) -> Result<(), E> {"]
+                #[doc = "     result?;"]
+                #[doc = "     Ok(())"]
+                #[doc = " }"]
+                #[doc = " ```"]
+                #[automatically_derived]
+                #[allow(unused_qualifications, clippy::all)]
+                impl ::std::convert::From<std::fmt::Error> for E {
+                    #[track_caller]
+                    fn from(val: std::fmt::Error) -> Self {
+                        E::Fmt {source: val}
+                    }
+                }
+
+                impl E {
+                    #[doc = "The types this error can be created from with std::convert::From (and therefore the ?-operator)."]
+                    pub const FROM_TYPES: &'static [&'static str] = &["std::num::ParseIntError", "std::fmt::Error"];
+                }
+
+                #[automatically_derived]
+                #[allow(unused_qualifications, clippy::all)]
+                impl ::std::fmt::Display for E {
+                    fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+                        {
+                            (match self {
+                                E::Other => ::std::write!(f, "Something else went wrong"),
+                                E::Parse (_0,) => ::std::write!(f, "{}", _0),
+                                E::Fmt {source,} => ::std::write!(f, "{}", source),
+                            })?;
+
+                            if f.alternate() {
+                                let mut source = ::std::error::Error::source(self);
+                                while let ::std::option::Option::Some(error) = source {
+                                    ::std::write!(f, ": caused by: {}", error)?;
+                                    source = ::std::error::Error::source(error);
+                                }
+                            }
+
+                            ::std::result::Result::Ok(())
+                        }
+                    }
+                }
+            }
+        )
+    }
+
+    #[test]
+    #[should_panic(expected = "The enum 'E' has no variant 'Missing' listed in 'wrap_map'.")]
+    fn wrap_map_unknown_variant_should_panic() {
+        assert_enum_implementation_as_expected!(
+            item: {
+                #[error(wrap_map = "std::fmt::Error => Missing")]
+                enum E {
+                    Fmt(std::fmt::Error)
+                }
+            }
+
+            expected: {
+                should panic
+            }
+        )
+    }
+
+    #[test]
+    #[should_panic(expected = "The type 'std::io::Error' listed in 'wrap_map' does not match the type 'std::fmt::Error' of the field of variant 'Fmt'.")]
+    fn wrap_map_type_mismatch_should_panic() {
+        assert_enum_implementation_as_expected!(
+            item: {
+                #[error(wrap_map = "std::io::Error => Fmt")]
+                enum E {
+                    Fmt(std::fmt::Error)
+                }
+            }
+
+            expected: {
+                should panic
+            }
+        )
+    }
+
+    #[test]
+    fn includes() {
+        assert_enum_implementation_as_expected!(
+            item: {
+                #[error(includes = "std::fmt::Error")]
+                enum E {
+                    #[error(message = "Something else went wrong")]
+                    Other
                 }
             }
 
             expected: {
                 #[derive(Debug)]
                 enum E {
-                    Named {foo: usize},
-                    Unnamed(f32),
-                    Unit
+                    Other,
+                    Error(std::fmt::Error)
                 }
 
-                impl std::error::Error for E {}
+                #[automatically_derived]
+                #[allow(unused_qualifications, clippy::all)]
+                impl ::std::error::Error for E {
+                    fn source(&self) -> ::std::option::Option<&(dyn ::std::error::Error + 'static)> {
+                        match self {
+                            E::Error { 0: e, .. } => ::std::error::Error::source(e),
+                            _ => ::std::option::Option::None
+                        }
+                    }
+                }
 
-                impl std::convert::From<usize> for E {
-                    fn from(val: usize) -> Self {
-                        E::Named {foo: val}
+                #[doc = " Converts a `std::fmt::Error` into a `E`, which allows using the ?-operator like"]
+                #[doc = ""]
+                #[doc = " ```ignore"]
+                #[doc = " fn example(result: Result<(), std::fmt::Error>) -> Result<(), E> {"]
+                #[doc = "     result?;"]
+                #[doc = "     Ok(())"]
+                #[doc = " }"]
+                #[doc = " ```"]
+                #[automatically_derived]
+                #[allow(unused_qualifications, clippy::all)]
+                impl ::std::convert::From<std::fmt::Error> for E {
+                    #[track_caller]
+                    fn from(val: std::fmt::Error) -> Self {
+                        E::Error(val)
                     }
                 }
 
-                impl std::convert::From<f32> for E {
-                    fn from(val: f32) -> Self {
-                        E::Unnamed(val)
+                impl E {
+                    #[doc = "The types this error can be created from with std::convert::From (and therefore the ?-operator)."]
+                    pub const FROM_TYPES: &'static [&'static str] = &["std::fmt::Error"];
+                }
+
+                #[automatically_derived]
+                #[allow(unused_qualifications, clippy::all)]
+                impl ::std::fmt::Display for E {
+                    fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+                        {
+                            (match self {
+                                E::Other => ::std::write!(f, "Something else went wrong"),
+                                E::Error (_0,) => ::std::write!(f, "{}", _0),
+                            })?;
+
+                            if f.alternate() {
+                                let mut source = ::std::error::Error::source(self);
+                                while let ::std::option::Option::Some(error) = source {
+                                    ::std::write!(f, ": caused by: {}", error)?;
+                                    source = ::std::error::Error::source(error);
+                                }
+                            }
+
+                            ::std::result::Result::Ok(())
+                        }
+                    }
+                }
+            }
+        )
+    }
+
+    #[test]
+    #[should_panic(expected = "The enum 'E' already has a variant 'Error', so the error 'std::fmt::Error' cannot be included.")]
+    fn includes_existing_variant_should_panic() {
+        assert_enum_implementation_as_expected!(
+            item: {
+                #[error(includes = "std::fmt::Error")]
+                enum E {
+                    #[error(message = "error")]
+                    Error
+                }
+            }
+
+            expected: {
+                should panic
+            }
+        )
+    }
+
+    #[test]
+    fn forward() {
+        assert_enum_implementation_as_expected!(
+            item: {
+                #[error]
+                enum E {
+                    #[error(forward)]
+                    Inner(std::fmt::Error),
+                    #[error(message = "Something else went wrong")]
+                    Other
+                }
+            }
+
+            expected: {
+                #[derive(Debug)]
+                enum E {
+                    Inner(std::fmt::Error),
+                    Other
+                }
+
+                #[automatically_derived]
+                #[allow(unused_qualifications, clippy::all)]
+                impl ::std::error::Error for E {
+                    fn source(&self) -> ::std::option::Option<&(dyn ::std::error::Error + 'static)> {
+                        match self {
+                            E::Inner { 0: e, .. } => ::std::error::Error::source(e),
+                            _ => ::std::option::Option::None
+                        }
+                    }
+                }
+
+                #[automatically_derived]
+                #[allow(unused_qualifications, clippy::all)]
+                impl ::std::fmt::Display for E {
+                    fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+                        {
+                            (match self {
+                                E::Other => ::std::write!(f, "Something else went wrong"),
+                                E::Inner (_0,) => ::std::write!(f, "{}", _0),
+                            })?;
+
+                            if f.alternate() {
+                                let mut source = ::std::error::Error::source(self);
+                                while let ::std::option::Option::Some(error) = source {
+                                    ::std::write!(f, ": caused by: {}", error)?;
+                                    source = ::std::error::Error::source(error);
+                                }
+                            }
+
+                            ::std::result::Result::Ok(())
+                        }
+                    }
+                }
+            }
+        )
+    }
+
+    #[test]
+    #[should_panic(expected = "The variant 'Inner' is marked with 'forward', so it displays the wrapped value and cannot have a message.")]
+    fn forward_with_message_should_panic() {
+        assert_enum_implementation_as_expected!(
+            item: {
+                #[error]
+                enum E {
+                    #[error(forward, message = "inner failed")]
+                    Inner(std::fmt::Error)
+                }
+            }
+
+            expected: {
+                should panic
+            }
+        )
+    }
+
+    #[test]
+    #[should_panic(expected = "The variant 'Inner' is marked with 'forward', but does not have exactly one field which is not skipped.")]
+    fn forward_two_fields_should_panic() {
+        assert_enum_implementation_as_expected!(
+            item: {
+                #[error]
+                enum E {
+                    #[error(forward)]
+                    Inner(std::fmt::Error, usize)
+                }
+            }
+
+            expected: {
+                should panic
+            }
+        )
+    }
+
+    #[test]
+    fn from_io() {
+        assert_enum_implementation_as_expected!(
+            item: {
+                #[error(message = "An io error occurred")]
+                enum E {
+                    #[error(from_io = "NotFound, PermissionDenied")]
+                    Missing(std::io::Error),
+                    #[error(from_io = "_")]
+                    Other { source: std::io::Error }
+                }
+            }
+
+            expected: {
+                #[derive(Debug)]
+                enum E {
+                    Missing(std::io::Error),
+                    Other { source: std::io::Error }
+                }
+
+                #[automatically_derived]
+                #[allow(unused_qualifications, clippy::all)]
+                impl ::std::error::Error for E {}
+
+                #[doc = " Converts a `std::io::Error` into a `E`, which allows using the ?-operator like"]
+                #[doc = ""]
+                #[doc = " ```ignore"]
+                #[doc = " fn example(result: Result<(), std::io::Error>) -> Result<(), E> {"]
+                #[doc = "     result?;"]
+                #[doc = "     Ok(())"]
+                #[doc = " }"]
+                #[doc = " ```"]
+                #[automatically_derived]
+                #[allow(unused_qualifications, clippy::all)]
+                impl ::std::convert::From<::std::io::Error> for E {
+                    #[track_caller]
+                    fn from(val: ::std::io::Error) -> Self {
+                        match val.kind() {
+                            ::std::io::ErrorKind::NotFound | ::std::io::ErrorKind::PermissionDenied => { E::Missing(val) },
+                            _ => { E::Other {source: val} }
+                        }
+                    }
+                }
+
+                impl E {
+                    #[doc = "The types this error can be created from with std::convert::From (and therefore the ?-operator)."]
+                    pub const FROM_TYPES: &'static [&'static str] = &["std::io::Error"];
+                }
+
+                #[automatically_derived]
+                #[allow(unused_qualifications, clippy::all)]
+                impl ::std::fmt::Display for E {
+                    fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+                        match self {
+                            _ => ::std::write!(f, "An io error occurred")
+                        }
+                    }
+                }
+
+                impl E {
+                    #[doc = " Return the kind of the wrapped std::io::Error, if this error wraps one."]
+                    pub fn io_kind(&self) -> ::std::option::Option<::std::io::ErrorKind> {
+                        match self {
+                            E::Missing { 0: e, .. } => ::std::option::Option::Some(e.kind()),
+                            E::Other { source: e, .. } => ::std::option::Option::Some(e.kind()),
+                        }
+                    }
+                }
+            }
+        )
+    }
+
+    #[test]
+    #[should_panic(expected = "The enum 'E' uses 'from_io', but no variant handles the remaining kinds. Mark one with 'from_io = \"_\"'.")]
+    fn from_io_without_fallback_should_panic() {
+        assert_enum_implementation_as_expected!(
+            item: {
+                #[error(message = "An io error occurred")]
+                enum E {
+                    #[error(from_io = "NotFound")]
+                    Missing(std::io::Error)
+                }
+            }
+
+            expected: {
+                should panic
+            }
+        )
+    }
+
+    #[test]
+    #[should_panic(expected = "The kind 'NotFound' is listed more than once in 'from_io'.")]
+    fn from_io_duplicate_kind_should_panic() {
+        assert_enum_implementation_as_expected!(
+            item: {
+                #[error(message = "An io error occurred")]
+                enum E {
+                    #[error(from_io = "NotFound")]
+                    Missing(std::io::Error),
+                    #[error(from_io = "NotFound, _")]
+                    Other(std::io::Error)
+                }
+            }
+
+            expected: {
+                should panic
+            }
+        )
+    }
+
+    #[test]
+    fn other() {
+        assert_enum_implementation_as_expected!(
+            item: {
+                #[error]
+                enum E {
+                    #[error(message = "The value is invalid")]
+                    Invalid,
+                    #[error(other)]
+                    Other(Box<dyn std::error::Error + Send + Sync>)
+                }
+            }
+
+            expected: {
+                #[derive(Debug)]
+                enum E {
+                    Invalid,
+                    Other(Box<dyn std::error::Error + Send + Sync>)
+                }
+
+                #[automatically_derived]
+                #[allow(unused_qualifications, clippy::all)]
+                impl ::std::error::Error for E {
+                    fn source(&self) -> ::std::option::Option<&(dyn ::std::error::Error + 'static)> {
+                        match self {
+                            E::Other { 0: e, .. } => ::std::error::Error::source(&**e),
+                            _ => ::std::option::Option::None
+                        }
+                    }
+                }
+
+                #[doc = " Converts a `Box<dyn std::error::Error + Send + Sync>` into a `E`, which allows using the ?-operator like"]
+                #[doc = ""]
+                #[doc = " ```ignore"]
+                #[doc = " fn example(result: Result<(), Box<dyn std::error::Error + Send + Sync>>) -> Result<(), E> {"]
+                #[doc = "     result?;"]
+                #[doc = "     Ok(())"]
+                #[doc = " }"]
+                #[doc = " ```"]
+                #[automatically_derived]
+                #[allow(unused_qualifications, clippy::all)]
+                impl ::std::convert::From<Box<dyn std::error::Error + Send + Sync> > for E {
+                    #[track_caller]
+                    fn from(val: Box<dyn std::error::Error + Send + Sync>) -> Self {
+                        E::Other(val)
+                    }
+                }
+
+                impl E {
+                    #[doc = "The types this error can be created from with std::convert::From (and therefore the ?-operator)."]
+                    pub const FROM_TYPES: &'static [&'static str] = &["Box<dyn std::error::Error + Send + Sync>"];
+                }
+
+                impl E {
+                    #[doc = " Wrap an unexpected error as 'E::Other', like 'result.map_err(E::wrap)?'."]
+                    pub fn wrap<__T: ::std::convert::Into<Box<dyn std::error::Error + Send + Sync> > >(error: __T) -> Self {
+                        E::Other { 0: ::std::convert::Into::into(error) }
+                    }
+                }
+
+                #[automatically_derived]
+                #[allow(unused_qualifications, clippy::all)]
+                impl ::std::fmt::Display for E {
+                    fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+                        {
+                            (match self {
+                                E::Invalid => ::std::write!(f, "The value is invalid"),
+                                E::Other (_0,) => ::std::write!(f, "{}", _0),
+                            })?;
+
+                            if f.alternate() {
+                                let mut source = ::std::error::Error::source(self);
+                                while let ::std::option::Option::Some(error) = source {
+                                    ::std::write!(f, ": caused by: {}", error)?;
+                                    source = ::std::error::Error::source(error);
+                                }
+                            }
+
+                            ::std::result::Result::Ok(())
+                        }
                     }
                 }
+            }
+        )
+    }
+
+    #[test]
+    #[should_panic(expected = "Only one variant can be marked with 'other', but 'Second' is the second one.")]
+    fn duplicate_other_should_panic() {
+        assert_enum_implementation_as_expected!(
+            item: {
+                #[error]
+                enum E {
+                    #[error(other)]
+                    First(Box<dyn std::error::Error + Send + Sync>),
+                    #[error(other)]
+                    Second(Box<dyn std::error::Error + Send + Sync>)
+                }
+            }
+
+            expected: {
+                should panic
+            }
+        )
+    }
+
+    #[test]
+    fn variant_structs() {
+        assert_enum_implementation_as_expected!(
+            item: {
+                #[error(message = "Something went wrong", variant_structs)]
+                pub enum E {
+                    /// The key is missing
+                    Missing { key: String },
+                    Invalid(usize, #[error(skip)] bool),
+                    Other
+                }
+            }
+
+            expected: {
+                #[derive(Debug)]
+                pub enum E {
+                    /// The key is missing
+                    Missing { key: String },
+                    Invalid(usize, bool),
+                    Other
+                }
+
+                #[automatically_derived]
+                #[allow(unused_qualifications, clippy::all)]
+                impl ::std::error::Error for E {}
 
-                impl std::fmt::Display for E {
-                    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                #[automatically_derived]
+                #[allow(unused_qualifications, clippy::all)]
+                impl ::std::fmt::Display for E {
+                    fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
                         match self {
-                            E::Named {foo,} => write!(f, "The foo value: {}", foo),
-                            E::Unnamed (_0,) => write!(f, "The first value: {}", _0),
-                            E::Unit => write!(f, "Something went wrong"),
+                            _ => ::std::write!(f, "Something went wrong")
                         }
                     }
                 }
+
+                #[doc = " The fields of 'E::Missing' as a struct, which converts into the variant."]
+                /// The key is missing
+                #[derive(Debug)]
+                pub struct EMissing { pub key: String }
+
+                #[automatically_derived]
+                #[allow(unused_qualifications, clippy::all)]
+                impl ::std::convert::From<EMissing> for E {
+                    fn from(value: EMissing) -> Self {
+                        E::Missing { key: value.key }
+                    }
+                }
+
+                #[doc = " The fields of 'E::Invalid' as a struct, which converts into the variant."]
+                #[derive(Debug)]
+                pub struct EInvalid(pub usize, pub bool);
+
+                #[automatically_derived]
+                #[allow(unused_qualifications, clippy::all)]
+                impl ::std::convert::From<EInvalid> for E {
+                    fn from(value: EInvalid) -> Self {
+                        E::Invalid { 0: value.0, 1: value.1 }
+                    }
+                }
+
+                #[doc = " The fields of 'E::Other' as a struct, which converts into the variant."]
+                #[derive(Debug)]
+                pub struct EOther;
+
+                #[automatically_derived]
+                #[allow(unused_qualifications, clippy::all)]
+                impl ::std::convert::From<EOther> for E {
+                    fn from(_: EOther) -> Self {
+                        E::Other {}
+                    }
+                }
+            }
+        )
+    }
+
+    #[test]
+    #[should_panic(expected = "The parameter 'variant_structs' cannot be used on the generic enum 'E'.")]
+    fn variant_structs_with_generics_should_panic() {
+        assert_enum_implementation_as_expected!(
+            item: {
+                #[error(message = "Something went wrong", variant_structs)]
+                enum E<T> {
+                    Value(T)
+                }
+            }
+
+            expected: {
+                should panic
             }
         )
     }
@@ -544,13 +3691,15 @@ mod tests {
                 let error_attribute_index = item_enum.attrs
                     .iter()
                     .enumerate()
-                    .find(|(_, a)|crate::common::attribute_is_error(a))
+                    .find(|(_, a)|$crate::common::attribute_is_error(a))
                     .expect("One attribute should be 'error'").0;
 
-                let attribute_args = crate::test_helper::extract_attribute_args(item_enum.attrs.remove(error_attribute_index));
-                let implementation_ts = crate::enum_error::implement(attribute_args, item_enum).to_string();
+                let attribute_args = $crate::test_helper::extract_attribute_args(item_enum.attrs.remove(error_attribute_index));
+                let implementation_ts = $crate::enum_error::implement(attribute_args, item_enum)
+                    .unwrap_or_else(|e| panic!("{}", e))
+                    .to_string();
                 let expected = quote::quote!($($expected)*).to_string();
-                crate::test_helper::assert_tokens_are_equal(implementation_ts, expected)
+                $crate::test_helper::assert_tokens_are_equal(implementation_ts, expected)
             }
         };
     }
@@ -0,0 +1,216 @@
+use syn::{Attribute, Fields, FieldsNamed, FieldsUnnamed, Ident, ItemEnum, LitStr, NestedMeta, parse_quote, Token, Variant, Visibility};
+use syn::__private::TokenStream2;
+use syn::parse::{Parse, ParseStream};
+use syn::punctuated::Punctuated;
+
+use crate::enum_error;
+
+/// The keyword after a variant which implements From for it.
+const FROM_KEYWORD: &str = "from";
+
+/// The input of the macro error_enum, a compact definition of an error enum, like
+///
+/// pub MyError: "Something went wrong" {
+///     NotFound(String): "missing {0}",
+///     Io(std::io::Error) from,
+///     Unknown
+/// }
+///
+/// The enum and every variant can have attributes (like doc comments or further error attributes) and a message after a colon.
+pub struct ErrorEnumInput {
+    attrs: Vec<Attribute>,
+    vis: Visibility,
+    ident: Ident,
+    message: Option<LitStr>,
+    variants: Punctuated<ErrorEnumVariant, Token![,]>,
+}
+
+/// A variant in the input of error_enum, like 'NotFound(String): "missing {0}"' or 'Io(std::io::Error) from'.
+struct ErrorEnumVariant {
+    attrs: Vec<Attribute>,
+    ident: Ident,
+    fields: Fields,
+    message: Option<LitStr>,
+    from: bool,
+}
+
+impl Parse for ErrorEnumInput {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let attrs = input.call(Attribute::parse_outer)?;
+        let vis = input.parse()?;
+        let ident = input.parse()?;
+        let message = parse_message(input)?;
+
+        let content;
+        syn::braced!(content in input);
+        let variants = content.parse_terminated(ErrorEnumVariant::parse)?;
+
+        Ok(ErrorEnumInput { attrs, vis, ident, message, variants })
+    }
+}
+
+impl Parse for ErrorEnumVariant {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let attrs = input.call(Attribute::parse_outer)?;
+        let ident = input.parse()?;
+        let fields = match () {
+            _ if input.peek(syn::token::Paren) => Fields::Unnamed(input.parse::<FieldsUnnamed>()?),
+            _ if input.peek(syn::token::Brace) => Fields::Named(input.parse::<FieldsNamed>()?),
+            _ => Fields::Unit
+        };
+        let message = parse_message(input)?;
+
+        let from = match input.peek(Ident) {
+            true => {
+                let keyword = input.parse::<Ident>()?;
+                match keyword == FROM_KEYWORD {
+                    true => true,
+                    false => return Err(syn::Error::new(keyword.span(), format!("Expected '{}', ',' or the end of the variants.", FROM_KEYWORD)))
+                }
+            }
+            false => false
+        };
+
+        Ok(ErrorEnumVariant { attrs, ident, fields, message, from })
+    }
+}
+
+/// Parse a message after a colon, like ': "missing {0}"', if there is one.
+fn parse_message(input: ParseStream) -> syn::Result<Option<LitStr>> {
+    match input.peek(Token![:]) {
+        true => {
+            input.parse::<Token![:]>()?;
+            Ok(Some(input.parse()?))
+        }
+        false => Ok(None)
+    }
+}
+
+/// Define the enum of the given input and generate its implementations, like the error attribute on the equivalent enum would:
+/// the message of the enum becomes its parameter 'message' and every variant gets an error attribute with its message
+/// and 'impl_from' if it is marked with 'from'.
+///
+/// Fails if an implementation cannot be created.
+pub fn implement(input: ErrorEnumInput) -> syn::Result<TokenStream2> {
+    let attr_args: Vec<NestedMeta> = match &input.message {
+        Some(message) => vec![parse_quote!(message = #message)],
+        None => vec![]
+    };
+
+    let variants = input.variants
+        .into_iter()
+        .map(|v| {
+            let mut attrs = v.attrs;
+            match (v.message, v.from) {
+                (Some(message), true) => attrs.push(parse_quote!(#[error(message = #message, impl_from)])),
+                (Some(message), false) => attrs.push(parse_quote!(#[error(message = #message)])),
+                (None, true) => attrs.push(parse_quote!(#[error(impl_from)])),
+                (None, false) => {}
+            }
+
+            Variant { attrs, ident: v.ident, fields: v.fields, discriminant: None }
+        })
+        .collect::<Punctuated<Variant, Token![,]>>();
+
+    let ErrorEnumInput { attrs, vis, ident, .. } = input;
+    let item_enum: ItemEnum = parse_quote! {
+        #(#attrs)*
+        #vis enum #ident {
+            #variants
+        }
+    };
+
+    enum_error::implement(attr_args, item_enum)
+}
+
+#[cfg(test)]
+mod tests {
+    use quote::quote;
+    use syn::__private::TokenStream2;
+
+    use crate::error_enum::{ErrorEnumInput, implement};
+    use crate::test_helper::assert_tokens_are_equal;
+
+    #[test]
+    fn error_enum() {
+        let input = quote! {
+            #[derive(Clone)]
+            pub E: "Something went wrong" {
+                NotFound(String): "missing {0}",
+                #[doc = " The line could not be parsed."]
+                Named {line: usize}: "invalid line {line}",
+                Parse(std::num::ParseIntError) from,
+                Unit,
+            }
+        };
+
+        let expected = quote! {
+            #[derive(Debug)]
+            #[derive(Clone)]
+            pub enum E {
+                NotFound(String),
+                #[doc = " The line could not be parsed."]
+                Named {line: usize},
+                Parse(std::num::ParseIntError),
+                Unit
+            }
+
+            #[automatically_derived]
+            #[allow(unused_qualifications, clippy::all)]
+            impl ::std::error::Error for E {}
+
+            #[doc = " Converts a `std::num::ParseIntError` into a `E`, which allows using the ?-operator like"]
+            #[doc = ""]
+            #[doc = " ```ignore"]
+            #[doc = " fn example(result: Result<(), std::num::ParseIntError>) -> Result<(), E> {"]
+            #[doc = "     result?;"]
+            #[doc = "     Ok(())"]
+            #[doc = " }"]
+            #[doc = " ```"]
+            #[automatically_derived]
+            #[allow(unused_qualifications, clippy::all)]
+            impl ::std::convert::From<std::num::ParseIntError> for E {
+                #[track_caller]
+                fn from(val: std::num::ParseIntError) -> Self {
+                    E::Parse(val)
+                }
+            }
+
+            impl E {
+                #[doc = "The types this error can be created from with std::convert::From (and therefore the ?-operator)."]
+                pub const FROM_TYPES: &'static [&'static str] = &["std::num::ParseIntError"];
+            }
+
+            #[automatically_derived]
+            #[allow(unused_qualifications, clippy::all)]
+            impl ::std::fmt::Display for E {
+                fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+                    match self {
+                        E::NotFound(_0,) => ::std::write!(f, "missing {}", _0),
+                        E::Named {line,} => ::std::write!(f, "invalid line {}", line),
+                        _ => ::std::write!(f, "Something went wrong")
+                    }
+                }
+            }
+        };
+
+        assert_tokens_are_equal(expand(input), expected.to_string())
+    }
+
+    #[test]
+    #[should_panic(expected = "Expected 'from', ',' or the end of the variants.")]
+    fn error_enum_unknown_keyword_should_panic() {
+        expand(quote! {
+            E {
+                Io(std::io::Error) into
+            }
+        });
+    }
+
+    fn expand(input: TokenStream2) -> String {
+        syn::parse2::<ErrorEnumInput>(input)
+            .and_then(implement)
+            .unwrap_or_else(|e| panic!("{}", e))
+            .to_string()
+    }
+}
@@ -0,0 +1,162 @@
+use quote::quote;
+use syn::{AttributeArgs, Error, Item, ItemEnum, ItemMod, NestedMeta};
+use syn::__private::TokenStream2;
+
+use crate::common::{error_attribute_parameters, path_to_name};
+use crate::parameters::{MESSAGE, Parameters};
+use crate::validator::{ParameterLocation, Validator};
+use crate::{enum_error, struct_error};
+
+/// Generate the implementations for every struct and enum in the given module, like the error attribute
+/// does for a single one. The given parameters are the defaults of all items: an item gets every default which
+/// can be used on it and is not set in its own error attributes. Other items (like functions or nested modules) are kept as they are.
+///
+/// Fails if the parameters are invalid, the module has no body or an implementation cannot be created.
+pub fn implement(attr_args: AttributeArgs, mut item_mod: ItemMod) -> syn::Result<TokenStream2> {
+    let parameters = Parameters::from_attribute_args(attr_args.clone())?;
+    Validator::new(ParameterLocation::Module).validate(&parameters)?;
+
+    let items = match item_mod.content.as_mut() {
+        Some((_, items)) => items,
+        None => return Err(Error::new_spanned(&item_mod, "The error attribute requires a module with a body, like 'mod errors { ... }'."))
+    };
+
+    for item in items.iter_mut() {
+        let expansion = match item {
+            Item::Struct(item_struct) => {
+                let defaults = defaults_for(&attr_args, ParameterLocation::Struct, error_attribute_parameters(&item_struct.attrs)?)?;
+                struct_error::implement(defaults, item_struct.clone())?
+            }
+            Item::Enum(item_enum) => {
+                let mut defaults = defaults_for(&attr_args, ParameterLocation::Enum, error_attribute_parameters(&item_enum.attrs)?)?;
+                if all_variants_have_messages(item_enum)? {
+                    // the default message would never be displayed, which is an error for enums
+                    defaults.retain(|nested| !matches!(nested, NestedMeta::Meta(meta) if meta.path().is_ident(MESSAGE)));
+                }
+                enum_error::implement(defaults, item_enum.clone())?
+            }
+            _ => continue
+        };
+        *item = Item::Verbatim(expansion);
+    }
+
+    Ok(quote! {#item_mod})
+}
+
+/// Check if every variant of the given enum has a message of its own.
+fn all_variants_have_messages(item_enum: &ItemEnum) -> syn::Result<bool> {
+    for variant in &item_enum.variants {
+        match error_attribute_parameters(&variant.attrs)? {
+            Some(parameters) if parameters.has_parameter(MESSAGE) => {}
+            _ => return Ok(false)
+        }
+    }
+
+    Ok(true)
+}
+
+/// Return the defaults which can be used at the given location and are not overridden by the given parameters of the item.
+fn defaults_for(defaults: &[NestedMeta], location: ParameterLocation, item_parameters: Option<Parameters>) -> syn::Result<AttributeArgs> {
+    let mut result = vec![];
+
+    for nested in defaults {
+        let name = match nested {
+            NestedMeta::Meta(meta) => path_to_name(meta.path())?,
+            NestedMeta::Lit(_) => continue
+        };
+
+        let overridden = item_parameters.as_ref().map(|p| p.has_parameter(&name)).unwrap_or(false);
+        if location.allows(&name) && !overridden {
+            result.push(nested.clone())
+        }
+    }
+
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use quote::quote;
+    use syn::{Attribute, ItemMod, parse_quote};
+
+    use crate::error_module::implement;
+    use crate::test_helper::{assert_tokens_are_equal, extract_attribute_args};
+
+    #[test]
+    fn module_defaults() {
+        let attribute: Attribute = parse_quote!(#[error(message = "Something went wrong", no_std)]);
+        let item_mod: ItemMod = parse_quote! {
+            pub mod errors {
+                use std::fmt::Debug;
+
+                #[error(message = "The value {self.0} is invalid")]
+                pub struct InvalidValue(pub usize);
+
+                pub enum RequestError {
+                    Timeout
+                }
+            }
+        };
+
+        let expected = quote! {
+            pub mod errors {
+                use std::fmt::Debug;
+
+                #[derive(Debug)]
+                pub struct InvalidValue(pub usize);
+
+                #[automatically_derived]
+                #[allow(unused_qualifications, clippy::all)]
+                impl ::core::error::Error for InvalidValue {}
+
+                #[automatically_derived]
+                #[allow(unused_qualifications, clippy::all)]
+                impl ::core::fmt::Display for InvalidValue {
+                    fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+                        ::core::write!(f, "The value {} is invalid", self.0)
+                    }
+                }
+
+                #[derive(Debug)]
+                pub enum RequestError {
+                    Timeout
+                }
+
+                #[automatically_derived]
+                #[allow(unused_qualifications, clippy::all)]
+                impl ::core::error::Error for RequestError {}
+
+                #[automatically_derived]
+                #[allow(unused_qualifications, clippy::all)]
+                impl ::core::fmt::Display for RequestError {
+                    fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+                        match self {
+                            _ => ::core::write!(f, "Something went wrong")
+                        }
+                    }
+                }
+            }
+        };
+
+        let implementation = implement(extract_attribute_args(attribute), item_mod).unwrap_or_else(|e| panic!("{}", e));
+        assert_tokens_are_equal(implementation.to_string(), expected.to_string())
+    }
+
+    #[test]
+    fn module_without_body_fails() {
+        let attribute: Attribute = parse_quote!(#[error(no_std)]);
+        let item_mod: ItemMod = parse_quote!(mod errors;);
+
+        let error = implement(extract_attribute_args(attribute), item_mod).err().unwrap();
+        assert_eq!(error.to_string(), "The error attribute requires a module with a body, like 'mod errors { ... }'.")
+    }
+
+    #[test]
+    fn misplaced_module_parameter_fails() {
+        let attribute: Attribute = parse_quote!(#[error(no_from)]);
+        let item_mod: ItemMod = parse_quote!(mod errors {});
+
+        let error = implement(extract_attribute_args(attribute), item_mod).err().unwrap();
+        assert_eq!(error.to_string(), "The parameter 'no_from' is not allowed on modules.")
+    }
+}
@@ -0,0 +1,181 @@
+use std::fmt::Formatter;
+
+use quote::{format_ident, quote};
+use syn::__private::{Span, TokenStream2};
+use syn::{Generics, Ident, Visibility};
+
+use crate::common::trait_impl_attributes;
+use crate::impl_aggregate::AggregateImplementationError::*;
+use crate::parameters::{AGGREGATE, Parameters};
+
+/// Creates a wrapper '<name of the error>s', which holds multiple errors of the same type.
+/// This is meant for validation pipelines, which report every problem at once instead of stopping at the first.
+///
+/// The wrapper can be collected from an iterator of errors and turned into a Result with 'into_result'.
+pub struct AggregateImplementor<'a> {
+    ident: &'a Ident,
+    vis: &'a Visibility,
+    generics: &'a Generics,
+    parameters: &'a Parameters,
+    /// Additional derives for the wrapper, see HELPER_DERIVE
+    helper_derive: &'a TokenStream2,
+}
+
+impl<'a> AggregateImplementor<'a> {
+    pub fn new(ident: &'a Ident, vis: &'a Visibility, generics: &'a Generics, parameters: &'a Parameters, helper_derive: &'a TokenStream2) -> Self {
+        AggregateImplementor { ident, vis, generics, parameters, helper_derive }
+    }
+
+    /// Create the wrapper if AGGREGATE is set.
+    ///
+    /// Fails if the error has no Display implementation, as the wrapper displays the messages of all errors.
+    pub fn implement(self, display_implemented: bool) -> Result<TokenStream2, AggregateImplementationError> {
+        let span = match self.parameters.span_for_name(AGGREGATE) {
+            Some(span) if self.parameters.bool_for_name(AGGREGATE) => span,
+            _ => return Ok(quote! {})
+        };
+
+        if !display_implemented {
+            return Err(AggregateWithoutDisplay(self.ident.clone(), span));
+        }
+
+        let ident = self.ident;
+        let vis = self.vis;
+        let helper_derive = self.helper_derive;
+        let wrapper_ident = format_ident!("{}s", ident);
+        let wrapper_doc = format!(" A collection of '{}' errors, which can be collected from an iterator.", ident);
+        let (impl_generics, type_generics, where_clause) = self.generics.split_for_impl();
+
+        let impl_attributes = trait_impl_attributes();
+
+        Ok(quote! {
+            #[doc = #wrapper_doc]
+            #[derive(Debug)]
+            #helper_derive
+            #vis struct #wrapper_ident #impl_generics #where_clause {
+                errors: Vec<#ident #type_generics>,
+            }
+
+            impl #impl_generics #wrapper_ident #type_generics #where_clause {
+                #[doc = " Create an empty collection."]
+                pub fn new() -> Self {
+                    #wrapper_ident { errors: Vec::new() }
+                }
+
+                #[doc = " Add an error to the collection."]
+                pub fn push(&mut self, error: #ident #type_generics) {
+                    self.errors.push(error)
+                }
+
+                #[doc = " Return all collected errors."]
+                pub fn errors(&self) -> &[#ident #type_generics] {
+                    &self.errors
+                }
+
+                #[doc = " Return the number of collected errors."]
+                pub fn len(&self) -> usize {
+                    self.errors.len()
+                }
+
+                #[doc = " Return true if no error was collected."]
+                pub fn is_empty(&self) -> bool {
+                    self.errors.is_empty()
+                }
+
+                #[doc = " Return Ok if no error was collected, otherwise return the collection as error."]
+                pub fn into_result(self) -> ::std::result::Result<(), Self> {
+                    match self.errors.is_empty() {
+                        true => Ok(()),
+                        false => Err(self)
+                    }
+                }
+            }
+
+            #impl_attributes
+            impl #impl_generics ::std::default::Default for #wrapper_ident #type_generics #where_clause {
+                fn default() -> Self {
+                    Self::new()
+                }
+            }
+
+            #impl_attributes
+            impl #impl_generics ::std::convert::From<Vec<#ident #type_generics>> for #wrapper_ident #type_generics #where_clause {
+                fn from(errors: Vec<#ident #type_generics>) -> Self {
+                    #wrapper_ident { errors }
+                }
+            }
+
+            #impl_attributes
+            impl #impl_generics ::std::iter::FromIterator<#ident #type_generics> for #wrapper_ident #type_generics #where_clause {
+                fn from_iter<__I: ::std::iter::IntoIterator<Item=#ident #type_generics>>(iter: __I) -> Self {
+                    #wrapper_ident { errors: iter.into_iter().collect() }
+                }
+            }
+
+            #impl_attributes
+            impl #impl_generics ::std::iter::Extend<#ident #type_generics> for #wrapper_ident #type_generics #where_clause {
+                fn extend<__I: ::std::iter::IntoIterator<Item=#ident #type_generics>>(&mut self, iter: __I) {
+                    self.errors.extend(iter)
+                }
+            }
+
+            #impl_attributes
+            impl #impl_generics ::std::iter::IntoIterator for #wrapper_ident #type_generics #where_clause {
+                type Item = #ident #type_generics;
+                type IntoIter = ::std::vec::IntoIter<#ident #type_generics>;
+
+                fn into_iter(self) -> Self::IntoIter {
+                    self.errors.into_iter()
+                }
+            }
+
+            #impl_attributes
+            impl #impl_generics ::std::fmt::Display for #wrapper_ident #type_generics #where_clause {
+                fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+                    for (i, error) in self.errors.iter().enumerate() {
+                        if i > 0 {
+                            f.write_str("; ")?;
+                        }
+                        write!(f, "{}", error)?;
+                    }
+                    Ok(())
+                }
+            }
+
+            #impl_attributes
+            impl #impl_generics ::std::error::Error for #wrapper_ident #type_generics #where_clause {}
+        })
+    }
+}
+
+/// Error that might occur when the aggregation wrapper is created.
+#[derive(Debug)]
+pub enum AggregateImplementationError {
+    /// The wrapper should be created, but the error has no message.
+    AggregateWithoutDisplay(Ident, Span),
+}
+
+impl AggregateImplementationError {
+    /// Return the span of the code which caused this error.
+    pub fn span(&self) -> Span {
+        match self {
+            AggregateWithoutDisplay(_, span) => *span
+        }
+    }
+}
+
+impl std::error::Error for AggregateImplementationError {}
+
+impl std::fmt::Display for AggregateImplementationError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AggregateWithoutDisplay(ident, _) => write!(f, "The parameter '{}' requires a message for '{}', as the wrapper displays the messages of all errors.", AGGREGATE, ident)
+        }
+    }
+}
+
+impl From<AggregateImplementationError> for syn::Error {
+    fn from(e: AggregateImplementationError) -> Self {
+        syn::Error::new(e.span(), e)
+    }
+}
@@ -0,0 +1,52 @@
+use quote::{format_ident, quote};
+use syn::__private::TokenStream2;
+use syn::ItemEnum;
+
+use crate::common::to_snake_case;
+use crate::parameters::{ASSERT_MACRO, Parameters};
+
+/// Creates a companion macro 'assert_handles_all_<enum name in snake case>!' for an enum.
+///
+/// The macro takes a list of variant names and fails to compile if the list does not cover
+/// every variant of the enum (or contains names which are no variants). This keeps external
+/// dispatch tables in sync with the enum.
+pub struct AssertMacroImplementor<'a> {
+    item_enum: &'a ItemEnum,
+    enum_parameters: &'a Parameters,
+}
+
+impl<'a> AssertMacroImplementor<'a> {
+    pub fn new(item_enum: &'a ItemEnum, enum_parameters: &'a Parameters) -> Self {
+        AssertMacroImplementor { item_enum, enum_parameters }
+    }
+
+    /// Create the macro if the parameter ASSERT_MACRO is set, otherwise return an empty token stream.
+    ///
+    /// The macro expands to a match over all given variants inside an anonymous constant,
+    /// so the exhaustiveness check of the compiler does the actual work.
+    pub fn implement(self) -> TokenStream2 {
+        if !self.enum_parameters.bool_for_name(ASSERT_MACRO) {
+            return quote! {};
+        }
+
+        let ident = &self.item_enum.ident;
+        let (impl_generics, type_generics, where_clause) = self.item_enum.generics.split_for_impl();
+        let macro_ident = format_ident!("assert_handles_all_{}", to_snake_case(&ident.to_string()));
+
+        quote! {
+            #[allow(unused_macros)]
+            macro_rules! #macro_ident {
+                ($($variant:ident),* $(,)?) => {
+                    const _: () = {
+                        #[allow(dead_code)]
+                        fn assert_handles_all #impl_generics (e: &#ident #type_generics) #where_clause {
+                            match e {
+                                $(#ident::$variant { .. } => {})*
+                            }
+                        }
+                    };
+                };
+            }
+        }
+    }
+}
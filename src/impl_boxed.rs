@@ -0,0 +1,78 @@
+use quote::quote;
+use syn::__private::TokenStream2;
+use syn::{Generics, Ident};
+
+use crate::common::std_root;
+use crate::parameters::{ALLOC, DYN_ERROR, NO_STD, Parameters};
+
+/// Creates the methods which turn the error into a trait object.
+///
+/// With ALLOC, this is the method 'boxed', which turns the error into an 'alloc::boxed::Box<dyn core::error::Error>'.
+/// This is meant for no_std crates which use alloc. The conversion with From/? into the boxed
+/// trait object is already provided by alloc itself, so only the method is generated.
+///
+/// With DYN_ERROR, 'boxed' returns a trait object which can be sent between threads instead, and 'as_dyn_error'
+/// borrows the error as trait object. Without std and alloc, there is no Box, so only 'as_dyn_error' is created.
+pub struct BoxedImplementor<'a> {
+    ident: &'a Ident,
+    generics: &'a Generics,
+    parameters: &'a Parameters,
+}
+
+impl<'a> BoxedImplementor<'a> {
+    pub fn new(ident: &'a Ident, generics: &'a Generics, parameters: &'a Parameters) -> Self {
+        BoxedImplementor { ident, generics, parameters }
+    }
+
+    /// Create the methods if the parameter ALLOC or DYN_ERROR is set, otherwise return an empty token stream.
+    pub fn implement(self) -> TokenStream2 {
+        let alloc = self.parameters.bool_for_name(ALLOC);
+        let dyn_error = self.parameters.bool_for_name(DYN_ERROR);
+        if !alloc && !dyn_error {
+            return quote! {};
+        }
+
+        let ident = self.ident;
+        let root = std_root(self.parameters);
+        let (impl_generics, type_generics, where_clause) = self.generics.split_for_impl();
+
+        let boxed = match (dyn_error, alloc, self.parameters.bool_for_name(NO_STD)) {
+            (false, _, _) => quote! {
+                #[doc = " Box this error as a trait object, like 'alloc::boxed::Box::<dyn core::error::Error>::from' would."]
+                pub fn boxed(self) -> alloc::boxed::Box<dyn ::core::error::Error> where Self: 'static {
+                    alloc::boxed::Box::new(self)
+                }
+            },
+            (true, true, _) => implement_send_boxed(quote! {alloc::boxed::Box}, &root),
+            (true, false, false) => implement_send_boxed(quote! {::std::boxed::Box}, &root),
+            (true, false, true) => quote! {}
+        };
+        let as_dyn_error = match dyn_error {
+            true => quote! {
+                #[doc = " Borrow this error as a trait object."]
+                pub fn as_dyn_error(&self) -> &(dyn #root::error::Error + 'static) where Self: 'static {
+                    self
+                }
+            },
+            false => quote! {}
+        };
+
+        quote! {
+            impl #impl_generics #ident #type_generics #where_clause {
+                #boxed
+                #as_dyn_error
+            }
+        }
+    }
+}
+
+/// Create the method 'boxed', which boxes the error (with the given path to Box) as a trait object
+/// which can be sent between threads.
+fn implement_send_boxed(box_path: TokenStream2, root: &TokenStream2) -> TokenStream2 {
+    quote! {
+        #[doc = " Box this error as a trait object which can be sent between threads."]
+        pub fn boxed(self) -> #box_path<dyn #root::error::Error + Send + Sync + 'static> where Self: Send + Sync + 'static {
+            #box_path::new(self)
+        }
+    }
+}
@@ -0,0 +1,137 @@
+use std::convert::TryFrom;
+
+use quote::{format_ident, quote};
+use syn::__private::TokenStream2;
+use syn::ItemEnum;
+
+use crate::enum_error::VariantWithParams;
+use crate::impl_wrap_map::WrapMap;
+use crate::common::{cfg_attributes, std_root};
+use crate::impl_errno::ErrnoImplementor;
+use crate::impl_retryable::RetryableImplementor;
+use crate::parameters::{CATALOG, EXIT_CODE, Parameters, RETRY_AFTER, SEVERITY, STATUS};
+
+/// Creates the associated constant 'CATALOG', which lists an entry for every variant of an enum with its name,
+/// message template and behavior, like the HTTP status (see STATUS), the exit code (see EXIT_CODE), the errno
+/// value (see ERRNO), the severity (see SEVERITY) and if it can be retried (see RETRYABLE and RETRY_AFTER).
+/// This gives documentation tooling and operations a single source of truth for all errors.
+///
+/// The entries have the type '<name of the enum>Info', which is created as well.
+pub struct CatalogImplementor<'a> {
+    item_enum: &'a ItemEnum,
+    enum_parameters: &'a Parameters,
+    variants_with_parameters: &'a [VariantWithParams<'a>],
+    wrap_map: &'a WrapMap<'a>,
+}
+
+impl<'a> CatalogImplementor<'a> {
+    pub fn new(item_enum: &'a ItemEnum, enum_parameters: &'a Parameters, variants_with_parameters: &'a [VariantWithParams<'a>], wrap_map: &'a WrapMap<'a>) -> Self {
+        CatalogImplementor { item_enum, enum_parameters, variants_with_parameters, wrap_map }
+    }
+
+    /// Create the constant and the type of its entries if the parameter CATALOG is set.
+    ///
+    /// A value of a variant is the one set on the variant, the one set on the enum or None if neither is set.
+    /// Entries of variants which are configured out are omitted with the cfg attributes of the variant.
+    /// The message template is found like the one of the kind (see WrapMap::template_for).
+    pub fn implement(self) -> TokenStream2 {
+        if !self.enum_parameters.bool_for_name(CATALOG) {
+            return quote! {};
+        }
+
+        let ident = &self.item_enum.ident;
+        let vis = &self.item_enum.vis;
+        let info_ident = format_ident!("{}Info", ident);
+        let root = std_root(self.enum_parameters);
+        let info_doc = format!(" Describes a variant of '{}', see '{}::CATALOG'.", ident, ident);
+        let (impl_generics, type_generics, where_clause) = self.item_enum.generics.split_for_impl();
+
+        let entries = self.variants_with_parameters
+            .iter()
+            .map(|(v, p_opt)| {
+                let name = v.ident.to_string();
+                let message = self.wrap_map.template_for(v, p_opt.as_ref(), self.enum_parameters);
+                let status = self.integer(p_opt.as_ref(), STATUS)
+                    .and_then(|status| u16::try_from(status).ok());
+                let exit_code = self.integer(p_opt.as_ref(), EXIT_CODE)
+                    .and_then(|code| u8::try_from(code).ok());
+                let status = implement_option(status.map(|s| quote! {#s}));
+                let exit_code = implement_option(exit_code.map(|c| quote! {#c}));
+                let errno = p_opt.as_ref()
+                    .and_then(|p| ErrnoImplementor::errno(p).ok().flatten())
+                    .or_else(|| ErrnoImplementor::errno(self.enum_parameters).ok().flatten());
+                let message = implement_option(message.map(|m| quote! {#m}));
+                let errno = implement_option(errno);
+                let severity = implement_option(self.string(p_opt.as_ref(), SEVERITY).map(|s| quote! {#s}));
+                let retryable = RetryableImplementor::variant_retryable(p_opt.as_ref(), self.enum_parameters);
+                let retry_after = implement_option(self.integer(p_opt.as_ref(), RETRY_AFTER)
+                    .map(|seconds| quote! {#root::time::Duration::from_secs(#seconds)}));
+
+                let cfg = cfg_attributes(v);
+
+                quote! {
+                    #cfg #info_ident {
+                        name: #name,
+                        message: #message,
+                        status: #status,
+                        exit_code: #exit_code,
+                        errno: #errno,
+                        severity: #severity,
+                        retryable: #retryable,
+                        retry_after: #retry_after,
+                    }
+                }
+            });
+
+        quote! {
+            #[doc = #info_doc]
+            #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+            #vis struct #info_ident {
+                #[doc = " The name of the variant."]
+                pub name: &'static str,
+                #[doc = " The message of the variant with the placeholders of the fields unresolved, if it has one."]
+                pub message: Option<&'static str>,
+                #[doc = " The HTTP status of the variant, if it has one."]
+                pub status: Option<u16>,
+                #[doc = " The exit code of the variant, if it has one."]
+                pub exit_code: Option<u8>,
+                #[doc = " The errno value of the variant, if it has one."]
+                pub errno: Option<i32>,
+                #[doc = " The severity of the variant ('warning', 'error' or 'fatal'), if it has one."]
+                pub severity: Option<&'static str>,
+                #[doc = " True if the failed operation of the variant can be retried."]
+                pub retryable: bool,
+                #[doc = " The time after which the failed operation of the variant should be retried, if it has one."]
+                pub retry_after: Option<#root::time::Duration>,
+            }
+
+            impl #impl_generics #ident #type_generics #where_clause {
+                #[doc = " The names, messages and behavior of all variants."]
+                pub const CATALOG: &'static [#info_ident] = &[#(#entries),*];
+            }
+        }
+    }
+
+    /// Return the value of the given integer parameter of a variant, or the one of the enum if the variant has none.
+    fn integer(&self, variant_parameters: Option<&Parameters>, name: &str) -> Option<u64> {
+        variant_parameters
+            .and_then(|p| p.integer_for_name(name))
+            .or_else(|| self.enum_parameters.integer_for_name(name))
+    }
+
+    /// Return the value of the given string parameter of a variant, or the one of the enum if the variant has none.
+    fn string(&self, variant_parameters: Option<&Parameters>, name: &str) -> Option<String> {
+        variant_parameters
+            .and_then(|p| p.string_for_name(name))
+            .or_else(|| self.enum_parameters.string_for_name(name))
+            .map(str::to_string)
+    }
+}
+
+/// Create 'Some(value)' for the given value or 'None'.
+fn implement_option(value: Option<TokenStream2>) -> TokenStream2 {
+    match value {
+        Some(value) => quote! {Some(#value)},
+        None => quote! {None}
+    }
+}
@@ -0,0 +1,143 @@
+use std::fmt::Formatter;
+
+use quote::quote;
+use syn::__private::{Span, TokenStream2};
+use syn::{Generics, Ident};
+
+use crate::common::cfg_attributes;
+use crate::enum_error::VariantWithParams;
+use crate::impl_category::CategoryImplementationError::*;
+use crate::parameters::{CATEGORY, Parameters};
+
+/// Creates the method 'category', which returns the coarse category set with the parameter CATEGORY, like "io" or "validation".
+/// This way, error dashboards can group errors by categories which are defined next to the messages.
+pub struct CategoryImplementor<'a> {
+    ident: &'a Ident,
+    generics: &'a Generics,
+    parameters: &'a Parameters,
+}
+
+impl<'a> CategoryImplementor<'a> {
+    pub fn new(ident: &'a Ident, generics: &'a Generics, parameters: &'a Parameters) -> Self {
+        CategoryImplementor { ident, generics, parameters }
+    }
+
+    /// Create the method for a struct, if CATEGORY is set.
+    pub fn implement_for_struct(self) -> Result<TokenStream2, CategoryImplementationError> {
+        match Self::category(self.parameters)? {
+            Some(category) => Ok(self.implement_method(quote! {#category})),
+            None => Ok(quote! {})
+        }
+    }
+
+    /// Create the method for an enum, if CATEGORY is set on the enum or any variant.
+    ///
+    /// Variants without CATEGORY use the category of the enum. A made up default category would
+    /// end up as its own group on dashboards, so if the enum has none, every variant must have one.
+    pub fn implement_for_enum(self, variants_with_parameters: &[VariantWithParams<'_>]) -> Result<TokenStream2, CategoryImplementationError> {
+        let variant_categories = variants_with_parameters
+            .iter()
+            .map(|(v, p_opt)| match p_opt {
+                Some(p) => Ok((*v, Self::category(p)?)),
+                None => Ok((*v, None))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        let default_category = Self::category(self.parameters)?;
+
+        if default_category.is_none() && variant_categories.iter().all(|(_, category)| category.is_none()) {
+            return Ok(quote! {});
+        }
+
+        let missing_variants = variant_categories
+            .iter()
+            .filter(|(_, category)| category.is_none() && default_category.is_none())
+            .map(|(v, _)| v.ident.clone())
+            .collect::<Vec<_>>();
+
+        if !missing_variants.is_empty() {
+            return Err(MissingCategory(self.ident.clone(), missing_variants));
+        }
+
+        let ident = self.ident;
+        let match_arms = variant_categories
+            .into_iter()
+            .map(|(v, category)| {
+                let variant_ident = &v.ident;
+                let cfg = cfg_attributes(v);
+                let category = category.or_else(|| default_category.clone()).unwrap();
+                quote! {#cfg #ident::#variant_ident { .. } => #category}
+            });
+
+        Ok(self.implement_method(quote! {
+            match self {
+                #(#match_arms,)*
+            }
+        }))
+    }
+
+    fn implement_method(&self, body: TokenStream2) -> TokenStream2 {
+        let ident = self.ident;
+        let (impl_generics, type_generics, where_clause) = self.generics.split_for_impl();
+
+        quote! {
+            impl #impl_generics #ident #type_generics #where_clause {
+                #[doc = " Return the category of this error, to group it with similar errors."]
+                pub fn category(&self) -> &'static str {
+                    #body
+                }
+            }
+        }
+    }
+
+    /// Return the category set in the given parameters, if any.
+    fn category(parameters: &Parameters) -> Result<Option<String>, CategoryImplementationError> {
+        let (category, span) = match (parameters.string_for_name(CATEGORY), parameters.span_for_name(CATEGORY)) {
+            (Some(category), Some(span)) => (category, span),
+            _ => return Ok(None)
+        };
+
+        match category.trim().is_empty() {
+            true => Err(EmptyCategory(span)),
+            false => Ok(Some(category.to_string()))
+        }
+    }
+}
+
+/// Error that might occur when the category method is created.
+#[derive(Debug)]
+pub enum CategoryImplementationError {
+    /// The category is an empty string.
+    EmptyCategory(Span),
+    /// Some variants have no category and the enum has no default. Every such variant is listed here.
+    MissingCategory(Ident, Vec<Ident>),
+}
+
+impl CategoryImplementationError {
+    /// Return the span of the code which caused this error.
+    pub fn span(&self) -> Span {
+        match self {
+            EmptyCategory(span) => *span,
+            MissingCategory(enum_ident, idents) => idents.first().unwrap_or(enum_ident).span()
+        }
+    }
+}
+
+impl std::error::Error for CategoryImplementationError {}
+
+impl std::fmt::Display for CategoryImplementationError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            EmptyCategory(_) => write!(f, "The parameter '{}' expects a non-empty category.", CATEGORY),
+            MissingCategory(enum_ident, idents) => {
+                let idents_string = idents.iter().map(|i| i.to_string()).collect::<Vec<_>>().join(",");
+                write!(f, "The following variants of enum '{}' have no '{}' value and the enum has no default: {}", enum_ident, CATEGORY, idents_string)
+            }
+        }
+    }
+}
+
+impl From<CategoryImplementationError> for syn::Error {
+    fn from(e: CategoryImplementationError) -> Self {
+        syn::Error::new(e.span(), e)
+    }
+}
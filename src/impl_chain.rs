@@ -0,0 +1,107 @@
+use std::fmt::Formatter;
+
+use quote::quote;
+use syn::__private::{Span, TokenStream2};
+use syn::{Generics, Ident};
+
+use crate::common::std_root;
+use crate::impl_chain::ChainImplementationError::*;
+use crate::parameters::{CHAIN, Parameters};
+
+/// Creates the methods 'chain', which iterates over the error and all of its sources, and 'render_chain',
+/// which renders the messages of the chain with every source on its own indented line, like
+///
+/// loading the config failed
+///   caused by: the port is invalid
+///     caused by: invalid digit found in string
+pub struct ChainImplementor<'a> {
+    ident: &'a Ident,
+    generics: &'a Generics,
+    parameters: &'a Parameters,
+}
+
+impl<'a> ChainImplementor<'a> {
+    pub fn new(ident: &'a Ident, generics: &'a Generics, parameters: &'a Parameters) -> Self {
+        ChainImplementor { ident, generics, parameters }
+    }
+
+    /// Create the methods if CHAIN is set.
+    ///
+    /// Fails if the error has no Display implementation, as the chain is rendered from the messages.
+    pub fn implement(self, display_implemented: bool) -> Result<TokenStream2, ChainImplementationError> {
+        let span = match self.parameters.span_for_name(CHAIN) {
+            Some(span) if self.parameters.bool_for_name(CHAIN) => span,
+            _ => return Ok(quote! {})
+        };
+
+        if !display_implemented {
+            return Err(ChainWithoutDisplay(self.ident.clone(), span));
+        }
+
+        let ident = self.ident;
+        let root = std_root(self.parameters);
+        let (impl_generics, type_generics, where_clause) = self.generics.split_for_impl();
+
+        Ok(quote! {
+            impl #impl_generics #ident #type_generics #where_clause {
+                #[doc = " Return an iterator over this error and all of its sources, beginning with this error."]
+                pub fn chain(&self) -> impl #root::iter::Iterator<Item = &(dyn #root::error::Error + '_)> + '_ {
+                    let mut next: #root::option::Option<&(dyn #root::error::Error + '_)> = #root::option::Option::Some(self);
+                    #root::iter::from_fn(move || {
+                        let current = next?;
+                        next = current.source().map(|source| source as &(dyn #root::error::Error + '_));
+                        #root::option::Option::Some(current)
+                    })
+                }
+
+                #[doc = " Render the message of this error and of all of its sources, every source on its own indented line."]
+                pub fn render_chain(&self) -> String {
+                    let mut rendered = String::new();
+
+                    for (depth, error) in self.chain().enumerate() {
+                        if depth > 0 {
+                            rendered.push('\n');
+                            rendered.push_str(&"  ".repeat(depth));
+                            rendered.push_str("caused by: ");
+                        }
+                        rendered.push_str(&error.to_string());
+                    }
+
+                    rendered
+                }
+            }
+        })
+    }
+}
+
+/// Error that might occur when the chain methods are created.
+#[derive(Debug)]
+pub enum ChainImplementationError {
+    /// CHAIN was set, but the error has no Display implementation.
+    ChainWithoutDisplay(Ident, Span),
+}
+
+impl ChainImplementationError {
+    /// Return the span of the code which caused this error.
+    pub fn span(&self) -> Span {
+        match self {
+            ChainWithoutDisplay(_, span) => *span
+        }
+    }
+}
+
+impl std::error::Error for ChainImplementationError {}
+
+impl std::fmt::Display for ChainImplementationError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ChainWithoutDisplay(ident, _) => write!(f, "The parameter '{}' requires a message for '{}', as the chain is rendered from the messages.", CHAIN, ident)
+        }
+    }
+}
+
+impl From<ChainImplementationError> for syn::Error {
+    fn from(e: ChainImplementationError) -> Self {
+        syn::Error::new(e.span(), e)
+    }
+}
@@ -0,0 +1,220 @@
+use std::fmt::Formatter;
+
+use quote::{format_ident, quote};
+use syn::__private::{Span, TokenStream2};
+use syn::{Fields, Ident, ItemEnum, Variant};
+
+use crate::common::{cfg_attributes, field_is_location, field_is_phantom, function_ident, std_root, to_snake_case};
+use crate::enum_error::VariantWithParams;
+use crate::impl_constructors::ConstructorsImplementationError::*;
+use crate::impl_from::{FromContext, FromImplementationError};
+use crate::parameters::{CONSTRUCTORS, Parameters};
+
+/// Creates a constructor function for every variant of an enum, named after the variant in snake case,
+/// like 'MyError::parsing_failed(line: usize) -> Self' for the variant 'ParsingFailed { line: usize }'.
+/// Names which are keywords become raw identifiers, like 'MyError::r#type()' for the variant 'Type'.
+///
+/// Every field becomes an argument, except for caller locations (see common::field_is_location), which
+/// are filled with the location of the caller, and phantom markers (see common::field_is_phantom), which are filled with PhantomData.
+///
+/// Variants with arguments also get a lazy constructor like 'MyError::parsing_failed_with(|| compute_line())', which takes
+/// closures computing the fields and returns a closure creating the variant. It fits into methods like 'Option::ok_or_else',
+/// so expensive values are only computed on the error path.
+///
+/// The variants are created like in From implementations (see FromContext::implement_construction), so TRACE and LOG
+/// record errors created with constructors as well.
+pub struct ConstructorsImplementor<'a> {
+    item_enum: &'a ItemEnum,
+    enum_parameters: &'a Parameters,
+    variants_with_parameters: &'a [VariantWithParams<'a>],
+    context: &'a FromContext,
+}
+
+impl<'a> ConstructorsImplementor<'a> {
+    pub fn new(item_enum: &'a ItemEnum, enum_parameters: &'a Parameters, variants_with_parameters: &'a [VariantWithParams<'a>], context: &'a FromContext) -> Self {
+        ConstructorsImplementor { item_enum, enum_parameters, variants_with_parameters, context }
+    }
+
+    /// Create the constructors if the parameter CONSTRUCTORS is set, otherwise return an empty token stream.
+    ///
+    /// Fails if the name of a constructor is a keyword which cannot be a raw identifier, like 'crate' for the variant 'Crate'.
+    pub fn implement(self) -> Result<TokenStream2, ConstructorsImplementationError> {
+        if !self.enum_parameters.bool_for_name(CONSTRUCTORS) {
+            return Ok(quote! {});
+        }
+
+        let ident = &self.item_enum.ident;
+        let constructors = self.variants_with_parameters
+            .iter()
+            .map(|(v, p_opt)| {
+                let context = self.context.for_variant(&v.ident, p_opt.as_ref()).map_err(Construction)?;
+                let constructor = self.implement_constructor(v, &context)?;
+                let lazy_constructor = self.implement_lazy_constructor(v, &context);
+                Ok(quote! {
+                    #constructor
+                    #lazy_constructor
+                })
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        let (impl_generics, type_generics, where_clause) = self.item_enum.generics.split_for_impl();
+
+        Ok(quote! {
+            impl #impl_generics #ident #type_generics #where_clause {
+                #(#constructors)*
+            }
+        })
+    }
+
+    fn implement_constructor(&self, variant: &Variant, context: &FromContext) -> Result<TokenStream2, ConstructorsImplementationError> {
+        let ident = &self.item_enum.ident;
+        let variant_ident = &variant.ident;
+        let constructor_name = to_snake_case(&variant_ident.to_string());
+        let constructor_ident = function_ident(&constructor_name, variant_ident.span())
+            .ok_or_else(|| ReservedConstructorName(variant_ident.clone(), constructor_name.clone()))?;
+        let doc = format!(" Create the variant '{}::{}'.", ident, variant_ident);
+        let cfg = cfg_attributes(variant);
+        let root = std_root(self.enum_parameters);
+
+        let names = variant_field_names(variant);
+        let arguments = variant.fields
+            .iter()
+            .zip(names.iter())
+            .filter(|(f, _)| !field_is_location(f) && !field_is_phantom(f))
+            .map(|(f, name)| {
+                let ty = &f.ty;
+                quote! {#name: #ty}
+            });
+        let values = variant.fields
+            .iter()
+            .zip(names.iter())
+            .map(|(f, name)| match (field_is_location(f), field_is_phantom(f)) {
+                (true, _) => quote! {#root::panic::Location::caller()},
+                (false, true) => quote! {#root::marker::PhantomData},
+                (false, false) => quote! {#name}
+            })
+            .collect::<Vec<_>>();
+        let track_caller = match variant.fields.iter().any(field_is_location) {
+            true => quote! {#[track_caller]},
+            false => quote! {}
+        };
+
+        let construction = match &variant.fields {
+            Fields::Named(_) => quote! {#ident::#variant_ident { #(#names: #values),* }},
+            Fields::Unnamed(_) => quote! {#ident::#variant_ident ( #(#values),* )},
+            Fields::Unit => quote! {#ident::#variant_ident}
+        };
+        let construction = context.implement_construction(construction);
+
+        Ok(quote! {
+            #cfg
+            #[doc = #doc]
+            #track_caller
+            pub fn #constructor_ident(#(#arguments),*) -> Self {
+                #construction
+            }
+        })
+    }
+
+    /// Create the lazy constructor '<variant name in snake case>_with', which takes a closure for every argument of the
+    /// constructor and returns a closure creating the variant. The location of the caller is taken when the lazy
+    /// constructor is called. If the constructor has no arguments, return an empty token stream.
+    fn implement_lazy_constructor(&self, variant: &Variant, context: &FromContext) -> TokenStream2 {
+        if variant.fields.iter().all(|f| field_is_location(f) || field_is_phantom(f)) {
+            return quote! {};
+        }
+
+        let ident = &self.item_enum.ident;
+        let variant_ident = &variant.ident;
+        let constructor_ident = format_ident!("{}_with", to_snake_case(&variant_ident.to_string()));
+        let cfg = cfg_attributes(variant);
+        let doc = format!(" Create the variant '{}::{}' lazily. The fields are only computed when the returned closure is called.", ident, variant_ident);
+        let root = std_root(self.enum_parameters);
+
+        let names = variant_field_names(variant);
+        let arguments = variant.fields
+            .iter()
+            .zip(names.iter())
+            .filter(|(f, _)| !field_is_location(f) && !field_is_phantom(f))
+            .map(|(f, name)| {
+                let ty = &f.ty;
+                quote! {#name: impl FnOnce() -> #ty}
+            });
+        let values = variant.fields
+            .iter()
+            .zip(names.iter())
+            .map(|(f, name)| match (field_is_location(f), field_is_phantom(f)) {
+                (true, _) => quote! {__location},
+                (false, true) => quote! {#root::marker::PhantomData},
+                (false, false) => quote! {#name()}
+            })
+            .collect::<Vec<_>>();
+        let (track_caller, location) = match variant.fields.iter().any(field_is_location) {
+            true => (quote! {#[track_caller]}, quote! {let __location = #root::panic::Location::caller();}),
+            false => (quote! {}, quote! {})
+        };
+
+        let construction = match &variant.fields {
+            Fields::Named(_) => quote! {#ident::#variant_ident { #(#names: #values),* }},
+            _ => quote! {#ident::#variant_ident ( #(#values),* )}
+        };
+        let construction = context.implement_construction(construction);
+
+        quote! {
+            #cfg
+            #[doc = #doc]
+            #track_caller
+            pub fn #constructor_ident(#(#arguments),*) -> impl FnOnce() -> Self {
+                #location
+                move || { #construction }
+            }
+        }
+    }
+}
+
+/// Return the names of the fields of the given variant, which are '_0', '_1', ... for unnamed fields.
+fn variant_field_names(variant: &Variant) -> Vec<Ident> {
+    variant.fields
+        .iter()
+        .enumerate()
+        .map(|(i, f)| match &f.ident {
+            Some(ident) => ident.clone(),
+            None => format_ident!("_{}", i)
+        })
+        .collect()
+}
+
+/// Error that might occur when the constructors are created.
+#[derive(Debug)]
+pub enum ConstructorsImplementationError {
+    /// The name of the constructor of the variant is a keyword which cannot be a raw identifier, like 'crate'.
+    ReservedConstructorName(Ident, String),
+    /// The settings of TRACE or LOG, which also apply to constructors, are invalid.
+    Construction(FromImplementationError),
+}
+
+impl ConstructorsImplementationError {
+    /// Return the span of the code which caused this error.
+    pub fn span(&self) -> Span {
+        match self {
+            ReservedConstructorName(variant, _) => variant.span(),
+            Construction(e) => e.span()
+        }
+    }
+}
+
+impl std::error::Error for ConstructorsImplementationError {}
+
+impl std::fmt::Display for ConstructorsImplementationError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ReservedConstructorName(variant, name) => write!(f, "The parameter '{}' cannot create a constructor for the variant '{}', as '{}' is a keyword which cannot be used as function name.", CONSTRUCTORS, variant, name),
+            Construction(e) => write!(f, "{}", e)
+        }
+    }
+}
+
+impl From<ConstructorsImplementationError> for syn::Error {
+    fn from(e: ConstructorsImplementationError) -> Self {
+        syn::Error::new(e.span(), e)
+    }
+}
@@ -0,0 +1,125 @@
+use quote::{format_ident, quote};
+use syn::__private::TokenStream2;
+use syn::{Fields, ItemEnum, Path, Variant};
+
+use crate::common::{allow_lints_attribute, cfg_attributes, field_is_redacted, REDACTED, std_root};
+use crate::impl_debug::{DebugMode, debug_mode, generics_with_debug_bounds, implement_derive, implement_display_forwarding};
+use crate::parameters::{Parameters, REDACT_DEBUG};
+
+/// Creates the std::fmt::Debug implementation for an enum.
+///
+/// By default, Debug is just derived. If REDACT_DEBUG is set and any field is marked with REDACT,
+/// Debug is implemented manually like the derived one, but the redacted fields are printed as REDACTED.
+pub struct EnumDebugImplementor<'a> {
+    item_enum: &'a ItemEnum,
+    enum_parameters: &'a Parameters,
+}
+
+impl<'a> EnumDebugImplementor<'a> {
+    pub fn new(item_enum: &'a ItemEnum, enum_parameters: &'a Parameters) -> Self {
+        EnumDebugImplementor { item_enum, enum_parameters }
+    }
+
+    /// Return the derive attribute for Debug, if Debug is neither derived already nor implemented manually,
+    /// together with the given derives (see DERIVES).
+    pub fn implement_derive(&self, derives: &[Path]) -> syn::Result<TokenStream2> {
+        implement_derive(&self.item_enum.ident, &self.item_enum.attrs, self.enum_parameters, self.is_manual(), derives)
+    }
+
+    /// Return the manual Debug implementation, if any field is redacted or DEBUG is "display".
+    ///
+    /// Fails if DEBUG is "display", but Display is not implemented (display_implemented).
+    pub fn implement(self, display_implemented: bool) -> syn::Result<TokenStream2> {
+        match debug_mode(self.enum_parameters)? {
+            DebugMode::Derive => Ok(self.implement_manual()),
+            DebugMode::Off => Ok(quote! {}),
+            DebugMode::Display => implement_display_forwarding(&self.item_enum.ident, &self.item_enum.generics, self.enum_parameters, display_implemented)
+        }
+    }
+
+    fn implement_manual(self) -> TokenStream2 {
+        if !self.is_manual() {
+            return quote! {};
+        }
+
+        let ident = &self.item_enum.ident;
+        let root = std_root(self.enum_parameters);
+        let generics = generics_with_debug_bounds(&self.item_enum.generics, self.enum_parameters);
+        let (impl_generics, type_generics, where_clause) = generics.split_for_impl();
+        let arms = self.item_enum.variants
+            .iter()
+            .map(|v| self.implement_arm(v, &root));
+
+        let allow_lints = allow_lints_attribute();
+
+        quote! {
+            #allow_lints
+            impl #impl_generics #root::fmt::Debug for #ident #type_generics #where_clause {
+                fn fmt(&self, f: &mut #root::fmt::Formatter<'_>) -> #root::fmt::Result {
+                    match self {
+                        #(#arms),*
+                    }
+                }
+            }
+        }
+    }
+
+    fn implement_arm(&self, variant: &Variant, root: &TokenStream2) -> TokenStream2 {
+        let ident = &self.item_enum.ident;
+        let variant_ident = &variant.ident;
+        let cfg = cfg_attributes(variant);
+        let name = variant_ident.to_string();
+        let redacted = quote! {&#root::format_args!(#REDACTED)};
+
+        match &variant.fields {
+            Fields::Named(named) => {
+                let bindings = named.named
+                    .iter()
+                    .filter(|f| !field_is_redacted(f))
+                    .map(|f| f.ident.as_ref().unwrap());
+                let fields = named.named
+                    .iter()
+                    .map(|f| {
+                        let field_ident = f.ident.as_ref().unwrap();
+                        let field_name = field_ident.to_string();
+                        match field_is_redacted(f) {
+                            true => quote! {.field(#field_name, #redacted)},
+                            false => quote! {.field(#field_name, #field_ident)}
+                        }
+                    });
+
+                quote! {#cfg #ident::#variant_ident { #(#bindings,)* .. } => f.debug_struct(#name) #(#fields)* .finish()}
+            }
+            Fields::Unnamed(unnamed) => {
+                let bindings = unnamed.unnamed
+                    .iter()
+                    .enumerate()
+                    .map(|(i, f)| match field_is_redacted(f) {
+                        true => quote! {_},
+                        false => {
+                            let binding = format_ident!("_{}", i);
+                            quote! {#binding}
+                        }
+                    })
+                    .collect::<Vec<_>>();
+                let fields = unnamed.unnamed
+                    .iter()
+                    .zip(&bindings)
+                    .map(|(f, binding)| match field_is_redacted(f) {
+                        true => quote! {.field(#redacted)},
+                        false => quote! {.field(#binding)}
+                    });
+
+                quote! {#cfg #ident::#variant_ident ( #(#bindings),* ) => f.debug_tuple(#name) #(#fields)* .finish()}
+            }
+            Fields::Unit => quote! {#cfg #ident::#variant_ident => f.write_str(#name)}
+        }
+    }
+
+    fn is_manual(&self) -> bool {
+        self.enum_parameters.bool_for_name(REDACT_DEBUG) && self.item_enum.variants
+            .iter()
+            .flat_map(|v| v.fields.iter())
+            .any(field_is_redacted)
+    }
+}
@@ -0,0 +1,113 @@
+pub mod enums;
+pub mod structs;
+
+use quote::quote;
+use syn::__private::TokenStream2;
+use syn::{Attribute, Error, GenericParam, Generics, Ident, Meta, NestedMeta, Path, parse_quote};
+
+use crate::common::{allow_lints_attribute, std_root};
+use crate::parameters::{DEBUG, Parameters};
+
+/// How Debug is provided for an error, see DEBUG.
+enum DebugMode {
+    /// Debug is derived or implemented manually to print labels and redacted fields.
+    Derive,
+    /// Debug is left to the user.
+    Off,
+    /// Debug forwards to Display.
+    Display,
+}
+
+/// Return the DebugMode set with DEBUG in the given parameters.
+///
+/// Fails if the value is unknown.
+fn debug_mode(parameters: &Parameters) -> syn::Result<DebugMode> {
+    match (parameters.string_for_name(DEBUG), parameters.span_for_name(DEBUG)) {
+        (Some("off"), _) => Ok(DebugMode::Off),
+        (Some("display"), _) => Ok(DebugMode::Display),
+        (Some(value), Some(span)) => Err(Error::new(span, format!("The value '{}' of '{}' is unknown. Possible values are: off, display", value, DEBUG))),
+        _ => Ok(DebugMode::Derive)
+    }
+}
+
+/// Return the derive attribute for Debug together with the given derives (see DERIVES). Debug is left out if the item
+/// already derives it with one of the given attributes, if it is implemented manually (manual_debug) or if DEBUG is set.
+///
+/// Fails if the item derives Debug, but it is implemented by the macro.
+fn implement_derive(ident: &Ident, attributes: &[Attribute], parameters: &Parameters, manual_debug: bool, derives: &[Path]) -> syn::Result<TokenStream2> {
+    let (debug_derived, manual_debug) = match debug_mode(parameters)? {
+        DebugMode::Derive => (derives_debug(attributes), manual_debug),
+        DebugMode::Off => (false, true),
+        DebugMode::Display => (derives_debug(attributes), true)
+    };
+
+    if debug_derived && manual_debug {
+        return Err(Error::new(ident.span(), format!("'{}' derives Debug, but Debug is implemented by error_generator to print labels, redacted fields or the message. Please remove the derive of Debug or set '{} = \"off\"'.", ident, DEBUG)));
+    }
+
+    match (debug_derived || manual_debug, derives.is_empty()) {
+        (true, true) => Ok(quote! {}),
+        (true, false) => Ok(quote! {#[derive(#(#derives),*)]}),
+        (false, _) => Ok(quote! {#[derive(Debug #(, #derives)*)]})
+    }
+}
+
+/// Return true if any of the given attributes is a derive which contains Debug, like '#[derive(Clone, Debug)]'.
+fn derives_debug(attributes: &[Attribute]) -> bool {
+    attributes
+        .iter()
+        .filter(|a| a.path.is_ident("derive"))
+        .filter_map(|a| match a.parse_meta() {
+            Ok(Meta::List(list)) => Some(list.nested),
+            _ => None
+        })
+        .flatten()
+        .any(|nested| match nested {
+            NestedMeta::Meta(Meta::Path(path)) => path.segments.last().map(|s| s.ident == "Debug").unwrap_or(false),
+            _ => false
+        })
+}
+
+/// Create the Debug implementation which forwards to Display, so unwrapping the error prints its message.
+///
+/// Fails if Display is not implemented (display_implemented).
+fn implement_display_forwarding(ident: &Ident, generics: &Generics, parameters: &Parameters, display_implemented: bool) -> syn::Result<TokenStream2> {
+    if !display_implemented {
+        let span = parameters.span_for_name(DEBUG).unwrap_or_else(|| ident.span());
+        return Err(Error::new(span, format!("'{} = \"display\"' requires a message for '{}', as Debug prints the message.", DEBUG, ident)));
+    }
+
+    let root = std_root(parameters);
+    let (impl_generics, type_generics, where_clause) = generics.split_for_impl();
+
+    let allow_lints = allow_lints_attribute();
+
+    Ok(quote! {
+        #allow_lints
+        impl #impl_generics #root::fmt::Debug for #ident #type_generics #where_clause {
+            fn fmt(&self, f: &mut #root::fmt::Formatter<'_>) -> #root::fmt::Result {
+                #root::fmt::Display::fmt(self, f)
+            }
+        }
+    })
+}
+
+/// Like #[derive(Debug)], require every type parameter of the given generics to implement Debug.
+fn generics_with_debug_bounds(generics: &Generics, parameters: &Parameters) -> Generics {
+    let mut generics = generics.clone();
+    let type_params = generics.params
+        .iter()
+        .filter_map(|p| match p {
+            GenericParam::Type(t) => Some(t.ident.clone()),
+            _ => None
+        })
+        .collect::<Vec<_>>();
+
+    let root = std_root(parameters);
+    let where_clause = generics.make_where_clause();
+    for type_param in type_params {
+        where_clause.predicates.push(parse_quote!(#type_param: #root::fmt::Debug));
+    }
+
+    generics
+}
@@ -0,0 +1,99 @@
+use quote::quote;
+use syn::__private::TokenStream2;
+use syn::{Index, ItemStruct, Path};
+
+use crate::common::{FieldWithParams, REDACTED, allow_lints_attribute, field_is_redacted, std_root};
+use crate::impl_debug::{DebugMode, debug_mode, generics_with_debug_bounds, implement_derive, implement_display_forwarding};
+use crate::parameters::{LABEL, Parameters, REDACT_DEBUG};
+
+/// Creates the std::fmt::Debug implementation for a struct.
+///
+/// By default, Debug is just derived. If any field has a label, Debug is implemented manually
+/// and every labelled field is printed with its label, like 'path ("config file"): "/etc/app"'.
+/// If REDACT_DEBUG is set, Debug is also implemented manually and fields marked with REDACT are printed as REDACTED.
+pub struct StructDebugImplementor<'a> {
+    item_struct: &'a ItemStruct,
+    struct_parameters: &'a Parameters,
+    fields_with_parameters: &'a [FieldWithParams<'a>],
+}
+
+impl<'a> StructDebugImplementor<'a> {
+    pub fn new(item_struct: &'a ItemStruct, struct_parameters: &'a Parameters, fields_with_parameters: &'a [FieldWithParams<'a>]) -> Self {
+        StructDebugImplementor { item_struct, struct_parameters, fields_with_parameters }
+    }
+
+    /// Return the derive attribute for Debug, if Debug is neither derived already nor implemented manually,
+    /// together with the given derives (see DERIVES).
+    pub fn implement_derive(&self, derives: &[Path]) -> syn::Result<TokenStream2> {
+        implement_derive(&self.item_struct.ident, &self.item_struct.attrs, self.struct_parameters, self.is_manual(), derives)
+    }
+
+    /// Return the manual Debug implementation, if any field has a label or is redacted or DEBUG is "display".
+    ///
+    /// Fails if DEBUG is "display", but Display is not implemented (display_implemented).
+    pub fn implement(self, display_implemented: bool) -> syn::Result<TokenStream2> {
+        match debug_mode(self.struct_parameters)? {
+            DebugMode::Derive => Ok(self.implement_manual()),
+            DebugMode::Off => Ok(quote! {}),
+            DebugMode::Display => implement_display_forwarding(&self.item_struct.ident, &self.item_struct.generics, self.struct_parameters, display_implemented)
+        }
+    }
+
+    fn implement_manual(self) -> TokenStream2 {
+        if !self.is_manual() {
+            return quote! {};
+        }
+
+        let ident = &self.item_struct.ident;
+        let name = ident.to_string();
+        let root = std_root(self.struct_parameters);
+        let generics = generics_with_debug_bounds(&self.item_struct.generics, self.struct_parameters);
+        let (impl_generics, type_generics, where_clause) = generics.split_for_impl();
+        let fields = self.fields_with_parameters
+            .iter()
+            .enumerate()
+            .map(|(i, (field, parameters))| {
+                let member = match &field.ident {
+                    Some(ident) => quote!(#ident),
+                    None => {
+                        let index = Index::from(i);
+                        quote!(#index)
+                    }
+                };
+                let field_name = match parameters.as_ref().and_then(|p| p.string_for_name(LABEL)) {
+                    Some(label) => format!("{} ({:?})", member, label),
+                    None => member.to_string()
+                };
+                match self.redacts_debug() && field_is_redacted(field) {
+                    true => quote! {.field(#field_name, &#root::format_args!(#REDACTED))},
+                    false => quote! {.field(#field_name, &self.#member)}
+                }
+            });
+
+        let allow_lints = allow_lints_attribute();
+
+        quote! {
+            #allow_lints
+            impl #impl_generics #root::fmt::Debug for #ident #type_generics #where_clause {
+                fn fmt(&self, f: &mut #root::fmt::Formatter<'_>) -> #root::fmt::Result {
+                    f.debug_struct(#name) #(#fields)* .finish()
+                }
+            }
+        }
+    }
+
+    fn is_manual(&self) -> bool {
+        self.has_labels() || (self.redacts_debug() && self.fields_with_parameters.iter().any(|(f, _)| field_is_redacted(f)))
+    }
+
+    fn redacts_debug(&self) -> bool {
+        self.struct_parameters.bool_for_name(REDACT_DEBUG)
+    }
+
+    fn has_labels(&self) -> bool {
+        self.fields_with_parameters
+            .iter()
+            .filter_map(|(_, p)| p.as_ref())
+            .any(|p| p.has_parameter(LABEL))
+    }
+}
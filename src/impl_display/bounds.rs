@@ -0,0 +1,210 @@
+use proc_macro2::TokenTree;
+use quote::{format_ident, ToTokens};
+use syn::{Expr, Field, Fields, Generics, Ident, parse_quote, WherePredicate};
+use syn::__private::TokenStream2;
+
+use crate::common::{field_is_redacted, field_is_usable, field_uses_debug_placeholder};
+use crate::template::Template;
+
+/// Return the bounds the given message requires for the fields it formats whose type uses a generic type parameter,
+/// like 'T: ::std::fmt::Display' for "{self.0}" on 'struct S<T>(T)'. The formatting trait is the one selected by the format spec
+/// of the placeholder (or Debug for fields marked with DEBUG_PLACEHOLDER), so "{self.0:x}" requires 'T: ::std::fmt::LowerHex'.
+///
+/// Only placeholders which format a usable field directly are considered, which are accesses on one of the given receivers
+/// (like "self.0" or "e.path") and, if bind_fields is set, the bindings of the fields in a match arm (like "path" or "_0").
+/// Bounds of other expressions (like method calls) cannot be inferred and must be declared on the item. Invalid messages
+/// have no bounds, they are reported when the Display implementation is created.
+pub fn message_bounds(message: &str, fields: &Fields, receivers: &[&str], bind_fields: bool, generics: &Generics, root: &TokenStream2) -> Vec<WherePredicate> {
+    let mut template = match Template::parse(message) {
+        Ok(template) => template,
+        Err(_) => return vec![]
+    };
+    template.resolve_positional_placeholders(receivers[0]);
+    let type_params = generics.type_params().map(|p| &p.ident).collect::<Vec<_>>();
+
+    template.formatting_traits()
+        .into_iter()
+        .filter_map(|(expression, formatting_trait)| Some((formatted_field(expression, fields, receivers, bind_fields)?, formatting_trait)))
+        .filter(|(f, _)| field_is_usable(f) && !field_is_redacted(f) && uses_type_param(f.ty.to_token_stream(), &type_params))
+        .map(|(f, formatting_trait)| {
+            let ty = &f.ty;
+            let formatting_trait = match formatting_trait == "Display" && field_uses_debug_placeholder(f) {
+                true => format_ident!("Debug"),
+                false => format_ident!("{}", formatting_trait)
+            };
+            parse_quote!(#ty: #root::fmt::#formatting_trait)
+        })
+        .collect()
+}
+
+/// Return the bounds the given source fields require if their type uses a generic type parameter, like
+/// 'T: ::std::error::Error + 'static' for a wrapped 'T', as std::error::Error::source returns '&(dyn Error + 'static)'.
+pub fn source_bounds<'f>(sources: impl IntoIterator<Item=&'f Field>, generics: &Generics, root: &TokenStream2) -> Vec<WherePredicate> {
+    let type_params = generics.type_params().map(|p| &p.ident).collect::<Vec<_>>();
+
+    sources
+        .into_iter()
+        .filter(|f| uses_type_param(f.ty.to_token_stream(), &type_params))
+        .map(|f| {
+            let ty = &f.ty;
+            parse_quote!(#ty: #root::error::Error + 'static)
+        })
+        .collect()
+}
+
+/// Return the bounds of the std::error::Error implementation of an item with the given generics. Error requires Debug and Display,
+/// which only hold for some of the generic type arguments if Debug is derived or Display has bounds (see message_bounds).
+/// This way, generic errors don't need these bounds on their definition.
+pub fn error_bounds(generics: &Generics, root: &TokenStream2) -> Vec<WherePredicate> {
+    match generics.type_params().next() {
+        Some(_) => vec![parse_quote!(Self: #root::fmt::Debug + #root::fmt::Display)],
+        None => vec![]
+    }
+}
+
+/// Return the given generics with the given bounds added to their where clause. Bounds which are already contained are skipped.
+pub fn with_bounds(generics: &Generics, bounds: &[WherePredicate]) -> Generics {
+    let mut generics = generics.clone();
+    let where_clause = generics.make_where_clause();
+
+    for bound in bounds {
+        let bound_string = bound.to_token_stream().to_string();
+        if !where_clause.predicates.iter().any(|p| p.to_token_stream().to_string() == bound_string) {
+            where_clause.predicates.push(bound.clone())
+        }
+    }
+
+    generics
+}
+
+/// Return the field the given expression formats directly, like the field 'path' for "e.path" or "path" (if bind_fields is set).
+fn formatted_field<'f>(expression: &TokenStream2, fields: &'f Fields, receivers: &[&str], bind_fields: bool) -> Option<&'f Field> {
+    let expr = syn::parse2::<Expr>(expression.clone()).ok()?;
+
+    match unwrap_expression(&expr) {
+        Expr::Field(field) => match unwrap_expression(&field.base) {
+            Expr::Path(path) if receivers.iter().any(|r| path.path.is_ident(r)) => {
+                let member = field.member.to_token_stream().to_string();
+                fields
+                    .iter()
+                    .enumerate()
+                    .find(|(i, f)| f.ident.as_ref().map(Ident::to_string).unwrap_or_else(|| i.to_string()) == member)
+                    .map(|(_, f)| f)
+            }
+            _ => None
+        },
+        Expr::Path(path) if bind_fields => {
+            let binding = path.path.get_ident()?.to_string();
+            fields
+                .iter()
+                .enumerate()
+                .find(|(i, f)| f.ident.as_ref().map(Ident::to_string).unwrap_or_else(|| format!("_{}", i)) == binding)
+                .map(|(_, f)| f)
+        }
+        _ => None
+    }
+}
+
+/// Remove the parentheses and references around the given expression, like "&(e.path)" becomes "e.path".
+fn unwrap_expression(expr: &Expr) -> &Expr {
+    match expr {
+        Expr::Paren(paren) => unwrap_expression(&paren.expr),
+        Expr::Reference(reference) => unwrap_expression(&reference.expr),
+        Expr::Group(group) => unwrap_expression(&group.expr),
+        expr => expr
+    }
+}
+
+/// Check if the given tokens (of a type) contain one of the given type parameters, like 'T' in "Vec<T>".
+fn uses_type_param(tokens: TokenStream2, type_params: &[&Ident]) -> bool {
+    tokens.into_iter().any(|t| match t {
+        TokenTree::Group(group) => uses_type_param(group.stream(), type_params),
+        TokenTree::Ident(ident) => type_params.contains(&&ident),
+        _ => false
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use quote::{quote, ToTokens};
+    use syn::{ItemStruct, parse_quote};
+
+    use crate::impl_display::bounds::{message_bounds, source_bounds, with_bounds};
+
+    #[test]
+    fn no_bounds_of_phantom_and_skipped_fields() {
+        let item_struct: ItemStruct = parse_quote! {
+            struct Value<T, U, V>(T, PhantomData<U>, #[error(skip)] V);
+        };
+
+        let bounds = message_bounds("{_0} {1} {2}", &item_struct.fields, &["e"], true, &item_struct.generics, &quote!(::std))
+            .iter()
+            .map(|b| b.to_token_stream().to_string())
+            .collect::<Vec<_>>();
+
+        assert_eq!(bounds, vec![quote!(T: ::std::fmt::Display).to_string()])
+    }
+
+    #[test]
+    fn bounds_of_generic_fields() {
+        let item_struct: ItemStruct = parse_quote! {
+            struct S<T, U> {
+                value: T,
+                values: Vec<U>,
+                count: usize,
+                #[error(debug_placeholder)]
+                debug: T
+            }
+        };
+
+        let bounds = message_bounds("{self.value} {e.values:x} {self.count} {self.debug} {self.value.len()}", &item_struct.fields, &["self", "e"], false, &item_struct.generics, &quote!(::std))
+            .iter()
+            .map(|b| b.to_token_stream().to_string())
+            .collect::<Vec<_>>();
+
+        let expected = vec![
+            quote!(T: ::std::fmt::Display).to_string(),
+            quote!(Vec<U>: ::std::fmt::LowerHex).to_string(),
+            quote!(T: ::std::fmt::Debug).to_string()
+        ];
+        assert_eq!(bounds, expected)
+    }
+
+    #[test]
+    fn bounds_of_bindings() {
+        let item_struct: ItemStruct = parse_quote!(struct S<T>(T, T););
+
+        let bounds = message_bounds("{_0} {1:?}", &item_struct.fields, &["e"], true, &item_struct.generics, &quote!(::std))
+            .iter()
+            .map(|b| b.to_token_stream().to_string())
+            .collect::<Vec<_>>();
+
+        assert_eq!(bounds, vec![quote!(T: ::std::fmt::Display).to_string(), quote!(T: ::std::fmt::Debug).to_string()])
+    }
+
+    #[test]
+    fn bounds_of_generic_sources() {
+        let item_struct: ItemStruct = parse_quote!(struct S<T>(T, Box<T>, std::io::Error););
+
+        let bounds = source_bounds(&item_struct.fields, &item_struct.generics, &quote!(::core))
+            .iter()
+            .map(|b| b.to_token_stream().to_string())
+            .collect::<Vec<_>>();
+
+        let expected = vec![
+            quote!(T: ::core::error::Error + 'static).to_string(),
+            quote!(Box<T>: ::core::error::Error + 'static).to_string()
+        ];
+        assert_eq!(bounds, expected)
+    }
+
+    #[test]
+    fn with_bounds_skips_contained_bounds() {
+        let item_struct: ItemStruct = parse_quote!(struct S<T>(T) where T: ::std::fmt::Display;);
+
+        let generics = with_bounds(&item_struct.generics, &[parse_quote!(T: ::std::fmt::Display), parse_quote!(T: ::std::fmt::Debug)]);
+
+        let expected = quote!(where T: ::std::fmt::Display, T: ::std::fmt::Debug);
+        assert_eq!(generics.where_clause.to_token_stream().to_string(), expected.to_string())
+    }
+}
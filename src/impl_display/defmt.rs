@@ -0,0 +1,230 @@
+use quote::quote;
+use syn::__private::{Span, TokenStream2};
+use syn::{Generics, Ident};
+
+#[cfg(feature = "defmt")]
+use crate::common::trait_impl_attributes;
+use crate::impl_display::DisplayImplementationError;
+use crate::impl_display::DisplayImplementationError::*;
+use crate::parameters::{DEFMT, FMT, MESSAGE_CONST, Parameters};
+
+/// Return the span of DEFMT, if a defmt::Format implementation should be created for the item with the given parameters.
+///
+/// Fails if the message of the item is only known at runtime (see FMT and MESSAGE_CONST), as defmt needs
+/// the message as literal to keep the formatting on the host.
+pub fn defmt_span(parameters: &Parameters) -> Result<Option<Span>, DisplayImplementationError> {
+    let span = match parameters.span_for_name(DEFMT) {
+        Some(span) if parameters.bool_for_name(DEFMT) => span,
+        _ => return Ok(None)
+    };
+
+    match [FMT, MESSAGE_CONST].iter().find(|name| parameters.has_parameter(name)) {
+        Some(name) => Err(DefmtWithRuntimeMessage(name, span)),
+        None => Ok(Some(span))
+    }
+}
+
+/// Return the macro which writes messages with defmt. It takes the same arguments as write!.
+pub fn defmt_write_macro() -> TokenStream2 {
+    quote!(defmt::write)
+}
+
+/// Escape the braces of the given text, so it can be added to a message as literal text, like PREFIX and SUFFIX.
+pub fn escape_braces(text: &str) -> String {
+    text.replace('{', "{{").replace('}', "}}")
+}
+
+/// Create the defmt::Format implementation with the given body of 'format', which writes the message
+/// with the macro of defmt_write_macro. The using crate needs to depend on defmt.
+#[cfg(feature = "defmt")]
+pub fn implement_format(ident: &Ident, generics: &Generics, body: TokenStream2, _span: Span) -> Result<TokenStream2, DisplayImplementationError> {
+    let (impl_generics, type_generics, where_clause) = generics.split_for_impl();
+
+    let impl_attributes = trait_impl_attributes();
+
+    Ok(quote! {
+        #impl_attributes
+        impl #impl_generics defmt::Format for #ident #type_generics #where_clause {
+            fn format(&self, f: defmt::Formatter<'_>) {
+                #body
+            }
+        }
+    })
+}
+
+/// Without the feature 'defmt', DEFMT cannot be used.
+#[cfg(not(feature = "defmt"))]
+pub fn implement_format(_ident: &Ident, _generics: &Generics, _body: TokenStream2, span: Span) -> Result<TokenStream2, DisplayImplementationError> {
+    Err(DefmtFeatureNotEnabled(span))
+}
+
+#[cfg(test)]
+mod tests {
+    use syn::Attribute;
+    use syn::parse_quote;
+
+    use crate::{assert_enum_implementation_as_expected, assert_struct_implementation_as_expected};
+    use crate::impl_display::defmt::{defmt_span, escape_braces};
+    use crate::parameters::Parameters;
+
+    #[test]
+    fn defmt_span_works() {
+        assert!(defmt_span(&to_parameters(parse_quote!(#[error(message = "failed", defmt)]))).ok().unwrap().is_some());
+        assert!(defmt_span(&to_parameters(parse_quote!(#[error(message = "failed", defmt = false)]))).ok().unwrap().is_none());
+        assert!(defmt_span(&to_parameters(parse_quote!(#[error(message = "failed")]))).ok().unwrap().is_none());
+
+        let error = defmt_span(&to_parameters(parse_quote!(#[error(fmt = "format_error", defmt)]))).err().unwrap();
+        assert_eq!(error.to_string(), "The parameter 'defmt' cannot be used together with 'fmt', as defmt needs the message at compile time.")
+    }
+
+    #[test]
+    fn escape_braces_works() {
+        assert_eq!(escape_braces("[{app}] "), "[{{app}}] ")
+    }
+
+    #[cfg(feature = "defmt")]
+    #[test]
+    fn defmt_struct() {
+        assert_struct_implementation_as_expected!(
+            item: {
+                #[error(message = "Sensor {self.id} failed", defmt)]
+                struct S {
+                    id: u8
+                }
+            }
+
+            expected: {
+                #[derive(Debug)]
+                struct S {
+                    id: u8
+                }
+
+                #[automatically_derived]
+                #[allow(unused_qualifications, clippy::all)]
+                impl ::std::error::Error for S {}
+
+                #[automatically_derived]
+                #[allow(unused_qualifications, clippy::all)]
+                impl ::std::fmt::Display for S {
+                    fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+                        ::std::write!(f, "Sensor {} failed", self.id)
+                    }
+                }
+
+                #[automatically_derived]
+                #[allow(unused_qualifications, clippy::all)]
+                impl defmt::Format for S {
+                    fn format(&self, f: defmt::Formatter<'_>) {
+                        defmt::write!(f, "Sensor {} failed", self.id)
+                    }
+                }
+            }
+        )
+    }
+
+    #[cfg(feature = "defmt")]
+    #[test]
+    fn defmt_enum() {
+        assert_enum_implementation_as_expected!(
+            item: {
+                #[error(message = "Something went wrong", prefix = "{sensor} ", defmt)]
+                enum E {
+                    #[error(message = "The foo value: {foo}")]
+                    Named {foo: usize},
+                    Unit
+                }
+            }
+
+            expected: {
+                #[derive(Debug)]
+                enum E {
+                    Named {foo: usize},
+                    Unit
+                }
+
+                #[automatically_derived]
+                #[allow(unused_qualifications, clippy::all)]
+                impl ::std::error::Error for E {}
+
+                #[automatically_derived]
+                #[allow(unused_qualifications, clippy::all)]
+                impl ::std::fmt::Display for E {
+                    fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+                        {
+                            f.write_str("{sensor} ")?;
+                            match self {
+                                E::Named {foo,} => ::std::write!(f, "The foo value: {}", foo),
+                                _ => ::std::write!(f, "Something went wrong")
+                            }
+                        }
+                    }
+                }
+
+                #[automatically_derived]
+                #[allow(unused_qualifications, clippy::all)]
+                impl defmt::Format for E {
+                    fn format(&self, f: defmt::Formatter<'_>) {
+                        match self {
+                            E::Named {foo,} => defmt::write!(f, "{{sensor}} The foo value: {}", foo),
+                            _ => defmt::write!(f, "{{sensor}} Something went wrong")
+                        }
+                    }
+                }
+            }
+        )
+    }
+
+    #[test]
+    #[should_panic(expected = "The parameter 'defmt' cannot be used together with 'message_const', as defmt needs the message at compile time.")]
+    fn defmt_with_message_const_should_panic() {
+        assert_enum_implementation_as_expected!(
+            item: {
+                #[error(message = "failed", defmt)]
+                enum E {
+                    #[error(message_const = "MESSAGE")]
+                    Unit,
+                    Other
+                }
+            }
+
+            expected: {
+                should panic
+            }
+        )
+    }
+
+    #[test]
+    #[should_panic(expected = "The parameter 'defmt' requires a message for 'S', as the message is written with defmt.")]
+    fn defmt_without_message_should_panic() {
+        assert_struct_implementation_as_expected!(
+            item: {
+                #[error(defmt)]
+                struct S;
+            }
+
+            expected: {
+                should panic
+            }
+        )
+    }
+
+    #[cfg(not(feature = "defmt"))]
+    #[test]
+    #[should_panic(expected = "The parameter 'defmt' requires the feature 'defmt' of error_generator.")]
+    fn defmt_without_feature_should_panic() {
+        assert_struct_implementation_as_expected!(
+            item: {
+                #[error(message = "failed", defmt)]
+                struct S;
+            }
+
+            expected: {
+                should panic
+            }
+        )
+    }
+
+    fn to_parameters(attribute: Attribute) -> Parameters {
+        Parameters::from_attribute(&attribute).unwrap()
+    }
+}
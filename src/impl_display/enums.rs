@@ -1,22 +1,28 @@
 use quote::quote;
-use syn::{ItemEnum, Variant};
-use syn::__private::TokenStream2;
+use syn::{ItemEnum, Variant, WherePredicate};
+use syn::__private::{Span, TokenStream2};
 
 use crate::enum_error::VariantWithParams;
-use crate::impl_display::DisplayImplementationError;
+use crate::impl_display::{DisplayImplementationError, implement_display, implement_formatter_call, write_macro};
 use crate::impl_display::DisplayImplementationError::*;
+use crate::impl_display::bounds::{message_bounds, with_bounds};
+use crate::impl_display::defmt::{defmt_span, defmt_write_macro, escape_braces, implement_format};
 use crate::impl_display::match_arm::MatchArmImplementor;
-use crate::parameters::{MESSAGE, Parameters};
+use crate::impl_display::message_const::implement_interpolation;
+use crate::impl_wrap_map::WrapMap;
+use crate::common::{implement_fallback_arm, std_root, UNKNOWN_VARIANT_NAME};
+use crate::parameters::{MESSAGE, MESSAGE_CONST, MSG_KEY, Parameters, PREFIX, SUFFIX};
 
 pub struct EnumDisplayImplementor<'a> {
     item_enum: &'a ItemEnum,
     enum_parameters: &'a Parameters,
-    variants_with_parameters: &'a Vec<VariantWithParams<'a>>
+    variants_with_parameters: &'a Vec<VariantWithParams<'a>>,
+    wrap_map: &'a WrapMap<'a>,
 }
 
 impl<'a> EnumDisplayImplementor<'a> {
-    pub fn new(item_enum: &'a ItemEnum, enum_parameters: &'a Parameters, variants_with_parameters: &'a Vec<VariantWithParams<'a>>) -> Self {
-        EnumDisplayImplementor { item_enum, enum_parameters, variants_with_parameters }
+    pub fn new(item_enum: &'a ItemEnum, enum_parameters: &'a Parameters, variants_with_parameters: &'a Vec<VariantWithParams<'a>>, wrap_map: &'a WrapMap<'a>) -> Self {
+        EnumDisplayImplementor { item_enum, enum_parameters, variants_with_parameters, wrap_map }
     }
 
     /// Create the std::fmt::Display implementation for the given enum and its variants.
@@ -25,44 +31,142 @@ impl<'a> EnumDisplayImplementor<'a> {
     ///
     /// This might fail if
     ///  not every variant has a message set and no default was set
-    ///  OR every variant has a message and a default was set (this is an error to keep the code clean from useless parameters).
+    ///  OR every variant has a message and a default was set (this is an error to keep the code clean from useless parameters)
+    ///  OR a custom formatter was set and any message was set as well.
     pub fn implement(self) -> Result<TokenStream2, DisplayImplementationError> {
         let variants_with_message = self.get_variants_with_message();
 
+        if let Some(formatter_call) = implement_formatter_call(&self.item_enum.ident, self.enum_parameters)? {
+            if let Some((_, _, span)) = variants_with_message.first() {
+                return Err(MessageAndFormatter(self.item_enum.ident.clone(), *span));
+            }
+
+            self.check_message_keys_have_messages(&[])?;
+            return Ok(self.create_formatter_implementation(formatter_call));
+        }
+
+        let variants_with_message = self.add_wrapped_variant_messages(variants_with_message);
+
         if self.display_should_not_be_implemented(&variants_with_message) {
-            return Ok(quote! {})
+            return match self.affix_parameter() {
+                Some((name, span)) => Err(AffixWithoutDisplay(name, self.item_enum.ident.clone(), span)),
+                None => Ok(quote! {})
+            };
         }
 
         self.check_set_messages_are_valid(&variants_with_message)?;
+        self.check_message_keys_have_messages(&variants_with_message)?;
+
+        let root = std_root(self.enum_parameters);
+        let match_arms = variants_with_message
+            .into_iter()
+            .map(|(v, m, span)| {
+                let implementor = MatchArmImplementor::new(&self.item_enum.ident, &m, span).with_write_macro(write_macro(&root));
+                match self.parameters_of(v) {
+                    Some(parameters) => implementor.with_variant_parameters(parameters, root.clone()).implement_for(v),
+                    None => implementor.implement_for(v)
+                }
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        self.create_implementation(match_arms)
+    }
+
+    /// Create the defmt::Format implementation for the given enum if DEFMT is set, which writes the same messages as Display.
+    /// PREFIX and SUFFIX are added to the messages as literal text.
+    ///
+    /// Fails if the enum has no messages or any variant has a MESSAGE_CONST.
+    pub fn implement_defmt(self) -> Result<TokenStream2, DisplayImplementationError> {
+        let span = match defmt_span(self.enum_parameters)? {
+            Some(span) => span,
+            None => return Ok(quote! {})
+        };
+
+        if self.variants_with_parameters.iter().filter_map(|(_, p)| p.as_ref()).any(|p| p.has_parameter(MESSAGE_CONST)) {
+            return Err(DefmtWithRuntimeMessage(MESSAGE_CONST, span));
+        }
+
+        let variants_with_message = self.add_wrapped_variant_messages(self.get_variants_with_message());
+
+        if self.display_should_not_be_implemented(&variants_with_message) {
+            return Err(DefmtWithoutMessage(self.item_enum.ident.clone(), span));
+        }
 
         let match_arms = variants_with_message
             .into_iter()
-            .map(|(v, m)| MatchArmImplementor::new(&self.item_enum.ident, &m).implement_for(v))
-            .collect::<Vec<_>>();
+            .map(|(v, m, message_span)| MatchArmImplementor::new(&self.item_enum.ident, &self.add_affixes(&m), message_span)
+                .with_write_macro(defmt_write_macro())
+                .implement_for(v))
+            .collect::<Result<Vec<_>, _>>()?;
+        let default_match_arm = match (self.enum_parameters.string_for_name(MESSAGE), self.enum_parameters.span_for_name(MESSAGE)) {
+            (Some(m), Some(message_span)) => MatchArmImplementor::new(&self.item_enum.ident, &self.add_affixes(m), message_span)
+                .with_write_macro(defmt_write_macro())
+                .implement_default()?,
+            _ => quote! {}
+        };
+
+        implement_format(&self.item_enum.ident, &self.item_enum.generics, quote! {
+            match self {
+                #(#match_arms,)*
+                #default_match_arm
+            }
+        }, span)
+    }
+
+    /// Return the given message surrounded with PREFIX and SUFFIX as literal text.
+    fn add_affixes(&self, message: &str) -> String {
+        let prefix = self.enum_parameters.string_for_name(PREFIX).unwrap_or_default();
+        let suffix = self.enum_parameters.string_for_name(SUFFIX).unwrap_or_default();
+        format!("{}{}{}", escape_braces(prefix), message, escape_braces(suffix))
+    }
 
-        Ok(self.create_implementation(match_arms))
+    /// Return the bounds the messages of the variants require for generic fields, which are added to the Display implementation
+    /// (see bounds::message_bounds).
+    /// The bounds of the wrapped sources are added as well, as their chain of sources is appended to the messages.
+    fn display_bounds(&self) -> Vec<WherePredicate> {
+        let root = std_root(self.enum_parameters);
+
+        self.add_wrapped_variant_messages(self.get_variants_with_message())
+            .into_iter()
+            .flat_map(|(v, m, _)| message_bounds(&m, &v.fields, &["e"], true, &self.item_enum.generics, &root))
+            .chain(self.wrap_map.source_bounds(&root))
+            .collect()
     }
 
-    /// Return a Vec of all variants witch a set Display message.
-    fn get_variants_with_message(&self) -> Vec<(&Variant, String)> {
+    /// Return a Vec of all variants witch a set Display message, together with the message and its span.
+    /// Variants with MESSAGE_CONST are contained with an empty message, as the message is only known at runtime.
+    fn get_variants_with_message(&self) -> Vec<(&Variant, String, Span)> {
         self.variants_with_parameters
             .iter()
-            .filter_map(|(v, p_opt)| match p_opt {
-                Some(p) => Some((v, p)),
-                _ => None
-            })
-            .filter_map(|(v, p)| match p.string_for_name(MESSAGE) {
-                Some(m) => Some((*v, m)),
-                _ => None
+            .filter_map(|(v, p_opt)| p_opt.as_ref().map(|p| (v, p)))
+            .filter_map(|(v, p)| match p.span_for_name(MESSAGE_CONST) {
+                Some(span) => Some((*v, String::new(), span)),
+                None => Some((*v, p.string_for_name(MESSAGE)?.to_string(), p.span_for_name(MESSAGE)?))
             })
             .collect()
     }
 
+    /// Add the message of every variant listed in WRAP_MAP, which has no message of its own, and of every
+    /// variant marked with FORWARD or OTHER. Such a variant displays the message of the wrapped value.
+    fn add_wrapped_variant_messages<'b>(&'b self, mut variants_with_message: Vec<(&'b Variant, String, Span)>) -> Vec<(&'b Variant, String, Span)> {
+        for variant in self.wrap_map.variants().into_iter().chain(self.wrap_map.forwarded_variants()).chain(self.wrap_map.other_variant()) {
+            if variants_with_message.iter().any(|(v, _, _)| v.ident == variant.ident) {
+                continue;
+            }
+
+            if let Some((message, span)) = self.wrap_map.message_for(variant) {
+                variants_with_message.push((variant, message, span))
+            }
+        }
+
+        variants_with_message
+    }
+
     /// If
     ///  our enum does not have a Display message in it's parameters
     ///  AND none of our variants has a Display message set
     /// Display should not be implemented
-    fn display_should_not_be_implemented(&self, variants_with_message: &Vec<(&Variant, String)>) -> bool {
+    fn display_should_not_be_implemented(&self, variants_with_message: &[(&Variant, String, Span)]) -> bool {
         !self.enum_parameters.has_parameter(MESSAGE) && variants_with_message.is_empty()
     }
 
@@ -70,7 +174,7 @@ impl<'a> EnumDisplayImplementor<'a> {
     ///  not every variant has a message and no default message was set
     ///  OR
     ///  all variants have a message, but a default message was provided anyways.
-    fn check_set_messages_are_valid(&self, variants_with_message: &Vec<(&Variant, String)>) -> Result<(), DisplayImplementationError> {
+    fn check_set_messages_are_valid(&self, variants_with_message: &[(&Variant, String, Span)]) -> Result<(), DisplayImplementationError> {
         let num_variants = self.item_enum.variants.len();
         let num_set_messages = variants_with_message.len();
         let default_message_set = self.enum_parameters.has_parameter(MESSAGE);
@@ -80,39 +184,105 @@ impl<'a> EnumDisplayImplementor<'a> {
         }
 
         if default_message_set && num_variants == num_set_messages {
-            return Err(UnnecessaryDefaultMessage(self.item_enum.ident.clone()))
+            return Err(UnnecessaryDefaultMessage(self.item_enum.ident.clone(), self.enum_parameters.span_for_name(MESSAGE).unwrap()))
         }
 
         Ok(())
     }
 
-    fn create_implementation(&self, match_arms: Vec<TokenStream2>) -> TokenStream2 {
-        let ident = &self.item_enum.ident;
-        let generics = &self.item_enum.generics;
-        let (impl_generics, type_generics, where_clause) = generics.split_for_impl();
-        let default_match_arm = self.create_default_match_arm();
+    /// Check that every variant with MSG_KEY has a message of its own, which is displayed if the key cannot be looked up.
+    /// Variants without a message would fall back to the default message, which cannot display their fields.
+    fn check_message_keys_have_messages(&self, variants_with_message: &[(&Variant, String, Span)]) -> Result<(), DisplayImplementationError> {
+        let variant_without_message = self.variants_with_parameters
+            .iter()
+            .filter(|(v, _)| !variants_with_message.iter().any(|(vm, _, _)| vm.ident == v.ident))
+            .filter_map(|(_, p)| p.as_ref())
+            .find_map(|p| Some((p.string_for_name(MSG_KEY)?, p.span_for_name(MSG_KEY)?)));
 
-        quote! {
-            impl #impl_generics std::fmt::Display for #ident #type_generics #where_clause {
-                fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-                    match self {
-                        #(#match_arms,)*
-                        #default_match_arm
-                    }
-                }
+        match variant_without_message {
+            Some((key, span)) => Err(MsgKeyWithoutMessage(key.to_string(), span)),
+            None => Ok(())
+        }
+    }
+
+    /// Return the parameters of the given variant, if it has any.
+    fn parameters_of(&self, variant: &Variant) -> Option<&Parameters> {
+        self.variants_with_parameters
+            .iter()
+            .find(|(v, _)| v.ident == variant.ident)
+            .and_then(|(_, p)| p.as_ref())
+    }
+
+    fn create_implementation(&self, match_arms: Vec<TokenStream2>) -> Result<TokenStream2, DisplayImplementationError> {
+        let default_match_arm = match self.create_default_match_arm()? {
+            arm if arm.is_empty() => implement_fallback_arm(self.item_enum, quote! {f.write_str(#UNKNOWN_VARIANT_NAME)}),
+            arm => arm
+        };
+
+        let generics = with_bounds(&self.item_enum.generics, &self.display_bounds());
+        let display_implementation = implement_display(&self.item_enum.ident, &generics, self.enum_parameters, self.implement_affixes(quote! {
+            match self {
+                #(#match_arms,)*
+                #default_match_arm
             }
+        }), self.wrap_map.has_source());
+        let uses_message_const = self.variants_with_parameters
+            .iter()
+            .filter_map(|(_, p)| p.as_ref())
+            .any(|p| p.has_parameter(MESSAGE_CONST));
+        let interpolation = match uses_message_const {
+            true => implement_interpolation(&self.item_enum.ident, &self.item_enum.generics, &std_root(self.enum_parameters)),
+            false => quote! {}
+        };
+
+        Ok(quote! {
+            #display_implementation
+            #interpolation
+        })
+    }
+
+    fn create_formatter_implementation(&self, formatter_call: TokenStream2) -> TokenStream2 {
+        let generics = with_bounds(&self.item_enum.generics, &self.display_bounds());
+        implement_display(&self.item_enum.ident, &generics, self.enum_parameters, self.implement_affixes(formatter_call), self.wrap_map.has_source())
+    }
+
+    /// Surround the message of every variant with PREFIX and SUFFIX, if they are set.
+    fn implement_affixes(&self, body: TokenStream2) -> TokenStream2 {
+        let prefix = self.enum_parameters.string_for_name(PREFIX).map(|prefix| quote! {f.write_str(#prefix)?;});
+        let suffix = self.enum_parameters.string_for_name(SUFFIX).map(|suffix| quote! {f.write_str(#suffix)});
+
+        match (prefix, suffix) {
+            (None, None) => body,
+            (prefix, Some(suffix)) => quote! {{
+                #prefix
+                (#body)?;
+                #suffix
+            }},
+            (prefix, None) => quote! {{
+                #prefix
+                #body
+            }}
         }
     }
 
+    /// Return the name and span of PREFIX or SUFFIX, if any of them is set.
+    fn affix_parameter(&self) -> Option<(&'static str, Span)> {
+        [PREFIX, SUFFIX]
+            .iter()
+            .find_map(|name| Some((*name, self.enum_parameters.span_for_name(name)?)))
+    }
+
     /// Create the default match arm for the Display implementation, which is necessary
     /// if not all variants have a message set.
     ///
     /// Note: EnumDisplayImplementor::check_set_messages_are_valid verifies if
     /// some messages are missing and a default is set, so it's not done here again.
-    fn create_default_match_arm(&self) -> TokenStream2 {
-        match self.enum_parameters.string_for_name(MESSAGE) {
-            Some(m) => MatchArmImplementor::new(&self.item_enum.ident, &m).implement_default(),
-            None => quote! {}
+    fn create_default_match_arm(&self) -> Result<TokenStream2, DisplayImplementationError> {
+        match (self.enum_parameters.string_for_name(MESSAGE), self.enum_parameters.span_for_name(MESSAGE)) {
+            (Some(m), Some(span)) => MatchArmImplementor::new(&self.item_enum.ident, m, span)
+                .with_write_macro(write_macro(&std_root(self.enum_parameters)))
+                .implement_default(),
+            _ => Ok(quote! {})
         }
     }
 }
\ No newline at end of file
@@ -0,0 +1,95 @@
+use quote::quote;
+use syn::__private::{Span, TokenStream2};
+
+use crate::impl_display::DisplayImplementationError;
+use crate::impl_display::DisplayImplementationError::*;
+use crate::parameters::{MESSAGE, MESSAGE_CONST, MSG_KEY, Parameters};
+
+/// The environment variable which contains the path to the lookup function for messages with MSG_KEY, like "crate::i18n::lookup".
+/// Set it for a whole workspace with the [env] section of '.cargo/config.toml'.
+#[cfg(feature = "i18n")]
+const LOOKUP_VARIABLE: &str = "ERROR_GENERATOR_I18N";
+
+/// Looks up the messages of structs and variants with MSG_KEY in a localization (like a Fluent bundle) at runtime.
+/// Requires the feature 'i18n'.
+///
+/// The lookup function is registered in ERROR_GENERATOR_I18N and has the signature
+/// 'fn(&str, &[(&str, &dyn Display)]) -> Option<String>'. It gets the key and the fields as named arguments (see implement_named_argument).
+/// If it returns None (like for a missing translation), the inline message (or MESSAGE_CONST) is displayed instead.
+pub struct I18nImplementor<'a> {
+    parameters: &'a Parameters,
+    root: &'a TokenStream2,
+}
+
+impl<'a> I18nImplementor<'a> {
+    pub fn new(parameters: &'a Parameters, root: &'a TokenStream2) -> Self {
+        I18nImplementor { parameters, root }
+    }
+
+    /// Wrap the given write implementation of the inline message into the lookup of the message with MSG_KEY.
+    /// Returns the write implementation unchanged if MSG_KEY is not set.
+    ///
+    /// Fails if MSG_KEY has no inline message to fall back to, the lookup function is not registered
+    /// or the feature 'i18n' is not enabled.
+    pub fn implement(&self, arguments: &[(String, TokenStream2)], write_implementation: TokenStream2) -> Result<TokenStream2, DisplayImplementationError> {
+        let (key, span) = match (self.parameters.string_for_name(MSG_KEY), self.parameters.span_for_name(MSG_KEY)) {
+            (Some(key), Some(span)) => (key, span),
+            _ => return Ok(write_implementation)
+        };
+
+        if !self.parameters.has_parameter(MESSAGE) && !self.parameters.has_parameter(MESSAGE_CONST) {
+            return Err(MsgKeyWithoutMessage(key.to_string(), span));
+        }
+
+        let root = self.root;
+        let arguments = arguments
+            .iter()
+            .map(|(name, value)| quote! {(#name, #value as &dyn #root::fmt::Display)});
+        let lookup = lookup_path(span)?;
+
+        Ok(quote! {
+            match #lookup(#key, &[#(#arguments),*]) {
+                #root::option::Option::Some(message) => f.write_str(&message),
+                #root::option::Option::None => #write_implementation
+            }
+        })
+    }
+}
+
+/// Return the path of the lookup function registered in ERROR_GENERATOR_I18N.
+#[cfg(feature = "i18n")]
+fn lookup_path(span: Span) -> Result<syn::Path, DisplayImplementationError> {
+    parse_lookup_path(std::env::var(LOOKUP_VARIABLE).ok(), span)
+}
+
+#[cfg(feature = "i18n")]
+fn parse_lookup_path(path: Option<String>, span: Span) -> Result<syn::Path, DisplayImplementationError> {
+    let path = path
+        .ok_or_else(|| InvalidI18nLookup(format!("The parameter '{}' requires a lookup function registered in '{}'.", MSG_KEY, LOOKUP_VARIABLE), span))?;
+
+    syn::parse_str::<syn::Path>(path.trim())
+        .map_err(|_| InvalidI18nLookup(format!("'{}' in '{}' is not a valid path to a function.", path.trim(), LOOKUP_VARIABLE), span))
+}
+
+/// Without the feature 'i18n', MSG_KEY cannot be used.
+#[cfg(not(feature = "i18n"))]
+fn lookup_path(span: Span) -> Result<syn::Path, DisplayImplementationError> {
+    Err(I18nFeatureNotEnabled(span))
+}
+
+#[cfg(all(test, feature = "i18n"))]
+mod tests {
+    #[test]
+    fn parse_lookup_path_works() {
+        use syn::__private::Span;
+
+        use crate::common::tokens_to_readable_string;
+        use crate::impl_display::i18n::parse_lookup_path;
+
+        let path = parse_lookup_path(Some(" crate::i18n::lookup ".to_string()), Span::call_site()).ok().unwrap();
+        assert_eq!(tokens_to_readable_string(&path), "crate::i18n::lookup");
+
+        let error = parse_lookup_path(None, Span::call_site()).err().unwrap();
+        assert_eq!(error.to_string(), "The parameter 'msg_key' requires a lookup function registered in 'ERROR_GENERATOR_I18N'.")
+    }
+}
@@ -1,81 +1,267 @@
 use quote::{format_ident, quote};
-use syn::{FieldsNamed, FieldsUnnamed, Ident, Variant};
-use syn::__private::TokenStream2;
+use syn::{Field, FieldsNamed, FieldsUnnamed, Ident, Variant};
+use syn::__private::{Span, TokenStream2};
 use syn::Fields::*;
 
+use crate::common::{cfg_attributes, field_is_redacted, field_is_usable, field_uses_debug_placeholder};
+use crate::impl_display::{check_placeholder_names, check_positional_placeholders, DisplayImplementationError, implement_named_argument};
+use crate::impl_display::DisplayImplementationError::SkippedFieldInVariantMessage;
+use crate::impl_display::i18n::I18nImplementor;
+use crate::impl_display::message_const::MessageConstImplementor;
 use crate::impl_display::write::WriteImplementor;
+use crate::parameters::Parameters;
+use crate::template::Template;
 
 /// Creates match arms for match expressions in an enums std::fmt::Display implementation.
 pub struct MatchArmImplementor<'a> {
     enum_ident: &'a Ident,
-    message: &'a str
+    message: &'a str,
+    message_span: Span,
+    /// The bindings of the fields marked with DEBUG_PLACEHOLDER, like "payload" or "_0"
+    debug_expressions: Vec<String>,
+    /// The members of the variant which are bound in the match arm, together with their binding, like "0" and '_0'
+    bound_members: Vec<(String, TokenStream2)>,
+    /// The accesses of the fields marked with REDACT on e and as their (unbound) bindings, like "e.password" and 'password'
+    redacted_bindings: Vec<(String, Option<String>)>,
+    /// The parameters of the variant together with the std root, used for MSG_KEY and MESSAGE_CONST
+    variant_parameters: Option<(&'a Parameters, TokenStream2)>,
+    /// The fields as named arguments for the lookup of MSG_KEY and MESSAGE_CONST, like "path" and '&path'
+    named_arguments: Vec<(String, TokenStream2)>,
+    /// The macro which writes the message, see WriteImplementor::with_write_macro
+    write_macro: Option<TokenStream2>,
 }
 
 impl<'a> MatchArmImplementor<'a> {
-    pub fn new(enum_ident: &'a Ident, message: &'a str) -> Self {
-        MatchArmImplementor { enum_ident, message }
+    pub fn new(enum_ident: &'a Ident, message: &'a str, message_span: Span) -> Self {
+        MatchArmImplementor { enum_ident, message, message_span, debug_expressions: vec![], bound_members: vec![], redacted_bindings: vec![], variant_parameters: None, named_arguments: vec![], write_macro: None }
     }
 
-    pub fn implement_default(self) -> TokenStream2 {
-        let write_implementation = WriteImplementor::new().implement(self.message.to_string());
+    /// Use the MSG_KEY and MESSAGE_CONST of the given variant parameters, see I18nImplementor and MessageConstImplementor.
+    pub fn with_variant_parameters(mut self, variant_parameters: &'a Parameters, root: TokenStream2) -> Self {
+        self.variant_parameters = Some((variant_parameters, root));
+        self
+    }
+
+    /// Write the message with the given macro instead of write!, see WriteImplementor::with_write_macro.
+    pub fn with_write_macro(mut self, write_macro: TokenStream2) -> Self {
+        self.write_macro = Some(write_macro);
+        self
+    }
 
-        quote! {
+    pub fn implement_default(self) -> Result<TokenStream2, DisplayImplementationError> {
+        check_positional_placeholders(self.message, &self.enum_ident.to_string(), &Unit, self.message_span)?;
+        let write_implementation = self.implement_write()?;
+
+        Ok(quote! {
             _ => #write_implementation
-        }
+        })
     }
 
-    pub fn implement_for(self, variant: &Variant) -> TokenStream2 {
+    pub fn implement_for(mut self, variant: &Variant) -> Result<TokenStream2, DisplayImplementationError> {
+        self.check_field_accesses(variant)?;
+        self.debug_expressions = variant.fields
+            .iter()
+            .enumerate()
+            .filter(|(_, f)| field_uses_debug_placeholder(f))
+            .map(|(i, f)| match &f.ident {
+                Some(ident) => ident.to_string(),
+                None => format!("_{}", i)
+            })
+            .collect();
+        self.bound_members = variant.fields
+            .iter()
+            .enumerate()
+            .filter(|(_, f)| field_is_bound(f))
+            .map(|(i, f)| match &f.ident {
+                Some(ident) => (ident.to_string(), quote!(#ident)),
+                None => {
+                    let binding = format_ident!("_{}", i);
+                    (i.to_string(), quote!(#binding))
+                }
+            })
+            .collect();
+        self.redacted_bindings = variant.fields
+            .iter()
+            .enumerate()
+            .filter(|(_, f)| field_is_redacted(f) && field_is_usable(f))
+            .flat_map(|(i, f)| match &f.ident {
+                Some(ident) => vec![("e".to_string(), Some(ident.to_string())), (ident.to_string(), None)],
+                None => vec![("e".to_string(), Some(i.to_string())), (format!("_{}", i), None)]
+            })
+            .collect();
+        if let Some((_, root)) = &self.variant_parameters {
+            self.named_arguments = variant.fields
+                .iter()
+                .enumerate()
+                .filter(|(_, f)| field_is_usable(f))
+                .map(|(i, f)| match &f.ident {
+                    Some(ident) => implement_named_argument(ident.to_string(), f, quote!(#ident), root),
+                    None => {
+                        let binding = format_ident!("_{}", i);
+                        implement_named_argument(i.to_string(), f, quote!(#binding), root)
+                    }
+                })
+                .collect();
+        }
+
         let ident = &variant.ident;
-        match &variant.fields {
+        let cfg = cfg_attributes(variant);
+        let match_arm = match &variant.fields {
             Named(f) => self.implement_named(ident, f),
             Unnamed(f) => self.implement_unnamed(ident, f),
             Unit => self.implement_unit(ident)
+        }?;
+        Ok(quote! {#cfg #match_arm})
+    }
+
+    /// Check if the message tries to access a skipped field or a phantom marker of the variant with 'e.<field>' or
+    /// a positional placeholder like "{1}". This doesn't work, as these fields are not bound in the match arm. All
+    /// other fields can be accessed like this, as the accesses are replaced with the bound fields
+    /// (see WriteImplementor::with_bound_members).
+    ///
+    /// Afterwards, the placeholders are checked against the fields of the variant (see check_positional_placeholders
+    /// and check_placeholder_names).
+    ///
+    /// Invalid messages are not checked here, they are reported when the write! call is created.
+    fn check_field_accesses(&self, variant: &Variant) -> Result<(), DisplayImplementationError> {
+        let template = match Template::parse(self.message) {
+            Ok(template) => template,
+            Err(_) => return Ok(())
+        };
+
+        let skipped_fields = variant.fields
+            .iter()
+            .enumerate()
+            .filter(|(_, f)| !field_is_usable(f))
+            .map(|(i, f)| match &f.ident {
+                Some(ident) => ident.to_string(),
+                None => i.to_string()
+            })
+            .collect::<Vec<_>>();
+
+        let misused_index = template
+            .positional_indices()
+            .into_iter()
+            .find(|index| skipped_fields.contains(&index.to_string()));
+
+        if let Some(index) = misused_index {
+            return Err(SkippedFieldInVariantMessage(format!("{{{}}}", index), self.message_span));
+        }
+
+        let misused_field = template
+            .members_accessed_on("e")
+            .into_iter()
+            .find(|member| skipped_fields.contains(member));
+
+        if let Some(field) = misused_field {
+            return Err(SkippedFieldInVariantMessage(format!("e.{}", field), self.message_span));
         }
+
+        let members = variant.fields
+            .iter()
+            .enumerate()
+            .map(|(i, f)| match &f.ident {
+                Some(ident) => ident.to_string(),
+                None => i.to_string()
+            })
+            .collect::<Vec<_>>();
+        let bindings = variant.fields
+            .iter()
+            .enumerate()
+            .filter(|(_, f)| field_is_usable(f))
+            .map(|(i, f)| match &f.ident {
+                Some(ident) => ident.to_string(),
+                None => format!("_{}", i)
+            })
+            .collect::<Vec<_>>();
+        let item = format!("{}::{}", self.enum_ident, variant.ident);
+        check_positional_placeholders(self.message, &item, &variant.fields, self.message_span)?;
+        check_placeholder_names(self.message, &item, &["e"], &members, &bindings, self.message_span)
     }
 
-    fn implement_named(self, variant_ident: &Ident, fields: &FieldsNamed) -> TokenStream2 {
+    fn implement_write(mut self) -> Result<TokenStream2, DisplayImplementationError> {
+        let (parameters, root) = match self.variant_parameters.take() {
+            Some((parameters, root)) => (parameters, root),
+            None => return self.implement_message_write()
+        };
+        let named_arguments = std::mem::take(&mut self.named_arguments);
+
+        let write_implementation = match MessageConstImplementor::new(parameters).implement(&named_arguments, &root)? {
+            Some(call) => call,
+            None => self.implement_message_write()?
+        };
+        I18nImplementor::new(parameters, &root).implement(&named_arguments, write_implementation)
+    }
+
+    fn implement_message_write(self) -> Result<TokenStream2, DisplayImplementationError> {
+        let implementor = WriteImplementor::new(self.message_span)
+            .with_debug_expressions(self.debug_expressions)
+            .with_bound_members(self.bound_members)
+            .with_redacted_accesses(self.redacted_bindings);
+
+        match self.write_macro {
+            Some(write_macro) => implementor.with_write_macro(write_macro).implement(self.message),
+            None => implementor.implement(self.message)
+        }
+    }
+
+    fn implement_named(self, variant_ident: &Ident, fields: &FieldsNamed) -> Result<TokenStream2, DisplayImplementationError> {
         let field_names = fields.named
             .iter()
+            .filter(|f| field_is_bound(f))
             .map(|f| f.ident.as_ref().unwrap());
+        let rest = match fields.named.iter().all(field_is_bound) {
+            true => quote!(),
+            false => quote!(..)
+        };
 
         let enum_ident = self.enum_ident;
-        let write_implementation = WriteImplementor::new().implement(self.message.to_string());
+        let write_implementation = self.implement_write()?;
 
-        quote! {
-           #enum_ident :: #variant_ident { #(#field_names,)* } => #write_implementation
-        }
+        Ok(quote! {
+           #enum_ident :: #variant_ident { #(#field_names,)* #rest } => #write_implementation
+        })
     }
 
-    fn implement_unnamed(self, variant_ident: &Ident, fields: &FieldsUnnamed) -> TokenStream2 {
-        let field_names = (0..fields.unnamed.len())
-            .into_iter()
-            .map(|i| format!("_{}", i))
-            .map(|ident_str| {
-                let ident = format_ident!("{}", ident_str);
-                quote!(#ident)
+    fn implement_unnamed(self, variant_ident: &Ident, fields: &FieldsUnnamed) -> Result<TokenStream2, DisplayImplementationError> {
+        let field_names = fields.unnamed
+            .iter()
+            .enumerate()
+            .map(|(i, f)| match field_is_bound(f) {
+                false => quote!(_),
+                true => {
+                    let ident = format_ident!("_{}", i);
+                    quote!(#ident)
+                }
             });
 
         let enum_ident = self.enum_ident;
-        let write_implementation = WriteImplementor::new().implement(self.message.to_string());
+        let write_implementation = self.implement_write()?;
 
-        quote! {
+        Ok(quote! {
             #enum_ident :: #variant_ident ( #(#field_names,)* ) => #write_implementation
-        }
+        })
     }
 
-    fn implement_unit(self, variant_ident: &Ident) -> TokenStream2 {
+    fn implement_unit(self, variant_ident: &Ident) -> Result<TokenStream2, DisplayImplementationError> {
         let enum_ident = self.enum_ident;
-        let write_implementation = WriteImplementor::new().implement(self.message.to_string());
+        let write_implementation = self.implement_write()?;
 
-        quote! {
+        Ok(quote! {
             #enum_ident :: #variant_ident => #write_implementation
-        }
+        })
     }
 }
 
+/// Check if the field is bound in a match arm. Redacted fields are never displayed, so they are not bound either.
+fn field_is_bound(field: &Field) -> bool {
+    field_is_usable(field) && !field_is_redacted(field)
+}
+
 #[cfg(test)]
 mod tests {
     use syn::{parse_quote, Variant};
+    use syn::__private::Span;
 
     use crate::impl_display::match_arm::MatchArmImplementor;
     use crate::test_helper::assert_tokens_are_equal;
@@ -108,6 +294,26 @@ mod tests {
         assert_tokens_are_equal(ts, expected)
     }
 
+    #[test]
+    fn implement_named_skipped_works() {
+        let var = parse_quote!(Foo {val: usize, #[error(skip)] buffer: Vec<u8>});
+        let message = "Print the val: {val}";
+
+        let ts = implement_for(var, message);
+        let expected = r#"Enum::Foo { val, .. } => write!(f, "Print the val: {}", val)"#;
+        assert_tokens_are_equal(ts, expected)
+    }
+
+    #[test]
+    fn implement_unnamed_skipped_works() {
+        let var = parse_quote!(Foo (#[error(skip)] Vec<u8>, usize));
+        let message = "Print the val: {_1}";
+
+        let ts = implement_for(var, message);
+        let expected = r#"Enum::Foo( _, _1, ) => write!(f, "Print the val: {}", _1)"#;
+        assert_tokens_are_equal(ts, expected)
+    }
+
     #[test]
     fn implement_unit_works() {
         let var = parse_quote!(Foo);
@@ -118,12 +324,95 @@ mod tests {
         assert_tokens_are_equal(ts, expected)
     }
 
+    #[test]
+    fn implement_field_access_on_e_works() {
+        let var = parse_quote!(Foo {path: std::path::PathBuf});
+        let message = "Print the path: {e.path.display()}";
+
+        let ts = implement_for(var, message);
+        let expected = r#"Enum::Foo { path, } => write!(f, "Print the path: {}", path.display())"#;
+        assert_tokens_are_equal(ts, expected)
+    }
+
+    #[test]
+    fn implement_index_access_on_e_works() {
+        let var = parse_quote!(Foo (usize));
+        let message = "Print the square: {e.0.pow(2)}";
+
+        let ts = implement_for(var, message);
+        let expected = r#"Enum::Foo( _0, ) => write!(f, "Print the square: {}", _0.pow(2))"#;
+        assert_tokens_are_equal(ts, expected)
+    }
+
+    #[test]
+    fn implement_skipped_field_access_on_e_fails() {
+        let var = parse_quote!(Foo {val: usize, #[error(skip)] buffer: Vec<u8>});
+        let error = MatchArmImplementor::new(&parse_quote!(Enum), "{e.buffer.len()}", Span::call_site()).implement_for(&var).err().unwrap();
+        assert_eq!(error.to_string(), "'e.buffer' cannot be used in this variant message, as the field is skipped or a PhantomData marker.")
+    }
+
+    #[test]
+    fn implement_skipped_positional_placeholder_fails() {
+        let var = parse_quote!(Foo (u8, #[error(skip)] u8));
+        let error = MatchArmImplementor::new(&parse_quote!(Enum), "x {1}", Span::call_site()).implement_for(&var).err().unwrap();
+        assert_eq!(error.to_string(), "'{1}' cannot be used in this variant message, as the field is skipped or a PhantomData marker.");
+
+        let var = parse_quote!(Foo (u8, std::marker::PhantomData<u8>));
+        let error = MatchArmImplementor::new(&parse_quote!(Enum), "x {1}", Span::call_site()).implement_for(&var).err().unwrap();
+        assert_eq!(error.to_string(), "'{1}' cannot be used in this variant message, as the field is skipped or a PhantomData marker.")
+    }
+
+    #[test]
+    fn implement_unknown_field_fails() {
+        let var = parse_quote!(Foo {line: usize, path: String});
+        let error = MatchArmImplementor::new(&parse_quote!(Enum), "{lien} in {path}", Span::call_site()).implement_for(&var).err().unwrap();
+        assert_eq!(error.to_string(), "'lien' in the message of 'Enum::Foo' is no field. Valid names are: line, path.");
+
+        let error = MatchArmImplementor::new(&parse_quote!(Enum), "{e.lien}", Span::call_site()).implement_for(&var).err().unwrap();
+        assert_eq!(error.to_string(), "'e.lien' in the message of 'Enum::Foo' is no field. Valid names are: e.line, e.path.");
+
+        let error = MatchArmImplementor::new(&parse_quote!(Enum), "{_1}", Span::call_site()).implement_for(&parse_quote!(Foo)).err().unwrap();
+        assert_eq!(error.to_string(), "'_1' in the message of 'Enum::Foo' is no field, as 'Enum::Foo' has no fields.")
+    }
+
+    #[test]
+    fn implement_positional_placeholders_works() {
+        let var = parse_quote!(Foo (usize, #[error(skip)] Vec<u8>, String));
+        let message = "{2} {0:>4}";
+
+        let ts = implement_for(var, message);
+        let expected = r#"Enum::Foo( _0, _, _2, ) => write!(f, "{} {:>4}", _2, _0)"#;
+        assert_tokens_are_equal(ts, expected)
+    }
+
+    #[test]
+    fn implement_positional_placeholder_out_of_range_fails() {
+        let error = MatchArmImplementor::new(&parse_quote!(Enum), "{3}", Span::call_site()).implement_for(&parse_quote!(Foo (usize, usize))).err().unwrap();
+        assert_eq!(error.to_string(), "'{3}' in the message of 'Enum::Foo' is out of range, as 'Enum::Foo' has 2 unnamed fields.");
+
+        let error = MatchArmImplementor::new(&parse_quote!(Enum), "{0}", Span::call_site()).implement_for(&parse_quote!(Foo {val: usize})).err().unwrap();
+        assert_eq!(error.to_string(), "'{0}' in the message of 'Enum::Foo' is out of range, as 'Enum::Foo' has no unnamed fields.");
+
+        let error = MatchArmImplementor::new(&parse_quote!(Enum), "{0}", Span::call_site()).implement_default().err().unwrap();
+        assert_eq!(error.to_string(), "'{0}' in the message of 'Enum' is out of range, as 'Enum' has no unnamed fields.")
+    }
+
+    #[test]
+    fn implement_names_which_are_no_fields_works() {
+        let var = parse_quote!(Foo (usize));
+        let message = "{_0} {MAX} {self.kind()} {f.alternate()} {format_value(_0)}";
+
+        let ts = implement_for(var, message);
+        let expected = r#"Enum::Foo( _0, ) => write!(f, "{} {} {} {} {}", _0, MAX, self.kind(), f.alternate(), format_value(_0))"#;
+        assert_tokens_are_equal(ts, expected)
+    }
+
     fn implement_default(message: &str) -> String {
-        MatchArmImplementor::new(&parse_quote!(Enum), message).implement_default().to_string()
+        MatchArmImplementor::new(&parse_quote!(Enum), message, Span::call_site()).implement_default().unwrap().to_string()
     }
 
     /// Implement the match arm for the given variant and message. The enum ident is always "Enum".
     fn implement_for(var: Variant, message: &str) -> String {
-        MatchArmImplementor::new(&parse_quote!(Enum), message).implement_for(&var).to_string()
+        MatchArmImplementor::new(&parse_quote!(Enum), message, Span::call_site()).implement_for(&var).unwrap().to_string()
     }
 }
\ No newline at end of file
@@ -0,0 +1,94 @@
+use quote::{format_ident, quote};
+use syn::__private::TokenStream2;
+use syn::{Generics, Ident, Visibility};
+
+use crate::common::trait_impl_attributes;
+use crate::impl_display::DisplayImplementationError;
+use crate::impl_display::DisplayImplementationError::MemoizeWithoutDisplay;
+use crate::parameters::{MEMOIZE_DISPLAY, Parameters};
+
+/// Creates a wrapper 'Memoized<name of the error>', which renders the message of the error only once and
+/// caches it. This helps if the message expressions are expensive and the error is formatted repeatedly,
+/// for example by logging layers.
+///
+/// The wrapper is created with the method 'memoized' on the error.
+pub struct MemoizeImplementor<'a> {
+    ident: &'a Ident,
+    vis: &'a Visibility,
+    generics: &'a Generics,
+    parameters: &'a Parameters,
+    /// Additional derives for the wrapper, see HELPER_DERIVE
+    helper_derive: &'a TokenStream2,
+}
+
+impl<'a> MemoizeImplementor<'a> {
+    pub fn new(ident: &'a Ident, vis: &'a Visibility, generics: &'a Generics, parameters: &'a Parameters, helper_derive: &'a TokenStream2) -> Self {
+        MemoizeImplementor { ident, vis, generics, parameters, helper_derive }
+    }
+
+    /// Create the wrapper if MEMOIZE_DISPLAY is set.
+    ///
+    /// Fails if the error has no Display implementation, as there is nothing to cache.
+    pub fn implement(self, display_implemented: bool) -> Result<TokenStream2, DisplayImplementationError> {
+        let span = match self.parameters.span_for_name(MEMOIZE_DISPLAY) {
+            Some(span) if self.parameters.bool_for_name(MEMOIZE_DISPLAY) => span,
+            _ => return Ok(quote! {})
+        };
+
+        if !display_implemented {
+            return Err(MemoizeWithoutDisplay(self.ident.clone(), span));
+        }
+
+        let ident = self.ident;
+        let vis = self.vis;
+        let helper_derive = self.helper_derive;
+        let wrapper_ident = format_ident!("Memoized{}", ident);
+        let wrapper_doc = format!(" Wrapper around '{}' which renders its message only once.", ident);
+        let (impl_generics, type_generics, where_clause) = self.generics.split_for_impl();
+
+        let impl_attributes = trait_impl_attributes();
+
+        Ok(quote! {
+            #[doc = #wrapper_doc]
+            #[derive(Debug)]
+            #helper_derive
+            #vis struct #wrapper_ident #impl_generics #where_clause {
+                error: #ident #type_generics,
+                message: ::std::sync::OnceLock<::std::string::String>,
+            }
+
+            impl #impl_generics #ident #type_generics #where_clause {
+                #[doc = " Wrap this error, so its message is only rendered once."]
+                pub fn memoized(self) -> #wrapper_ident #type_generics {
+                    #wrapper_ident { error: self, message: ::std::sync::OnceLock::new() }
+                }
+            }
+
+            impl #impl_generics #wrapper_ident #type_generics #where_clause {
+                #[doc = " Return the wrapped error."]
+                pub fn error(&self) -> &#ident #type_generics {
+                    &self.error
+                }
+
+                #[doc = " Return the wrapped error and discard the cached message."]
+                pub fn into_inner(self) -> #ident #type_generics {
+                    self.error
+                }
+            }
+
+            #impl_attributes
+            impl #impl_generics ::std::fmt::Display for #wrapper_ident #type_generics #where_clause {
+                fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+                    f.write_str(self.message.get_or_init(|| ::std::string::ToString::to_string(&self.error)))
+                }
+            }
+
+            #impl_attributes
+            impl #impl_generics ::std::error::Error for #wrapper_ident #type_generics #where_clause {
+                fn source(&self) -> ::std::option::Option<&(dyn ::std::error::Error + 'static)> {
+                    ::std::error::Error::source(&self.error)
+                }
+            }
+        })
+    }
+}
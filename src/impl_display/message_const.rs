@@ -0,0 +1,130 @@
+use quote::quote;
+use syn::__private::TokenStream2;
+use syn::{Generics, Ident, Path};
+
+use crate::impl_display::DisplayImplementationError;
+use crate::impl_display::DisplayImplementationError::*;
+use crate::parameters::{MESSAGE, MESSAGE_CONST, Parameters};
+
+/// Creates the Display of structs and variants whose message is a const or static '&str' set with MESSAGE_CONST,
+/// like 'message_const = "crate::messages::READ_FAILED"'. This way, all messages can be kept in one module.
+///
+/// The value of the const is unknown while the macro expands, so its placeholders are filled at runtime
+/// (see implement_interpolation). Only fields can be used as placeholders, like "{path}", "{self.path}", "{e.path}" or "{0}".
+pub struct MessageConstImplementor<'a> {
+    parameters: &'a Parameters,
+}
+
+impl<'a> MessageConstImplementor<'a> {
+    pub fn new(parameters: &'a Parameters) -> Self {
+        MessageConstImplementor { parameters }
+    }
+
+    /// Create the call of the interpolation of the const with the given named arguments (see implement_named_argument).
+    /// Returns None if MESSAGE_CONST is not set.
+    ///
+    /// Fails if a message is set as well or the value is not a path.
+    pub fn implement(&self, arguments: &[(String, TokenStream2)], root: &TokenStream2) -> Result<Option<TokenStream2>, DisplayImplementationError> {
+        let (path, span) = match (self.parameters.string_for_name(MESSAGE_CONST), self.parameters.span_for_name(MESSAGE_CONST)) {
+            (Some(path), Some(span)) => (path, span),
+            _ => return Ok(None)
+        };
+
+        if self.parameters.has_parameter(MESSAGE) {
+            return Err(MessageAndMessageConst(span));
+        }
+
+        let path = syn::parse_str::<Path>(path).map_err(|_| InvalidMessageConstPath(path.to_string(), span))?;
+        let arguments = arguments
+            .iter()
+            .map(|(name, value)| quote! {(#name, #value as &dyn #root::fmt::Display)});
+
+        Ok(Some(quote! {Self::__interpolate_message(f, #path, &[#(#arguments),*])}))
+    }
+}
+
+/// Create the function which writes a message of MESSAGE_CONST with its placeholders filled with the named arguments.
+/// "{{" and "}}" are escaped braces and unknown placeholders are written unchanged.
+pub fn implement_interpolation(ident: &Ident, generics: &Generics, root: &TokenStream2) -> TokenStream2 {
+    let (impl_generics, type_generics, where_clause) = generics.split_for_impl();
+
+    quote! {
+        impl #impl_generics #ident #type_generics #where_clause {
+            #[doc(hidden)]
+            fn __interpolate_message(f: &mut #root::fmt::Formatter<'_>, message: &str, arguments: &[(&str, &dyn #root::fmt::Display)]) -> #root::fmt::Result {
+                let mut rest = message;
+
+                while let #root::option::Option::Some(i) = rest.find(&['{', '}'][..]) {
+                    f.write_str(&rest[..i])?;
+                    let brace = &rest[i..i + 1];
+                    rest = &rest[i + 1..];
+
+                    let end = match (brace, rest.find('}')) {
+                        ("{", #root::option::Option::Some(end)) if !rest.starts_with('{') => end,
+                        _ => {
+                            f.write_str(brace)?;
+                            rest = rest.strip_prefix(brace).unwrap_or(rest);
+                            continue;
+                        }
+                    };
+
+                    let name = rest[..end].trim();
+                    let field = name.strip_prefix("self.").or_else(|| name.strip_prefix("e.")).unwrap_or(name);
+                    match arguments.iter().find(|(n, _)| *n == field) {
+                        #root::option::Option::Some((_, value)) => #root::fmt::Display::fmt(value, f)?,
+                        #root::option::Option::None => #root::write!(f, "{{{}}}", name)?
+                    }
+                    rest = &rest[end + 1..];
+                }
+
+                f.write_str(rest)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use quote::quote;
+    use syn::{Attribute, parse_quote};
+
+    use crate::impl_display::message_const::MessageConstImplementor;
+    use crate::parameters::Parameters;
+    use crate::test_helper::assert_tokens_are_equal;
+
+    #[test]
+    fn implement_works() {
+        let parameters = to_parameters(parse_quote!(#[error(message_const = "crate::messages::READ_FAILED")]));
+        let arguments = vec![("path".to_string(), quote!(&self.path))];
+
+        let ts = MessageConstImplementor::new(&parameters).implement(&arguments, &quote!(std)).ok().unwrap().unwrap().to_string();
+        let expected = quote! {
+            Self::__interpolate_message(f, crate::messages::READ_FAILED, &[("path", &self.path as &dyn std::fmt::Display)])
+        }.to_string();
+        assert_tokens_are_equal(ts, expected)
+    }
+
+    #[test]
+    fn implement_without_message_const_works() {
+        let parameters = to_parameters(parse_quote!(#[error(message = "failed")]));
+        assert!(MessageConstImplementor::new(&parameters).implement(&[], &quote!(std)).ok().unwrap().is_none())
+    }
+
+    #[test]
+    fn implement_with_message_fails() {
+        let parameters = to_parameters(parse_quote!(#[error(message = "failed", message_const = "READ_FAILED")]));
+        let error = MessageConstImplementor::new(&parameters).implement(&[], &quote!(std)).err().unwrap();
+        assert_eq!(error.to_string(), "The parameters 'message' and 'message_const' cannot be used together. Choose only one.")
+    }
+
+    #[test]
+    fn implement_invalid_path_fails() {
+        let parameters = to_parameters(parse_quote!(#[error(message_const = "messages::")]));
+        let error = MessageConstImplementor::new(&parameters).implement(&[], &quote!(std)).err().unwrap();
+        assert_eq!(error.to_string(), "'messages::' is not a valid path to a const or static message.")
+    }
+
+    fn to_parameters(attribute: Attribute) -> Parameters {
+        Parameters::from_attribute(&attribute).unwrap()
+    }
+}
@@ -1,18 +1,299 @@
 use std::fmt::Formatter;
 
-use syn::Ident;
+use quote::quote;
+use syn::__private::{Span, TokenStream2};
+use syn::{Field, Fields, Generics, Ident, Path};
 
+use crate::common::{REDACTED, field_is_redacted, field_uses_debug_placeholder, std_root, trait_impl_attributes};
 use crate::impl_display::DisplayImplementationError::*;
+use crate::parameters::{DEFMT, FMT, MESSAGE, MESSAGE_CONST, MSG_KEY, Parameters, RENDER_INTO, RUNTIME_VERBOSITY};
+use crate::template::{Template, TemplateError};
 
 pub mod structs;
 pub mod enums;
 pub mod write;
+pub mod memoize;
+pub mod i18n;
+pub mod message_const;
+pub mod defmt;
+pub mod render_into;
+pub mod bounds;
 mod match_arm;
 
+/// Create the std::fmt::Display implementation with the given body of 'fmt', together with the
+/// methods which depend on it.
+///
+/// If the error has a source, the body is extended to append the chain of sources when the alternate flag is set ('{:#}').
+/// If RUNTIME_VERBOSITY is set, the body is extended to also print the Debug representation of the error
+/// while the verbosity of the error type is enabled.
+pub fn implement_display(ident: &Ident, generics: &Generics, parameters: &Parameters, body: TokenStream2, has_source: bool) -> TokenStream2 {
+    let (impl_generics, type_generics, where_clause) = generics.split_for_impl();
+    let root = std_root(parameters);
+
+    let body = match has_source {
+        true => implement_source_chain_body(body, &root),
+        false => body
+    };
+
+    let (body, verbosity_switch) = match parameters.bool_for_name(RUNTIME_VERBOSITY) {
+        true => (implement_verbose_body(body, &root), implement_verbosity_switch(ident, generics, &root)),
+        false => (body, quote! {})
+    };
+
+    let impl_attributes = trait_impl_attributes();
+
+    quote! {
+        #impl_attributes
+        impl #impl_generics #root::fmt::Display for #ident #type_generics #where_clause {
+            fn fmt(&self, f: &mut #root::fmt::Formatter<'_>) -> #root::fmt::Result {
+                #body
+            }
+        }
+
+        #verbosity_switch
+    }
+}
+
+/// Extend the body of 'fmt' to append every source of the error, like "reading failed: caused by: file not found",
+/// if the alternate flag is set. The sources are displayed without the flag, so every source is only written once.
+fn implement_source_chain_body(body: TokenStream2, root: &TokenStream2) -> TokenStream2 {
+    quote! {{
+        (#body)?;
+
+        if f.alternate() {
+            let mut source = #root::error::Error::source(self);
+            while let #root::option::Option::Some(error) = source {
+                #root::write!(f, ": caused by: {}", error)?;
+                source = #root::error::Error::source(error);
+            }
+        }
+
+        #root::result::Result::Ok(())
+    }}
+}
+
+/// Extend the body of 'fmt' to append the Debug representation of the error if the verbosity is enabled.
+fn implement_verbose_body(body: TokenStream2, root: &TokenStream2) -> TokenStream2 {
+    quote! {
+        (#body)?;
+
+        match Self::error_verbosity() {
+            true => #root::write!(f, " ({:?})", self),
+            false => #root::result::Result::Ok(())
+        }
+    }
+}
+
+/// Create the methods to switch the verbosity of the Display implementation at runtime.
+///
+/// The flag is a static, so it is shared by the whole process (and by all instances of generic errors).
+fn implement_verbosity_switch(ident: &Ident, generics: &Generics, root: &TokenStream2) -> TokenStream2 {
+    let (impl_generics, type_generics, where_clause) = generics.split_for_impl();
+
+    quote! {
+        impl #impl_generics #ident #type_generics #where_clause {
+            #[doc(hidden)]
+            fn __verbosity_flag() -> &'static #root::sync::atomic::AtomicBool {
+                static VERBOSITY: #root::sync::atomic::AtomicBool = #root::sync::atomic::AtomicBool::new(false);
+                &VERBOSITY
+            }
+
+            #[doc = " Enable or disable the verbose messages of this error type for the whole process."]
+            pub fn set_error_verbosity(verbose: bool) {
+                Self::__verbosity_flag().store(verbose, #root::sync::atomic::Ordering::Relaxed)
+            }
+
+            #[doc = " Return if the verbose messages of this error type are enabled."]
+            pub fn error_verbosity() -> bool {
+                Self::__verbosity_flag().load(#root::sync::atomic::Ordering::Relaxed)
+            }
+        }
+    }
+}
+
+/// Return the macro which writes the messages in Display implementations, which is write! of the given std root.
+pub fn write_macro(root: &TokenStream2) -> TokenStream2 {
+    quote!(#root::write)
+}
+
+/// Create a call of the custom formatter function set with the parameter FMT, like
+/// 'my_module::format_my_error(self, f)'. Returns None if FMT is not set.
+///
+/// Fails if the path is invalid or the item also has a message, as only one of both can be used.
+pub fn implement_formatter_call(ident: &Ident, parameters: &Parameters) -> Result<Option<TokenStream2>, DisplayImplementationError> {
+    let (path, span) = match (parameters.string_for_name(FMT), parameters.span_for_name(FMT)) {
+        (Some(path), Some(span)) => (path, span),
+        _ => return Ok(None)
+    };
+
+    if parameters.has_parameter(MESSAGE) || parameters.has_parameter(MESSAGE_CONST) {
+        return Err(MessageAndFormatter(ident.clone(), span));
+    }
+
+    let path = syn::parse_str::<Path>(path).map_err(|_| InvalidFormatterPath(path.to_string(), span))?;
+    Ok(Some(quote! {#path(self, f)}))
+}
+
+/// Return the named argument for the given field, which is accessed with the given expression, like "path" and '&self.path'.
+/// Named arguments are passed as '&dyn Display' to the lookup of MSG_KEY and the interpolation of MESSAGE_CONST.
+/// Redacted fields are passed as REDACTED and fields marked with DEBUG_PLACEHOLDER are formatted with Debug.
+pub fn implement_named_argument(name: String, field: &Field, access: TokenStream2, root: &TokenStream2) -> (String, TokenStream2) {
+    let value = match (field_is_redacted(field), field_uses_debug_placeholder(field)) {
+        (true, _) => quote! {&#REDACTED},
+        (false, true) => quote! {&#root::format_args!("{:?}", #access)},
+        (false, false) => quote! {&#access}
+    };
+
+    (name, value)
+}
+
+/// Names which are in scope of every message and never refer to fields: the error itself, the alias 'e' for it
+/// and the formatter.
+const SCOPE_NAMES: &[&str] = &["self", "e", "f"];
+
+/// Check if the placeholders of the given message of the given item only refer to existing fields. Members accessed on
+/// the given receivers (like "line" in "{self.line}") must be one of the given members, and variables (like 'line' in
+/// "{line.to_string()}") one of the given bindings. Variables starting with an uppercase letter (like consts) and
+/// names in scope of every message (see SCOPE_NAMES) are not checked, as they are no fields.
+///
+/// Invalid messages are not checked here, they are reported when the write! call is created.
+pub fn check_placeholder_names(message: &str, item: &str, receivers: &[&str], members: &[String], bindings: &[String], span: Span) -> Result<(), DisplayImplementationError> {
+    let template = match Template::parse(message) {
+        Ok(template) => template,
+        Err(_) => return Ok(())
+    };
+
+    let accesses = || members.iter().map(|member| format!("{}.{}", receivers[0], member)).collect::<Vec<_>>();
+    let unknown_member = receivers
+        .iter()
+        .flat_map(|receiver| template
+            .members_accessed_on(receiver)
+            .into_iter()
+            .filter(|member| !members.contains(member))
+            .map(move |member| format!("{}.{}", receiver, member)))
+        .next();
+    if let Some(access) = unknown_member {
+        return Err(UnknownField(access, item.to_string(), accesses(), span));
+    }
+
+    let unknown_variable = template
+        .variables()
+        .into_iter()
+        .filter(|variable| !SCOPE_NAMES.contains(&variable.as_str()) && !variable.starts_with(|c: char| c.is_uppercase()))
+        .find(|variable| !bindings.contains(variable));
+    match unknown_variable {
+        // without bindings (like in structs), fields can only be accessed on the receiver
+        Some(variable) if bindings.is_empty() && members.contains(&variable) => {
+            let access = format!("{}.{}", receivers[0], variable);
+            Err(FieldWithoutReceiver(variable, item.to_string(), access, span))
+        }
+        Some(variable) if bindings.is_empty() => Err(UnknownField(variable, item.to_string(), accesses(), span)),
+        Some(variable) => Err(UnknownField(variable, item.to_string(), bindings.to_vec(), span)),
+        None => Ok(())
+    }
+}
+
+/// Check if the positional placeholders of the given message of the given item (like "{1}") refer to one of its
+/// unnamed fields. Positional placeholders are accesses of the unnamed fields on 'e' (see Template::resolve_positional_placeholders).
+///
+/// Invalid messages are not checked here, they are reported when the write! call is created.
+pub fn check_positional_placeholders(message: &str, item: &str, fields: &Fields, span: Span) -> Result<(), DisplayImplementationError> {
+    let unnamed_fields = match fields {
+        Fields::Unnamed(unnamed) => unnamed.unnamed.len(),
+        _ => 0
+    };
+
+    let out_of_range = Template::parse(message)
+        .ok()
+        .and_then(|template| template.positional_indices().into_iter().find(|index| *index >= unnamed_fields));
+    match out_of_range {
+        Some(index) => Err(PositionalPlaceholderOutOfRange(index, item.to_string(), unnamed_fields, span)),
+        None => Ok(())
+    }
+}
+
+/// Error that might occur when the generation of a std::fmt::Display implementation
+/// fails for structs or enums.
 #[derive(Debug)]
 pub enum DisplayImplementationError {
     MissingMessages(Ident),
-    UnnecessaryDefaultMessage(Ident)
+    UnnecessaryDefaultMessage(Ident, Span),
+    /// A message could not be parsed into a template, like an expression which is opened with '{', but never closed.
+    InvalidTemplate(TemplateError, Span),
+    /// A message and a custom formatter were both provided for the same item.
+    MessageAndFormatter(Ident, Span),
+    /// The value of the FMT parameter is not a path to a function.
+    InvalidFormatterPath(String, Span),
+    /// A variant message accesses a skipped field or a phantom marker of the variant with 'e.<field>' or a positional
+    /// placeholder, but these fields are not bound. Contains the access, like 'e.buffer' or '{1}'.
+    SkippedFieldInVariantMessage(String, Span),
+    /// A placeholder refers to a field (the first contained name) which the contained item does not have.
+    /// Contains the names which can be used instead.
+    UnknownField(String, String, Vec<String>, Span),
+    /// A placeholder refers to a field (the first contained name) of the contained item without the receiver,
+    /// like "{line}" instead of "{self.line}". Contains the access which works.
+    FieldWithoutReceiver(String, String, String, Span),
+    /// A positional placeholder refers to an unnamed field (the contained index) which the contained item does not have.
+    /// Contains the number of unnamed fields of the item.
+    PositionalPlaceholderOutOfRange(usize, String, usize, Span),
+    /// MEMOIZE_DISPLAY was set, but the error has no Display implementation.
+    MemoizeWithoutDisplay(Ident, Span),
+    /// PREFIX or SUFFIX (the contained name) was set, but the enum has no Display implementation.
+    AffixWithoutDisplay(&'static str, Ident, Span),
+    /// MESSAGE_CONST was set together with a message.
+    MessageAndMessageConst(Span),
+    /// The value of MESSAGE_CONST is not a path to a const or static.
+    InvalidMessageConstPath(String, Span),
+    /// MSG_KEY was set without an inline message to fall back to. Contains the key.
+    MsgKeyWithoutMessage(String, Span),
+    /// The lookup function for MSG_KEY is not registered or invalid, with a description of the problem.
+    #[cfg(feature = "i18n")]
+    InvalidI18nLookup(String, Span),
+    /// MSG_KEY was used, but the feature 'i18n' is not enabled.
+    #[allow(dead_code)]
+    I18nFeatureNotEnabled(Span),
+    /// DEFMT was set, but the error has no message which could be written with defmt.
+    DefmtWithoutMessage(Ident, Span),
+    /// DEFMT was set together with a parameter (the contained name) whose message is only known at runtime.
+    DefmtWithRuntimeMessage(&'static str, Span),
+    /// DEFMT was used, but the feature 'defmt' is not enabled.
+    #[allow(dead_code)]
+    DefmtFeatureNotEnabled(Span),
+    /// RENDER_INTO was set, but the error has no Display implementation.
+    RenderIntoWithoutDisplay(Ident, Span),
+    /// RENDER_INTO was used, but the feature 'embedded' is not enabled.
+    #[allow(dead_code)]
+    RenderIntoFeatureNotEnabled(Span),
+}
+
+impl DisplayImplementationError {
+    /// Return the span of the code which caused this error.
+    pub fn span(&self) -> Span {
+        match self {
+            MissingMessages(ident) => ident.span(),
+            UnnecessaryDefaultMessage(_, span) => *span,
+            InvalidTemplate(_, span) => *span,
+            MessageAndFormatter(_, span) => *span,
+            InvalidFormatterPath(_, span) => *span,
+            SkippedFieldInVariantMessage(_, span) => *span,
+            UnknownField(_, _, _, span) => *span,
+            FieldWithoutReceiver(_, _, _, span) => *span,
+            PositionalPlaceholderOutOfRange(_, _, _, span) => *span,
+            MemoizeWithoutDisplay(_, span) => *span,
+            AffixWithoutDisplay(_, _, span) => *span,
+            MessageAndMessageConst(span) => *span,
+            InvalidMessageConstPath(_, span) => *span,
+            MsgKeyWithoutMessage(_, span) => *span,
+            #[cfg(feature = "i18n")]
+            InvalidI18nLookup(_, span) => *span,
+            I18nFeatureNotEnabled(span) => *span,
+            DefmtWithoutMessage(_, span) => *span,
+            DefmtWithRuntimeMessage(_, span) => *span,
+            DefmtFeatureNotEnabled(span) => *span,
+            RenderIntoWithoutDisplay(_, span) => *span,
+            RenderIntoFeatureNotEnabled(span) => *span
+        }
+    }
 }
 
 impl std::error::Error for DisplayImplementationError {}
@@ -21,7 +302,69 @@ impl std::fmt::Display for DisplayImplementationError {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         match self {
             MissingMessages(ident) => write!(f, "Not all variants of enum '{}' have a Display message. Consider adding a default message at the enum item.", ident),
-            UnnecessaryDefaultMessage(ident) => write!(f, "All variants for enum '{}' have a Display message, but a default was provided anyways. Please remove the default.", ident)
+            UnnecessaryDefaultMessage(ident, _) => write!(f, "All variants for enum '{}' have a Display message, but a default was provided anyways. Please remove the default.", ident),
+            InvalidTemplate(e, _) => write!(f, "{}", e),
+            MessageAndFormatter(ident, _) => write!(f, "'{}' has both a message and a custom formatter function. Choose only one.", ident),
+            InvalidFormatterPath(path, _) => write!(f, "'{}' is not a valid path to a formatter function.", path),
+            SkippedFieldInVariantMessage(access, _) => write!(f, "'{}' cannot be used in this variant message, as the field is skipped or a PhantomData marker.", access),
+            UnknownField(name, item, valid_names, _) if valid_names.is_empty() => write!(f, "'{}' in the message of '{}' is no field, as '{}' has no fields.", name, item, item),
+            UnknownField(name, item, valid_names, _) => write!(f, "'{}' in the message of '{}' is no field. Valid names are: {}.", name, item, valid_names.join(", ")),
+            FieldWithoutReceiver(name, item, access, _) => write!(f, "'{}' in the message of '{}' is no variable, use '{{{}}}' to access the field.", name, item, access),
+            PositionalPlaceholderOutOfRange(index, item, 0, _) => write!(f, "'{{{}}}' in the message of '{}' is out of range, as '{}' has no unnamed fields.", index, item, item),
+            PositionalPlaceholderOutOfRange(index, item, 1, _) => write!(f, "'{{{}}}' in the message of '{}' is out of range, as '{}' has 1 unnamed field.", index, item, item),
+            PositionalPlaceholderOutOfRange(index, item, count, _) => write!(f, "'{{{}}}' in the message of '{}' is out of range, as '{}' has {} unnamed fields.", index, item, item, count),
+            MemoizeWithoutDisplay(ident, _) => write!(f, "'{}' cannot memoize its message, as it has neither a message nor a custom formatter.", ident),
+            AffixWithoutDisplay(name, ident, _) => write!(f, "The parameter '{}' requires a message for '{}', as it is added to the message.", name, ident),
+            MessageAndMessageConst(_) => write!(f, "The parameters '{}' and '{}' cannot be used together. Choose only one.", MESSAGE, MESSAGE_CONST),
+            InvalidMessageConstPath(path, _) => write!(f, "'{}' is not a valid path to a const or static message.", path),
+            MsgKeyWithoutMessage(key, _) => write!(f, "The message key '{}' requires a message, which is displayed if the key cannot be looked up.", key),
+            #[cfg(feature = "i18n")]
+            InvalidI18nLookup(description, _) => write!(f, "{}", description),
+            I18nFeatureNotEnabled(_) => write!(f, "The parameter '{}' requires the feature 'i18n' of error_generator.", MSG_KEY),
+            DefmtWithoutMessage(ident, _) => write!(f, "The parameter '{}' requires a message for '{}', as the message is written with defmt.", DEFMT, ident),
+            DefmtWithRuntimeMessage(name, _) => write!(f, "The parameter '{}' cannot be used together with '{}', as defmt needs the message at compile time.", DEFMT, name),
+            DefmtFeatureNotEnabled(_) => write!(f, "The parameter '{}' requires the feature 'defmt' of error_generator.", DEFMT),
+            RenderIntoWithoutDisplay(ident, _) => write!(f, "The parameter '{}' requires a message for '{}', as the message is rendered into the string.", RENDER_INTO, ident),
+            RenderIntoFeatureNotEnabled(_) => write!(f, "The parameter '{}' requires the feature 'embedded' of error_generator.", RENDER_INTO)
         }
     }
-}
\ No newline at end of file
+}
+
+impl From<DisplayImplementationError> for syn::Error {
+    fn from(e: DisplayImplementationError) -> Self {
+        syn::Error::new(e.span(), e)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use quote::quote;
+    use syn::Field;
+    use syn::parse::Parser;
+
+    use crate::impl_display::implement_named_argument;
+
+    #[test]
+    fn implement_named_argument_works() {
+        let fields = [
+            quote!(path: String),
+            quote!(#[error(redact)] password: String),
+            quote!(#[error(debug_placeholder)] payload: Vec<u8>)
+        ];
+        let arguments = fields
+            .iter()
+            .map(|f| Field::parse_named.parse2(f.clone()).unwrap())
+            .map(|f| {
+                let ident = f.ident.clone().unwrap();
+                let (name, value) = implement_named_argument(ident.to_string(), &f, quote!(self.#ident), &quote!(std));
+                format!("{}: {}", name, value)
+            })
+            .collect::<Vec<_>>();
+
+        assert_eq!(arguments, vec![
+            "path: & self . path",
+            "password: & \"[REDACTED]\"",
+            "payload: & std :: format_args ! (\"{:?}\" , self . payload)"
+        ])
+    }
+}
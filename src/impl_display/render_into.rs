@@ -0,0 +1,115 @@
+use quote::quote;
+use syn::__private::{Span, TokenStream2};
+use syn::{Generics, Ident};
+
+use crate::impl_display::DisplayImplementationError;
+use crate::impl_display::DisplayImplementationError::*;
+use crate::parameters::{Parameters, RENDER_INTO};
+
+/// Creates the method 'render_into', which writes the Display message into a fixed capacity
+/// heapless::String without allocating. The using crate needs to depend on heapless.
+pub struct RenderIntoImplementor<'a> {
+    ident: &'a Ident,
+    generics: &'a Generics,
+    parameters: &'a Parameters,
+}
+
+impl<'a> RenderIntoImplementor<'a> {
+    pub fn new(ident: &'a Ident, generics: &'a Generics, parameters: &'a Parameters) -> Self {
+        RenderIntoImplementor { ident, generics, parameters }
+    }
+
+    /// Create the method if RENDER_INTO is set.
+    ///
+    /// Fails if the error has no Display implementation, as there is no message to render,
+    /// or if the feature 'embedded' is not enabled.
+    pub fn implement(self, display_implemented: bool) -> Result<TokenStream2, DisplayImplementationError> {
+        let span = match self.parameters.span_for_name(RENDER_INTO) {
+            Some(span) if self.parameters.bool_for_name(RENDER_INTO) => span,
+            _ => return Ok(quote! {})
+        };
+
+        if !display_implemented {
+            return Err(RenderIntoWithoutDisplay(self.ident.clone(), span));
+        }
+
+        implement_render_into(self.ident, self.generics, span)
+    }
+}
+
+#[cfg(feature = "embedded")]
+fn implement_render_into(ident: &Ident, generics: &Generics, _span: Span) -> Result<TokenStream2, DisplayImplementationError> {
+    let (impl_generics, type_generics, where_clause) = generics.split_for_impl();
+
+    Ok(quote! {
+        impl #impl_generics #ident #type_generics #where_clause {
+            #[doc = " Render the message into a fixed capacity string without allocating."]
+            #[doc = " Fails if the message does not fit into the capacity of the string. In this case, 'buf'"]
+            #[doc = " contains the part of the message which was written before the capacity was exceeded."]
+            pub fn render_into<const __N: usize>(&self, buf: &mut ::heapless::String<__N>) -> ::core::result::Result<(), ::core::fmt::Error> {
+                ::core::fmt::Write::write_fmt(buf, ::core::format_args!("{}", self))
+            }
+        }
+    })
+}
+
+/// Without the feature 'embedded', RENDER_INTO cannot be used.
+#[cfg(not(feature = "embedded"))]
+fn implement_render_into(_ident: &Ident, _generics: &Generics, span: Span) -> Result<TokenStream2, DisplayImplementationError> {
+    Err(RenderIntoFeatureNotEnabled(span))
+}
+
+#[cfg(test)]
+mod tests {
+    use syn::Attribute;
+    use syn::parse_quote;
+
+    use crate::impl_display::render_into::RenderIntoImplementor;
+    use crate::parameters::Parameters;
+    #[cfg(feature = "embedded")]
+    use crate::test_helper::assert_tokens_are_equal;
+
+    #[test]
+    fn implement_without_parameter_works() {
+        let parameters = to_parameters(parse_quote!(#[error(message = "failed")]));
+        let ts = RenderIntoImplementor::new(&parse_quote!(E), &parse_quote!(), &parameters).implement(true).ok().unwrap();
+        assert!(ts.is_empty())
+    }
+
+    #[cfg(feature = "embedded")]
+    #[test]
+    fn implement_works() {
+        let parameters = to_parameters(parse_quote!(#[error(message = "failed", render_into)]));
+        let ts = RenderIntoImplementor::new(&parse_quote!(E), &parse_quote!(<T>), &parameters).implement(true).ok().unwrap().to_string();
+        let expected = quote::quote! {
+            impl<T> E<T> {
+                #[doc = " Render the message into a fixed capacity string without allocating."]
+                #[doc = " Fails if the message does not fit into the capacity of the string. In this case, 'buf'"]
+                #[doc = " contains the part of the message which was written before the capacity was exceeded."]
+                pub fn render_into<const __N: usize>(&self, buf: &mut ::heapless::String<__N>) -> ::core::result::Result<(), ::core::fmt::Error> {
+                    ::core::fmt::Write::write_fmt(buf, ::core::format_args!("{}", self))
+                }
+            }
+        }.to_string();
+        assert_tokens_are_equal(ts, expected)
+    }
+
+    #[test]
+    fn implement_without_display_fails() {
+        let parameters = to_parameters(parse_quote!(#[error(render_into)]));
+        let error = RenderIntoImplementor::new(&parse_quote!(E), &parse_quote!(), &parameters).implement(false).err().unwrap();
+        assert_eq!(error.to_string(), "The parameter 'render_into' requires a message for 'E', as the message is rendered into the string.")
+    }
+
+    #[cfg(not(feature = "embedded"))]
+    #[test]
+    fn implement_without_feature_fails() {
+        let parameters = to_parameters(parse_quote!(#[error(message = "failed", render_into)]));
+        let error = RenderIntoImplementor::new(&parse_quote!(E), &parse_quote!(), &parameters).implement(true).err().unwrap();
+        assert_eq!(error.to_string(), "The parameter 'render_into' requires the feature 'embedded' of error_generator.")
+    }
+
+    fn to_parameters(attribute: Attribute) -> Parameters {
+        Parameters::from_attribute(&attribute).unwrap()
+    }
+}
@@ -1,35 +1,175 @@
-use quote::quote;
-use syn::__private::TokenStream2;
-use syn::ItemStruct;
+use quote::{quote, ToTokens};
+use syn::__private::{Span, TokenStream2};
+use syn::{Index, ItemStruct, Member, WherePredicate};
 
+use crate::common::{field_is_phantom, field_is_redacted, field_is_skipped, field_uses_debug_placeholder, single_source_field, std_root};
+use crate::impl_display::{check_placeholder_names, check_positional_placeholders, DisplayImplementationError, implement_display, implement_formatter_call, implement_named_argument, write_macro};
+use crate::impl_display::DisplayImplementationError::DefmtWithoutMessage;
+use crate::impl_display::bounds::{message_bounds, source_bounds, with_bounds};
+use crate::impl_display::defmt::{defmt_span, defmt_write_macro, implement_format};
+use crate::impl_display::i18n::I18nImplementor;
+use crate::impl_display::message_const::{implement_interpolation, MessageConstImplementor};
 use crate::impl_display::write::WriteImplementor;
 use crate::parameters::{MESSAGE, Parameters};
 
 pub struct StructDisplayImplementor<'a> {
     item_struct: &'a ItemStruct,
     parameters: &'a Parameters,
+    /// If the struct has a source, which is appended to the message with the alternate flag
+    has_source: bool,
 }
 
 impl<'a> StructDisplayImplementor<'a> {
-    pub fn new(item_struct: &'a ItemStruct, parameters: &'a Parameters) -> Self {
-        StructDisplayImplementor { item_struct, parameters }
+    pub fn new(item_struct: &'a ItemStruct, parameters: &'a Parameters, has_source: bool) -> Self {
+        StructDisplayImplementor { item_struct, parameters, has_source }
     }
 
-    pub fn implement(self) -> TokenStream2 {
-        let write_implementation = match self.parameters.string_for_name(MESSAGE) {
-            Some(m) => WriteImplementor::new().implement(m),
-            None => return quote! {}
+    /// Create the std::fmt::Display implementation for the given struct.
+    ///
+    /// If the struct has neither a message nor a custom formatter, Display will not be implemented.
+    pub fn implement(self) -> Result<TokenStream2, DisplayImplementationError> {
+        let formatter_call = implement_formatter_call(&self.item_struct.ident, self.parameters)?;
+        let root = std_root(self.parameters);
+        let i18n_implementor = I18nImplementor::new(self.parameters, &root);
+        let named_arguments = self.named_arguments(&root);
+        let message_const_call = MessageConstImplementor::new(self.parameters).implement(&named_arguments, &root)?;
+        let interpolation = match message_const_call.is_some() {
+            true => implement_interpolation(&self.item_struct.ident, &self.item_struct.generics, &root),
+            false => quote! {}
         };
-
-        let ident = &self.item_struct.ident;
-        let generics = &self.item_struct.generics;
-        let (impl_generics, type_generics, where_clause) = generics.split_for_impl();
-        quote! {
-            impl #impl_generics std::fmt::Display for #ident #type_generics #where_clause {
-                fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-                    #write_implementation
-                }
+        let write_implementation = match (formatter_call.or(message_const_call), self.parameters.string_for_name(MESSAGE), self.parameters.span_for_name(MESSAGE)) {
+            (Some(call), _, _) => call,
+            (None, Some(m), Some(span)) => self.check_placeholder_names(m, span).and_then(|_| WriteImplementor::new(span)
+                .with_write_macro(write_macro(&root))
+                .with_debug_expressions(self.debug_expressions())
+                .with_bound_members(self.bound_members())
+                .with_redacted_accesses(self.redacted_accesses())
+                .implement(m))?,
+            _ => {
+                // fails if MSG_KEY is set without a message
+                i18n_implementor.implement(&[], quote! {})?;
+                return Ok(quote! {});
             }
+        };
+        let write_implementation = i18n_implementor.implement(&named_arguments, write_implementation)?;
+        let generics = with_bounds(&self.item_struct.generics, &self.display_bounds());
+        let display_implementation = implement_display(&self.item_struct.ident, &generics, self.parameters, write_implementation, self.has_source);
+
+        Ok(quote! {
+            #display_implementation
+            #interpolation
+        })
+    }
+
+    /// Create the defmt::Format implementation for the given struct if DEFMT is set, which writes the same message as Display.
+    ///
+    /// Fails if the struct has no message.
+    pub fn implement_defmt(self) -> Result<TokenStream2, DisplayImplementationError> {
+        let span = match defmt_span(self.parameters)? {
+            Some(span) => span,
+            None => return Ok(quote! {})
+        };
+
+        let write_implementation = match (self.parameters.string_for_name(MESSAGE), self.parameters.span_for_name(MESSAGE)) {
+            (Some(m), Some(message_span)) => WriteImplementor::new(message_span)
+                .with_write_macro(defmt_write_macro())
+                .with_debug_expressions(self.debug_expressions())
+                .with_bound_members(self.bound_members())
+                .with_redacted_accesses(self.redacted_accesses())
+                .implement(m)?,
+            _ => return Err(DefmtWithoutMessage(self.item_struct.ident.clone(), span))
+        };
+
+        implement_format(&self.item_struct.ident, &self.item_struct.generics, write_implementation, span)
+    }
+
+    /// Return the bounds the message requires for generic fields, which are added to the Display implementation
+    /// (see bounds::message_bounds). If the struct has a source, the bounds of the source are added as well,
+    /// as its chain of sources is appended to the message.
+    fn display_bounds(&self) -> Vec<WherePredicate> {
+        let root = std_root(self.parameters);
+        let mut bounds = match self.parameters.string_for_name(MESSAGE) {
+            Some(message) => message_bounds(message, &self.item_struct.fields, &["self", "e"], false, &self.item_struct.generics, &root),
+            None => vec![]
+        };
+
+        if self.has_source {
+            bounds.extend(source_bounds(single_source_field(&self.item_struct.fields).map(|(_, f)| f), &self.item_struct.generics, &root))
         }
+        bounds
+    }
+
+    /// Check if the placeholders of the given message only access existing fields on self or e (see check_placeholder_names)
+    /// and positional placeholders are in range (see check_positional_placeholders).
+    fn check_placeholder_names(&self, message: &str, span: Span) -> Result<(), DisplayImplementationError> {
+        let item = self.item_struct.ident.to_string();
+        let members = self.bound_members().into_iter().map(|(member, _)| member).collect::<Vec<_>>();
+        check_placeholder_names(message, &item, &["self", "e"], &members, &[], span)?;
+        check_positional_placeholders(message, &item, &self.item_struct.fields, span)
+    }
+
+    /// Return the accesses of all fields on self, which replace the accesses on e, like 'self.payload' for "e.payload".
+    fn bound_members(&self) -> Vec<(String, TokenStream2)> {
+        self.item_struct.fields
+            .iter()
+            .enumerate()
+            .map(|(i, f)| {
+                let member = match &f.ident {
+                    Some(ident) => Member::Named(ident.clone()),
+                    None => Member::Unnamed(Index::from(i))
+                };
+                (member.to_token_stream().to_string(), quote! {self.#member})
+            })
+            .collect()
+    }
+
+    /// Return the fields as named arguments for the lookup of MSG_KEY and MESSAGE_CONST, like "payload" and '&self.payload'.
+    fn named_arguments(&self, root: &TokenStream2) -> Vec<(String, TokenStream2)> {
+        self.item_struct.fields
+            .iter()
+            .enumerate()
+            .filter(|(_, f)| !field_is_skipped(f) && !field_is_phantom(f))
+            .map(|(i, f)| {
+                let member = match &f.ident {
+                    Some(ident) => Member::Named(ident.clone()),
+                    None => Member::Unnamed(Index::from(i))
+                };
+                implement_named_argument(member.to_token_stream().to_string(), f, quote! {self.#member}, root)
+            })
+            .collect()
+    }
+
+    /// Return the accesses of all fields marked with REDACT on self, like 'self' and "password".
+    /// Accesses on e are already replaced with the ones on self when the message is redacted.
+    fn redacted_accesses(&self) -> Vec<(String, Option<String>)> {
+        self.item_struct.fields
+            .iter()
+            .enumerate()
+            .filter(|(_, f)| field_is_redacted(f))
+            .map(|(i, f)| {
+                let member = match &f.ident {
+                    Some(ident) => ident.to_string(),
+                    None => i.to_string()
+                };
+                ("self".to_string(), Some(member))
+            })
+            .collect()
+    }
+
+    /// Return the accesses of all fields marked with DEBUG_PLACEHOLDER, on self and on e
+    /// (whose accesses are replaced with the ones on self, see bound_members), like "self.payload" and "e.payload".
+    fn debug_expressions(&self) -> Vec<String> {
+        self.item_struct.fields
+            .iter()
+            .enumerate()
+            .filter(|(_, f)| field_uses_debug_placeholder(f))
+            .flat_map(|(i, f)| {
+                let member = match &f.ident {
+                    Some(ident) => ident.to_string(),
+                    None => i.to_string()
+                };
+                vec![format!("self.{}", member), format!("e.{}", member)]
+            })
+            .collect()
     }
 }
\ No newline at end of file
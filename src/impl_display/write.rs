@@ -1,19 +1,51 @@
 use quote::quote;
-use syn::__private::TokenStream2;
+use syn::__private::{Span, TokenStream2};
+
+use crate::impl_display::DisplayImplementationError;
+use crate::impl_display::DisplayImplementationError::*;
+use crate::template::Template;
 
 pub struct WriteImplementor {
-    current_expression: Option<String>,
-    expressions: Vec<String>,
-    current_depth: usize,
+    /// The span of the message, used to report invalid expressions
+    span: Span,
+    /// The expressions which are formatted with Debug instead of Display, see DEBUG_PLACEHOLDER
+    debug_expressions: Vec<String>,
+    /// The members of 'e' whose accesses are replaced with the bound fields of a variant, like "path" with 'path'
+    bound_members: Vec<(String, TokenStream2)>,
+    /// The accesses of fields marked with REDACT, see Template::redact
+    redacted_accesses: Vec<(String, Option<String>)>,
+    /// The macro which writes the message, 'write' for Display and 'defmt::write' for defmt::Format
+    write_macro: TokenStream2,
 }
 
 impl WriteImplementor {
-    pub fn new() -> Self {
-        WriteImplementor {
-            current_expression: None,
-            expressions: vec![],
-            current_depth: 0,
-        }
+    pub fn new(span: Span) -> Self {
+        WriteImplementor { span, debug_expressions: vec![], bound_members: vec![], redacted_accesses: vec![], write_macro: quote!(write) }
+    }
+
+    /// Replace the accesses of the given members on 'e' (like "e.path" or "e.0") with the given expressions,
+    /// which are the fields bound in the match arm of a variant.
+    pub fn with_bound_members(mut self, bound_members: Vec<(String, TokenStream2)>) -> Self {
+        self.bound_members = bound_members;
+        self
+    }
+
+    /// Replace every expression using one of the given accesses (like 'self' and "password") with REDACTED.
+    pub fn with_redacted_accesses(mut self, redacted_accesses: Vec<(String, Option<String>)>) -> Self {
+        self.redacted_accesses = redacted_accesses;
+        self
+    }
+
+    /// Write the message with the given macro instead of write!, which takes the same arguments, like 'defmt::write'.
+    pub fn with_write_macro(mut self, write_macro: TokenStream2) -> Self {
+        self.write_macro = write_macro;
+        self
+    }
+
+    /// Format the given expressions (written as readable strings, like "self.payload") with Debug instead of Display.
+    pub fn with_debug_expressions(mut self, debug_expressions: Vec<String>) -> Self {
+        self.debug_expressions = debug_expressions;
+        self
     }
 
     /// Creates an implementation of a write! macro call for a given message.
@@ -23,113 +55,89 @@ impl WriteImplementor {
     /// "This is my value: {e.critical_string_value()}"
     /// will become
     /// write!{f, "This is my value {}", e.critical_string_value()}
-    pub fn implement(mut self, message: String) -> TokenStream2 {
-        let new_message: String = message
-            .chars()
-            .filter(|c| match c {
-                '{' => self.handle_opening_parenthesis(*c),
-                '}' => self.handle_closing_parenthesis(*c),
-                c if self.currently_creates_expression() => {
-                    self.append_to_expression(*c);
-                    false
-                },
-                _ => true
-            })
-            .collect();
-
-
-        self.create_write_implementation(new_message)
-    }
-
-    fn handle_opening_parenthesis(&mut self, parenthesis: char) -> bool {
-        match self.currently_creates_expression() {
-            true => {
-                self.append_to_expression(parenthesis);
-                self.increase_depth();
-                false
-            },
-            false => {
-                self.start_creation();
-                true
-            }
-        }
+    ///
+    /// See Template for the syntax of messages.
+    ///
+    /// Fails if the message cannot be parsed into a template.
+    pub fn implement(self, message: &str) -> Result<TokenStream2, DisplayImplementationError> {
+        let mut template = Template::parse(message).map_err(|e| InvalidTemplate(e, self.span))?;
+        template.resolve_positional_placeholders("e");
+        template.replace_member_accesses("e", &self.bound_members);
+        template.redact(&self.redacted_accesses);
+
+        let format_string = template.format_string(&self.debug_expressions);
+        let expressions = template.arguments();
+
+        let write_macro = self.write_macro;
+        Ok(quote! {#write_macro!(f, #format_string #(,#expressions)*)})
     }
+}
 
-    fn handle_closing_parenthesis(&mut self, parenthesis: char) -> bool {
-        match self.currently_creates_expression() {
-            true => match self.current_depth == 1 {
-                true => {
-                    self.end_creation();
-                    true
-                },
-                false => {
-                    self.append_to_expression(parenthesis);
-                    self.decrease_depth();
-                    false
-                }
-            },
-            false => true
-        }
-    }
+#[cfg(test)]
+mod tests {
+    use quote::quote;
+    use syn::__private::Span;
 
-    fn currently_creates_expression(&self) -> bool {
-        self.current_expression.is_some()
-    }
+    use crate::impl_display::write::WriteImplementor;
+    use crate::test_helper::assert_tokens_are_equal;
 
-    fn append_to_expression(&mut self, c: char) {
-        if let Some(e) = self.current_expression.as_mut() {
-            e.push(c)
-        }
+    #[test]
+    fn implement_works() {
+        let message = "some complex stuff: {e.foo()}, {if b {42} else {43}}";
+        let ts = WriteImplementor::new(Span::call_site()).implement(message).unwrap().to_string();
+        let expected = r#"write!(f, "some complex stuff: {}, {}", e.foo(), if b {42} else {43})"#;
+        assert_tokens_are_equal(ts, expected)
     }
 
-    fn increase_depth(&mut self) {
-        self.current_depth += 1
+    #[test]
+    fn implement_multiple_expressions_works() {
+        let message = "complex: { {let mut i = 0; i += 1; i} }";
+        let ts = WriteImplementor::new(Span::call_site()).implement(message).unwrap().to_string();
+        let expected = r#"write!(f, "complex: {}", {let mut i = 0; i += 1; i})"#;
+        assert_tokens_are_equal(ts, expected)
     }
 
-    fn decrease_depth(&mut self) {
-        self.current_depth -= 1
+    #[test]
+    fn implement_escaped_braces_works() {
+        let message = r#"{{"value": {e.val}}}"#;
+        let ts = WriteImplementor::new(Span::call_site()).implement(message).unwrap().to_string();
+        let expected = r#"write!(f, "{{\"value\": {}}}", e.val)"#;
+        assert_tokens_are_equal(ts, expected)
     }
 
-    fn start_creation(&mut self) {
-        self.current_expression = Some(String::new());
-        self.increase_depth()
+    #[test]
+    fn implement_with_write_macro_works() {
+        let message = "the value {e.val}";
+        let ts = WriteImplementor::new(Span::call_site()).with_write_macro(quote!(defmt::write)).implement(message).unwrap().to_string();
+        let expected = r#"defmt::write!(f, "the value {}", e.val)"#;
+        assert_tokens_are_equal(ts, expected)
     }
 
-    fn end_creation(&mut self) {
-        self.expressions.push(self.current_expression.as_mut().unwrap().clone());
-        self.current_expression = None;
-        self.decrease_depth()
+    #[test]
+    fn implement_format_specs_works() {
+        let message = "{e.name:<10}|{e.ratio:>8.2}|{e.flags:#010b}";
+        let ts = WriteImplementor::new(Span::call_site()).implement(message).unwrap().to_string();
+        let expected = r#"write!(f, "{:<10}|{:>8.2}|{:#010b}", e.name, e.ratio, e.flags)"#;
+        assert_tokens_are_equal(ts, expected)
     }
 
-    fn create_write_implementation(self, message: String) -> TokenStream2 {
-        let expressions: TokenStream2 = self.expressions
-            .into_iter()
-            .map(|e| e.parse::<TokenStream2>().unwrap())
-            .map(|ts| quote! {,#ts})
-            .collect();
-
-        quote! {write!(f, #message #expressions)}
+    #[test]
+    fn implement_repeated_expressions_works() {
+        let message = "{e.line} of {e.file} (line {e.line:>4})";
+        let ts = WriteImplementor::new(Span::call_site()).implement(message).unwrap().to_string();
+        let expected = r#"write!(f, "{0} of {1} (line {0:>4})", e.line, e.file)"#;
+        assert_tokens_are_equal(ts, expected)
     }
-}
-
-#[cfg(test)]
-mod tests {
-    use crate::impl_display::write::WriteImplementor;
-    use crate::test_helper::assert_tokens_are_equal;
 
     #[test]
-    fn implement_works() {
-        let message = "some complex stuff: {e.foo()}, {if b {42} else {43}}".to_string();
-        let ts = WriteImplementor::new().implement(message).to_string();
-        let expected = r#"write!(f, "some complex stuff: {}, {}", e.foo(), if b {42} else {43})"#;
-        assert_tokens_are_equal(ts, expected)
+    fn implement_unclosed_expression_fails() {
+        let message = "unclosed: {e.foo()";
+        assert!(WriteImplementor::new(Span::call_site()).implement(message).is_err())
     }
 
     #[test]
-    fn implement_multiple_expressions_works() {
-        let message = "complex: {{let mut i = 0; i += 1; i}}".to_string();
-        let ts = WriteImplementor::new().implement(message).to_string();
-        let expected = r#"write!(f, "complex: {}", {let mut i = 0; i += 1; i})"#;
-        assert_tokens_are_equal(ts, expected)
+    fn implement_invalid_expression_fails() {
+        let message = "invalid: {e.foo(}";
+        assert!(WriteImplementor::new(Span::call_site()).implement(message).is_err())
     }
 }
\ No newline at end of file
@@ -0,0 +1,243 @@
+use syn::__private::{Span, TokenStream2};
+
+#[cfg(feature = "message_registry")]
+use crate::common::implement_warning;
+
+/// A message of an item, with the name of the item (like "MyError" or "MyError::ReadFailed") and the span of the message.
+pub type NamedMessage = (String, String, Span);
+
+/// Records the messages of all errors of a crate in a registry file and warns when two different items share
+/// the same message, which usually is a copy-paste error that makes logs ambiguous. Requires the feature 'message_registry'.
+///
+/// The registry is the file in the environment variable ERROR_GENERATOR_MESSAGE_REGISTRY, or 'error_generator_messages.tsv'
+/// in the target directory (see registry_path). It is shared by all crates of a workspace, but messages are only compared
+/// within the same crate (see CARGO_CRATE_NAME). Every line holds the crate, the item and the message, separated by tabs.
+pub struct DuplicateMessagesImplementor {
+    /// The name of the expanded struct or enum, which owns all entries of its variants.
+    owner: String,
+    messages: Vec<NamedMessage>,
+}
+
+impl DuplicateMessagesImplementor {
+    pub fn new(owner: String, messages: Vec<NamedMessage>) -> Self {
+        DuplicateMessagesImplementor { owner, messages }
+    }
+
+    /// Record the messages and create a warning for every message which another item of the crate already has.
+    /// Nothing is recorded if the macro is not expanded by cargo, as the crate and the target directory are unknown then.
+    #[cfg(feature = "message_registry")]
+    pub fn implement(self) -> TokenStream2 {
+        let path = registry_path(
+            std::env::var("ERROR_GENERATOR_MESSAGE_REGISTRY").ok(),
+            std::env::var("CARGO_TARGET_DIR").ok(),
+            std::env::var("OUT_DIR").ok(),
+        );
+
+        match (path, std::env::var("CARGO_CRATE_NAME")) {
+            (Some(path), Ok(crate_name)) => self.record(&path, &crate_name),
+            _ => quote::quote! {}
+        }
+    }
+
+    /// Without the feature 'message_registry', messages are not recorded.
+    #[cfg(not(feature = "message_registry"))]
+    pub fn implement(self) -> TokenStream2 {
+        let _ = (self.owner, self.messages);
+        quote::quote! {}
+    }
+
+    /// Record the messages of the given crate in the registry at the given path and create the warnings.
+    /// Messages without any text besides placeholders (like "{_0}") are ignored, as they are no copy-paste errors.
+    ///
+    /// The entries which were recorded for the owner before are replaced, so renamed variants and changed messages
+    /// are not compared anymore. Failing to read or write the registry never fails the expansion, the messages are just not checked then.
+    #[cfg(feature = "message_registry")]
+    fn record(self, path: &std::path::Path, crate_name: &str) -> TokenStream2 {
+        let crate_name = crate_name.to_string();
+        let owner = self.owner;
+        let owner_prefix = format!("{}::", owner);
+        let mut entries = std::fs::read_to_string(path)
+            .map(|content| content.lines().filter_map(parse_entry).collect::<Vec<_>>())
+            .unwrap_or_default();
+        entries.retain(|(c, i, _)| *c != crate_name || (*i != owner && !i.starts_with(&owner_prefix)));
+
+        let mut warnings = vec![];
+        let mut new_entries = vec![];
+        for (item, message, span) in self.messages.into_iter().filter(|(_, m, _)| has_text(m)) {
+            let duplicate = entries
+                .iter()
+                .chain(new_entries.iter())
+                .find(|(c, i, m)| *c == crate_name && *i != item && *m == message);
+            if let Some((_, other, _)) = duplicate {
+                warnings.push(implement_warning("duplicate_message", &format!("The message of '{}' is the same as the one of '{}': \"{}\"", item, other, message), span));
+            }
+
+            let entry = (crate_name.clone(), item, message);
+            if !new_entries.contains(&entry) {
+                new_entries.push(entry)
+            }
+        }
+
+        let content = entries
+            .into_iter()
+            .chain(new_entries)
+            .map(|(c, i, m)| format!("{}\t{}\t{}\n", escape(&c), escape(&i), escape(&m)))
+            .collect::<String>();
+        let _ = write_registry(path, &content);
+
+        quote::quote! {#(#warnings)*}
+    }
+}
+
+/// Return the path of the registry. It is the given ERROR_GENERATOR_MESSAGE_REGISTRY, or 'error_generator_messages.tsv'
+/// in the given CARGO_TARGET_DIR. If neither is set, the target directory is derived from the given OUT_DIR, which
+/// cargo sets for crates with a build script, like 'target/debug/build/<package>/out'.
+///
+/// Returns None if the target directory is unknown, as the registry would otherwise be written relative to the
+/// working directory of the compiler, which differs between the members of a workspace and tools like rust-analyzer.
+#[cfg(feature = "message_registry")]
+fn registry_path(registry: Option<String>, target_dir: Option<String>, out_dir: Option<String>) -> Option<std::path::PathBuf> {
+    const FILE_NAME: &str = "error_generator_messages.tsv";
+
+    match (registry, target_dir, out_dir) {
+        (Some(registry), _, _) => Some(registry.into()),
+        (None, Some(target_dir), _) => Some(std::path::Path::new(&target_dir).join(FILE_NAME)),
+        (None, None, Some(out_dir)) => std::path::Path::new(&out_dir)
+            .ancestors()
+            .find(|dir| dir.file_name().map(|name| name == "build").unwrap_or(false))
+            .and_then(|build_dir| build_dir.parent())
+            .map(|profile_dir| profile_dir.join(FILE_NAME)),
+        (None, None, None) => None
+    }
+}
+
+/// Replace the content of the registry at the given path. The content is written to a temporary file first,
+/// so crates expanded in parallel never read a partially written registry.
+#[cfg(feature = "message_registry")]
+fn write_registry(path: &std::path::Path, content: &str) -> std::io::Result<()> {
+    let temporary = path.with_extension(format!("tsv.{}", std::process::id()));
+    std::fs::write(&temporary, content)?;
+    std::fs::rename(&temporary, path)
+}
+
+/// Check if the given message contains any text which is not part of a placeholder.
+#[cfg(feature = "message_registry")]
+fn has_text(message: &str) -> bool {
+    crate::template::Template::parse(message)
+        .map(|template| template.has_literal_text())
+        .unwrap_or(true)
+}
+
+/// Parse a line of the registry into the crate, the item and the message.
+#[cfg(feature = "message_registry")]
+fn parse_entry(line: &str) -> Option<(String, String, String)> {
+    let mut parts = line.split('\t').map(unescape);
+    Some((parts.next()?, parts.next()?, parts.next()?))
+}
+
+/// Escape backslashes, tabs and line breaks, so a value fits into a single column of the registry.
+#[cfg(feature = "message_registry")]
+fn escape(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('\t', "\\t").replace('\n', "\\n")
+}
+
+/// Reverse 'escape'.
+#[cfg(feature = "message_registry")]
+fn unescape(value: &str) -> String {
+    let mut result = String::new();
+    let mut chars = value.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => match chars.next() {
+                Some('t') => result.push('\t'),
+                Some('n') => result.push('\n'),
+                Some(other) => result.push(other),
+                None => result.push('\\')
+            },
+            c => result.push(c)
+        }
+    }
+    result
+}
+
+#[cfg(all(test, feature = "message_registry"))]
+mod tests {
+    use std::path::PathBuf;
+
+    use syn::__private::Span;
+
+    use crate::impl_duplicate_messages::{DuplicateMessagesImplementor, escape, parse_entry, registry_path};
+
+    #[test]
+    fn escape_and_parse_entry_work() {
+        let line = format!("{}\t{}\t{}", escape("c"), escape("E::A"), escape("a\tb\\n\nc"));
+        assert_eq!(parse_entry(&line), Some(("c".to_string(), "E::A".to_string(), "a\tb\\n\nc".to_string())))
+    }
+
+    #[test]
+    fn registry_path_works() {
+        let path = |registry: Option<&str>, target_dir: Option<&str>, out_dir: Option<&str>| registry_path(registry.map(String::from), target_dir.map(String::from), out_dir.map(String::from));
+
+        assert_eq!(path(Some("messages.tsv"), Some("/t"), None), Some(PathBuf::from("messages.tsv")));
+        assert_eq!(path(None, Some("/t"), Some("/o/debug/build/c-1/out")), Some(PathBuf::from("/t/error_generator_messages.tsv")));
+        assert_eq!(path(None, None, Some("/o/debug/build/c-1/out")), Some(PathBuf::from("/o/debug/error_generator_messages.tsv")));
+        assert_eq!(path(None, None, Some("/o/out")), None);
+        assert_eq!(path(None, None, None), None)
+    }
+
+    #[test]
+    fn duplicate_messages_are_warned() {
+        let path = registry("warned");
+        let record = |owner: &str, item: &str, message: &str| DuplicateMessagesImplementor::new(owner.to_string(), vec![(item.to_string(), message.to_string(), Span::call_site())]).record(&path, "c");
+
+        let first = record("E", "E::A", "failed");
+        let second = record("E", "E::A", "failed");
+        let placeholder_only = record("F", "F::B", "{_0}");
+        let other_placeholder_only = record("G", "G::C", "{_0}");
+        let third = record("S", "S", "failed");
+        let other_crate = DuplicateMessagesImplementor::new("T".to_string(), vec![("T".to_string(), "failed".to_string(), Span::call_site())]).record(&path, "d");
+        std::fs::remove_file(&path).unwrap();
+
+        assert!(first.is_empty());
+        assert!(second.is_empty());
+        assert!(placeholder_only.is_empty());
+        assert!(other_placeholder_only.is_empty());
+        assert!(third.to_string().contains("The message of 'S' is the same as the one of 'E::A': \\\"failed\\\""));
+        assert!(other_crate.is_empty())
+    }
+
+    #[test]
+    fn duplicate_messages_within_an_item_are_warned() {
+        let path = registry("item");
+        let messages = vec![
+            ("E::A".to_string(), "failed".to_string(), Span::call_site()),
+            ("E::B".to_string(), "failed".to_string(), Span::call_site())
+        ];
+
+        let warnings = DuplicateMessagesImplementor::new("E".to_string(), messages).record(&path, "c");
+        std::fs::remove_file(&path).unwrap();
+
+        assert!(warnings.to_string().contains("The message of 'E::B' is the same as the one of 'E::A'"))
+    }
+
+    #[test]
+    fn entries_of_the_owner_are_replaced() {
+        let path = registry("replaced");
+        let record = |owner: &str, items: &[&str]| DuplicateMessagesImplementor::new(owner.to_string(), items.iter().map(|item| (item.to_string(), "failed".to_string(), Span::call_site())).collect()).record(&path, "c");
+
+        record("E", &["E::First"]);
+        let renamed = record("E", &["E::Renamed"]);
+        let owner_with_prefix = record("EE", &["EE::Other"]);
+        let content = std::fs::read_to_string(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        assert!(renamed.is_empty());
+        assert!(owner_with_prefix.to_string().contains("The message of 'EE::Other' is the same as the one of 'E::Renamed'"));
+        assert_eq!(content, "c\tE::Renamed\tfailed\nc\tEE::Other\tfailed\n")
+    }
+
+    /// Return a path for a registry in the temporary directory, which is unique for the given test.
+    fn registry(test: &str) -> PathBuf {
+        std::env::temp_dir().join(format!("error_generator_messages_{}_{}.tsv", test, std::process::id()))
+    }
+}
@@ -0,0 +1,202 @@
+use std::convert::TryFrom;
+use std::fmt::Formatter;
+
+use quote::{format_ident, quote};
+use syn::__private::{Span, TokenStream2};
+use syn::{Generics, Ident};
+
+use crate::common::{cfg_attributes, trait_impl_attributes};
+use crate::enum_error::VariantWithParams;
+use crate::impl_errno::ErrnoImplementationError::*;
+use crate::parameters::{ERRNO, NO_STD, Parameters};
+
+/// Creates the method 'to_errno' (and its alias 'errno'), which returns the errno value set with the parameter ERRNO, and conversions
+/// of the error into i32 and std::io::Error. This way, wrappers around POSIX APIs (like FUSE filesystems)
+/// can propagate conventional errno values.
+///
+/// The value is either a number or the name of an errno constant of the libc crate, like "EINVAL".
+/// The names keep the values right on every platform.
+pub struct ErrnoImplementor<'a> {
+    ident: &'a Ident,
+    generics: &'a Generics,
+    parameters: &'a Parameters,
+}
+
+impl<'a> ErrnoImplementor<'a> {
+    pub fn new(ident: &'a Ident, generics: &'a Generics, parameters: &'a Parameters) -> Self {
+        ErrnoImplementor { ident, generics, parameters }
+    }
+
+    /// Create the method and conversion for a struct, if ERRNO is set.
+    pub fn implement_for_struct(self) -> Result<TokenStream2, ErrnoImplementationError> {
+        match Self::errno(self.parameters)? {
+            Some(errno) => Ok(self.implement_method(quote! {#errno})),
+            None => Ok(quote! {})
+        }
+    }
+
+    /// Create the method and conversion for an enum, if ERRNO is set on the enum or any variant.
+    ///
+    /// Variants without ERRNO use the value of the enum. There is no sensible default errno,
+    /// so if the enum has none, every variant must have one.
+    pub fn implement_for_enum(self, variants_with_parameters: &[VariantWithParams<'_>]) -> Result<TokenStream2, ErrnoImplementationError> {
+        let variant_errnos = variants_with_parameters
+            .iter()
+            .map(|(v, p_opt)| match p_opt {
+                Some(p) => Ok((*v, Self::errno(p)?)),
+                None => Ok((*v, None))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        let default_errno = Self::errno(self.parameters)?;
+
+        if default_errno.is_none() && variant_errnos.iter().all(|(_, errno)| errno.is_none()) {
+            return Ok(quote! {});
+        }
+
+        let missing_variants = variant_errnos
+            .iter()
+            .filter(|(_, errno)| errno.is_none() && default_errno.is_none())
+            .map(|(v, _)| v.ident.clone())
+            .collect::<Vec<_>>();
+
+        if !missing_variants.is_empty() {
+            return Err(MissingErrno(self.ident.clone(), missing_variants));
+        }
+
+        let ident = self.ident;
+        let match_arms = variant_errnos
+            .into_iter()
+            .map(|(v, errno)| {
+                let variant_ident = &v.ident;
+                let cfg = cfg_attributes(v);
+                let errno = errno.or_else(|| default_errno.clone()).unwrap();
+                quote! {#cfg #ident::#variant_ident { .. } => #errno}
+            });
+
+        Ok(self.implement_method(quote! {
+            match self {
+                #(#match_arms,)*
+            }
+        }))
+    }
+
+    fn implement_method(&self, body: TokenStream2) -> TokenStream2 {
+        let ident = self.ident;
+        let (impl_generics, type_generics, where_clause) = self.generics.split_for_impl();
+        let impl_attributes = trait_impl_attributes();
+        let io_error_conversion = match self.parameters.bool_for_name(NO_STD) {
+            true => quote! {},
+            false => quote! {
+                #impl_attributes
+                impl #impl_generics ::std::convert::From<#ident #type_generics> for ::std::io::Error #where_clause {
+                    fn from(e: #ident #type_generics) -> ::std::io::Error {
+                        ::std::io::Error::from_raw_os_error(e.to_errno())
+                    }
+                }
+            }
+        };
+
+        quote! {
+            impl #impl_generics #ident #type_generics #where_clause {
+                #[doc = " Return the errno value which describes this error."]
+                pub fn to_errno(&self) -> i32 {
+                    #body
+                }
+
+                #[doc = " Return the errno value which describes this error, the same as 'to_errno'."]
+                pub fn errno(&self) -> i32 {
+                    self.to_errno()
+                }
+            }
+
+            #impl_attributes
+            impl #impl_generics ::std::convert::From<#ident #type_generics> for i32 #where_clause {
+                fn from(e: #ident #type_generics) -> i32 {
+                    e.to_errno()
+                }
+            }
+
+            #io_error_conversion
+        }
+    }
+
+    /// Return the errno value set in the given parameters, if any.
+    ///
+    /// A name like "EINVAL" becomes the constant 'libc::EINVAL', so the using crate needs to depend on libc.
+    pub fn errno(parameters: &Parameters) -> Result<Option<TokenStream2>, ErrnoImplementationError> {
+        let span = match parameters.span_for_name(ERRNO) {
+            Some(span) => span,
+            None => return Ok(None)
+        };
+
+        if let Some(name) = parameters.string_alternative_for_name(ERRNO) {
+            return match is_errno_name(name) {
+                true => {
+                    let name = format_ident!("{}", name);
+                    Ok(Some(quote! {libc::#name}))
+                }
+                false => Err(InvalidErrnoName(name.to_string(), span))
+            };
+        }
+
+        // the type of the value was checked when the parameters were created
+        let errno = match parameters.integer_for_name(ERRNO) {
+            Some(errno) => errno,
+            None => return Ok(None)
+        };
+        match i32::try_from(errno) {
+            Ok(errno) => Ok(Some(quote! {#errno})),
+            Err(_) => Err(OutOfRange(errno, span))
+        }
+    }
+}
+
+/// Check if the given name looks like the name of an errno constant, like "EINVAL" or "E2BIG".
+fn is_errno_name(name: &str) -> bool {
+    name.len() > 1
+        && name.starts_with('E')
+        && name.chars().all(|c| c.is_ascii_uppercase() || c.is_ascii_digit())
+}
+
+/// Error that might occur when the errno method is created.
+#[derive(Debug)]
+pub enum ErrnoImplementationError {
+    /// The errno value does not fit into an i32.
+    OutOfRange(u64, Span),
+    /// The errno value is a string, but not the name of an errno constant.
+    InvalidErrnoName(String, Span),
+    /// Some variants have no errno value and the enum has no default. Every such variant is listed here.
+    MissingErrno(Ident, Vec<Ident>),
+}
+
+impl ErrnoImplementationError {
+    /// Return the span of the code which caused this error.
+    pub fn span(&self) -> Span {
+        match self {
+            OutOfRange(_, span) => *span,
+            InvalidErrnoName(_, span) => *span,
+            MissingErrno(enum_ident, idents) => idents.first().unwrap_or(enum_ident).span()
+        }
+    }
+}
+
+impl std::error::Error for ErrnoImplementationError {}
+
+impl std::fmt::Display for ErrnoImplementationError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            OutOfRange(errno, _) => write!(f, "The errno value {} does not fit into an i32.", errno),
+            InvalidErrnoName(name, _) => write!(f, "'{}' is not the name of an errno constant, like \"EINVAL\".", name),
+            MissingErrno(enum_ident, idents) => {
+                let idents_string = idents.iter().map(|i| i.to_string()).collect::<Vec<_>>().join(",");
+                write!(f, "The following variants of enum '{}' have no '{}' value and the enum has no default: {}", enum_ident, ERRNO, idents_string)
+            }
+        }
+    }
+}
+
+impl From<ErrnoImplementationError> for syn::Error {
+    fn from(e: ErrnoImplementationError) -> Self {
+        syn::Error::new(e.span(), e)
+    }
+}
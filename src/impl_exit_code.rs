@@ -0,0 +1,127 @@
+use std::convert::TryFrom;
+use std::fmt::Formatter;
+
+use quote::quote;
+use syn::__private::{Span, TokenStream2};
+use syn::{Generics, Ident};
+
+use crate::common::cfg_attributes;
+use crate::enum_error::VariantWithParams;
+use crate::impl_exit_code::ExitCodeImplementationError::*;
+use crate::parameters::{EXIT_CODE, Parameters};
+
+/// Creates the method 'exit_code', which returns the std::process::ExitCode set with the parameter EXIT_CODE.
+/// This way, binaries can exit with a meaningful status by returning the exit code from main.
+pub struct ExitCodeImplementor<'a> {
+    ident: &'a Ident,
+    generics: &'a Generics,
+    parameters: &'a Parameters,
+}
+
+impl<'a> ExitCodeImplementor<'a> {
+    pub fn new(ident: &'a Ident, generics: &'a Generics, parameters: &'a Parameters) -> Self {
+        ExitCodeImplementor { ident, generics, parameters }
+    }
+
+    /// Create the method for a struct, if EXIT_CODE is set.
+    pub fn implement_for_struct(self) -> Result<TokenStream2, ExitCodeImplementationError> {
+        match Self::exit_code(self.parameters)? {
+            Some(code) => Ok(self.implement_method(code)),
+            None => Ok(quote! {})
+        }
+    }
+
+    /// Create the method for an enum, if EXIT_CODE is set on the enum or any variant.
+    ///
+    /// Variants without EXIT_CODE use the exit code of the enum or ExitCode::FAILURE if the enum has none.
+    pub fn implement_for_enum(self, variants_with_parameters: &[VariantWithParams<'_>]) -> Result<TokenStream2, ExitCodeImplementationError> {
+        let variant_codes = variants_with_parameters
+            .iter()
+            .map(|(v, p_opt)| match p_opt {
+                Some(p) => Ok((*v, Self::exit_code(p)?)),
+                None => Ok((*v, None))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        let default_code = Self::exit_code(self.parameters)?;
+
+        if default_code.is_none() && variant_codes.iter().all(|(_, code)| code.is_none()) {
+            return Ok(quote! {});
+        }
+
+        let ident = self.ident;
+        let default_code = default_code.unwrap_or_else(|| quote! {::std::process::ExitCode::FAILURE});
+        let match_arms = variant_codes
+            .into_iter()
+            .map(|(v, code)| {
+                let variant_ident = &v.ident;
+                let cfg = cfg_attributes(v);
+                let code = code.unwrap_or_else(|| default_code.clone());
+                quote! {#cfg #ident::#variant_ident { .. } => #code}
+            });
+
+        Ok(self.implement_method(quote! {
+            match self {
+                #(#match_arms,)*
+            }
+        }))
+    }
+
+    fn implement_method(&self, body: TokenStream2) -> TokenStream2 {
+        let ident = self.ident;
+        let (impl_generics, type_generics, where_clause) = self.generics.split_for_impl();
+
+        quote! {
+            impl #impl_generics #ident #type_generics #where_clause {
+                #[doc = " Return the exit code a binary should exit with because of this error."]
+                pub fn exit_code(&self) -> ::std::process::ExitCode {
+                    #body
+                }
+            }
+        }
+    }
+
+    /// Return the creation of the ExitCode set in the given parameters, if any.
+    fn exit_code(parameters: &Parameters) -> Result<Option<TokenStream2>, ExitCodeImplementationError> {
+        let (code, span) = match (parameters.integer_for_name(EXIT_CODE), parameters.span_for_name(EXIT_CODE)) {
+            (Some(code), Some(span)) => (code, span),
+            _ => return Ok(None)
+        };
+
+        match u8::try_from(code) {
+            Ok(code) => Ok(Some(quote! {::std::process::ExitCode::from(#code)})),
+            Err(_) => Err(OutOfRange(code, span))
+        }
+    }
+}
+
+/// Error that might occur when the exit_code method is created.
+#[derive(Debug)]
+pub enum ExitCodeImplementationError {
+    /// The exit code does not fit into an u8, which is the range of std::process::ExitCode.
+    OutOfRange(u64, Span),
+}
+
+impl ExitCodeImplementationError {
+    /// Return the span of the code which caused this error.
+    pub fn span(&self) -> Span {
+        match self {
+            OutOfRange(_, span) => *span
+        }
+    }
+}
+
+impl std::error::Error for ExitCodeImplementationError {}
+
+impl std::fmt::Display for ExitCodeImplementationError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            OutOfRange(code, _) => write!(f, "The exit code {} is out of range. Exit codes must be between 0 and 255.", code)
+        }
+    }
+}
+
+impl From<ExitCodeImplementationError> for syn::Error {
+    fn from(e: ExitCodeImplementationError) -> Self {
+        syn::Error::new(e.span(), e)
+    }
+}
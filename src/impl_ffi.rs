@@ -0,0 +1,339 @@
+use std::fmt::Formatter;
+
+#[cfg(feature = "ffi")]
+use proc_macro2::Literal;
+use quote::quote;
+#[cfg(feature = "ffi")]
+use quote::format_ident;
+use syn::__private::{Span, TokenStream2};
+use syn::ItemEnum;
+
+use crate::common::std_root;
+#[cfg(feature = "ffi")]
+use crate::common::{cfg_attributes, to_snake_case};
+use crate::impl_ffi::FfiImplementationError::*;
+use crate::parameters::{FFI, Parameters};
+
+/// Creates the glue to expose an enum through a C API if the parameter FFI is set. Requires the feature 'ffi'.
+///
+/// This is the enum '<name of the enum>Code' with 'repr(C)', which mirrors the variants with the codes 1, 2, 3...
+/// (0 is left for success), and the two functions
+/// - '<name in snake_case>_code(&MyError) -> u32', which returns the code of an error
+/// - '<name in snake_case>_message(&MyError, buf, len) -> usize', which writes the message of an error into a buffer
+pub struct FfiImplementor<'a> {
+    item_enum: &'a ItemEnum,
+    enum_parameters: &'a Parameters,
+}
+
+impl<'a> FfiImplementor<'a> {
+    pub fn new(item_enum: &'a ItemEnum, enum_parameters: &'a Parameters) -> Self {
+        FfiImplementor { item_enum, enum_parameters }
+    }
+
+    /// Create the code enum and the extern functions if FFI is set, otherwise return an empty token stream.
+    ///
+    /// Fails if the enum has no Display implementation or is generic, as extern functions cannot be generic.
+    pub fn implement(self, display_implemented: bool) -> Result<TokenStream2, FfiImplementationError> {
+        let span = match self.enum_parameters.span_for_name(FFI) {
+            Some(span) if self.enum_parameters.bool_for_name(FFI) => span,
+            _ => return Ok(quote! {})
+        };
+
+        let ident = &self.item_enum.ident;
+
+        if !display_implemented {
+            return Err(FfiWithoutDisplay(ident.clone(), span));
+        }
+
+        if !self.item_enum.generics.params.is_empty() {
+            return Err(FfiWithGenerics(ident.clone(), span));
+        }
+
+        implement_ffi(self.item_enum, &std_root(self.enum_parameters), span)
+    }
+}
+
+#[cfg(feature = "ffi")]
+fn implement_ffi(item_enum: &ItemEnum, root: &TokenStream2, _span: Span) -> Result<TokenStream2, FfiImplementationError> {
+    let ident = &item_enum.ident;
+    let vis = &item_enum.vis;
+    let code_ident = format_ident!("{}Code", ident);
+    let code_function = format_ident!("{}_code", to_snake_case(&ident.to_string()));
+    let message_function = format_ident!("{}_message", to_snake_case(&ident.to_string()));
+    let code_doc = format!(" The codes of the variants of '{}' for C APIs, see '{}'.", ident, code_function);
+
+    let variant_idents = item_enum.variants
+        .iter()
+        .map(|v| &v.ident)
+        .collect::<Vec<_>>();
+    let cfgs = item_enum.variants
+        .iter()
+        .map(cfg_attributes)
+        .collect::<Vec<_>>();
+    let codes = (1..=variant_idents.len()).map(Literal::usize_unsuffixed);
+
+    Ok(quote! {
+        #[doc = #code_doc]
+        #[repr(C)]
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+        #vis enum #code_ident {
+            #(#cfgs #variant_idents = #codes),*
+        }
+
+        #[doc = " Return the code of the variant of the given error."]
+        #[no_mangle]
+        pub extern "C" fn #code_function(error: &#ident) -> u32 {
+            match error {
+                #(#cfgs #ident::#variant_idents { .. } => #code_ident::#variant_idents as u32,)*
+            }
+        }
+
+        #[doc = " Write the message of the given error as nul-terminated string into the buffer with the given length."]
+        #[doc = " A message which is too long is cut at the last char which fits. Returns the length of the whole message"]
+        #[doc = " without the nul, so a return value of 'len' or more means the buffer was too small."]
+        #[doc = ""]
+        #[doc = " # Safety"]
+        #[doc = " The buffer must be null or valid for writes of 'len' bytes."]
+        #[no_mangle]
+        pub unsafe extern "C" fn #message_function(error: &#ident, buf: *mut #root::ffi::c_char, len: usize) -> usize {
+            struct Writer<'a> {
+                buf: &'a mut [u8],
+                written: usize,
+                total: usize,
+                truncated: bool,
+            }
+
+            impl #root::fmt::Write for Writer<'_> {
+                fn write_str(&mut self, s: &str) -> #root::fmt::Result {
+                    let mut n = match self.truncated {
+                        true => 0,
+                        false => s.len().min(self.buf.len() - self.written)
+                    };
+                    while !s.is_char_boundary(n) {
+                        n -= 1;
+                    }
+
+                    self.buf[self.written..self.written + n].copy_from_slice(&s.as_bytes()[..n]);
+                    self.written += n;
+                    self.total += s.len();
+                    self.truncated |= n < s.len();
+                    Ok(())
+                }
+            }
+
+            let capacity = match buf.is_null() {
+                true => 0,
+                false => len
+            };
+            let mut writer = Writer {
+                buf: match capacity {
+                    0 => &mut [],
+                    _ => #root::slice::from_raw_parts_mut(buf as *mut u8, capacity - 1)
+                },
+                written: 0,
+                total: 0,
+                truncated: false,
+            };
+            let _ = #root::fmt::Write::write_fmt(&mut writer, #root::format_args!("{}", error));
+
+            if capacity > 0 {
+                *buf.add(writer.written) = 0;
+            }
+            writer.total
+        }
+    })
+}
+
+/// Without the feature 'ffi', FFI cannot be used.
+#[cfg(not(feature = "ffi"))]
+fn implement_ffi(_item_enum: &ItemEnum, _root: &TokenStream2, span: Span) -> Result<TokenStream2, FfiImplementationError> {
+    Err(FeatureNotEnabled(span))
+}
+
+/// Error that might occur when the FFI glue is created.
+#[derive(Debug)]
+pub enum FfiImplementationError {
+    /// The enum has no message, which the message function could write.
+    FfiWithoutDisplay(syn::Ident, Span),
+    /// The enum is generic, but extern functions cannot be.
+    FfiWithGenerics(syn::Ident, Span),
+    /// FFI was used, but the feature 'ffi' is not enabled.
+    #[allow(dead_code)]
+    FeatureNotEnabled(Span),
+}
+
+impl FfiImplementationError {
+    /// Return the span of the code which caused this error.
+    pub fn span(&self) -> Span {
+        match self {
+            FfiWithoutDisplay(_, span) => *span,
+            FfiWithGenerics(_, span) => *span,
+            FeatureNotEnabled(span) => *span
+        }
+    }
+}
+
+impl std::error::Error for FfiImplementationError {}
+
+impl std::fmt::Display for FfiImplementationError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            FfiWithoutDisplay(ident, _) => write!(f, "The parameter '{}' requires a message for '{}', as the message is passed to C.", FFI, ident),
+            FfiWithGenerics(ident, _) => write!(f, "The parameter '{}' cannot be used on the generic enum '{}', as extern functions cannot be generic.", FFI, ident),
+            FeatureNotEnabled(_) => write!(f, "The parameter '{}' requires the feature 'ffi' of error_generator.", FFI)
+        }
+    }
+}
+
+impl From<FfiImplementationError> for syn::Error {
+    fn from(e: FfiImplementationError) -> Self {
+        syn::Error::new(e.span(), e)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::assert_enum_implementation_as_expected;
+
+    #[test]
+    #[should_panic(expected = "The parameter 'ffi' cannot be used on the generic enum 'E', as extern functions cannot be generic.")]
+    fn generic_enum_should_panic() {
+        assert_enum_implementation_as_expected!(
+            item: {
+                #[error(message = "failed", ffi)]
+                enum E<T> {
+                    Value(T)
+                }
+            }
+
+            expected: {
+                should panic
+            }
+        )
+    }
+
+    #[cfg(feature = "ffi")]
+    #[test]
+    fn ffi() {
+        assert_enum_implementation_as_expected!(
+            item: {
+                #[error(message = "failed", ffi)]
+                pub enum MyError {
+                    NotFound,
+                    Other(u8)
+                }
+            }
+
+            expected: {
+                #[derive(Debug)]
+                pub enum MyError {
+                    NotFound,
+                    Other(u8)
+                }
+
+                #[automatically_derived]
+                #[allow(unused_qualifications, clippy::all)]
+                impl ::std::error::Error for MyError {}
+
+                #[automatically_derived]
+                #[allow(unused_qualifications, clippy::all)]
+                impl ::std::fmt::Display for MyError {
+                    fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+                        match self {
+                            _ => ::std::write!(f, "failed")
+                        }
+                    }
+                }
+
+                #[doc = " The codes of the variants of 'MyError' for C APIs, see 'my_error_code'."]
+                #[repr(C)]
+                #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+                pub enum MyErrorCode {
+                    NotFound = 1,
+                    Other = 2
+                }
+
+                #[doc = " Return the code of the variant of the given error."]
+                #[no_mangle]
+                pub extern "C" fn my_error_code(error: &MyError) -> u32 {
+                    match error {
+                        MyError::NotFound { .. } => MyErrorCode::NotFound as u32,
+                        MyError::Other { .. } => MyErrorCode::Other as u32,
+                    }
+                }
+
+                #[doc = " Write the message of the given error as nul-terminated string into the buffer with the given length."]
+                #[doc = " A message which is too long is cut at the last char which fits. Returns the length of the whole message"]
+                #[doc = " without the nul, so a return value of 'len' or more means the buffer was too small."]
+                #[doc = ""]
+                #[doc = " # Safety"]
+                #[doc = " The buffer must be null or valid for writes of 'len' bytes."]
+                #[no_mangle]
+                pub unsafe extern "C" fn my_error_message(error: &MyError, buf: *mut ::std::ffi::c_char, len: usize) -> usize {
+                    struct Writer<'a> {
+                        buf: &'a mut [u8],
+                        written: usize,
+                        total: usize,
+                        truncated: bool,
+                    }
+
+                    impl ::std::fmt::Write for Writer<'_> {
+                        fn write_str(&mut self, s: &str) -> ::std::fmt::Result {
+                            let mut n = match self.truncated {
+                                true => 0,
+                                false => s.len().min(self.buf.len() - self.written)
+                            };
+                            while !s.is_char_boundary(n) {
+                                n -= 1;
+                            }
+
+                            self.buf[self.written..self.written + n].copy_from_slice(&s.as_bytes()[..n]);
+                            self.written += n;
+                            self.total += s.len();
+                            self.truncated |= n < s.len();
+                            Ok(())
+                        }
+                    }
+
+                    let capacity = match buf.is_null() {
+                        true => 0,
+                        false => len
+                    };
+                    let mut writer = Writer {
+                        buf: match capacity {
+                            0 => &mut [],
+                            _ => ::std::slice::from_raw_parts_mut(buf as *mut u8, capacity - 1)
+                        },
+                        written: 0,
+                        total: 0,
+                        truncated: false,
+                    };
+                    let _ = ::std::fmt::Write::write_fmt(&mut writer, ::std::format_args!("{}", error));
+
+                    if capacity > 0 {
+                        *buf.add(writer.written) = 0;
+                    }
+                    writer.total
+                }
+            }
+        )
+    }
+
+    #[cfg(not(feature = "ffi"))]
+    #[test]
+    #[should_panic(expected = "The parameter 'ffi' requires the feature 'ffi' of error_generator.")]
+    fn ffi_without_feature_should_panic() {
+        assert_enum_implementation_as_expected!(
+            item: {
+                #[error(message = "failed", ffi)]
+                enum E {
+                    NotFound
+                }
+            }
+
+            expected: {
+                should panic
+            }
+        )
+    }
+}
@@ -1,22 +1,29 @@
-use quote::quote;
-use syn::{FieldsNamed, FieldsUnnamed, ItemEnum, Variant};
-use syn::__private::TokenStream2;
-use syn::Fields::*;
+use quote::{format_ident, quote};
+use syn::{Ident, ItemEnum, parse_quote, Token, Type, Variant};
+use syn::__private::{Span, TokenStream2};
+use syn::parse::Parser;
+use syn::punctuated::Punctuated;
 
+use crate::common::{cfg_attributes, from_source_fields, to_snake_case, tokens_to_readable_string, trait_impl_attributes};
+use crate::docs::implement_doc_comment;
 use crate::enum_error::VariantWithParams;
-use crate::impl_from::FromImplementationError;
-use crate::impl_from::FromImplementationError::{EnumNotExactlyOneField, ParameterOnEnumAndVariant};
-use crate::parameters::{IMPL_FROM, Parameters};
+use crate::impl_from::{FromContext, FromImplementationError, generic_field_param, implement_concrete_from, implement_field_initialization, implement_from, implement_from_types_constant};
+use crate::impl_from::FromImplementationError::*;
+use crate::impl_io_kind::IoKindImplementor;
+use crate::impl_wrap_map::WrapMap;
+use crate::parameters::{AUTO_FROM_TYPES, DEPRECATED_FROM, FROM_IO, FROM_IO_FALLBACK, GENERATE_FROM_TESTS, IMPL_FROM, IMPL_FROM_FOR, IMPL_FROM_INTO, INCLUDES, NO_FROM, Parameters, WRAP_MAP};
 
 pub struct EnumFromImplementer<'a> {
     item_enum: &'a ItemEnum,
     enum_parameters: &'a Parameters,
     variants_with_parameters: &'a Vec<VariantWithParams<'a>>,
+    context: &'a FromContext,
+    wrap_map: &'a WrapMap<'a>,
 }
 
 impl<'a> EnumFromImplementer<'a> {
-    pub fn new(item_enum: &'a ItemEnum, enum_parameters: &'a Parameters, variants_with_parameters: &'a Vec<VariantWithParams<'a>>) -> Self {
-        EnumFromImplementer { item_enum, enum_parameters, variants_with_parameters }
+    pub fn new(item_enum: &'a ItemEnum, enum_parameters: &'a Parameters, variants_with_parameters: &'a Vec<VariantWithParams<'a>>, context: &'a FromContext, wrap_map: &'a WrapMap<'a>) -> Self {
+        EnumFromImplementer { item_enum, enum_parameters, variants_with_parameters, context, wrap_map }
     }
 
     /// Creates std::convert::From implementations for every enum variant where
@@ -28,122 +35,517 @@ impl<'a> EnumFromImplementer<'a> {
     /// An error might occur if
     ///     a variant does not have exactly one field
     ///     the enum and one variant are both marked with the parameter 'impl_from'
+    ///     a type listed in 'auto_from_types' does not match exactly one variant
+    ///     'impl_from = "into"' is used, but From would be implemented for more than one variant
+    ///     two variants would implement From for the same type
     pub fn implement(self) -> Result<TokenStream2, FromImplementationError> {
         let global_implement = self.enum_parameters.bool_for_name(IMPL_FROM);
-        let impl_from_variants = self.get_impl_from_variants();
+        let impl_from_variants = self.get_variants_with_parameter(IMPL_FROM);
 
         self.validate_impl_from_settings(global_implement, &impl_from_variants)?;
 
-        let implementations = match global_implement {
-            true => self.implement_for_variants(self.item_enum.variants.iter()),
-            false => self.implement_for_variants(impl_from_variants)
+        let variants = match global_implement {
+            true => self.get_not_excluded_variants(),
+            false => Self::merge_variants(impl_from_variants, self.get_auto_from_variants()?)
         };
+        let variants = Self::merge_variants(variants, self.wrap_map.variants());
+        let variants = Self::merge_variants(variants, self.wrap_map.other_variant().into_iter().collect());
+        self.validate_unique_source_types(&variants)?;
+        let io_implementation = self.implement_for_io_kinds(&variants)?;
+        self.validate_deprecated_from(&variants)?;
+        let into = self.uses_into(&variants)?;
+        let implementations = self.implement_for_variants(variants.iter().copied(), into)?;
+        let io_type: Type = parse_quote!(std::io::Error);
+        let from_types = variants
+            .iter()
+            .map(|v| (self.variant_field_type(v), cfg_attributes(v)))
+            .chain(io_implementation.is_some().then(|| (&io_type, quote! {})))
+            .collect::<Vec<_>>();
+        let from_types_constant = implement_from_types_constant(&self.item_enum.ident, &self.item_enum.generics, &from_types);
+        let concrete_implementations = self.implement_for_concrete_types()?;
+        let tests = self.implement_tests(&variants)?;
+
+        Ok(quote! {
+            #(#implementations)*
+            #io_implementation
+            #from_types_constant
+            #(#concrete_implementations)*
+            #tests
+        })
+    }
+
+    /// Create a test module with a test for the From implementation of every given variant, if GENERATE_FROM_TESTS is set.
+    /// Each test converts the default value of the source type and checks that the expected variant is created.
+    /// Source types without a Default implementation are not checked, which is decided with autoref specialization.
+    ///
+    /// Fails if the enum is generic, as the tests require concrete source types.
+    fn implement_tests(&self, variants: &[&Variant]) -> Result<TokenStream2, FromImplementationError> {
+        let span = match self.enum_parameters.span_for_name(GENERATE_FROM_TESTS) {
+            Some(span) if self.enum_parameters.bool_for_name(GENERATE_FROM_TESTS) => span,
+            _ => return Ok(quote! {})
+        };
+
+        let enum_ident = &self.item_enum.ident;
+        if !self.item_enum.generics.params.is_empty() {
+            return Err(FromTestsWithGenerics(enum_ident.clone(), span));
+        }
+
+        if variants.is_empty() {
+            return Ok(quote! {});
+        }
+
+        let module_ident = format_ident!("__{}_from_tests", to_snake_case(&enum_ident.to_string()));
+        let tests = variants
+            .iter()
+            .map(|v| {
+                let variant_ident = &v.ident;
+                let test_ident = format_ident!("from_{}", to_snake_case(&variant_ident.to_string()));
+                let ty = self.variant_field_type(v);
+                let message = format!("converting {} should create {}::{}", tokens_to_readable_string(ty), enum_ident, variant_ident);
+                let cfg = cfg_attributes(v);
+
+                quote! {
+                    #cfg
+                    #[test]
+                    fn #test_ident() {
+                        if let Some(source) = (&Probe::<#ty>(::std::marker::PhantomData)).source() {
+                            assert!(matches!(#enum_ident::from(source), #enum_ident::#variant_ident { .. }), #message);
+                        }
+                    }
+                }
+            });
+
+        Ok(quote! {
+            #[cfg(test)]
+            mod #module_ident {
+                use super::*;
+
+                struct Probe<T>(::std::marker::PhantomData<T>);
+
+                trait WithDefault<T> {
+                    fn source(&self) -> Option<T>;
+                }
 
-        Ok(quote! {#(#implementations)*})
+                impl<T: Default> WithDefault<T> for Probe<T> {
+                    fn source(&self) -> Option<T> {
+                        Some(T::default())
+                    }
+                }
+
+                trait WithoutDefault<T> {
+                    fn source(&self) -> Option<T>;
+                }
+
+                impl<T> WithoutDefault<T> for &Probe<T> {
+                    fn source(&self) -> Option<T> {
+                        None
+                    }
+                }
+
+                #(#tests)*
+            }
+        })
     }
 
-    /// Return all variants with set IMPL_FROM parameter
-    fn get_impl_from_variants(&self) -> Vec<&Variant> {
+    /// Check if IMPL_FROM is set to IMPL_FROM_INTO on the enum or a variant.
+    ///
+    /// Fails if IMPL_FROM_INTO is used, but From would be implemented for more than one variant.
+    fn uses_into(&self, variants: &[&Variant]) -> Result<bool, FromImplementationError> {
+        let span = match self.enum_parameters.has_string_value(IMPL_FROM, IMPL_FROM_INTO) {
+            true => self.enum_parameters.span_for_name(IMPL_FROM),
+            false => self.variants_with_parameters
+                .iter()
+                .filter_map(|(_, p_opt)| p_opt.as_ref())
+                .find(|p| p.has_string_value(IMPL_FROM, IMPL_FROM_INTO))
+                .and_then(|p| p.span_for_name(IMPL_FROM))
+        };
+
+        match (span, variants.len()) {
+            (Some(span), n) if n > 1 => Err(IntoWithOtherFromImplementations(self.item_enum.ident.clone(), span)),
+            (span, _) => Ok(span.is_some())
+        }
+    }
+
+    /// Return all variants with the given boolean parameter set
+    fn get_variants_with_parameter(&self, name: &str) -> Vec<&Variant> {
         self.variants_with_parameters
             .iter()
-            .filter_map(|(v, p_opt)| match p_opt {
-                Some(p) => Some((v, p)),
-                _ => None
+            .filter_map(|(v, p_opt)| p_opt.as_ref().map(|p| (v, p)))
+            .filter_map(|(v, p)| match p.bool_for_name(name) {
+                true => Some(*v),
+                false => None
             })
-            .filter_map(|(v, p)| match p.bool_for_name(IMPL_FROM) {
+            .collect()
+    }
+
+    /// Return all variants with the given string parameter set
+    fn get_variants_with_string_parameter(&self, name: &str) -> Vec<&Variant> {
+        self.variants_with_parameters
+            .iter()
+            .filter_map(|(v, p_opt)| match p_opt.as_ref()?.has_parameter(name) {
                 true => Some(*v),
                 false => None
             })
             .collect()
     }
 
+    /// Return the variants selected by the AUTO_FROM_TYPES parameter. Every listed type must
+    /// be the type of the single field of exactly one variant.
+    fn get_auto_from_variants(&self) -> Result<Vec<&Variant>, FromImplementationError> {
+        let (type_list, span) = match (self.enum_parameters.string_for_name(AUTO_FROM_TYPES), self.enum_parameters.span_for_name(AUTO_FROM_TYPES)) {
+            (Some(type_list), Some(span)) => (type_list, span),
+            _ => return Ok(vec![])
+        };
+
+        Self::parse_type_list(type_list, span)?
+            .iter()
+            .map(tokens_to_readable_string)
+            .map(|type_name| {
+                let matching_variants = self.item_enum.variants
+                    .iter()
+                    .filter(|v| self.variant_num_fields(v) == 1)
+                    .filter(|v| tokens_to_readable_string(self.variant_field_type(v)) == type_name)
+                    .collect::<Vec<_>>();
+
+                match matching_variants.len() {
+                    1 => Ok(matching_variants[0]),
+                    0 => Err(AutoFromTypeWithoutVariant(self.item_enum.ident.clone(), type_name, span)),
+                    _ => Err(AutoFromTypeWithMultipleVariants(self.item_enum.ident.clone(), type_name, matching_variants.iter().map(|v| v.ident.clone()).collect(), span))
+                }
+            })
+            .collect()
+    }
+
+    fn parse_type_list(type_list: &str, span: Span) -> Result<Punctuated<Type, Token![,]>, FromImplementationError> {
+        Punctuated::<Type, Token![,]>::parse_terminated
+            .parse_str(type_list)
+            .map_err(|_| InvalidTypeList(type_list.to_string(), span))
+    }
+
+    /// Create the From implementations for every variant with IMPL_FROM_FOR and every type listed there.
+    /// The type parameter of the variants field is replaced with the listed type, like 'impl From<String> for E<String>'.
+    ///
+    /// These implementations are not listed in FROM_TYPES, as they only exist for some instantiations of the enum.
+    fn implement_for_concrete_types(&self) -> Result<Vec<TokenStream2>, FromImplementationError> {
+        let enum_ident = &self.item_enum.ident;
+        let generics = &self.item_enum.generics;
+        let mut implementations = vec![];
+
+        for (variant, parameters) in self.variants_with_parameters.iter().filter_map(|(v, p_opt)| Some((*v, p_opt.as_ref()?))) {
+            let (type_list, span) = match (parameters.string_for_name(IMPL_FROM_FOR), parameters.span_for_name(IMPL_FROM_FOR)) {
+                (Some(type_list), Some(span)) => (type_list, span),
+                _ => continue
+            };
+
+            let source = match from_source_fields(&variant.fields)[..] {
+                [source] if generic_field_param(generics, source).is_some() => source,
+                _ => return Err(ImplFromForWithoutGenericField(variant.ident.clone(), span))
+            };
+
+            let variant_ident = &variant.ident;
+            let cfg = cfg_attributes(variant);
+            let context = self.context.for_variant(variant_ident, Some(parameters))?;
+            for concrete in Self::parse_type_list(type_list, span)? {
+                let from_doc = self.context.implement_doc(&self.item_enum.ident, &self.item_enum.generics, &concrete);
+                let deprecation_doc = implement_deprecation_doc(Some(parameters));
+                let from_implementation = implement_concrete_from(enum_ident, generics, quote!(#enum_ident::#variant_ident), &variant.fields, source, &concrete, &context)?;
+                implementations.push(quote! {
+                    #cfg
+                    #from_doc
+                    #deprecation_doc
+                    #from_implementation
+                })
+            }
+        }
+
+        Ok(implementations)
+    }
+
+    /// Add all additional variants to the given variants, if they are not already contained.
+    fn merge_variants<'b>(mut variants: Vec<&'b Variant>, additional_variants: Vec<&'b Variant>) -> Vec<&'b Variant> {
+        for additional in additional_variants {
+            if !variants.iter().any(|v| v.ident == additional.ident) {
+                variants.push(additional)
+            }
+        }
+
+        variants
+    }
+
+    /// Return all variants which are not excluded from the global IMPL_FROM by NO_FROM.
+    /// Variants with IMPL_FROM_FOR or FROM_IO are excluded as well, as they get other implementations instead.
+    fn get_not_excluded_variants(&self) -> Vec<&Variant> {
+        let mut excluded_variants = self.get_variants_with_parameter(NO_FROM);
+        excluded_variants.extend(self.get_variants_with_string_parameter(IMPL_FROM_FOR));
+        excluded_variants.extend(self.get_variants_with_string_parameter(FROM_IO));
+
+        self.item_enum.variants
+            .iter()
+            .filter(|v| !excluded_variants.iter().any(|e| e.ident == v.ident))
+            .collect()
+    }
+
     /// Check the global IMPL_FROM setting and all variants with impl_from set.
     ///
     /// An error occurs if
     /// the global IMPL_FROM was set and at least one variant has impl_from set
     /// OR
+    /// the global IMPL_FROM and AUTO_FROM_TYPES were both set
+    /// OR
+    /// the global IMPL_FROM was set and no (not excluded) variant has any field
+    /// OR
     /// variants with IMPL_FROM set have not exactly one field.
     ///
     /// If the global IMPL_FROM is
-    ///     true, all variants are checked
+    ///     true, all variants without NO_FROM are checked
     ///     false, only variants with IMPL_FROM set are checked
-    fn validate_impl_from_settings(&self, global_impl_from: bool, impl_from_variants: &Vec<&Variant>) -> Result<(), FromImplementationError> {
-        if global_impl_from && impl_from_variants.len() > 0 {
-            return Err(ParameterOnEnumAndVariant(self.item_enum.ident.clone()));
-        }
-
-        let variant_idents_with_not_one_field = match global_impl_from {
-            true => self.item_enum.variants.iter()
-                .filter(|v| self.variant_num_fields(v) != 1)
-                .map(|v| v.ident.clone())
-                .collect::<Vec<_>>(),
-            false => impl_from_variants.iter()
-                .filter(|v| self.variant_num_fields(v) != 1)
-                .map(|v| v.ident.clone())
-                .collect::<Vec<_>>()
+    fn validate_impl_from_settings(&self, global_impl_from: bool, impl_from_variants: &[&Variant]) -> Result<(), FromImplementationError> {
+        if global_impl_from && !impl_from_variants.is_empty() {
+            return Err(ParameterOnEnumAndVariant(self.item_enum.ident.clone(), self.first_variant_span_for_name(IMPL_FROM)));
+        }
+
+        if let (true, Some(span)) = (global_impl_from, self.enum_parameters.span_for_name(AUTO_FROM_TYPES)) {
+            return Err(AutoFromTypesWithGlobalImplFrom(self.item_enum.ident.clone(), span));
+        }
+
+        let checked_variants = match global_impl_from {
+            true => self.get_not_excluded_variants(),
+            false => impl_from_variants.to_vec()
         };
 
+        if global_impl_from && checked_variants.iter().all(|v| self.variant_num_fields(v) == 0) {
+            return Err(EnumNoPayloadVariants(self.item_enum.ident.clone(), self.enum_parameters.span_for_name(IMPL_FROM).unwrap()));
+        }
+
+        let variant_idents_with_not_one_field = checked_variants
+            .iter()
+            .filter(|v| self.variant_num_fields(v) != 1)
+            .map(|v| v.ident.clone())
+            .collect::<Vec<_>>();
+
         match variant_idents_with_not_one_field.len() {
             0 => Ok(()),
             _ => Err(EnumNotExactlyOneField(self.item_enum.ident.clone(), variant_idents_with_not_one_field))
         }
     }
 
-    fn variant_num_fields(&self, variant: &Variant) -> usize {
-        match &variant.fields {
-            Named(f) => f.named.len(),
-            Unnamed(f) => f.unnamed.len(),
-            Unit => 0
+    /// Check that no two of the given variants convert from the same type, as their From
+    /// implementations would conflict.
+    ///
+    /// Only variants with the same cfg attributes are compared, as variants with different ones might never be
+    /// compiled together, like variants for 'feature = "a"' and 'not(feature = "a")'. If both are compiled after all,
+    /// the From implementations conflict and the compiler reports it.
+    fn validate_unique_source_types(&self, variants: &[&Variant]) -> Result<(), FromImplementationError> {
+        let type_names = variants
+            .iter()
+            .map(|v| (tokens_to_readable_string(self.variant_field_type(v)), cfg_attributes(v).to_string()))
+            .collect::<Vec<_>>();
+
+        for (i, (type_name, cfg)) in type_names.iter().enumerate() {
+            if let Some(j) = type_names[..i].iter().position(|(t, c)| t == type_name && c == cfg) {
+                let hint = self.duplicate_source_type_hint(variants[j], variants[i]);
+                return Err(DuplicateSourceType(self.item_enum.ident.clone(), type_name.clone(), variants[j].ident.clone(), variants[i].ident.clone(), hint));
+            }
         }
-    }
 
-    fn implement_for_variants<'b, I>(&self, variants: I) -> Vec<TokenStream2>
-        where I: IntoIterator<Item=&'b Variant> {
-        variants.into_iter()
-            .map(|v| self.implement_for_variant(v))
-            .collect()
+        Ok(())
     }
 
-    fn implement_for_variant(&self, variant: &Variant) -> TokenStream2 {
-        match &variant.fields {
-            Named(ref fields) => self.implement_for_named(variant, fields),
-            Unnamed(ref fields) => self.implement_for_unnamed(variant, fields),
-            Unit => unreachable!()
+    /// Return how to resolve the conflict of the given variants, which convert from the same type. The hint depends on the
+    /// parameter which creates the From implementation of one of the variants, preferring the second one:
+    /// the global IMPL_FROM is excluded with NO_FROM, IMPL_FROM of a variant is removed and the type is removed
+    /// from AUTO_FROM_TYPES. Otherwise, both variants are listed in WRAP_MAP or INCLUDES.
+    fn duplicate_source_type_hint(&self, first: &Variant, second: &Variant) -> String {
+        let is_wrapped = |v: &Variant| self.wrap_map.variants().iter().chain(self.wrap_map.other_variant().iter()).any(|w| w.ident == v.ident);
+        let candidates = [second, first];
+
+        if self.enum_parameters.bool_for_name(IMPL_FROM) {
+            if let Some(v) = candidates.iter().find(|v| !is_wrapped(v)) {
+                return format!("Exclude '{}' with '{}'.", v.ident, NO_FROM);
+            }
+        }
+
+        let impl_from_variants = self.get_variants_with_parameter(IMPL_FROM);
+        match candidates.iter().filter(|v| impl_from_variants.iter().any(|i| i.ident == v.ident)).collect::<Vec<_>>()[..] {
+            [_, _] => return format!("Remove '{}' from one of them.", IMPL_FROM),
+            [v] => return format!("Remove '{}' from '{}'.", IMPL_FROM, v.ident),
+            _ => {}
+        }
+
+        if candidates.iter().any(|v| !is_wrapped(v)) {
+            return format!("Remove the type from '{}'.", AUTO_FROM_TYPES);
         }
+
+        format!("List the type only once in '{}' and '{}'.", WRAP_MAP, INCLUDES)
     }
 
-    fn implement_for_named(&self, variant: &Variant, fields: &FieldsNamed) -> TokenStream2 {
+    /// Create the From implementation for std::io::Error, if any variant has FROM_IO set. The implementation
+    /// matches on the std::io::ErrorKind of the error and creates the variant which lists the kind,
+    /// or the variant with FROM_IO_FALLBACK for all other kinds.
+    ///
+    /// Fails if a variant with FROM_IO has not exactly one field, a kind is listed twice, no variant is the fallback
+    /// or one of the given variants (which get their own From implementations) converts from std::io::Error as well.
+    fn implement_for_io_kinds(&self, variants: &[&Variant]) -> Result<Option<TokenStream2>, FromImplementationError> {
         let enum_ident = &self.item_enum.ident;
-        let generics = &self.item_enum.generics;
-        let (impl_generics, type_generics, where_clause) = generics.split_for_impl();
-        let variant_ident = &variant.ident;
-        let field = fields.named.first().unwrap();
-        let ty = &field.ty;
-        let field_ident = field.ident.as_ref().unwrap();
-
-        quote! {
-            impl #impl_generics std::convert::From<#ty> for #enum_ident #type_generics #where_clause {
-                fn from(val: #ty) -> Self {
-                    #enum_ident::#variant_ident{ #field_ident : val }
+        let io_variants = self.variants_with_parameters
+            .iter()
+            .filter_map(|(v, p_opt)| {
+                let parameters = p_opt.as_ref()?;
+                Some((*v, parameters, parameters.string_for_name(FROM_IO)?, parameters.span_for_name(FROM_IO)?))
+            })
+            .collect::<Vec<_>>();
+
+        let first_span = match io_variants.first() {
+            Some((_, _, _, span)) => *span,
+            None => return Ok(None)
+        };
+
+        let variant_idents_with_not_one_field = io_variants
+            .iter()
+            .filter(|(v, _, _, _)| self.variant_num_fields(v) != 1)
+            .map(|(v, _, _, _)| v.ident.clone())
+            .collect::<Vec<_>>();
+        if !variant_idents_with_not_one_field.is_empty() {
+            return Err(EnumNotExactlyOneField(enum_ident.clone(), variant_idents_with_not_one_field));
+        }
+
+        if let Some(variant) = variants.iter().find(|v| IoKindImplementor::is_io_error(self.variant_field_type(v))) {
+            return Err(FromIoWithOtherIoConversion(enum_ident.clone(), variant.ident.clone(), first_span));
+        }
+
+        let mut listed_kinds: Vec<String> = vec![];
+        let mut match_arms = vec![];
+        let mut fallback_arm = None;
+        for (variant, parameters, kinds, span) in io_variants {
+            let variant_ident = &variant.ident;
+            let source = from_source_fields(&variant.fields)[0];
+            let context = self.context.for_variant(variant_ident, Some(parameters))?;
+            let field_initialization = implement_field_initialization(&variant.fields, source, quote! {val}, &context.root, quote! {"std::io::Error"})?;
+            let construction = context.implement_construction(quote! {#enum_ident::#variant_ident #field_initialization});
+            let cfg = cfg_attributes(variant);
+
+            let mut patterns = vec![];
+            for kind in kinds.split(',').map(str::trim) {
+                if listed_kinds.iter().any(|k| k == kind) {
+                    return Err(FromIoDuplicateKind(kind.to_string(), span));
+                }
+                listed_kinds.push(kind.to_string());
+
+                match kind {
+                    FROM_IO_FALLBACK => fallback_arm = Some(quote! {_ => { #construction }}),
+                    kind => {
+                        let kind = syn::parse_str::<Ident>(kind).map_err(|_| InvalidIoKind(kind.to_string(), span))?;
+                        patterns.push(quote! {::std::io::ErrorKind::#kind})
+                    }
+                }
+            }
+
+            if !patterns.is_empty() {
+                match_arms.push(quote! {#cfg #(#patterns)|* => { #construction }})
+            }
+        }
+
+        let fallback_arm = fallback_arm.ok_or_else(|| FromIoWithoutFallback(enum_ident.clone(), first_span))?;
+        let io_type: Type = parse_quote!(std::io::Error);
+        let from_doc = self.context.implement_doc(&self.item_enum.ident, &self.item_enum.generics, &io_type);
+        let root = &self.context.root;
+        let (impl_generics, type_generics, where_clause) = self.item_enum.generics.split_for_impl();
+
+        let impl_attributes = trait_impl_attributes();
+
+        Ok(Some(quote! {
+            #from_doc
+            #impl_attributes
+            impl #impl_generics #root::convert::From<::std::io::Error> for #enum_ident #type_generics #where_clause {
+                #[track_caller]
+                fn from(val: ::std::io::Error) -> Self {
+                    match val.kind() {
+                        #(#match_arms,)*
+                        #fallback_arm
+                    }
                 }
             }
+        }))
+    }
+
+    /// Check that every variant with DEPRECATED_FROM has a From implementation, which is the case if it is one of
+    /// the given variants or has IMPL_FROM_FOR set.
+    fn validate_deprecated_from(&self, variants: &[&Variant]) -> Result<(), FromImplementationError> {
+        for (variant, parameters) in self.variants_with_parameters.iter().filter_map(|(v, p_opt)| Some((*v, p_opt.as_ref()?))) {
+            let span = match parameters.span_for_name(DEPRECATED_FROM) {
+                Some(span) => span,
+                None => continue
+            };
+
+            if !variants.iter().any(|v| v.ident == variant.ident) && !parameters.has_parameter(IMPL_FROM_FOR) {
+                return Err(DeprecatedFromWithoutFrom(variant.ident.clone(), span));
+            }
         }
+
+        Ok(())
+    }
+
+    /// Return the span of the given parameter on the first variant which has it set.
+    fn first_variant_span_for_name(&self, name: &str) -> Span {
+        self.variants_with_parameters
+            .iter()
+            .find_map(|(_, p_opt)| p_opt.as_ref()?.span_for_name(name))
+            .unwrap_or_else(|| self.item_enum.ident.span())
+    }
+
+    /// Return the number of fields of the given variant which could be converted from.
+    fn variant_num_fields(&self, variant: &Variant) -> usize {
+        from_source_fields(&variant.fields).len()
     }
 
-    fn implement_for_unnamed(&self, variant: &Variant, fields: &FieldsUnnamed) -> TokenStream2 {
+    /// Return the type of the single source field of the given variant.
+    fn variant_field_type<'b>(&self, variant: &'b Variant) -> &'b Type {
+        &from_source_fields(&variant.fields)[0].ty
+    }
+
+    fn implement_for_variants<'b, I>(&self, variants: I, into: bool) -> Result<Vec<TokenStream2>, FromImplementationError>
+        where I: IntoIterator<Item=&'b Variant> {
+        variants.into_iter()
+            .map(|v| self.implement_for_variant(v, into))
+            .collect()
+    }
+
+    /// Implement From for the type of the single source field of the variant (or everything that
+    /// converts Into it, if 'into' is true). All other fields are initialized with their default value.
+    fn implement_for_variant(&self, variant: &Variant, into: bool) -> Result<TokenStream2, FromImplementationError> {
         let enum_ident = &self.item_enum.ident;
-        let generics = &self.item_enum.generics;
-        let (impl_generics, type_generics, where_clause) = generics.split_for_impl();
         let variant_ident = &variant.ident;
-        let field = fields.unnamed.first().unwrap();
-        let ty = &field.ty;
+        let source = from_source_fields(&variant.fields)[0];
+        let from_doc = self.context.implement_doc(&self.item_enum.ident, &self.item_enum.generics, &source.ty);
+        let deprecation_doc = implement_deprecation_doc(self.variant_parameters(variant));
+        let context = self.context.for_variant(variant_ident, self.variant_parameters(variant))?;
+        let from_implementation = implement_from(enum_ident, &self.item_enum.generics, quote!(#enum_ident::#variant_ident), &variant.fields, source, into, &context)?;
+        let cfg = cfg_attributes(variant);
 
-        quote! {
-            impl #impl_generics std::convert::From<#ty> for #enum_ident #type_generics #where_clause {
-                fn from(val: #ty) -> Self {
-                    #enum_ident::#variant_ident(val)
-                }
-            }
-        }
+        Ok(quote! {
+            #cfg
+            #from_doc
+            #deprecation_doc
+            #from_implementation
+        })
+    }
+
+    /// Return the parameters of the given variant, if it has any.
+    fn variant_parameters(&self, variant: &Variant) -> Option<&Parameters> {
+        self.variants_with_parameters
+            .iter()
+            .find(|(v, _)| v.ident == variant.ident)
+            .and_then(|(_, p_opt)| p_opt.as_ref())
     }
-}
\ No newline at end of file
+}
+
+/// Create the documentation of a From implementation, which marks it as deprecated with the note from
+/// DEPRECATED_FROM, if the variant has it.
+///
+/// The compiler does not accept #[deprecated] on trait implementations, so the deprecation can only be documented.
+fn implement_deprecation_doc(variant_parameters: Option<&Parameters>) -> TokenStream2 {
+    match variant_parameters.and_then(|p| p.string_for_name(DEPRECATED_FROM)) {
+        Some(note) => implement_doc_comment(&format!("\n# Deprecated\n\n{}", note)),
+        None => quote! {}
+    }
+}
@@ -1,25 +1,407 @@
 use std::fmt::Formatter;
 
-use syn::Ident;
+use quote::quote;
+use syn::__private::{Span, TokenStream2};
+use syn::{Expr, Field, Fields, GenericArgument, GenericParam, Generics, Ident, parse_quote, Type, WherePredicate};
 
+use crate::common::{field_is_location, field_is_phantom, field_is_source_type, field_parameters, std_root, tokens_to_readable_string, trait_impl_attributes};
+use crate::docs::implement_doc_comment;
 use crate::impl_from::FromImplementationError::*;
-use crate::parameters::IMPL_FROM;
+use crate::parameters::{AUTO_FROM_TYPES, DEFAULT, DEPRECATED_FROM, FROM_IO, FROM_IO_FALLBACK, GENERATE_FROM_TESTS, IMPL_FROM, IMPL_FROM_FOR, IMPL_FROM_INTO, LOG, NO_FROM, NO_FROM_DOCS, Parameters, TRACE, TRACE_WARN};
 
 pub mod structs;
 pub mod enums;
 
+/// The levels of the log crate, which can be used with LOG.
+const LOG_LEVELS: &[&str] = &["error", "warn", "info", "debug", "trace"];
+
+/// Settings which apply to every From implementation of an error and to its constructors (see ConstructorsImplementor).
+#[derive(Clone)]
+pub struct FromContext {
+    /// The root of the paths to std items, see common::std_root
+    root: TokenStream2,
+    /// The tracing macro which records the error whenever it is created with From or a constructor, see TRACE
+    trace: Option<TokenStream2>,
+    /// The log macro which logs the message whenever the error is created with From or a constructor, see LOG
+    log: Option<TokenStream2>,
+    /// If the error has a Display implementation, which is required by TRACE and LOG
+    display_implemented: bool,
+    /// If the From implementations are documented, which can be disabled with NO_FROM_DOCS
+    docs: bool,
+}
+
+impl FromContext {
+    /// Create the context from the parameters of the error.
+    ///
+    /// Fails if TRACE or LOG is set, but the error has no Display implementation, as the events contain the message.
+    pub fn new(ident: &Ident, parameters: &Parameters, display_implemented: bool) -> Result<Self, FromImplementationError> {
+        let trace = match parameters.span_for_name(TRACE) {
+            Some(span) if parameters.bool_for_name(TRACE) && !display_implemented => return Err(TraceWithoutDisplay(ident.clone(), span)),
+            Some(_) if parameters.has_string_value(TRACE, TRACE_WARN) => Some(quote!(tracing::warn)),
+            Some(_) if parameters.bool_for_name(TRACE) => Some(quote!(tracing::error)),
+            _ => None
+        };
+        let log = Self::log_macro(ident, parameters, display_implemented)?;
+
+        Ok(FromContext { root: std_root(parameters), trace, log, display_implemented, docs: !parameters.bool_for_name(NO_FROM_DOCS) })
+    }
+
+    /// Return the context for the From implementation or the constructors of a variant. The LOG level of the variant
+    /// replaces the one of the enum.
+    pub fn for_variant(&self, variant_ident: &Ident, variant_parameters: Option<&Parameters>) -> Result<Self, FromImplementationError> {
+        let mut context = self.clone();
+
+        if let Some(log) = variant_parameters.map(|p| Self::log_macro(variant_ident, p, self.display_implemented)).transpose()?.flatten() {
+            context.log = Some(log)
+        }
+
+        Ok(context)
+    }
+
+    /// Return the macro of the log crate for the level set with LOG, like 'log::warn'.
+    fn log_macro(ident: &Ident, parameters: &Parameters, display_implemented: bool) -> Result<Option<TokenStream2>, FromImplementationError> {
+        let (level, span) = match (parameters.string_for_name(LOG), parameters.span_for_name(LOG)) {
+            (Some(level), Some(span)) => (level, span),
+            _ => return Ok(None)
+        };
+
+        if !LOG_LEVELS.contains(&level) {
+            return Err(InvalidLogLevel(level.to_string(), span));
+        }
+
+        if !display_implemented {
+            return Err(LogWithoutDisplay(ident.clone(), span));
+        }
+
+        let level = Ident::new(level, span);
+        Ok(Some(quote!(log::#level)))
+    }
+
+    /// Create the documentation for the From implementation of the given type into the error with the given ident
+    /// and generics (see implement_from_doc), unless it was disabled with NO_FROM_DOCS.
+    fn implement_doc(&self, ident: &Ident, generics: &Generics, ty: &Type) -> TokenStream2 {
+        match self.docs {
+            true => implement_from_doc(ident, generics, ty),
+            false => quote! {}
+        }
+    }
+
+    /// Create the construction of the error inside of a From implementation or a constructor. If TRACE is set,
+    /// an event with the message and the Debug representation (so all field values) is emitted.
+    /// If LOG is set, the message is logged.
+    pub fn implement_construction(&self, construction: TokenStream2) -> TokenStream2 {
+        if self.trace.is_none() && self.log.is_none() {
+            return construction;
+        }
+
+        let trace = self.trace.iter();
+        let log = self.log.iter();
+
+        quote! {
+            let error = #construction;
+            #(#trace!(error = ?error, "{}", error);)*
+            #(#log!("{}", error);)*
+            error
+        }
+    }
+}
+
+/// Create the documentation of a From implementation, which shows how the conversion
+/// can be used with the ?-operator.
+fn implement_from_doc(ident: &Ident, generics: &Generics, ty: &Type) -> TokenStream2 {
+    let (_, type_generics, _) = generics.split_for_impl();
+    let error_name = tokens_to_readable_string(&quote!(#ident #type_generics));
+    let type_name = tokens_to_readable_string(ty);
+
+    implement_doc_comment(&format!(
+        "Converts a `{}` into a `{}`, which allows using the ?-operator like\n\n```ignore\nfn example(result: Result<(), {}>) -> Result<(), {}> {{\n    result?;\n    Ok(())\n}}\n```",
+        type_name, error_name, type_name, error_name
+    ))
+}
+
+/// Create the std::convert::From implementation for the type of the source field. The item is created with
+/// the constructor (like 'S' or 'E::Variant') and the initialization of the given fields.
+///
+/// If 'into' is true, From is implemented for every type which implements Into<ty>, like
+/// 'impl<__T: Into<String>> From<__T> for E'.
+///
+/// Fails if the default expression of any other field is invalid.
+pub fn implement_from(ident: &Ident, generics: &Generics, constructor: TokenStream2, fields: &Fields, source: &Field, into: bool, context: &FromContext) -> Result<TokenStream2, FromImplementationError> {
+    let (impl_generics, type_generics, where_clause) = generics.split_for_impl();
+    let ty = &source.ty;
+    let root = &context.root;
+
+    if !into {
+        let type_name = tokens_to_readable_string(ty);
+        let field_initialization = implement_field_initialization(fields, source, quote! {val}, root, quote! {#type_name})?;
+        let construction = context.implement_construction(quote! {#constructor #field_initialization});
+
+        let impl_attributes = trait_impl_attributes();
+
+        return Ok(quote! {
+            #impl_attributes
+            impl #impl_generics #root::convert::From<#ty> for #ident #type_generics #where_clause {
+                #[track_caller]
+                fn from(val: #ty) -> Self {
+                    #construction
+                }
+            }
+        });
+    }
+
+    let mut into_generics = generics.clone();
+    into_generics.params.push(parse_quote!(__T: #root::convert::Into<#ty>));
+    let (into_impl_generics, _, _) = into_generics.split_for_impl();
+    let field_initialization = implement_field_initialization(fields, source, quote! {#root::convert::Into::into(val)}, root, quote! {#root::any::type_name::<__T>()})?;
+    let construction = context.implement_construction(quote! {#constructor #field_initialization});
+
+    let impl_attributes = trait_impl_attributes();
+
+    Ok(quote! {
+        #impl_attributes
+        impl #into_impl_generics #root::convert::From<__T> for #ident #type_generics #where_clause {
+            #[track_caller]
+            fn from(val: __T) -> Self {
+                #construction
+            }
+        }
+    })
+}
+
+/// Return the type parameter of the given generics which is the type of the given field, if any.
+pub fn generic_field_param<'b>(generics: &'b Generics, field: &Field) -> Option<&'b Ident> {
+    generics.type_params()
+        .map(|t| &t.ident)
+        .find(|ident| matches!(&field.ty, Type::Path(p) if p.path.is_ident(*ident)))
+}
+
+/// Create the std::convert::From implementation for the concrete type, which replaces the type parameter
+/// of the source field, like 'impl From<String> for E<String>' for 'E<T>'.
+///
+/// The type of the source field must be a type parameter (see generic_field_param).
+/// The bounds of the type parameter become bounds of the concrete type, so they are still checked.
+pub fn implement_concrete_from(ident: &Ident, generics: &Generics, constructor: TokenStream2, fields: &Fields, source: &Field, concrete: &Type, context: &FromContext) -> Result<TokenStream2, FromImplementationError> {
+    let param = generic_field_param(generics, source).unwrap();
+    let mut concrete_generics = generics.clone();
+    let mut predicates: Vec<WherePredicate> = vec![];
+
+    concrete_generics.params = generics.params
+        .iter()
+        .filter(|p| match p {
+            GenericParam::Type(t) if t.ident == *param => {
+                let bounds = &t.bounds;
+                if !bounds.is_empty() {
+                    predicates.push(parse_quote!(#concrete: #bounds));
+                }
+                false
+            }
+            _ => true
+        })
+        .cloned()
+        .collect();
+
+    let where_clause = concrete_generics.make_where_clause();
+    for predicate in where_clause.predicates.iter_mut() {
+        if let WherePredicate::Type(predicate_type) = predicate {
+            if matches!(&predicate_type.bounded_ty, Type::Path(p) if p.path.is_ident(param)) {
+                predicate_type.bounded_ty = concrete.clone();
+            }
+        }
+    }
+    where_clause.predicates.extend(predicates);
+
+    let type_arguments = generics.params
+        .iter()
+        .map(|p| -> GenericArgument {
+            match p {
+                GenericParam::Type(t) if t.ident == *param => GenericArgument::Type(concrete.clone()),
+                GenericParam::Type(t) => {
+                    let ident = &t.ident;
+                    parse_quote!(#ident)
+                }
+                GenericParam::Lifetime(l) => GenericArgument::Lifetime(l.lifetime.clone()),
+                GenericParam::Const(c) => {
+                    let ident = &c.ident;
+                    parse_quote!(#ident)
+                }
+            }
+        });
+
+    let (impl_generics, _, where_clause) = concrete_generics.split_for_impl();
+    let root = &context.root;
+    let type_name = tokens_to_readable_string(concrete);
+    let field_initialization = implement_field_initialization(fields, source, quote! {val}, root, quote! {#type_name})?;
+    let construction = context.implement_construction(quote! {#constructor #field_initialization});
+
+    let impl_attributes = trait_impl_attributes();
+
+    Ok(quote! {
+        #impl_attributes
+        impl #impl_generics #root::convert::From<#concrete> for #ident <#(#type_arguments),*> #where_clause {
+            #[track_caller]
+            fn from(val: #concrete) -> Self {
+                #construction
+            }
+        }
+    })
+}
+
+/// Create the initialization of the given fields inside of a From implementation, like
+/// '{ a: val, b: Default::default() }' or '(val, Default::default())'.
+///
+/// The source field is set to the given value and fields marked with SOURCE_TYPE are set to the given
+/// name of the converted type. All other fields get their default value.
+fn implement_field_initialization(fields: &Fields, source: &Field, value: TokenStream2, root: &TokenStream2, type_name: TokenStream2) -> Result<TokenStream2, FromImplementationError> {
+    let values = fields
+        .iter()
+        .map(|field| match (std::ptr::eq(field, source), field_is_source_type(field)) {
+            (true, _) => Ok(value.clone()),
+            (false, true) => Ok(type_name.clone()),
+            (false, false) => implement_default_value(field, root)
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(match fields {
+        Fields::Named(_) => {
+            let idents = fields.iter().map(|f| f.ident.as_ref().unwrap());
+            quote! {{ #(#idents: #values),* }}
+        }
+        Fields::Unnamed(_) => quote! {( #(#values),* )},
+        Fields::Unit => quote! {}
+    })
+}
+
+/// Create the default value of a field, which is the expression of its DEFAULT parameter.
+/// Without DEFAULT, a caller location (see common::field_is_location) gets the location of the caller
+/// of From (which is marked with #[track_caller]), a phantom marker (see common::field_is_phantom) gets PhantomData
+/// and every other field gets Default::default().
+fn implement_default_value(field: &Field, root: &TokenStream2) -> Result<TokenStream2, FromImplementationError> {
+    let fallback = match (field_is_location(field), field_is_phantom(field)) {
+        (true, _) => quote! {#root::panic::Location::caller()},
+        (false, true) => quote! {#root::marker::PhantomData},
+        (false, false) => quote! {#root::default::Default::default()}
+    };
+
+    let parameters = match field_parameters(field) {
+        Some(parameters) => parameters,
+        None => return Ok(fallback)
+    };
+
+    match (parameters.string_for_name(DEFAULT), parameters.span_for_name(DEFAULT)) {
+        (Some(expression), Some(span)) => syn::parse_str::<Expr>(expression)
+            .map(|expr| quote! {#expr})
+            .map_err(|_| InvalidDefaultExpression(expression.to_string(), span)),
+        _ => Ok(fallback)
+    }
+}
+
+/// Create an associated constant FROM_TYPES on the given item, which lists the names of all types
+/// the item implements std::convert::From for. This way, the available conversions are visible in the
+/// documentation without reading the expansion.
+///
+/// Every type is given with the cfg attributes of its variant (see common::cfg_attributes), so types of
+/// variants which are configured out are not listed. If no types are given, the constant is omitted.
+pub fn implement_from_types_constant(ident: &Ident, generics: &Generics, types: &[(&Type, TokenStream2)]) -> TokenStream2 {
+    if types.is_empty() {
+        return quote! {};
+    }
+
+    let (impl_generics, type_generics, where_clause) = generics.split_for_impl();
+    let type_names = types.iter().map(|(ty, cfg)| {
+        let name = tokens_to_readable_string(ty);
+        quote! {#cfg #name}
+    });
+
+    quote! {
+        impl #impl_generics #ident #type_generics #where_clause {
+            #[doc = "The types this error can be created from with std::convert::From (and therefore the ?-operator)."]
+            pub const FROM_TYPES: &'static [&'static str] = &[#(#type_names),*];
+        }
+    }
+}
+
 /// Error that might occur when the generation of a std::convert::From implementation
 /// fails for structs or enums.
+///
+/// Every error knows the span of the code which caused it, so it can be
+/// converted to a syn::Error and reported as a compile error.
 #[derive(Debug)]
 pub enum FromImplementationError {
     /// A struct requires exactly one field for From to be implemented.
-    StructNotExactlyOneField(Ident),
+    StructNotExactlyOneField(Ident, Span),
     /// An enum variant requires exactly one field for From to be implemented.
     /// Every failed variant is listed here.
     EnumNotExactlyOneField(Ident, Vec<Ident>),
     /// The parameters::IMPL_FROM parameter was set on an enum and at least one variant.
     /// To keep the code clean, this is considered an error.
-    ParameterOnEnumAndVariant(Ident),
+    ParameterOnEnumAndVariant(Ident, Span),
+    /// The parameters::IMPL_FROM parameter was set on an enum, but none of its variants
+    /// carries a value which could be converted from.
+    EnumNoPayloadVariants(Ident, Span),
+    /// The value of parameters::AUTO_FROM_TYPES is not a comma separated list of types.
+    InvalidTypeList(String, Span),
+    /// A type listed in parameters::AUTO_FROM_TYPES is not the field type of any single field variant.
+    AutoFromTypeWithoutVariant(Ident, String, Span),
+    /// A type listed in parameters::AUTO_FROM_TYPES is the field type of multiple variants.
+    AutoFromTypeWithMultipleVariants(Ident, String, Vec<Ident>, Span),
+    /// parameters::AUTO_FROM_TYPES was set together with the global parameters::IMPL_FROM, which already covers every variant.
+    AutoFromTypesWithGlobalImplFrom(Ident, Span),
+    /// IMPL_FROM was set to IMPL_FROM_INTO, but more than one From implementation would be generated.
+    /// The generic implementation would conflict with every other one.
+    IntoWithOtherFromImplementations(Ident, Span),
+    /// The value of parameters::DEFAULT on a field is not a valid expression.
+    InvalidDefaultExpression(String, Span),
+    /// Two variants would implement From for the same type. Contains the type, both variants and how to resolve the conflict.
+    DuplicateSourceType(Ident, String, Ident, Ident, String),
+    /// parameters::IMPL_FROM_FOR was set on a variant whose single field is not a type parameter of the enum.
+    ImplFromForWithoutGenericField(Ident, Span),
+    /// parameters::TRACE was set, but the error has no message which could be recorded.
+    TraceWithoutDisplay(Ident, Span),
+    /// The value of parameters::LOG is not a level of the log crate.
+    InvalidLogLevel(String, Span),
+    /// parameters::LOG was set, but the error has no message which could be logged.
+    LogWithoutDisplay(Ident, Span),
+    /// parameters::GENERATE_FROM_TESTS was set on a generic enum, whose From implementations can't be tested without concrete types.
+    FromTestsWithGenerics(Ident, Span),
+    /// parameters::DEPRECATED_FROM was set on a variant which has no From implementation.
+    DeprecatedFromWithoutFrom(Ident, Span),
+    /// parameters::FROM_IO was set, but another variant converts from std::io::Error as well. Contains the enum and the other variant.
+    FromIoWithOtherIoConversion(Ident, Ident, Span),
+    /// A kind was listed more than once in parameters::FROM_IO.
+    FromIoDuplicateKind(String, Span),
+    /// A kind listed in parameters::FROM_IO is not a name of a std::io::ErrorKind.
+    InvalidIoKind(String, Span),
+    /// parameters::FROM_IO was set, but no variant handles the remaining kinds with parameters::FROM_IO_FALLBACK.
+    FromIoWithoutFallback(Ident, Span),
+}
+
+impl FromImplementationError {
+    /// Return the span of the code which caused this error.
+    pub fn span(&self) -> Span {
+        match self {
+            StructNotExactlyOneField(_, span) => *span,
+            EnumNotExactlyOneField(enum_ident, idents) => idents.first().unwrap_or(enum_ident).span(),
+            ParameterOnEnumAndVariant(_, span) => *span,
+            EnumNoPayloadVariants(_, span) => *span,
+            InvalidTypeList(_, span) => *span,
+            AutoFromTypeWithoutVariant(_, _, span) => *span,
+            AutoFromTypeWithMultipleVariants(_, _, _, span) => *span,
+            AutoFromTypesWithGlobalImplFrom(_, span) => *span,
+            IntoWithOtherFromImplementations(_, span) => *span,
+            InvalidDefaultExpression(_, span) => *span,
+            DuplicateSourceType(_, _, _, second, _) => second.span(),
+            ImplFromForWithoutGenericField(_, span) => *span,
+            TraceWithoutDisplay(_, span) => *span,
+            InvalidLogLevel(_, span) => *span,
+            LogWithoutDisplay(_, span) => *span,
+            FromTestsWithGenerics(_, span) => *span,
+            DeprecatedFromWithoutFrom(_, span) => *span,
+            FromIoWithOtherIoConversion(_, _, span) => *span,
+            FromIoDuplicateKind(_, span) => *span,
+            InvalidIoKind(_, span) => *span,
+            FromIoWithoutFallback(_, span) => *span
+        }
+    }
 }
 
 impl std::error::Error for FromImplementationError {}
@@ -27,7 +409,7 @@ impl std::error::Error for FromImplementationError {}
 impl std::fmt::Display for FromImplementationError {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         match self {
-            StructNotExactlyOneField(ident) => write!(f, "'std::convert::From' cannot be implemented for struct '{}', as it has not exactly one field.", ident),
+            StructNotExactlyOneField(ident, _) => write!(f, "'std::convert::From' cannot be implemented for struct '{}', as it has not exactly one field.", ident),
             EnumNotExactlyOneField(enum_ident, idents) => {
                 let idents_string: String = idents.iter()
                     .enumerate()
@@ -35,7 +417,34 @@ impl std::fmt::Display for FromImplementationError {
                     .collect();
                 write!(f, "'std::convert::From' cannot be implemented for enum '{}'. The following variants don't have exactly one field: {}", enum_ident, idents_string)
             }
-            ParameterOnEnumAndVariant(ident) => write!(f, "The '{}' parameter was set on enum '{}' and at least one of its variants. Choose only one (enum or variants).", IMPL_FROM, ident)
+            ParameterOnEnumAndVariant(ident, _) => write!(f, "The '{}' parameter was set on enum '{}' and at least one of its variants. Choose only one (enum or variants).", IMPL_FROM, ident),
+            EnumNoPayloadVariants(ident, _) => write!(f, "'{}' requires payload-carrying variants, but enum '{}' has none. Consider removing the '{}' parameter.", IMPL_FROM, ident, IMPL_FROM),
+            InvalidTypeList(type_list, _) => write!(f, "'{}' is not a comma separated list of types.", type_list),
+            AutoFromTypeWithoutVariant(ident, type_name, _) => write!(f, "The type '{}' from '{}' does not match the single field of any variant of enum '{}'.", type_name, AUTO_FROM_TYPES, ident),
+            AutoFromTypeWithMultipleVariants(ident, type_name, variants, _) => {
+                let variants_string = variants.iter().map(|v| v.to_string()).collect::<Vec<_>>().join(",");
+                write!(f, "The type '{}' from '{}' matches multiple variants of enum '{}': {}", type_name, AUTO_FROM_TYPES, ident, variants_string)
+            }
+            AutoFromTypesWithGlobalImplFrom(ident, _) => write!(f, "The parameters '{}' and '{}' were both set on enum '{}'. Choose only one.", IMPL_FROM, AUTO_FROM_TYPES, ident),
+            IntoWithOtherFromImplementations(ident, _) => write!(f, "'{} = \"{}\"' requires that only one From implementation is created for enum '{}', as the implementations would conflict.", IMPL_FROM, IMPL_FROM_INTO, ident),
+            InvalidDefaultExpression(expression, _) => write!(f, "'{}' is not a valid default expression.", expression),
+            DuplicateSourceType(ident, type_name, first, second, hint) => write!(f, "'std::convert::From<{}>' would be implemented twice for enum '{}', by the variants '{}' and '{}'. {}", type_name, ident, first, second, hint),
+            ImplFromForWithoutGenericField(variant, _) => write!(f, "'{}' requires the single field of variant '{}' to have a type parameter of the enum as type.", IMPL_FROM_FOR, variant),
+            TraceWithoutDisplay(ident, _) => write!(f, "The parameter '{}' requires a message for '{}', as it is recorded in the tracing event.", TRACE, ident),
+            InvalidLogLevel(level, _) => write!(f, "'{}' is not a level of the log crate. Possible levels are: {}", level, LOG_LEVELS.join(", ")),
+            LogWithoutDisplay(ident, _) => write!(f, "The parameter '{}' requires a message for '{}', as it is logged.", LOG, ident),
+            FromTestsWithGenerics(ident, _) => write!(f, "The parameter '{}' cannot be used on the generic enum '{}'.", GENERATE_FROM_TESTS, ident),
+            DeprecatedFromWithoutFrom(variant, _) => write!(f, "The parameter '{}' was set on variant '{}', but it has no From implementation which could be deprecated.", DEPRECATED_FROM, variant),
+            FromIoWithOtherIoConversion(ident, variant, _) => write!(f, "The variant '{}' of enum '{}' converts from std::io::Error, which conflicts with the variants with '{}'. Exclude it with '{}'.", variant, ident, FROM_IO, NO_FROM),
+            FromIoDuplicateKind(kind, _) => write!(f, "The kind '{}' is listed more than once in '{}'.", kind, FROM_IO),
+            InvalidIoKind(kind, _) => write!(f, "'{}' in '{}' is not the name of a std::io::ErrorKind.", kind, FROM_IO),
+            FromIoWithoutFallback(ident, _) => write!(f, "The enum '{}' uses '{}', but no variant handles the remaining kinds. Mark one with '{} = \"{}\"'.", ident, FROM_IO, FROM_IO, FROM_IO_FALLBACK)
         }
     }
-}
\ No newline at end of file
+}
+
+impl From<FromImplementationError> for syn::Error {
+    fn from(e: FromImplementationError) -> Self {
+        syn::Error::new(e.span(), e)
+    }
+}
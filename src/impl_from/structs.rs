@@ -1,76 +1,67 @@
 use quote::quote;
-use syn::{FieldsNamed, FieldsUnnamed, ItemStruct};
+use syn::{Fields, ItemStruct};
 use syn::__private::TokenStream2;
 use syn::Fields::*;
 
-use crate::impl_from::FromImplementationError;
+use crate::common::from_source_fields;
+use crate::impl_from::{FromContext, FromImplementationError, implement_from, implement_from_types_constant};
 use crate::impl_from::FromImplementationError::StructNotExactlyOneField;
-use crate::parameters::{IMPL_FROM, Parameters};
+use crate::impl_transparent::struct_is_transparent;
+use crate::parameters::{IMPL_FROM, IMPL_FROM_INTO, Parameters};
 
 pub struct StructFromImplementer<'a> {
     item_struct: &'a ItemStruct,
     struct_parameters: &'a Parameters,
+    context: &'a FromContext,
 }
 
 impl<'a> StructFromImplementer<'a> {
-    pub fn new(item_struct: &'a ItemStruct, struct_parameters: &'a Parameters) -> Self {
-        StructFromImplementer { item_struct, struct_parameters }
+    pub fn new(item_struct: &'a ItemStruct, struct_parameters: &'a Parameters, context: &'a FromContext) -> Self {
+        StructFromImplementer { item_struct, struct_parameters, context }
     }
 
     /// Create the std::convert::From implementation for a struct.
     ///
+    /// Structs marked with '#[repr(transparent)]' always implement From for the wrapped error.
     /// If the struct should not implement From, return an empty token stream.
-    /// Returns Result::Err if the struct is an unit or has not exactly one field.
+    /// Returns Result::Err if the struct is an unit or has not exactly one field (skipped fields are not counted).
     pub fn implement(self) -> Result<TokenStream2, FromImplementationError> {
-        if self.struct_parameters.bool_for_name(IMPL_FROM) == false {
+        if !self.struct_parameters.bool_for_name(IMPL_FROM) && !struct_is_transparent(self.item_struct) {
             return Ok(quote! {});
         }
 
         match &self.item_struct.fields {
-            Named(ref fields) => self.implement_for_named(fields),
-            Unnamed(ref fields) => self.implement_for_unnamed(fields),
-            Unit => Err(StructNotExactlyOneField(self.item_struct.ident.clone()))
+            Unit => Err(self.not_exactly_one_field_error()),
+            fields => self.implement_for_fields(fields)
         }
     }
 
-    fn implement_for_named(self, fields: &FieldsNamed) -> Result<TokenStream2, FromImplementationError> {
-        if fields.named.len() != 1 {
-            return Err(StructNotExactlyOneField(self.item_struct.ident.clone()));
-        }
-
-        let struct_ident = &self.item_struct.ident;
-        let generics = &self.item_struct.generics;
-        let (impl_generics, type_generics, where_clause) = generics.split_for_impl();
-        let field = fields.named.first().unwrap();
-        let ty = &field.ty;
-        let field_ident = field.ident.as_ref().unwrap();
-
-        Ok(quote! {
-            impl #impl_generics std::convert::From<#ty> for #struct_ident #type_generics #where_clause {
-                fn from(val: #ty) -> Self {
-                    #struct_ident{ #field_ident : val }
-                }
-            }
-        })
+    fn not_exactly_one_field_error(&self) -> FromImplementationError {
+        let span = self.struct_parameters.span_for_name(IMPL_FROM).unwrap_or_else(|| self.item_struct.ident.span());
+        StructNotExactlyOneField(self.item_struct.ident.clone(), span)
     }
 
-    fn implement_for_unnamed(self, fields: &FieldsUnnamed) -> Result<TokenStream2, FromImplementationError> {
-        if fields.unnamed.len() != 1 {
-            return Err(StructNotExactlyOneField(self.item_struct.ident.clone()));
+    /// Implement From for the type of the single source field (or everything that converts Into it,
+    /// if IMPL_FROM is set to IMPL_FROM_INTO). All other fields are initialized with their default value.
+    fn implement_for_fields(self, fields: &Fields) -> Result<TokenStream2, FromImplementationError> {
+        let from_fields = from_source_fields(fields);
+        if from_fields.len() != 1 {
+            return Err(self.not_exactly_one_field_error());
         }
 
         let struct_ident = &self.item_struct.ident;
         let generics = &self.item_struct.generics;
-        let (impl_generics, type_generics, where_clause) = generics.split_for_impl();
-        let field = fields.unnamed.first().unwrap();
-        let ty = &field.ty;
+        let ty = &from_fields[0].ty;
+        let from_doc = self.context.implement_doc(struct_ident, generics, ty);
+        let from_types_constant = implement_from_types_constant(struct_ident, generics, &[(ty, quote! {})]);
+        let into = self.struct_parameters.has_string_value(IMPL_FROM, IMPL_FROM_INTO);
+        let from_implementation = implement_from(struct_ident, generics, quote!(#struct_ident), fields, from_fields[0], into, self.context)?;
 
         Ok(quote! {
-            impl #impl_generics std::convert::From<#ty> for #struct_ident #type_generics #where_clause {
-                fn from(val: #ty) -> Self {
-                    #struct_ident(val)
-                }
-            }
+            #from_doc
+            #from_implementation
+
+            #from_types_constant
         })
     }
-}
\ No newline at end of file
+}
@@ -0,0 +1,175 @@
+use std::fmt::Formatter;
+
+use quote::quote;
+use syn::__private::{Span, TokenStream2};
+use syn::{Ident, ItemEnum, Type, Variant};
+
+use crate::common::{cfg_attributes, std_root, tokens_to_readable_string};
+use crate::impl_getters::GettersImplementationError::*;
+use crate::parameters::{GETTERS, Parameters};
+
+/// A named field with its type and all variants which have it.
+type FieldWithVariants<'a> = (&'a Ident, &'a Type, Vec<&'a Variant>);
+
+/// Creates an accessor method for every named field of the variants of an enum, like 'line(&self) -> Option<&usize>'.
+/// The accessor returns Some if the current variant has the field and None otherwise, which saves a full
+/// match to extract the payload of a specific variant.
+///
+/// The other parameters also create methods on the enum (like 'name' with VARIANT_NAME), so an accessor
+/// with the name of such a method is an error instead of a duplicate definition (see generated_methods).
+pub struct GettersImplementor<'a> {
+    item_enum: &'a ItemEnum,
+    enum_parameters: &'a Parameters,
+    generated_methods: Vec<(String, String)>,
+}
+
+impl<'a> GettersImplementor<'a> {
+    pub fn new(item_enum: &'a ItemEnum, enum_parameters: &'a Parameters) -> Self {
+        GettersImplementor { item_enum, enum_parameters, generated_methods: vec![] }
+    }
+
+    /// Set the methods the other parameters create on the enum, together with what creates them (see generated_methods).
+    pub fn with_generated_methods(mut self, generated_methods: Vec<(String, String)>) -> Self {
+        self.generated_methods = generated_methods;
+        self
+    }
+
+    /// Create the accessors if the parameter GETTERS is set, otherwise return an empty token stream.
+    ///
+    /// Fails if fields with the same name have different types in different variants or if an accessor
+    /// has the name of a method created by another parameter.
+    pub fn implement(self) -> Result<TokenStream2, GettersImplementationError> {
+        let span = match self.enum_parameters.span_for_name(GETTERS) {
+            Some(span) if self.enum_parameters.bool_for_name(GETTERS) => span,
+            _ => return Ok(quote! {})
+        };
+
+        let fields = self.fields_by_name(span)?;
+        self.check_generated_methods(&fields)?;
+
+        let ident = &self.item_enum.ident;
+        let root = std_root(self.enum_parameters);
+        let getters = fields
+            .into_iter()
+            .map(|(field_ident, ty, variants)| {
+                let doc = format!(" Return the field '{}' if the variant of this error has it.", field_ident);
+                let match_arms = variants
+                    .iter()
+                    .map(|v| {
+                        let variant_ident = &v.ident;
+                        let cfg = cfg_attributes(v);
+                        quote! {#cfg #ident::#variant_ident { #field_ident, .. } => #root::option::Option::Some(#field_ident)}
+                    })
+                    .collect::<Vec<_>>();
+                let default_match_arm = match variants.len() == self.item_enum.variants.len() {
+                    true => quote! {},
+                    false => quote! {_ => #root::option::Option::None}
+                };
+
+                quote! {
+                    #[doc = #doc]
+                    pub fn #field_ident(&self) -> #root::option::Option<&#ty> {
+                        match self {
+                            #(#match_arms,)*
+                            #default_match_arm
+                        }
+                    }
+                }
+            })
+            .collect::<Vec<_>>();
+        let (impl_generics, type_generics, where_clause) = self.item_enum.generics.split_for_impl();
+
+        Ok(quote! {
+            impl #impl_generics #ident #type_generics #where_clause {
+                #(#getters)*
+            }
+        })
+    }
+
+    /// Return every named field of the variants with its type and all variants which have it,
+    /// in the order the fields appear first.
+    ///
+    /// The cfg attributes of the variants are not considered, as every accessor has a single return type.
+    fn fields_by_name(&self, span: Span) -> Result<Vec<FieldWithVariants<'a>>, GettersImplementationError> {
+        let mut fields: Vec<FieldWithVariants<'a>> = vec![];
+
+        for variant in &self.item_enum.variants {
+            for field in &variant.fields {
+                let field_ident = match &field.ident {
+                    Some(field_ident) => field_ident,
+                    None => continue
+                };
+
+                match fields.iter_mut().find(|(i, _, _)| *i == field_ident) {
+                    Some((_, ty, _)) if tokens_to_readable_string(ty) != tokens_to_readable_string(&field.ty) => {
+                        return Err(ConflictingFieldTypes(field_ident.clone(), tokens_to_readable_string(ty), tokens_to_readable_string(&field.ty), span));
+                    }
+                    Some((_, _, variants)) => variants.push(variant),
+                    None => fields.push((field_ident, &field.ty, vec![variant]))
+                }
+            }
+        }
+
+        Ok(fields)
+    }
+
+    /// Check that no accessor has the name of a method created by another parameter.
+    fn check_generated_methods(&self, fields: &[FieldWithVariants]) -> Result<(), GettersImplementationError> {
+        for (field_ident, _, _) in fields {
+            if let Some((_, origin)) = self.generated_methods.iter().find(|(name, _)| *field_ident == name) {
+                return Err(ConflictingMethod(field_ident.to_string(), origin.clone(), field_ident.span()));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Return the given method names which are defined in the given implementation, together with the given origin,
+/// like ("name", "the parameter 'variant_name'") for the name implementation. Names which the implementation does not
+/// define are skipped, so candidates which depend on the variants (like the constructors) can be passed for all variants.
+pub fn generated_methods<I: IntoIterator<Item=String>>(implementation: &TokenStream2, candidates: I, origin: &str) -> Vec<(String, String)> {
+    let tokens = implementation.to_string();
+
+    candidates
+        .into_iter()
+        .filter(|name| tokens.contains(&format!("fn {} (", name)))
+        .map(|name| (name, origin.to_string()))
+        .collect()
+}
+
+/// Error that might occur when the accessors are created.
+#[derive(Debug)]
+pub enum GettersImplementationError {
+    /// Fields with the same name have different types, so there is no single return type for the accessor.
+    ConflictingFieldTypes(Ident, String, String, Span),
+    /// An accessor has the name of a method created by something else, which is named in the second value.
+    ConflictingMethod(String, String, Span),
+}
+
+impl GettersImplementationError {
+    /// Return the span of the code which caused this error.
+    pub fn span(&self) -> Span {
+        match self {
+            ConflictingFieldTypes(_, _, _, span) => *span,
+            ConflictingMethod(_, _, span) => *span
+        }
+    }
+}
+
+impl std::error::Error for GettersImplementationError {}
+
+impl std::fmt::Display for GettersImplementationError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ConflictingFieldTypes(field, first, second, _) => write!(f, "The parameter '{}' requires all fields named '{}' to have the same type, but found '{}' and '{}'. This includes variants under mutually exclusive '#[cfg]' attributes, so rename one of the fields.", GETTERS, field, first, second),
+            ConflictingMethod(name, origin, _) => write!(f, "The parameter '{}' creates the method '{}' for the field '{}', but the method is already created by {}. Rename the field or remove one of the parameters.", GETTERS, name, name, origin)
+        }
+    }
+}
+
+impl From<GettersImplementationError> for syn::Error {
+    fn from(e: GettersImplementationError) -> Self {
+        syn::Error::new(e.span(), e)
+    }
+}
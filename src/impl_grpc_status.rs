@@ -0,0 +1,281 @@
+use std::fmt::Formatter;
+
+use quote::{format_ident, quote};
+use syn::__private::{Span, TokenStream2};
+use syn::{Generics, Ident};
+
+use crate::common::{cfg_attributes, to_snake_case};
+#[cfg(feature = "tonic")]
+use crate::common::trait_impl_attributes;
+use crate::enum_error::VariantWithParams;
+use crate::impl_grpc_status::GrpcStatusImplementationError::*;
+use crate::parameters::{GRPC_CODE, Parameters};
+
+/// The names of all variants of tonic::Code.
+const GRPC_CODES: &[&str] = &[
+    "Ok",
+    "Cancelled",
+    "Unknown",
+    "InvalidArgument",
+    "DeadlineExceeded",
+    "NotFound",
+    "AlreadyExists",
+    "PermissionDenied",
+    "ResourceExhausted",
+    "FailedPrecondition",
+    "Aborted",
+    "OutOfRange",
+    "Unimplemented",
+    "Internal",
+    "Unavailable",
+    "DataLoss",
+    "Unauthenticated",
+];
+
+/// The code used for enum variants if neither the variant nor the enum has a GRPC_CODE.
+const DEFAULT_GRPC_CODE: &str = "Internal";
+
+/// Creates an implementation of From<error> for tonic::Status, which uses the code set with the parameter
+/// GRPC_CODE and the Display message. Requires the feature 'tonic'.
+pub struct GrpcStatusImplementor<'a> {
+    ident: &'a Ident,
+    generics: &'a Generics,
+    parameters: &'a Parameters,
+}
+
+impl<'a> GrpcStatusImplementor<'a> {
+    pub fn new(ident: &'a Ident, generics: &'a Generics, parameters: &'a Parameters) -> Self {
+        GrpcStatusImplementor { ident, generics, parameters }
+    }
+
+    /// Create the implementation for a struct, if GRPC_CODE is set.
+    pub fn implement_for_struct(self, display_implemented: bool) -> Result<TokenStream2, GrpcStatusImplementationError> {
+        let (code, span) = match Self::code(self.parameters)? {
+            Some(code_with_span) => code_with_span,
+            None => return Ok(quote! {})
+        };
+        self.check_display(display_implemented, span)?;
+
+        implement_from_for_status(self.ident, self.generics, quote! {#code}, span)
+    }
+
+    /// Create the implementation for an enum, if GRPC_CODE is set on the enum or any variant.
+    ///
+    /// Variants without GRPC_CODE use the code of the enum or tonic::Code::Internal if the enum has none.
+    pub fn implement_for_enum(self, variants_with_parameters: &[VariantWithParams<'_>], display_implemented: bool) -> Result<TokenStream2, GrpcStatusImplementationError> {
+        let variant_codes = variants_with_parameters
+            .iter()
+            .map(|(v, p_opt)| match p_opt {
+                Some(p) => Ok((*v, Self::code(p)?)),
+                None => Ok((*v, None))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        let default_code = Self::code(self.parameters)?;
+
+        let first_span = default_code.iter().chain(variant_codes.iter().filter_map(|(_, c)| c.as_ref())).map(|(_, span)| *span).next();
+        let span = match first_span {
+            Some(span) => span,
+            None => return Ok(quote! {})
+        };
+        self.check_display(display_implemented, span)?;
+
+        let ident = self.ident;
+        let default_code = default_code.map(|(code, _)| code).unwrap_or_else(|| Self::code_tokens(DEFAULT_GRPC_CODE));
+        let match_arms = variant_codes
+            .into_iter()
+            .map(|(v, code)| {
+                let variant_ident = &v.ident;
+                let cfg = cfg_attributes(v);
+                let code = code.map(|(code, _)| code).unwrap_or_else(|| default_code.clone());
+                quote! {#cfg #ident::#variant_ident { .. } => #code}
+            });
+
+        implement_from_for_status(self.ident, self.generics, quote! {
+            match &e {
+                #(#match_arms,)*
+            }
+        }, span)
+    }
+
+    /// The message of the status is the Display message, so it must exist.
+    fn check_display(&self, display_implemented: bool, span: Span) -> Result<(), GrpcStatusImplementationError> {
+        match display_implemented {
+            true => Ok(()),
+            false => Err(CodeWithoutDisplay(self.ident.clone(), span))
+        }
+    }
+
+    /// Return the tonic::Code set in the given parameters and its span, if any.
+    ///
+    /// The code is written in snake_case, like "not_found" for tonic::Code::NotFound.
+    fn code(parameters: &Parameters) -> Result<Option<(TokenStream2, Span)>, GrpcStatusImplementationError> {
+        let (code, span) = match (parameters.string_for_name(GRPC_CODE), parameters.span_for_name(GRPC_CODE)) {
+            (Some(code), Some(span)) => (code, span),
+            _ => return Ok(None)
+        };
+
+        match GRPC_CODES.iter().find(|c| to_snake_case(c) == code) {
+            Some(c) => Ok(Some((Self::code_tokens(c), span))),
+            None => Err(UnknownCode(code.to_string(), span))
+        }
+    }
+
+    fn code_tokens(code: &str) -> TokenStream2 {
+        let code = format_ident!("{}", code);
+        quote! {tonic::Code::#code}
+    }
+}
+
+/// Create the From implementation, which evaluates the given code expression (a tonic::Code with the error
+/// available as 'e') and creates the status with it and the Display message.
+#[cfg(feature = "tonic")]
+fn implement_from_for_status(ident: &Ident, generics: &Generics, code: TokenStream2, _span: Span) -> Result<TokenStream2, GrpcStatusImplementationError> {
+    let (impl_generics, type_generics, where_clause) = generics.split_for_impl();
+
+    let impl_attributes = trait_impl_attributes();
+
+    Ok(quote! {
+        #impl_attributes
+        impl #impl_generics ::std::convert::From<#ident #type_generics> for tonic::Status #where_clause {
+            fn from(e: #ident #type_generics) -> Self {
+                tonic::Status::new(#code, e.to_string())
+            }
+        }
+    })
+}
+
+/// Without the feature 'tonic', GRPC_CODE cannot be used.
+#[cfg(not(feature = "tonic"))]
+fn implement_from_for_status(_ident: &Ident, _generics: &Generics, _code: TokenStream2, span: Span) -> Result<TokenStream2, GrpcStatusImplementationError> {
+    Err(FeatureNotEnabled(span))
+}
+
+/// Error that might occur when the tonic::Status conversion is created.
+#[derive(Debug)]
+pub enum GrpcStatusImplementationError {
+    /// The code is not the snake_case name of a tonic::Code.
+    UnknownCode(String, Span),
+    /// The error has a code, but no message which could be used for the status.
+    CodeWithoutDisplay(Ident, Span),
+    /// GRPC_CODE was used, but the feature 'tonic' is not enabled.
+    #[allow(dead_code)]
+    FeatureNotEnabled(Span),
+}
+
+impl GrpcStatusImplementationError {
+    /// Return the span of the code which caused this error.
+    pub fn span(&self) -> Span {
+        match self {
+            UnknownCode(_, span) => *span,
+            CodeWithoutDisplay(_, span) => *span,
+            FeatureNotEnabled(span) => *span
+        }
+    }
+}
+
+impl std::error::Error for GrpcStatusImplementationError {}
+
+impl std::fmt::Display for GrpcStatusImplementationError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            UnknownCode(code, _) => {
+                let codes = GRPC_CODES.iter().map(|c| to_snake_case(c)).collect::<Vec<_>>().join(", ");
+                write!(f, "The gRPC code '{}' is unknown. Possible codes are: {}", code, codes)
+            }
+            CodeWithoutDisplay(ident, _) => write!(f, "The parameter '{}' requires a message for '{}', as it is used as status message.", GRPC_CODE, ident),
+            FeatureNotEnabled(_) => write!(f, "The parameter '{}' requires the feature 'tonic' of error_generator.", GRPC_CODE)
+        }
+    }
+}
+
+impl From<GrpcStatusImplementationError> for syn::Error {
+    fn from(e: GrpcStatusImplementationError) -> Self {
+        syn::Error::new(e.span(), e)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[cfg(feature = "tonic")]
+    use crate::assert_enum_implementation_as_expected;
+    use crate::assert_struct_implementation_as_expected;
+
+    #[test]
+    #[should_panic(expected = "The gRPC code 'missing' is unknown.")]
+    fn unknown_code_should_panic() {
+        assert_struct_implementation_as_expected!(
+            item: {
+                #[error(message = "not found", grpc_code = "missing")]
+                struct S;
+            }
+
+            expected: {
+                should panic
+            }
+        )
+    }
+
+    #[cfg(feature = "tonic")]
+    #[test]
+    fn enum_codes() {
+        assert_enum_implementation_as_expected!(
+            item: {
+                #[error(message = "request failed")]
+                enum E {
+                    #[error(grpc_code = "not_found")]
+                    NotFound,
+                    Internal(String)
+                }
+            }
+
+            expected: {
+                #[derive(Debug)]
+                enum E {
+                    NotFound,
+                    Internal(String)
+                }
+
+                #[automatically_derived]
+                #[allow(unused_qualifications, clippy::all)]
+                impl ::std::error::Error for E {}
+
+                #[automatically_derived]
+                #[allow(unused_qualifications, clippy::all)]
+                impl ::std::fmt::Display for E {
+                    fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+                        match self {
+                            _ => ::std::write!(f, "request failed")
+                        }
+                    }
+                }
+
+                #[automatically_derived]
+                #[allow(unused_qualifications, clippy::all)]
+                impl ::std::convert::From<E> for tonic::Status {
+                    fn from(e: E) -> Self {
+                        tonic::Status::new(match &e {
+                            E::NotFound { .. } => tonic::Code::NotFound,
+                            E::Internal { .. } => tonic::Code::Internal,
+                        }, e.to_string())
+                    }
+                }
+            }
+        )
+    }
+
+    #[cfg(not(feature = "tonic"))]
+    #[test]
+    #[should_panic(expected = "The parameter 'grpc_code' requires the feature 'tonic' of error_generator.")]
+    fn grpc_code_without_feature_should_panic() {
+        assert_struct_implementation_as_expected!(
+            item: {
+                #[error(message = "not found", grpc_code = "not_found")]
+                struct S;
+            }
+
+            expected: {
+                should panic
+            }
+        )
+    }
+}
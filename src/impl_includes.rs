@@ -0,0 +1,86 @@
+use std::fmt::Formatter;
+
+use syn::__private::Span;
+use syn::{Ident, ItemEnum, parse_quote, Token, Type};
+use syn::parse::Parser;
+use syn::punctuated::Punctuated;
+
+use crate::common::tokens_to_readable_string;
+use crate::impl_includes::IncludesError::*;
+use crate::parameters::{INCLUDES, Parameters};
+
+/// Return the errors listed in INCLUDES (like 'includes = "ParseError, io::IoErrorWrapper"') together with the
+/// name of the variant which wraps them. This is the last segment of the path of the type, like 'IoErrorWrapper'.
+///
+/// Fails if the list is not a comma separated list of paths.
+pub fn included_errors(enum_parameters: &Parameters) -> Result<Vec<(Type, Ident)>, IncludesError> {
+    let (list, span) = match (enum_parameters.string_for_name(INCLUDES), enum_parameters.span_for_name(INCLUDES)) {
+        (Some(list), Some(span)) => (list, span),
+        _ => return Ok(vec![])
+    };
+
+    Punctuated::<Type, Token![,]>::parse_terminated
+        .parse_str(list)
+        .map_err(|_| InvalidIncludes(list.to_string(), span))?
+        .into_iter()
+        .map(|ty| match &ty {
+            Type::Path(type_path) if type_path.qself.is_none() => {
+                let ident = type_path.path.segments.last().unwrap().ident.clone();
+                Ok((ty, ident))
+            }
+            _ => Err(InvalidIncludes(list.to_string(), span))
+        })
+        .collect()
+}
+
+/// Add a variant wrapping every error listed in INCLUDES to the enum, like 'ParseError(ParseError)'.
+/// The variants are listed in the wrap map (see WrapMap::parse), so they are converted from and display the included error.
+///
+/// Fails if the list is invalid or the enum already has a variant with the name of an included error.
+pub fn add_included_variants(item_enum: &mut ItemEnum, enum_parameters: &Parameters) -> Result<(), IncludesError> {
+    for (ty, ident) in included_errors(enum_parameters)? {
+        if item_enum.variants.iter().any(|v| v.ident == ident) {
+            return Err(VariantExists(item_enum.ident.clone(), ident, tokens_to_readable_string(&ty), enum_parameters.span_for_name(INCLUDES).unwrap()));
+        }
+
+        item_enum.variants.push(parse_quote!(#ident(#ty)))
+    }
+
+    Ok(())
+}
+
+/// Error that might occur when the errors listed in INCLUDES are added.
+#[derive(Debug)]
+pub enum IncludesError {
+    /// The list is not a comma separated list of paths.
+    InvalidIncludes(String, Span),
+    /// The enum already has a variant with the name of an included error.
+    VariantExists(Ident, Ident, String, Span),
+}
+
+impl IncludesError {
+    /// Return the span of the code which caused this error.
+    pub fn span(&self) -> Span {
+        match self {
+            InvalidIncludes(_, span) => *span,
+            VariantExists(_, _, _, span) => *span
+        }
+    }
+}
+
+impl std::error::Error for IncludesError {}
+
+impl std::fmt::Display for IncludesError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            InvalidIncludes(list, _) => write!(f, "'{}' is not a comma separated list of paths to error types.", list),
+            VariantExists(ident, variant, ty, _) => write!(f, "The enum '{}' already has a variant '{}', so the error '{}' cannot be included.", ident, variant, ty)
+        }
+    }
+}
+
+impl From<IncludesError> for syn::Error {
+    fn from(e: IncludesError) -> Self {
+        syn::Error::new(e.span(), e)
+    }
+}
@@ -0,0 +1,130 @@
+use std::fmt::Formatter;
+
+use quote::{format_ident, quote};
+use syn::__private::{Span, TokenStream2};
+use syn::{Ident, ItemEnum, ItemStruct};
+
+use crate::common::{cfg_attributes, single_source_field, std_root, to_snake_case};
+use crate::impl_into_inner::IntoInnerImplementationError::*;
+use crate::impl_transparent::struct_is_transparent;
+use crate::impl_wrap_map::WrapMap;
+use crate::parameters::{INTO_INNER, Parameters};
+
+/// Creates methods which hand the wrapped value of an error back, so callers can inspect or retry
+/// with the underlying error.
+///
+/// For structs with a single field (skipped fields are not counted), this is 'into_inner(self) -> T'.
+/// For enums, every variant with a single field gets 'into_<name of the variant in snake case>(self) -> Result<T, Self>',
+/// which returns the error itself if it is another variant. If the enum has variants listed in WRAP_MAP,
+/// 'into_source' returns the wrapped source of these variants as boxed error.
+pub struct IntoInnerImplementor<'a> {
+    parameters: &'a Parameters,
+}
+
+impl<'a> IntoInnerImplementor<'a> {
+    pub fn new(parameters: &'a Parameters) -> Self {
+        IntoInnerImplementor { parameters }
+    }
+
+    /// Create 'into_inner' if INTO_INNER is set or the struct is marked with '#[repr(transparent)]'.
+    ///
+    /// Fails if the struct has not exactly one field.
+    pub fn implement_for_struct(self, item_struct: &ItemStruct) -> Result<TokenStream2, IntoInnerImplementationError> {
+        if !self.parameters.bool_for_name(INTO_INNER) && !struct_is_transparent(item_struct) {
+            return Ok(quote! {});
+        }
+
+        let ident = &item_struct.ident;
+        let (member, field) = single_source_field(&item_struct.fields).ok_or_else(|| {
+            let span = self.parameters.span_for_name(INTO_INNER).unwrap_or_else(|| ident.span());
+            StructNotExactlyOneField(ident.clone(), span)
+        })?;
+        let ty = &field.ty;
+        let (impl_generics, type_generics, where_clause) = item_struct.generics.split_for_impl();
+
+        Ok(quote! {
+            impl #impl_generics #ident #type_generics #where_clause {
+                #[doc = " Return the wrapped error."]
+                pub fn into_inner(self) -> #ty {
+                    self.#member
+                }
+            }
+        })
+    }
+
+    /// Create the methods for all variants with a single field and 'into_source' if INTO_INNER is set.
+    pub fn implement_for_enum(self, item_enum: &ItemEnum, wrap_map: &WrapMap) -> TokenStream2 {
+        if !self.parameters.bool_for_name(INTO_INNER) {
+            return quote! {};
+        }
+
+        let ident = &item_enum.ident;
+        let default_match_arm = match item_enum.variants.len() {
+            1 => quote! {},
+            _ => quote! {_ => Err(self)}
+        };
+        let methods = item_enum.variants
+            .iter()
+            .filter_map(|v| single_source_field(&v.fields).map(|(member, field)| (v, member, field)))
+            .map(|(v, member, field)| {
+                let variant_ident = &v.ident;
+                let cfg = cfg_attributes(v);
+                let method_ident = format_ident!("into_{}", to_snake_case(&variant_ident.to_string()));
+                let doc = format!(" Return the wrapped value if this error is '{}::{}', otherwise the error itself.", ident, variant_ident);
+                let ty = &field.ty;
+
+                quote! {
+                    #cfg
+                    #[doc = #doc]
+                    pub fn #method_ident(self) -> Result<#ty, Self> {
+                        match self {
+                            #ident::#variant_ident { #member: inner, .. } => Ok(inner),
+                            #default_match_arm
+                        }
+                    }
+                }
+            })
+            .collect::<Vec<_>>();
+        let into_source = wrap_map.implement_into_source(&std_root(self.parameters));
+        let (impl_generics, type_generics, where_clause) = item_enum.generics.split_for_impl();
+
+        quote! {
+            impl #impl_generics #ident #type_generics #where_clause {
+                #(#methods)*
+                #into_source
+            }
+        }
+    }
+}
+
+/// Error that might occur when the methods are created.
+#[derive(Debug)]
+pub enum IntoInnerImplementationError {
+    /// INTO_INNER was set on a struct which has not exactly one field.
+    StructNotExactlyOneField(Ident, Span),
+}
+
+impl IntoInnerImplementationError {
+    /// Return the span of the code which caused this error.
+    pub fn span(&self) -> Span {
+        match self {
+            StructNotExactlyOneField(_, span) => *span
+        }
+    }
+}
+
+impl std::error::Error for IntoInnerImplementationError {}
+
+impl std::fmt::Display for IntoInnerImplementationError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            StructNotExactlyOneField(ident, _) => write!(f, "The parameter '{}' requires the struct '{}' to have exactly one field which is not skipped.", INTO_INNER, ident)
+        }
+    }
+}
+
+impl From<IntoInnerImplementationError> for syn::Error {
+    fn from(e: IntoInnerImplementationError) -> Self {
+        syn::Error::new(e.span(), e)
+    }
+}
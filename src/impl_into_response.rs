@@ -0,0 +1,323 @@
+use std::fmt::Formatter;
+
+use quote::quote;
+use syn::__private::{Span, TokenStream2};
+use syn::{Generics, Ident};
+
+use crate::common::cfg_attributes;
+#[cfg(feature = "axum")]
+use crate::common::trait_impl_attributes;
+use crate::enum_error::VariantWithParams;
+use crate::impl_into_response::IntoResponseImplementationError::*;
+use crate::parameters::{Parameters, STATUS};
+
+/// The status used for enum variants if neither the variant nor the enum has a STATUS.
+const DEFAULT_STATUS: u16 = 500;
+
+/// Creates an implementation of axum::response::IntoResponse, which responds with the HTTP status set with
+/// the parameter STATUS and the Display message as body. Requires the feature 'axum'.
+///
+/// If the error has the method 'retry_after' (see RETRY_AFTER), the response gets a Retry-After header as well.
+pub struct IntoResponseImplementor<'a> {
+    ident: &'a Ident,
+    generics: &'a Generics,
+    parameters: &'a Parameters,
+}
+
+impl<'a> IntoResponseImplementor<'a> {
+    pub fn new(ident: &'a Ident, generics: &'a Generics, parameters: &'a Parameters) -> Self {
+        IntoResponseImplementor { ident, generics, parameters }
+    }
+
+    /// Create the implementation for a struct, if STATUS is set.
+    pub fn implement_for_struct(self, display_implemented: bool, retry_after_implemented: bool) -> Result<TokenStream2, IntoResponseImplementationError> {
+        let (status, span) = match Self::status(self.parameters)? {
+            Some(status_with_span) => status_with_span,
+            None => return Ok(quote! {})
+        };
+        self.check_display(display_implemented, span)?;
+
+        implement_into_response(self.ident, self.generics, quote! {#status}, retry_after_implemented, span)
+    }
+
+    /// Create the implementation for an enum, if STATUS is set on the enum or any variant.
+    ///
+    /// Variants without STATUS use the status of the enum or 500 (Internal Server Error) if the enum has none.
+    pub fn implement_for_enum(self, variants_with_parameters: &[VariantWithParams<'_>], display_implemented: bool, retry_after_implemented: bool) -> Result<TokenStream2, IntoResponseImplementationError> {
+        let variant_statuses = variants_with_parameters
+            .iter()
+            .map(|(v, p_opt)| match p_opt {
+                Some(p) => Ok((*v, Self::status(p)?)),
+                None => Ok((*v, None))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        let default_status = Self::status(self.parameters)?;
+
+        let first_span = default_status.iter().chain(variant_statuses.iter().filter_map(|(_, s)| s.as_ref())).map(|(_, span)| *span).next();
+        let span = match first_span {
+            Some(span) => span,
+            None => return Ok(quote! {})
+        };
+        self.check_display(display_implemented, span)?;
+
+        let ident = self.ident;
+        let default_status = default_status.map(|(status, _)| status).unwrap_or(DEFAULT_STATUS);
+        let match_arms = variant_statuses
+            .into_iter()
+            .map(|(v, status)| {
+                let variant_ident = &v.ident;
+                let cfg = cfg_attributes(v);
+                let status = status.map(|(status, _)| status).unwrap_or(default_status);
+                quote! {#cfg #ident::#variant_ident { .. } => #status}
+            });
+
+        implement_into_response(self.ident, self.generics, quote! {
+            match &self {
+                #(#match_arms,)*
+            }
+        }, retry_after_implemented, span)
+    }
+
+    /// The body of the response is the Display message, so it must exist.
+    fn check_display(&self, display_implemented: bool, span: Span) -> Result<(), IntoResponseImplementationError> {
+        match display_implemented {
+            true => Ok(()),
+            false => Err(StatusWithoutDisplay(self.ident.clone(), span))
+        }
+    }
+
+    /// Return the status set in the given parameters and its span, if any.
+    fn status(parameters: &Parameters) -> Result<Option<(u16, Span)>, IntoResponseImplementationError> {
+        let (status, span) = match (parameters.integer_for_name(STATUS), parameters.span_for_name(STATUS)) {
+            (Some(status), Some(span)) => (status, span),
+            _ => return Ok(None)
+        };
+
+        match status {
+            100..=999 => Ok(Some((status as u16, span))),
+            _ => Err(InvalidStatus(status, span))
+        }
+    }
+}
+
+/// Create the IntoResponse implementation, which evaluates the given status expression (an u16) and
+/// responds with it and the Display message. If 'retry_after' is true, the Retry-After header is set
+/// with the result of the method 'retry_after'.
+#[cfg(feature = "axum")]
+fn implement_into_response(ident: &Ident, generics: &Generics, status: TokenStream2, retry_after: bool, _span: Span) -> Result<TokenStream2, IntoResponseImplementationError> {
+    let (impl_generics, type_generics, where_clause) = generics.split_for_impl();
+
+    if !retry_after {
+        let impl_attributes = trait_impl_attributes();
+
+        return Ok(quote! {
+            #impl_attributes
+            impl #impl_generics axum::response::IntoResponse for #ident #type_generics #where_clause {
+                fn into_response(self) -> axum::response::Response {
+                    let status = axum::http::StatusCode::from_u16(#status).unwrap_or(axum::http::StatusCode::INTERNAL_SERVER_ERROR);
+                    axum::response::IntoResponse::into_response((status, self.to_string()))
+                }
+            }
+        });
+    }
+
+    let impl_attributes = trait_impl_attributes();
+
+    Ok(quote! {
+        #impl_attributes
+        impl #impl_generics axum::response::IntoResponse for #ident #type_generics #where_clause {
+            fn into_response(self) -> axum::response::Response {
+                let status = axum::http::StatusCode::from_u16(#status).unwrap_or(axum::http::StatusCode::INTERNAL_SERVER_ERROR);
+                let retry_after = self.retry_after();
+                let mut response = axum::response::IntoResponse::into_response((status, self.to_string()));
+                if let Some(retry_after) = retry_after {
+                    response.headers_mut().insert(axum::http::header::RETRY_AFTER, axum::http::HeaderValue::from(retry_after.as_secs()));
+                }
+                response
+            }
+        }
+    })
+}
+
+/// Without the feature 'axum', STATUS cannot be used.
+#[cfg(not(feature = "axum"))]
+fn implement_into_response(_ident: &Ident, _generics: &Generics, _status: TokenStream2, _retry_after: bool, span: Span) -> Result<TokenStream2, IntoResponseImplementationError> {
+    Err(FeatureNotEnabled(span))
+}
+
+/// Error that might occur when the IntoResponse implementation is created.
+#[derive(Debug)]
+pub enum IntoResponseImplementationError {
+    /// The status is not a valid HTTP status code (100 to 999).
+    InvalidStatus(u64, Span),
+    /// The error has a status, but no message which could be used as the body.
+    StatusWithoutDisplay(Ident, Span),
+    /// STATUS was used, but the feature 'axum' is not enabled.
+    #[allow(dead_code)]
+    FeatureNotEnabled(Span),
+}
+
+impl IntoResponseImplementationError {
+    /// Return the span of the code which caused this error.
+    pub fn span(&self) -> Span {
+        match self {
+            InvalidStatus(_, span) => *span,
+            StatusWithoutDisplay(_, span) => *span,
+            FeatureNotEnabled(span) => *span
+        }
+    }
+}
+
+impl std::error::Error for IntoResponseImplementationError {}
+
+impl std::fmt::Display for IntoResponseImplementationError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            InvalidStatus(status, _) => write!(f, "The status {} is not a valid HTTP status code. Status codes must be between 100 and 999.", status),
+            StatusWithoutDisplay(ident, _) => write!(f, "The parameter '{}' requires a message for '{}', as it is used as response body.", STATUS, ident),
+            FeatureNotEnabled(_) => write!(f, "The parameter '{}' requires the feature 'axum' of error_generator.", STATUS)
+        }
+    }
+}
+
+impl From<IntoResponseImplementationError> for syn::Error {
+    fn from(e: IntoResponseImplementationError) -> Self {
+        syn::Error::new(e.span(), e)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[cfg(feature = "axum")]
+    use crate::assert_enum_implementation_as_expected;
+    use crate::assert_struct_implementation_as_expected;
+
+    #[test]
+    #[should_panic(expected = "The status 1000 is not a valid HTTP status code. Status codes must be between 100 and 999.")]
+    fn invalid_status_should_panic() {
+        assert_struct_implementation_as_expected!(
+            item: {
+                #[error(message = "not found", status = 1000)]
+                struct S;
+            }
+
+            expected: {
+                should panic
+            }
+        )
+    }
+
+    #[cfg(feature = "axum")]
+    #[test]
+    fn enum_statuses() {
+        assert_enum_implementation_as_expected!(
+            item: {
+                #[error(message = "request failed")]
+                enum E {
+                    #[error(status = 404)]
+                    NotFound,
+                    Internal(String)
+                }
+            }
+
+            expected: {
+                #[derive(Debug)]
+                enum E {
+                    NotFound,
+                    Internal(String)
+                }
+
+                #[automatically_derived]
+                #[allow(unused_qualifications, clippy::all)]
+                impl ::std::error::Error for E {}
+
+                #[automatically_derived]
+                #[allow(unused_qualifications, clippy::all)]
+                impl ::std::fmt::Display for E {
+                    fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+                        match self {
+                            _ => ::std::write!(f, "request failed")
+                        }
+                    }
+                }
+
+                #[automatically_derived]
+                #[allow(unused_qualifications, clippy::all)]
+                impl axum::response::IntoResponse for E {
+                    fn into_response(self) -> axum::response::Response {
+                        let status = axum::http::StatusCode::from_u16(match &self {
+                            E::NotFound { .. } => 404u16,
+                            E::Internal { .. } => 500u16,
+                        }).unwrap_or(axum::http::StatusCode::INTERNAL_SERVER_ERROR);
+                        axum::response::IntoResponse::into_response((status, self.to_string()))
+                    }
+                }
+            }
+        )
+    }
+
+    #[cfg(feature = "axum")]
+    #[test]
+    fn struct_retry_after() {
+        assert_struct_implementation_as_expected!(
+            item: {
+                #[error(message = "busy", status = 503, retry_after = 30)]
+                struct S;
+            }
+
+            expected: {
+                #[derive(Debug)]
+                struct S;
+
+                #[automatically_derived]
+                #[allow(unused_qualifications, clippy::all)]
+                impl ::std::error::Error for S {}
+
+                #[automatically_derived]
+                #[allow(unused_qualifications, clippy::all)]
+                impl ::std::fmt::Display for S {
+                    fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+                        ::std::write!(f, "busy")
+                    }
+                }
+
+                impl S {
+                    #[doc = " Return the time after which the failed operation should be retried, if it should be retried at all."]
+                    pub fn retry_after(&self) -> Option<::std::time::Duration> {
+                        Some(::std::time::Duration::from_secs(30u64))
+                    }
+                }
+
+                #[automatically_derived]
+                #[allow(unused_qualifications, clippy::all)]
+                impl axum::response::IntoResponse for S {
+                    fn into_response(self) -> axum::response::Response {
+                        let status = axum::http::StatusCode::from_u16(503u16).unwrap_or(axum::http::StatusCode::INTERNAL_SERVER_ERROR);
+                        let retry_after = self.retry_after();
+                        let mut response = axum::response::IntoResponse::into_response((status, self.to_string()));
+                        if let Some(retry_after) = retry_after {
+                            response.headers_mut().insert(axum::http::header::RETRY_AFTER, axum::http::HeaderValue::from(retry_after.as_secs()));
+                        }
+                        response
+                    }
+                }
+            }
+        )
+    }
+
+    #[cfg(not(feature = "axum"))]
+    #[test]
+    #[should_panic(expected = "The parameter 'status' requires the feature 'axum' of error_generator.")]
+    fn status_without_feature_should_panic() {
+        assert_struct_implementation_as_expected!(
+            item: {
+                #[error(message = "not found", status = 404)]
+                struct S;
+            }
+
+            expected: {
+                should panic
+            }
+        )
+    }
+}
@@ -0,0 +1,74 @@
+use quote::quote;
+use syn::__private::TokenStream2;
+use syn::{Index, ItemEnum, Member, Type, Variant};
+
+use crate::common::{cfg_attributes, tokens_to_readable_string};
+
+/// The ways to write the type std::io::Error which are recognized.
+const IO_ERROR_TYPES: &[&str] = &["std::io::Error", "::std::io::Error", "io::Error"];
+
+/// Creates the method 'io_kind', which returns the std::io::ErrorKind of the wrapped std::io::Error if
+/// the enum is a variant with such a field. This saves the double match in retry or fallback logic.
+///
+/// The method is only created if at least one variant has a field of type std::io::Error.
+pub struct IoKindImplementor<'a> {
+    item_enum: &'a ItemEnum,
+}
+
+impl<'a> IoKindImplementor<'a> {
+    pub fn new(item_enum: &'a ItemEnum) -> Self {
+        IoKindImplementor { item_enum }
+    }
+
+    pub fn implement(self) -> TokenStream2 {
+        let ident = &self.item_enum.ident;
+        let match_arms = self.item_enum.variants
+            .iter()
+            .filter_map(|v| {
+                let member = Self::io_error_member(v)?;
+                let variant_ident = &v.ident;
+                let cfg = cfg_attributes(v);
+                Some(quote! {#cfg #ident::#variant_ident { #member: e, .. } => ::std::option::Option::Some(e.kind())})
+            })
+            .collect::<Vec<_>>();
+
+        if match_arms.is_empty() {
+            return quote! {};
+        }
+
+        let default_match_arm = match match_arms.len() == self.item_enum.variants.len() {
+            true => quote! {},
+            false => quote! {_ => ::std::option::Option::None}
+        };
+        let (impl_generics, type_generics, where_clause) = self.item_enum.generics.split_for_impl();
+
+        quote! {
+            impl #impl_generics #ident #type_generics #where_clause {
+                #[doc = " Return the kind of the wrapped std::io::Error, if this error wraps one."]
+                pub fn io_kind(&self) -> ::std::option::Option<::std::io::ErrorKind> {
+                    match self {
+                        #(#match_arms,)*
+                        #default_match_arm
+                    }
+                }
+            }
+        }
+    }
+
+    /// Return the member of the first field of the given variant with the type std::io::Error.
+    fn io_error_member(variant: &Variant) -> Option<Member> {
+        variant.fields
+            .iter()
+            .enumerate()
+            .find(|(_, f)| Self::is_io_error(&f.ty))
+            .map(|(i, f)| match &f.ident {
+                Some(ident) => Member::Named(ident.clone()),
+                None => Member::Unnamed(Index::from(i))
+            })
+    }
+
+    /// Check if the given type is std::io::Error, written in one of the recognized ways.
+    pub fn is_io_error(ty: &Type) -> bool {
+        IO_ERROR_TYPES.contains(&tokens_to_readable_string(ty).as_str())
+    }
+}
@@ -0,0 +1,115 @@
+use std::fmt::Formatter;
+
+use quote::quote;
+use syn::__private::{Span, TokenStream2};
+use syn::{Generics, Ident, ItemEnum};
+
+use crate::common::implement_variant_name;
+use crate::impl_json::JsonImplementationError::*;
+use crate::parameters::{JSON, Parameters};
+
+/// Creates the method 'to_json', which renders the error as a single line JSON object like
+/// '{"error": "NotFound", "message": "..."}' without depending on serde. If ERRNO is set, the
+/// errno value is added as "code".
+pub struct JsonImplementor<'a> {
+    ident: &'a Ident,
+    generics: &'a Generics,
+    parameters: &'a Parameters,
+}
+
+impl<'a> JsonImplementor<'a> {
+    pub fn new(ident: &'a Ident, generics: &'a Generics, parameters: &'a Parameters) -> Self {
+        JsonImplementor { ident, generics, parameters }
+    }
+
+    /// Create the method for a struct, if JSON is set.
+    pub fn implement_for_struct(self, display_implemented: bool, errno_implemented: bool) -> Result<TokenStream2, JsonImplementationError> {
+        let name = self.ident.to_string();
+        self.implement(quote! {#name}, display_implemented, errno_implemented)
+    }
+
+    /// Create the method for an enum, if JSON is set. The name is the one of the variant.
+    pub fn implement_for_enum(self, item_enum: &ItemEnum, display_implemented: bool, errno_implemented: bool) -> Result<TokenStream2, JsonImplementationError> {
+        self.implement(implement_variant_name(item_enum), display_implemented, errno_implemented)
+    }
+
+    fn implement(&self, name: TokenStream2, display_implemented: bool, errno_implemented: bool) -> Result<TokenStream2, JsonImplementationError> {
+        let span = match self.parameters.span_for_name(JSON) {
+            Some(span) if self.parameters.bool_for_name(JSON) => span,
+            _ => return Ok(quote! {})
+        };
+
+        if !display_implemented {
+            return Err(JsonWithoutDisplay(self.ident.clone(), span));
+        }
+
+        let ident = self.ident;
+        let (impl_generics, type_generics, where_clause) = self.generics.split_for_impl();
+        let code = match errno_implemented {
+            true => quote! {json.push_str(&format!(", \"code\": {}", self.to_errno()));},
+            false => quote! {}
+        };
+
+        Ok(quote! {
+            impl #impl_generics #ident #type_generics #where_clause {
+                #[doc = " Render this error as a single line JSON object with its name and message."]
+                pub fn to_json(&self) -> String {
+                    fn escape(value: &str, json: &mut String) {
+                        for c in value.chars() {
+                            match c {
+                                '"' => json.push_str("\\\""),
+                                '\\' => json.push_str("\\\\"),
+                                '\n' => json.push_str("\\n"),
+                                '\r' => json.push_str("\\r"),
+                                '\t' => json.push_str("\\t"),
+                                c if (c as u32) < 0x20 => json.push_str(&format!("\\u{:04x}", c as u32)),
+                                c => json.push(c)
+                            }
+                        }
+                    }
+
+                    let mut json = String::from("{\"error\": \"");
+                    escape(#name, &mut json);
+                    json.push('"');
+                    #code
+                    json.push_str(", \"message\": \"");
+                    escape(&self.to_string(), &mut json);
+                    json.push_str("\"}");
+                    json
+                }
+            }
+        })
+    }
+}
+
+/// Error that might occur when the to_json method is created.
+#[derive(Debug)]
+pub enum JsonImplementationError {
+    /// The error should be rendered as JSON, but has no message.
+    JsonWithoutDisplay(Ident, Span),
+}
+
+impl JsonImplementationError {
+    /// Return the span of the code which caused this error.
+    pub fn span(&self) -> Span {
+        match self {
+            JsonWithoutDisplay(_, span) => *span
+        }
+    }
+}
+
+impl std::error::Error for JsonImplementationError {}
+
+impl std::fmt::Display for JsonImplementationError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            JsonWithoutDisplay(ident, _) => write!(f, "The parameter '{}' requires a message for '{}', as it is rendered into the JSON object.", JSON, ident)
+        }
+    }
+}
+
+impl From<JsonImplementationError> for syn::Error {
+    fn from(e: JsonImplementationError) -> Self {
+        syn::Error::new(e.span(), e)
+    }
+}
@@ -0,0 +1,91 @@
+use quote::{format_ident, quote};
+use syn::__private::TokenStream2;
+use syn::ItemEnum;
+
+use crate::common::{cfg_attributes, std_root, trait_impl_attributes};
+use crate::enum_error::VariantWithParams;
+use crate::impl_wrap_map::WrapMap;
+use crate::parameters::{KIND, Parameters};
+
+/// Creates the enum '<name of the enum>Kind', which has the same variants as the error but no fields,
+/// together with the method 'kind(&self)' and a From implementation for references to the error.
+///
+/// The kind is a lightweight, copyable label for metrics and documentation. 'message_template' returns
+/// the message of a kind with the placeholders of the fields unresolved.
+pub struct KindImplementor<'a> {
+    item_enum: &'a ItemEnum,
+    enum_parameters: &'a Parameters,
+    variants_with_parameters: &'a [VariantWithParams<'a>],
+    wrap_map: &'a WrapMap<'a>,
+}
+
+impl<'a> KindImplementor<'a> {
+    pub fn new(item_enum: &'a ItemEnum, enum_parameters: &'a Parameters, variants_with_parameters: &'a [VariantWithParams<'a>], wrap_map: &'a WrapMap<'a>) -> Self {
+        KindImplementor { item_enum, enum_parameters, variants_with_parameters, wrap_map }
+    }
+
+    /// Create the kind enum and its conversions if the parameter KIND is set, otherwise return an empty token stream.
+    pub fn implement(self) -> TokenStream2 {
+        if !self.enum_parameters.bool_for_name(KIND) {
+            return quote! {};
+        }
+
+        let ident = &self.item_enum.ident;
+        let vis = &self.item_enum.vis;
+        let kind_ident = format_ident!("{}Kind", ident);
+        let kind_doc = format!(" The variants of '{}' without their fields, see '{}::kind'.", ident, ident);
+        let root = std_root(self.enum_parameters);
+        let (impl_generics, type_generics, where_clause) = self.item_enum.generics.split_for_impl();
+
+        let variant_idents = self.item_enum.variants
+            .iter()
+            .map(|v| &v.ident)
+            .collect::<Vec<_>>();
+        let cfgs = self.item_enum.variants
+            .iter()
+            .map(cfg_attributes)
+            .collect::<Vec<_>>();
+        let templates = self.variants_with_parameters
+            .iter()
+            .map(|(v, p_opt)| match self.wrap_map.template_for(v, p_opt.as_ref(), self.enum_parameters) {
+                Some(template) => quote! {Some(#template)},
+                None => quote! {None}
+            })
+            .collect::<Vec<_>>();
+
+        let impl_attributes = trait_impl_attributes();
+
+        quote! {
+            #[doc = #kind_doc]
+            #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+            #vis enum #kind_ident {
+                #(#cfgs #variant_idents),*
+            }
+
+            impl #kind_ident {
+                #[doc = " Return the message of this kind with the placeholders of the fields unresolved, if it has one."]
+                pub fn message_template(&self) -> Option<&'static str> {
+                    match self {
+                        #(#cfgs #kind_ident::#variant_idents => #templates,)*
+                    }
+                }
+            }
+
+            impl #impl_generics #ident #type_generics #where_clause {
+                #[doc = " Return the kind of this error."]
+                pub fn kind(&self) -> #kind_ident {
+                    match self {
+                        #(#cfgs #ident::#variant_idents { .. } => #kind_ident::#variant_idents,)*
+                    }
+                }
+            }
+
+            #impl_attributes
+            impl #impl_generics #root::convert::From<&#ident #type_generics> for #kind_ident #where_clause {
+                fn from(e: &#ident #type_generics) -> Self {
+                    e.kind()
+                }
+            }
+        }
+    }
+}
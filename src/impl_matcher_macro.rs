@@ -0,0 +1,55 @@
+use quote::{format_ident, quote};
+use syn::__private::TokenStream2;
+use syn::ItemEnum;
+
+use crate::common::to_snake_case;
+use crate::parameters::{MATCHER_MACRO, Parameters};
+
+/// Creates a companion macro 'assert_<enum name in snake case>!' for an enum, which is meant for tests.
+///
+/// The macro takes a Result and a pattern of a variant, like 'assert_my_error!(result, NotFound { id })'.
+/// It panics if the result is Ok or holds another variant, and binds the fields of the pattern
+/// in the calling scope for further assertions.
+pub struct MatcherMacroImplementor<'a> {
+    item_enum: &'a ItemEnum,
+    enum_parameters: &'a Parameters,
+}
+
+impl<'a> MatcherMacroImplementor<'a> {
+    pub fn new(item_enum: &'a ItemEnum, enum_parameters: &'a Parameters) -> Self {
+        MatcherMacroImplementor { item_enum, enum_parameters }
+    }
+
+    /// Create the macro if the parameter MATCHER_MACRO is set, otherwise return an empty token stream.
+    ///
+    /// If the enum implements Display, the failure messages contain the message of the unexpected error,
+    /// otherwise its Debug representation.
+    pub fn implement(self, display_implemented: bool) -> TokenStream2 {
+        if !self.enum_parameters.bool_for_name(MATCHER_MACRO) {
+            return quote! {};
+        }
+
+        let ident = &self.item_enum.ident;
+        let name = ident.to_string();
+        let macro_ident = format_ident!("assert_{}", to_snake_case(&name));
+        let error_format = match display_implemented {
+            true => quote! {"{}"},
+            false => quote! {"{:?}"}
+        };
+
+        quote! {
+            #[allow(unused_macros)]
+            macro_rules! #macro_ident {
+                ($result:expr, $variant:ident $($fields:tt)?) => {
+                    let error = match $result {
+                        Ok(_) => panic!("expected {}::{}, but the result is Ok", #name, stringify!($variant)),
+                        Err(e) => e
+                    };
+                    let #ident::$variant $($fields)? = error else {
+                        panic!(concat!("expected {}::{}, but got: ", #error_format), #name, stringify!($variant), error)
+                    };
+                };
+            }
+        }
+    }
+}
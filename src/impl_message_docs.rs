@@ -0,0 +1,214 @@
+use std::fmt::Formatter;
+
+use syn::__private::Span;
+use syn::{Attribute, Ident, ItemEnum, ItemStruct, parse_quote};
+
+use crate::common::error_attribute_parameters;
+use crate::impl_message_docs::MessageDocsError::*;
+use crate::parameters::{MESSAGE, MESSAGE_DOCS, Parameters, PREFIX, SUFFIX};
+
+/// Add a doc comment with the message template to the struct if MESSAGE_DOCS is set, like
+/// 'Displayed as: `Could not read {self.path}`', so rustdoc shows what the error prints.
+///
+/// Fails if the struct has no message.
+pub fn add_message_docs_to_struct(item_struct: &mut ItemStruct, parameters: &Parameters) -> Result<(), MessageDocsError> {
+    let span = match message_docs_span(parameters) {
+        Some(span) => span,
+        None => return Ok(())
+    };
+
+    let message = parameters
+        .string_for_name(MESSAGE)
+        .ok_or_else(|| MessageDocsWithoutMessage(item_struct.ident.clone(), span))?;
+    add_doc_line(&mut item_struct.attrs, format!(" Displayed as: `{}`", message));
+    Ok(())
+}
+
+/// Add a doc comment with the message template to every variant with a message if MESSAGE_DOCS is set.
+/// The message of the enum is documented on the enum itself, as it is displayed by all variants without a message.
+/// PREFIX and SUFFIX are part of the documented messages.
+///
+/// Fails if neither the enum nor any variant has a message.
+pub fn add_message_docs_to_enum(item_enum: &mut ItemEnum, enum_parameters: &Parameters) -> Result<(), MessageDocsError> {
+    let span = match message_docs_span(enum_parameters) {
+        Some(span) => span,
+        None => return Ok(())
+    };
+
+    let prefix = enum_parameters.string_for_name(PREFIX).unwrap_or_default();
+    let suffix = enum_parameters.string_for_name(SUFFIX).unwrap_or_default();
+    let with_affixes = |message: &str| format!("{}{}{}", prefix, message, suffix);
+
+    let default_message = enum_parameters.string_for_name(MESSAGE).map(with_affixes);
+    let variant_messages = item_enum.variants
+        .iter()
+        .map(|v| error_attribute_parameters(&v.attrs)
+            .ok()
+            .flatten()
+            .and_then(|p| p.string_for_name(MESSAGE).map(with_affixes)))
+        .collect::<Vec<_>>();
+
+    if default_message.is_none() && variant_messages.iter().all(Option::is_none) {
+        return Err(MessageDocsWithoutMessage(item_enum.ident.clone(), span));
+    }
+
+    if let Some(message) = default_message {
+        add_doc_line(&mut item_enum.attrs, format!(" Variants without a message of their own are displayed as: `{}`", message));
+    }
+
+    item_enum.variants
+        .iter_mut()
+        .zip(variant_messages)
+        .filter_map(|(v, message_opt)| message_opt.map(|message| (v, message)))
+        .for_each(|(v, message)| add_doc_line(&mut v.attrs, format!(" Displayed as: `{}`", message)));
+    Ok(())
+}
+
+/// Return the span of MESSAGE_DOCS, if doc comments should be created for the item with the given parameters.
+fn message_docs_span(parameters: &Parameters) -> Option<Span> {
+    match parameters.span_for_name(MESSAGE_DOCS) {
+        Some(span) if parameters.bool_for_name(MESSAGE_DOCS) => Some(span),
+        _ => None
+    }
+}
+
+/// Append the given line to the doc comment in the given attributes. It becomes its own paragraph
+/// if there already is a doc comment.
+fn add_doc_line(attributes: &mut Vec<Attribute>, line: String) {
+    if attributes.iter().any(|attr| attr.path.is_ident("doc")) {
+        attributes.push(parse_quote!(#[doc = ""]))
+    }
+
+    attributes.push(parse_quote!(#[doc = #line]))
+}
+
+/// Error that might occur when the doc comments with the messages are added.
+#[derive(Debug)]
+pub enum MessageDocsError {
+    /// MESSAGE_DOCS was set, but there is no message which could be documented.
+    MessageDocsWithoutMessage(Ident, Span),
+}
+
+impl MessageDocsError {
+    /// Return the span of the code which caused this error.
+    pub fn span(&self) -> Span {
+        match self {
+            MessageDocsWithoutMessage(_, span) => *span
+        }
+    }
+}
+
+impl std::error::Error for MessageDocsError {}
+
+impl std::fmt::Display for MessageDocsError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            MessageDocsWithoutMessage(ident, _) => write!(f, "The parameter '{}' requires a message for '{}', as the message is added to the docs.", MESSAGE_DOCS, ident)
+        }
+    }
+}
+
+impl From<MessageDocsError> for syn::Error {
+    fn from(e: MessageDocsError) -> Self {
+        syn::Error::new(e.span(), e)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{assert_enum_implementation_as_expected, assert_struct_implementation_as_expected};
+
+    #[test]
+    fn message_docs_struct() {
+        assert_struct_implementation_as_expected!(
+            item: {
+                #[error(message = "Could not read {self.path}", message_docs)]
+                #[doc = " The config file could not be read."]
+                struct S {
+                    path: String
+                }
+            }
+
+            expected: {
+                #[derive(Debug)]
+                #[doc = " The config file could not be read."]
+                #[doc = ""]
+                #[doc = " Displayed as: `Could not read {self.path}`"]
+                struct S {
+                    path: String
+                }
+
+                #[automatically_derived]
+                #[allow(unused_qualifications, clippy::all)]
+                impl ::std::error::Error for S {}
+
+                #[automatically_derived]
+                #[allow(unused_qualifications, clippy::all)]
+                impl ::std::fmt::Display for S {
+                    fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+                        ::std::write!(f, "Could not read {}", self.path)
+                    }
+                }
+            }
+        )
+    }
+
+    #[test]
+    fn message_docs_enum() {
+        assert_enum_implementation_as_expected!(
+            item: {
+                #[error(message = "Something went wrong", prefix = "[app] ", message_docs)]
+                enum E {
+                    #[error(message = "The foo value: {foo}")]
+                    Named {foo: usize},
+                    Unit
+                }
+            }
+
+            expected: {
+                #[derive(Debug)]
+                #[doc = " Variants without a message of their own are displayed as: `[app] Something went wrong`"]
+                enum E {
+                    #[doc = " Displayed as: `[app] The foo value: {foo}`"]
+                    Named {foo: usize},
+                    Unit
+                }
+
+                #[automatically_derived]
+                #[allow(unused_qualifications, clippy::all)]
+                impl ::std::error::Error for E {}
+
+                #[automatically_derived]
+                #[allow(unused_qualifications, clippy::all)]
+                impl ::std::fmt::Display for E {
+                    fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+                        {
+                            f.write_str("[app] ")?;
+                            match self {
+                                E::Named {foo,} => ::std::write!(f, "The foo value: {}", foo),
+                                _ => ::std::write!(f, "Something went wrong")
+                            }
+                        }
+                    }
+                }
+            }
+        )
+    }
+
+    #[test]
+    #[should_panic(expected = "The parameter 'message_docs' requires a message for 'E', as the message is added to the docs.")]
+    fn message_docs_without_message_should_panic() {
+        assert_enum_implementation_as_expected!(
+            item: {
+                #[error(message_docs)]
+                enum E {
+                    Unit
+                }
+            }
+
+            expected: {
+                should panic
+            }
+        )
+    }
+}
@@ -0,0 +1,39 @@
+use quote::quote;
+use syn::__private::TokenStream2;
+use syn::ItemEnum;
+
+use crate::common::implement_variant_name;
+use crate::parameters::{Parameters, VARIANT_NAME};
+
+/// Creates the method 'name', which returns the name of the variant of an enum, like "ReadFileFailed".
+/// Metrics and logs can use it as a stable label without formatting the whole message.
+pub struct NameImplementor<'a> {
+    item_enum: &'a ItemEnum,
+    enum_parameters: &'a Parameters,
+}
+
+impl<'a> NameImplementor<'a> {
+    pub fn new(item_enum: &'a ItemEnum, enum_parameters: &'a Parameters) -> Self {
+        NameImplementor { item_enum, enum_parameters }
+    }
+
+    /// Create the method if the parameter VARIANT_NAME is set, otherwise return an empty token stream.
+    pub fn implement(self) -> TokenStream2 {
+        if !self.enum_parameters.bool_for_name(VARIANT_NAME) {
+            return quote! {};
+        }
+
+        let ident = &self.item_enum.ident;
+        let body = implement_variant_name(self.item_enum);
+        let (impl_generics, type_generics, where_clause) = self.item_enum.generics.split_for_impl();
+
+        quote! {
+            impl #impl_generics #ident #type_generics #where_clause {
+                #[doc = " Return the name of the variant of this error."]
+                pub fn name(&self) -> &'static str {
+                    #body
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,52 @@
+use quote::{format_ident, quote};
+use syn::__private::TokenStream2;
+use syn::ItemEnum;
+
+use crate::common::{cfg_attributes, to_snake_case};
+use crate::parameters::{Parameters, PREDICATES};
+
+/// Creates a predicate method for every variant of an enum, named after the variant in snake case,
+/// like 'is_parsing_failed(&self) -> bool' for the variant 'ParsingFailed'. This keeps checks for the kind of
+/// an error terse at the call site.
+pub struct PredicatesImplementor<'a> {
+    item_enum: &'a ItemEnum,
+    enum_parameters: &'a Parameters,
+}
+
+impl<'a> PredicatesImplementor<'a> {
+    pub fn new(item_enum: &'a ItemEnum, enum_parameters: &'a Parameters) -> Self {
+        PredicatesImplementor { item_enum, enum_parameters }
+    }
+
+    /// Create the predicates if the parameter PREDICATES is set, otherwise return an empty token stream.
+    pub fn implement(self) -> TokenStream2 {
+        if !self.enum_parameters.bool_for_name(PREDICATES) {
+            return quote! {};
+        }
+
+        let ident = &self.item_enum.ident;
+        let predicates = self.item_enum.variants
+            .iter()
+            .map(|v| {
+                let variant_ident = &v.ident;
+                let cfg = cfg_attributes(v);
+                let predicate_ident = format_ident!("is_{}", to_snake_case(&variant_ident.to_string()));
+                let doc = format!(" Return true if this error is the variant '{}::{}'.", ident, variant_ident);
+
+                quote! {
+                    #cfg
+                    #[doc = #doc]
+                    pub fn #predicate_ident(&self) -> bool {
+                        matches!(self, #ident::#variant_ident { .. })
+                    }
+                }
+            });
+        let (impl_generics, type_generics, where_clause) = self.item_enum.generics.split_for_impl();
+
+        quote! {
+            impl #impl_generics #ident #type_generics #where_clause {
+                #(#predicates)*
+            }
+        }
+    }
+}
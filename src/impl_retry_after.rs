@@ -0,0 +1,84 @@
+use quote::quote;
+use syn::__private::TokenStream2;
+use syn::{Generics, Ident};
+
+use crate::common::{cfg_attributes, std_root};
+use crate::enum_error::VariantWithParams;
+use crate::parameters::{Parameters, RETRY_AFTER};
+
+/// Creates the method 'retry_after', which returns the number of seconds set with the parameter RETRY_AFTER
+/// as std::time::Duration. Web services can tell their clients when to retry a request with it, which
+/// the IntoResponse implementation does with the Retry-After header.
+pub struct RetryAfterImplementor<'a> {
+    ident: &'a Ident,
+    generics: &'a Generics,
+    parameters: &'a Parameters,
+}
+
+impl<'a> RetryAfterImplementor<'a> {
+    pub fn new(ident: &'a Ident, generics: &'a Generics, parameters: &'a Parameters) -> Self {
+        RetryAfterImplementor { ident, generics, parameters }
+    }
+
+    /// Create the method for a struct, if RETRY_AFTER is set.
+    pub fn implement_for_struct(self) -> TokenStream2 {
+        match self.retry_after(self.parameters) {
+            Some(retry_after) => self.implement_method(quote! {Some(#retry_after)}),
+            None => quote! {}
+        }
+    }
+
+    /// Create the method for an enum, if RETRY_AFTER is set on the enum or any variant.
+    ///
+    /// Variants without RETRY_AFTER use the value of the enum or None if the enum has none.
+    pub fn implement_for_enum(self, variants_with_parameters: &[VariantWithParams<'_>]) -> TokenStream2 {
+        let default_retry_after = self.retry_after(self.parameters);
+        let variant_retry_afters = variants_with_parameters
+            .iter()
+            .map(|(v, p_opt)| (*v, p_opt.as_ref().and_then(|p| self.retry_after(p)).or_else(|| default_retry_after.clone())))
+            .collect::<Vec<_>>();
+
+        if variant_retry_afters.iter().all(|(_, retry_after)| retry_after.is_none()) {
+            return quote! {};
+        }
+
+        let ident = self.ident;
+        let match_arms = variant_retry_afters
+            .into_iter()
+            .map(|(v, retry_after)| {
+                let variant_ident = &v.ident;
+                let cfg = cfg_attributes(v);
+                match retry_after {
+                    Some(retry_after) => quote! {#cfg #ident::#variant_ident { .. } => Some(#retry_after)},
+                    None => quote! {#cfg #ident::#variant_ident { .. } => None}
+                }
+            });
+
+        self.implement_method(quote! {
+            match self {
+                #(#match_arms,)*
+            }
+        })
+    }
+
+    fn implement_method(&self, body: TokenStream2) -> TokenStream2 {
+        let ident = self.ident;
+        let root = std_root(self.parameters);
+        let (impl_generics, type_generics, where_clause) = self.generics.split_for_impl();
+
+        quote! {
+            impl #impl_generics #ident #type_generics #where_clause {
+                #[doc = " Return the time after which the failed operation should be retried, if it should be retried at all."]
+                pub fn retry_after(&self) -> Option<#root::time::Duration> {
+                    #body
+                }
+            }
+        }
+    }
+
+    /// Return the creation of the Duration set in the given parameters, if any.
+    fn retry_after(&self, parameters: &Parameters) -> Option<TokenStream2> {
+        let root = std_root(self.parameters);
+        parameters.integer_for_name(RETRY_AFTER).map(|seconds| quote! {#root::time::Duration::from_secs(#seconds)})
+    }
+}
@@ -0,0 +1,97 @@
+use quote::quote;
+use syn::__private::TokenStream2;
+use syn::{Generics, Ident};
+
+use crate::common::cfg_attributes;
+use crate::enum_error::VariantWithParams;
+use crate::parameters::{Parameters, RETRY_AFTER, RETRYABLE};
+
+/// Creates the method 'is_retryable', which returns if the failed operation can be retried, as set with the
+/// parameter RETRYABLE. This way, retry middleware can decide without knowing the concrete variants.
+pub struct RetryableImplementor<'a> {
+    ident: &'a Ident,
+    generics: &'a Generics,
+    parameters: &'a Parameters,
+}
+
+impl<'a> RetryableImplementor<'a> {
+    pub fn new(ident: &'a Ident, generics: &'a Generics, parameters: &'a Parameters) -> Self {
+        RetryableImplementor { ident, generics, parameters }
+    }
+
+    /// Create the method for a struct, if RETRYABLE is set.
+    pub fn implement_for_struct(self) -> TokenStream2 {
+        match Self::retryable(self.parameters) {
+            Some(retryable) => self.implement_method(quote! {#retryable}),
+            None => quote! {}
+        }
+    }
+
+    /// Create the method for an enum, if RETRYABLE is set on the enum or any variant.
+    ///
+    /// Variants without RETRYABLE are retryable if they have a RETRY_AFTER, otherwise they use
+    /// the value of the enum (again implied by its RETRY_AFTER) or false if the enum has none.
+    pub fn implement_for_enum(self, variants_with_parameters: &[VariantWithParams<'_>]) -> TokenStream2 {
+        let default_retryable = Self::retryable(self.parameters);
+        let any_variant_retryable = variants_with_parameters
+            .iter()
+            .any(|(_, p_opt)| p_opt.as_ref().and_then(Self::retryable).is_some());
+
+        if default_retryable.is_none() && !any_variant_retryable {
+            return quote! {};
+        }
+
+        let ident = self.ident;
+        let match_arms = variants_with_parameters
+            .iter()
+            .map(|(v, p_opt)| {
+                let variant_ident = &v.ident;
+                let cfg = cfg_attributes(v);
+                let retryable = Self::variant_retryable(p_opt.as_ref(), self.parameters);
+                quote! {#cfg #ident::#variant_ident { .. } => #retryable}
+            });
+
+        self.implement_method(quote! {
+            match self {
+                #(#match_arms,)*
+            }
+        })
+    }
+
+    fn implement_method(&self, body: TokenStream2) -> TokenStream2 {
+        let ident = self.ident;
+        let (impl_generics, type_generics, where_clause) = self.generics.split_for_impl();
+
+        quote! {
+            impl #impl_generics #ident #type_generics #where_clause {
+                #[doc = " Return true if the failed operation can be retried."]
+                pub fn is_retryable(&self) -> bool {
+                    #body
+                }
+            }
+        }
+    }
+
+    /// Return if a variant with the given parameters is retryable.
+    ///
+    /// This is the value of its RETRYABLE, true if it has a RETRY_AFTER, otherwise the value of the enum
+    /// (again implied by its RETRY_AFTER) or false if the enum has none.
+    pub fn variant_retryable(variant_parameters: Option<&Parameters>, enum_parameters: &Parameters) -> bool {
+        variant_parameters
+            .and_then(|p| Self::retryable(p).or_else(|| Self::retry_after_set(p)))
+            .or_else(|| Self::retryable(enum_parameters).or_else(|| Self::retry_after_set(enum_parameters)))
+            .unwrap_or(false)
+    }
+
+    /// Return the value of RETRYABLE, if it is set in the given parameters.
+    fn retryable(parameters: &Parameters) -> Option<bool> {
+        parameters
+            .has_parameter(RETRYABLE)
+            .then(|| parameters.bool_for_name(RETRYABLE))
+    }
+
+    /// Return true if RETRY_AFTER is set in the given parameters, which implies that the operation can be retried.
+    fn retry_after_set(parameters: &Parameters) -> Option<bool> {
+        parameters.has_parameter(RETRY_AFTER).then_some(true)
+    }
+}
@@ -0,0 +1,213 @@
+use std::fmt::Formatter;
+
+use quote::quote;
+use syn::__private::{Span, TokenStream2};
+use syn::{Generics, Ident, ItemEnum};
+
+use crate::common::implement_variant_name;
+#[cfg(feature = "serde")]
+use crate::common::trait_impl_attributes;
+use crate::impl_serialize::SerializeImplementationError::*;
+use crate::parameters::{Parameters, SERIALIZE};
+
+/// Creates an implementation of serde::Serialize, which serializes the error as a struct with the name
+/// of the error (or variant), its errno value as code (if ERRNO is set) and the Display message.
+/// Requires the feature 'serde'.
+pub struct SerializeImplementor<'a> {
+    ident: &'a Ident,
+    generics: &'a Generics,
+    parameters: &'a Parameters,
+}
+
+impl<'a> SerializeImplementor<'a> {
+    pub fn new(ident: &'a Ident, generics: &'a Generics, parameters: &'a Parameters) -> Self {
+        SerializeImplementor { ident, generics, parameters }
+    }
+
+    /// Create the implementation for a struct, if SERIALIZE is set.
+    pub fn implement_for_struct(self, display_implemented: bool, errno_implemented: bool) -> Result<TokenStream2, SerializeImplementationError> {
+        let name = self.ident.to_string();
+        self.implement(quote! {#name}, display_implemented, errno_implemented)
+    }
+
+    /// Create the implementation for an enum, if SERIALIZE is set. The name is the one of the variant.
+    pub fn implement_for_enum(self, item_enum: &ItemEnum, display_implemented: bool, errno_implemented: bool) -> Result<TokenStream2, SerializeImplementationError> {
+        self.implement(implement_variant_name(item_enum), display_implemented, errno_implemented)
+    }
+
+    fn implement(&self, name: TokenStream2, display_implemented: bool, errno_implemented: bool) -> Result<TokenStream2, SerializeImplementationError> {
+        let span = match self.parameters.span_for_name(SERIALIZE) {
+            Some(span) if self.parameters.bool_for_name(SERIALIZE) => span,
+            _ => return Ok(quote! {})
+        };
+
+        if !display_implemented {
+            return Err(SerializeWithoutDisplay(self.ident.clone(), span));
+        }
+
+        let code = match errno_implemented {
+            true => Some(quote! {self.to_errno()}),
+            false => None
+        };
+
+        implement_serialize(self.ident, self.generics, name, code, span)
+    }
+}
+
+/// Create the Serialize implementation with the fields 'error' (from the given name expression),
+/// 'code' (from the given code expression, if any) and 'message'.
+#[cfg(feature = "serde")]
+fn implement_serialize(ident: &Ident, generics: &Generics, name: TokenStream2, code: Option<TokenStream2>, _span: Span) -> Result<TokenStream2, SerializeImplementationError> {
+    let (impl_generics, type_generics, where_clause) = generics.split_for_impl();
+    let ident_name = ident.to_string();
+    let num_fields = if code.is_some() { 3usize } else { 2usize };
+    let code_field = code.map(|code| quote! {
+        serde::ser::SerializeStruct::serialize_field(&mut state, "code", &#code)?;
+    });
+
+    let impl_attributes = trait_impl_attributes();
+
+    Ok(quote! {
+        #impl_attributes
+        impl #impl_generics serde::Serialize for #ident #type_generics #where_clause {
+            fn serialize<__S: serde::Serializer>(&self, serializer: __S) -> ::std::result::Result<__S::Ok, __S::Error> {
+                let mut state = serde::Serializer::serialize_struct(serializer, #ident_name, #num_fields)?;
+                serde::ser::SerializeStruct::serialize_field(&mut state, "error", #name)?;
+                #code_field
+                serde::ser::SerializeStruct::serialize_field(&mut state, "message", &self.to_string())?;
+                serde::ser::SerializeStruct::end(state)
+            }
+        }
+    })
+}
+
+/// Without the feature 'serde', SERIALIZE cannot be used.
+#[cfg(not(feature = "serde"))]
+fn implement_serialize(_ident: &Ident, _generics: &Generics, _name: TokenStream2, _code: Option<TokenStream2>, span: Span) -> Result<TokenStream2, SerializeImplementationError> {
+    Err(FeatureNotEnabled(span))
+}
+
+/// Error that might occur when the Serialize implementation is created.
+#[derive(Debug)]
+pub enum SerializeImplementationError {
+    /// The error should be serialized, but has no message.
+    SerializeWithoutDisplay(Ident, Span),
+    /// SERIALIZE was used, but the feature 'serde' is not enabled.
+    #[allow(dead_code)]
+    FeatureNotEnabled(Span),
+}
+
+impl SerializeImplementationError {
+    /// Return the span of the code which caused this error.
+    pub fn span(&self) -> Span {
+        match self {
+            SerializeWithoutDisplay(_, span) => *span,
+            FeatureNotEnabled(span) => *span
+        }
+    }
+}
+
+impl std::error::Error for SerializeImplementationError {}
+
+impl std::fmt::Display for SerializeImplementationError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SerializeWithoutDisplay(ident, _) => write!(f, "The parameter '{}' requires a message for '{}', as it is serialized.", SERIALIZE, ident),
+            FeatureNotEnabled(_) => write!(f, "The parameter '{}' requires the feature 'serde' of error_generator.", SERIALIZE)
+        }
+    }
+}
+
+impl From<SerializeImplementationError> for syn::Error {
+    fn from(e: SerializeImplementationError) -> Self {
+        syn::Error::new(e.span(), e)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[cfg(feature = "serde")]
+    use crate::assert_enum_implementation_as_expected;
+    use crate::assert_struct_implementation_as_expected;
+
+    #[test]
+    #[should_panic(expected = "The parameter 'serialize' requires a message for 'S', as it is serialized.")]
+    fn serialize_without_message_should_panic() {
+        assert_struct_implementation_as_expected!(
+            item: {
+                #[error(serialize)]
+                struct S;
+            }
+
+            expected: {
+                should panic
+            }
+        )
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn enum_serialize() {
+        assert_enum_implementation_as_expected!(
+            item: {
+                #[error(message = "request failed", serialize)]
+                enum E {
+                    NotFound,
+                    Internal(String)
+                }
+            }
+
+            expected: {
+                #[derive(Debug)]
+                enum E {
+                    NotFound,
+                    Internal(String)
+                }
+
+                #[automatically_derived]
+                #[allow(unused_qualifications, clippy::all)]
+                impl ::std::error::Error for E {}
+
+                #[automatically_derived]
+                #[allow(unused_qualifications, clippy::all)]
+                impl ::std::fmt::Display for E {
+                    fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+                        match self {
+                            _ => ::std::write!(f, "request failed")
+                        }
+                    }
+                }
+
+                #[automatically_derived]
+                #[allow(unused_qualifications, clippy::all)]
+                impl serde::Serialize for E {
+                    fn serialize<__S: serde::Serializer>(&self, serializer: __S) -> ::std::result::Result<__S::Ok, __S::Error> {
+                        let mut state = serde::Serializer::serialize_struct(serializer, "E", 2usize)?;
+                        serde::ser::SerializeStruct::serialize_field(&mut state, "error", match self {
+                            E::NotFound { .. } => "NotFound",
+                            E::Internal { .. } => "Internal",
+                        })?;
+                        serde::ser::SerializeStruct::serialize_field(&mut state, "message", &self.to_string())?;
+                        serde::ser::SerializeStruct::end(state)
+                    }
+                }
+            }
+        )
+    }
+
+    #[cfg(not(feature = "serde"))]
+    #[test]
+    #[should_panic(expected = "The parameter 'serialize' requires the feature 'serde' of error_generator.")]
+    fn serialize_without_feature_should_panic() {
+        assert_struct_implementation_as_expected!(
+            item: {
+                #[error(message = "failed", serialize)]
+                struct S;
+            }
+
+            expected: {
+                should panic
+            }
+        )
+    }
+}
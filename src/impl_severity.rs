@@ -0,0 +1,160 @@
+use std::fmt::Formatter;
+
+use quote::{format_ident, quote};
+use syn::__private::{Span, TokenStream2};
+use syn::{Generics, Ident, Visibility};
+
+use crate::common::cfg_attributes;
+use crate::enum_error::VariantWithParams;
+use crate::impl_severity::SeverityImplementationError::*;
+use crate::parameters::{Parameters, SEVERITY};
+
+/// The values of SEVERITY and the variants of the generated severity enum, from the lowest to the highest severity.
+const SEVERITIES: &[(&str, &str)] = &[("warning", "Warning"), ("error", "Error"), ("fatal", "Fatal")];
+
+/// The severity used for enum variants if neither the variant nor the enum has a SEVERITY.
+const DEFAULT_SEVERITY: &str = "Error";
+
+/// Creates the enum '<name of the error>Severity' with the variants Warning, Error and Fatal, together with the
+/// method 'severity(&self)', which returns the severity set with the parameter SEVERITY.
+///
+/// Monitoring code can route errors to different alert channels by their severity, without a parallel match
+/// over all variants. The severities are ordered, so thresholds like 'e.severity() >= MyErrorSeverity::Error' work.
+pub struct SeverityImplementor<'a> {
+    ident: &'a Ident,
+    vis: &'a Visibility,
+    generics: &'a Generics,
+    parameters: &'a Parameters,
+}
+
+impl<'a> SeverityImplementor<'a> {
+    pub fn new(ident: &'a Ident, vis: &'a Visibility, generics: &'a Generics, parameters: &'a Parameters) -> Self {
+        SeverityImplementor { ident, vis, generics, parameters }
+    }
+
+    /// Create the severity enum and method for a struct, if SEVERITY is set.
+    pub fn implement_for_struct(self) -> Result<TokenStream2, SeverityImplementationError> {
+        match Self::severity(self.parameters)? {
+            Some(severity) => {
+                let severity_ident = self.severity_ident();
+                Ok(self.implement_method(quote! {#severity_ident::#severity}))
+            }
+            None => Ok(quote! {})
+        }
+    }
+
+    /// Create the severity enum and method for an enum, if SEVERITY is set on the enum or any variant.
+    ///
+    /// Variants without SEVERITY use the severity of the enum or Error if the enum has none.
+    pub fn implement_for_enum(self, variants_with_parameters: &[VariantWithParams<'_>]) -> Result<TokenStream2, SeverityImplementationError> {
+        let variant_severities = variants_with_parameters
+            .iter()
+            .map(|(v, p_opt)| match p_opt {
+                Some(p) => Ok((*v, Self::severity(p)?)),
+                None => Ok((*v, None))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        let default_severity = Self::severity(self.parameters)?;
+
+        if default_severity.is_none() && variant_severities.iter().all(|(_, severity)| severity.is_none()) {
+            return Ok(quote! {});
+        }
+
+        let ident = self.ident;
+        let default_severity = default_severity.unwrap_or_else(|| format_ident!("{}", DEFAULT_SEVERITY));
+        let severity_ident = self.severity_ident();
+        let match_arms = variant_severities
+            .into_iter()
+            .map(|(v, severity)| {
+                let variant_ident = &v.ident;
+                let cfg = cfg_attributes(v);
+                let severity = severity.unwrap_or_else(|| default_severity.clone());
+                quote! {#cfg #ident::#variant_ident { .. } => #severity_ident::#severity}
+            });
+
+        Ok(self.implement_method(quote! {
+            match self {
+                #(#match_arms,)*
+            }
+        }))
+    }
+
+    /// Create the severity enum and the method 'severity', which evaluates the given body.
+    fn implement_method(&self, body: TokenStream2) -> TokenStream2 {
+        let ident = self.ident;
+        let vis = self.vis;
+        let severity_ident = self.severity_ident();
+        let severity_doc = format!(" The severity of '{}', see '{}::severity'. Ordered from Warning to Fatal.", ident, ident);
+        let (impl_generics, type_generics, where_clause) = self.generics.split_for_impl();
+        let variants = SEVERITIES
+            .iter()
+            .map(|(_, variant)| format_ident!("{}", variant));
+
+        quote! {
+            #[doc = #severity_doc]
+            #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+            #vis enum #severity_ident {
+                #(#variants),*
+            }
+
+            impl #impl_generics #ident #type_generics #where_clause {
+                #[doc = " Return the severity of this error."]
+                pub fn severity(&self) -> #severity_ident {
+                    #body
+                }
+            }
+        }
+    }
+
+    fn severity_ident(&self) -> Ident {
+        format_ident!("{}Severity", self.ident)
+    }
+
+    /// Return the variant of the severity enum set in the given parameters, if any.
+    fn severity(parameters: &Parameters) -> Result<Option<Ident>, SeverityImplementationError> {
+        let (severity, span) = match (parameters.string_for_name(SEVERITY), parameters.span_for_name(SEVERITY)) {
+            (Some(severity), Some(span)) => (severity, span),
+            _ => return Ok(None)
+        };
+
+        match SEVERITIES.iter().find(|(value, _)| *value == severity) {
+            Some((_, variant)) => Ok(Some(format_ident!("{}", variant))),
+            None => Err(UnknownSeverity(severity.to_string(), span))
+        }
+    }
+}
+
+/// Error that might occur when the severity method is created.
+#[derive(Debug)]
+pub enum SeverityImplementationError {
+    /// The severity is not one of SEVERITIES.
+    UnknownSeverity(String, Span),
+}
+
+impl SeverityImplementationError {
+    /// Return the span of the severity which caused this error.
+    pub fn span(&self) -> Span {
+        match self {
+            UnknownSeverity(_, span) => *span
+        }
+    }
+}
+
+impl std::error::Error for SeverityImplementationError {}
+
+impl std::fmt::Display for SeverityImplementationError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            UnknownSeverity(severity, _) => {
+                let severities = SEVERITIES.iter().map(|(value, _)| *value).collect::<Vec<_>>().join(", ");
+                write!(f, "The severity '{}' is unknown. Possible severities are: {}", severity, severities)
+            }
+        }
+    }
+}
+
+impl From<SeverityImplementationError> for syn::Error {
+    fn from(e: SeverityImplementationError) -> Self {
+        syn::Error::new(e.span(), e)
+    }
+}
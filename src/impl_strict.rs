@@ -0,0 +1,217 @@
+use quote::quote;
+use syn::__private::{Span, TokenStream2};
+use syn::{Fields, ItemEnum, ItemStruct};
+
+use crate::common::{field_is_location, field_is_phantom, field_is_skipped, field_is_source_type, implement_warning};
+use crate::enum_error::VariantWithParams;
+use crate::impl_wrap_map::WrapMap;
+use crate::parameters::{MESSAGE, Parameters, STRICT};
+use crate::template::Template;
+
+/// Warns about messages which use none of the fields of their struct or variant if STRICT is set.
+/// Such messages are often stale, as the fields were added or renamed after the message was written.
+///
+/// Any use of self or e counts as use of the fields, as methods called on them might read the fields.
+/// Skipped fields, caller locations, phantom markers and fields with SOURCE_TYPE are not required to be used.
+pub struct StrictImplementor<'a> {
+    parameters: &'a Parameters,
+}
+
+impl<'a> StrictImplementor<'a> {
+    pub fn new(parameters: &'a Parameters) -> Self {
+        StrictImplementor { parameters }
+    }
+
+    /// Create a warning if the message of the struct uses none of its fields.
+    /// Structs with a source are not checked, as their message describes the wrapped error.
+    pub fn implement_for_struct(&self, item_struct: &ItemStruct, has_source: bool) -> TokenStream2 {
+        if !self.parameters.bool_for_name(STRICT) || has_source {
+            return quote! {};
+        }
+
+        match (self.parameters.string_for_name(MESSAGE), self.parameters.span_for_name(MESSAGE)) {
+            (Some(message), Some(span)) => implement_unused_fields_warning(&item_struct.ident.to_string(), message, &item_struct.fields, span),
+            _ => quote! {}
+        }
+    }
+
+    /// Create a warning for every variant whose own message uses none of its fields. Variants without a message of their own
+    /// display the message of the enum, which cannot use fields. Variants from WRAP_MAP display the wrapped value and are not checked.
+    pub fn implement_for_enum(&self, item_enum: &ItemEnum, variants_with_parameters: &[VariantWithParams], wrap_map: &WrapMap) -> TokenStream2 {
+        if !self.parameters.bool_for_name(STRICT) {
+            return quote! {};
+        }
+
+        let warnings = variants_with_parameters
+            .iter()
+            .filter(|(v, _)| wrap_map.message_for(v).is_none())
+            .filter_map(|(v, p_opt)| {
+                let parameters = p_opt.as_ref()?;
+                let item = format!("{}::{}", item_enum.ident, v.ident);
+                Some(implement_unused_fields_warning(&item, parameters.string_for_name(MESSAGE)?, &v.fields, parameters.span_for_name(MESSAGE)?))
+            });
+
+        quote! {
+            #(#warnings)*
+        }
+    }
+}
+
+/// Create a warning at the given span if the given message uses none of the given fields. Invalid messages are not checked,
+/// they are reported when the Display implementation is created.
+fn implement_unused_fields_warning(item: &str, message: &str, fields: &Fields, span: Span) -> TokenStream2 {
+    let has_relevant_fields = fields
+        .iter()
+        .any(|f| !field_is_skipped(f) && !field_is_location(f) && !field_is_source_type(f) && !field_is_phantom(f));
+    let mut template = match (has_relevant_fields, Template::parse(message)) {
+        (true, Ok(template)) => template,
+        _ => return quote! {}
+    };
+    template.resolve_positional_placeholders("e");
+
+    let accesses = fields
+        .iter()
+        .enumerate()
+        .filter(|(_, f)| !field_is_skipped(f))
+        .map(|(i, f)| match &f.ident {
+            Some(ident) => (ident.to_string(), None),
+            None => (format!("_{}", i), None)
+        })
+        .chain(["self", "e"].iter().map(|name| (name.to_string(), None)))
+        .collect::<Vec<_>>();
+
+    match template.uses_any(&accesses) {
+        true => quote! {},
+        false => implement_warning("message_without_fields", &format!("The message of '{}' uses none of its fields.", item), span)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{assert_enum_implementation_as_expected, assert_struct_implementation_as_expected};
+
+    #[test]
+    fn strict_struct() {
+        assert_struct_implementation_as_expected!(
+            item: {
+                #[error(message = "Could not read the file", strict)]
+                struct S {
+                    path: String
+                }
+            }
+
+            expected: {
+                #[derive(Debug)]
+                struct S {
+                    path: String
+                }
+
+                #[automatically_derived]
+                #[allow(unused_qualifications, clippy::all)]
+                impl ::std::error::Error for S {}
+
+                #[automatically_derived]
+                #[allow(unused_qualifications, clippy::all)]
+                impl ::std::fmt::Display for S {
+                    fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+                        ::std::write!(f, "Could not read the file")
+                    }
+                }
+
+                const _: () = {
+                    #[deprecated(note = "The message of 'S' uses none of its fields.")]
+                    #[allow(non_upper_case_globals)]
+                    const message_without_fields: () = ();
+                    message_without_fields
+                };
+            }
+        )
+    }
+
+    #[test]
+    fn strict_struct_with_used_field() {
+        assert_struct_implementation_as_expected!(
+            item: {
+                #[error(message = "Could not read {self.path}", strict)]
+                struct S {
+                    path: String
+                }
+            }
+
+            expected: {
+                #[derive(Debug)]
+                struct S {
+                    path: String
+                }
+
+                #[automatically_derived]
+                #[allow(unused_qualifications, clippy::all)]
+                impl ::std::error::Error for S {}
+
+                #[automatically_derived]
+                #[allow(unused_qualifications, clippy::all)]
+                impl ::std::fmt::Display for S {
+                    fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+                        ::std::write!(f, "Could not read {}", self.path)
+                    }
+                }
+            }
+        )
+    }
+
+    #[test]
+    fn strict_enum() {
+        assert_enum_implementation_as_expected!(
+            item: {
+                #[error(message = "Something went wrong", strict)]
+                enum E {
+                    #[error(message = "The foo value: {foo}")]
+                    Named {foo: usize},
+                    #[error(message = "The value is invalid")]
+                    Unnamed(usize),
+                    #[error(message = "The first value: {0}")]
+                    Positional(usize, usize),
+                    #[error(message = "Nothing to see")]
+                    Unit,
+                    Other(usize)
+                }
+            }
+
+            expected: {
+                #[derive(Debug)]
+                enum E {
+                    Named {foo: usize},
+                    Unnamed(usize),
+                    Positional(usize, usize),
+                    Unit,
+                    Other(usize)
+                }
+
+                #[automatically_derived]
+                #[allow(unused_qualifications, clippy::all)]
+                impl ::std::error::Error for E {}
+
+                #[automatically_derived]
+                #[allow(unused_qualifications, clippy::all)]
+                impl ::std::fmt::Display for E {
+                    fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+                        match self {
+                            E::Named {foo,} => ::std::write!(f, "The foo value: {}", foo),
+                            E::Unnamed(_0,) => ::std::write!(f, "The value is invalid"),
+                            E::Positional(_0, _1,) => ::std::write!(f, "The first value: {}", _0),
+                            E::Unit => ::std::write!(f, "Nothing to see"),
+                            _ => ::std::write!(f, "Something went wrong")
+                        }
+                    }
+                }
+
+                const _: () = {
+                    #[deprecated(note = "The message of 'E::Unnamed' uses none of its fields.")]
+                    #[allow(non_upper_case_globals)]
+                    const message_without_fields: () = ();
+                    message_without_fields
+                };
+            }
+        )
+    }
+}
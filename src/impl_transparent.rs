@@ -0,0 +1,132 @@
+use std::fmt::Formatter;
+
+use quote::quote;
+use syn::__private::{Span, TokenStream2};
+use syn::{Ident, ItemStruct, Member, Meta, NestedMeta, WherePredicate};
+
+use crate::common::{single_source_field, std_root};
+use crate::impl_display::bounds::source_bounds;
+use crate::impl_transparent::TransparentImplementationError::*;
+use crate::parameters::Parameters;
+
+/// Creates the implementations for structs marked with '#[repr(transparent)]', which wrap a single error
+/// (the field a From implementation would convert from, see common::from_source_fields).
+///
+/// The repr is kept and no field is added, so the layout of the struct stays the one of the wrapped error
+/// and it can safely cross FFI boundaries. Without a message, the struct displays the message of the wrapped
+/// error and returns its source, so the wrapper is invisible in the chain of sources. With a message,
+/// the wrapped error is the source. In both cases, From is implemented for the wrapped error and
+/// 'into_inner' returns it again (see IntoInnerImplementor).
+pub struct TransparentImplementor<'a> {
+    item_struct: &'a ItemStruct,
+    parameters: &'a Parameters,
+}
+
+impl<'a> TransparentImplementor<'a> {
+    pub fn new(item_struct: &'a ItemStruct, parameters: &'a Parameters) -> Self {
+        TransparentImplementor { item_struct, parameters }
+    }
+
+    /// Return the accessor of the wrapped error (like 'inner' or '0'), or None if the struct is not marked with '#[repr(transparent)]'.
+    ///
+    /// Fails if the struct has not exactly one field which is not skipped.
+    pub fn wrapped_member(&self) -> Result<Option<Member>, TransparentImplementationError> {
+        if !struct_is_transparent(self.item_struct) {
+            return Ok(None);
+        }
+
+        match single_source_field(&self.item_struct.fields) {
+            Some((member, _)) => Ok(Some(member)),
+            None => Err(NotExactlyOneField(self.item_struct.ident.clone(), self.item_struct.ident.span()))
+        }
+    }
+
+    /// Return the bounds the wrapped error requires if its type is generic (see bounds::source_bounds).
+    /// If the struct is not transparent, there are no bounds.
+    pub fn source_bounds(&self) -> Result<Vec<WherePredicate>, TransparentImplementationError> {
+        let root = std_root(self.parameters);
+
+        Ok(match self.wrapped_member()? {
+            Some(_) => source_bounds(single_source_field(&self.item_struct.fields).map(|(_, f)| f), &self.item_struct.generics, &root),
+            None => vec![]
+        })
+    }
+
+    /// Create the body of std::fmt::Display::fmt, which displays the wrapped error.
+    /// If the struct is not transparent, return an empty token stream.
+    pub fn implement_display_body(&self) -> Result<TokenStream2, TransparentImplementationError> {
+        let root = std_root(self.parameters);
+
+        Ok(match self.wrapped_member()? {
+            Some(member) => quote! {#root::fmt::Display::fmt(&self.#member, f)},
+            None => quote! {}
+        })
+    }
+
+    /// Create the method std::error::Error::source. If the message is forwarded to the wrapped error,
+    /// the source of the wrapped error is returned, otherwise the wrapped error itself.
+    /// If the struct is not transparent, the default implementation is kept.
+    pub fn implement_source(&self, message_forwarded: bool) -> Result<TokenStream2, TransparentImplementationError> {
+        let root = std_root(self.parameters);
+        let member = match self.wrapped_member()? {
+            Some(member) => member,
+            None => return Ok(quote! {})
+        };
+
+        let body = match message_forwarded {
+            true => quote! {#root::error::Error::source(&self.#member)},
+            false => quote! {#root::option::Option::Some(&self.#member)}
+        };
+
+        Ok(quote! {
+            fn source(&self) -> #root::option::Option<&(dyn #root::error::Error + 'static)> {
+                #body
+            }
+        })
+    }
+
+}
+
+/// Check if the given struct is marked with '#[repr(transparent)]'.
+pub fn struct_is_transparent(item_struct: &ItemStruct) -> bool {
+    item_struct.attrs
+        .iter()
+        .filter(|a| a.path.is_ident("repr"))
+        .filter_map(|a| a.parse_meta().ok())
+        .any(|meta| match meta {
+            Meta::List(list) => list.nested.iter().any(|n| matches!(n, NestedMeta::Meta(Meta::Path(p)) if p.is_ident("transparent"))),
+            _ => false
+        })
+}
+
+/// Error that might occur when the implementations for a transparent struct are created.
+#[derive(Debug)]
+pub enum TransparentImplementationError {
+    /// The struct is transparent, but has not exactly one field which could be the wrapped error.
+    NotExactlyOneField(Ident, Span),
+}
+
+impl TransparentImplementationError {
+    /// Return the span of the code which caused this error.
+    pub fn span(&self) -> Span {
+        match self {
+            NotExactlyOneField(_, span) => *span
+        }
+    }
+}
+
+impl std::error::Error for TransparentImplementationError {}
+
+impl std::fmt::Display for TransparentImplementationError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            NotExactlyOneField(ident, _) => write!(f, "The struct '{}' is marked with '#[repr(transparent)]', so it must have exactly one field which is not skipped.", ident)
+        }
+    }
+}
+
+impl From<TransparentImplementationError> for syn::Error {
+    fn from(e: TransparentImplementationError) -> Self {
+        syn::Error::new(e.span(), e)
+    }
+}
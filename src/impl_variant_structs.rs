@@ -0,0 +1,148 @@
+use std::fmt::Formatter;
+
+use quote::{format_ident, quote};
+use syn::__private::{Span, TokenStream2};
+use syn::{Attribute, Fields, Ident, Index, ItemEnum, Member, Variant};
+
+use crate::common::{cfg_attributes, std_root, trait_impl_attributes};
+use crate::impl_variant_structs::VariantStructsError::*;
+use crate::parameters::{Parameters, VARIANT_STRUCTS};
+
+/// Creates a struct '<name of the enum><name of the variant>' for every variant, which has the same fields as the variant,
+/// together with a From implementation which turns the struct into the variant. This way, the fields of a variant can be
+/// passed around and constructed as a real type, like 'ConfigError::from(ConfigErrorMissing { key })'.
+///
+/// The structs and their fields have the visibility of the enum. Only the doc comments of the variants and fields are kept.
+pub struct VariantStructsImplementor<'a> {
+    item_enum: &'a ItemEnum,
+    parameters: &'a Parameters,
+    /// Additional derives for the structs, see HELPER_DERIVE
+    helper_derive: &'a TokenStream2,
+}
+
+impl<'a> VariantStructsImplementor<'a> {
+    pub fn new(item_enum: &'a ItemEnum, parameters: &'a Parameters, helper_derive: &'a TokenStream2) -> Self {
+        VariantStructsImplementor { item_enum, parameters, helper_derive }
+    }
+
+    /// Create the structs if VARIANT_STRUCTS is set.
+    ///
+    /// Fails if the enum is generic, as a struct would need to know which type parameters its fields use.
+    pub fn implement(self) -> Result<TokenStream2, VariantStructsError> {
+        let span = match self.parameters.span_for_name(VARIANT_STRUCTS) {
+            Some(span) if self.parameters.bool_for_name(VARIANT_STRUCTS) => span,
+            _ => return Ok(quote! {})
+        };
+
+        if !self.item_enum.generics.params.is_empty() {
+            return Err(VariantStructsWithGenerics(self.item_enum.ident.clone(), span));
+        }
+
+        let structs = self.item_enum.variants
+            .iter()
+            .map(|v| self.implement_for_variant(v));
+
+        Ok(quote! {#(#structs)*})
+    }
+
+    fn implement_for_variant(&self, variant: &Variant) -> TokenStream2 {
+        let ident = &self.item_enum.ident;
+        let vis = &self.item_enum.vis;
+        let helper_derive = self.helper_derive;
+        let root = std_root(self.parameters);
+        let variant_ident = &variant.ident;
+        let struct_ident = format_ident!("{}{}", ident, variant_ident);
+        let struct_doc = format!(" The fields of '{}::{}' as a struct, which converts into the variant.", ident, variant_ident);
+        let variant_docs = doc_attributes(&variant.attrs);
+        let cfg = cfg_attributes(variant);
+
+        let fields = variant.fields
+            .iter()
+            .map(|f| {
+                let docs = doc_attributes(&f.attrs);
+                let ty = &f.ty;
+                match &f.ident {
+                    Some(field_ident) => quote! {#(#docs)* #vis #field_ident: #ty},
+                    None => quote! {#(#docs)* #vis #ty}
+                }
+            })
+            .collect::<Vec<_>>();
+        let body = match &variant.fields {
+            Fields::Named(_) => quote! {{ #(#fields),* }},
+            Fields::Unnamed(_) => quote! {( #(#fields),* );},
+            Fields::Unit => quote! {;}
+        };
+
+        let members = variant.fields
+            .iter()
+            .enumerate()
+            .map(|(i, f)| match &f.ident {
+                Some(field_ident) => Member::Named(field_ident.clone()),
+                None => Member::Unnamed(Index::from(i))
+            })
+            .collect::<Vec<_>>();
+        let value = match members.is_empty() {
+            true => quote! {_},
+            false => quote! {value}
+        };
+
+        let impl_attributes = trait_impl_attributes();
+
+        quote! {
+            #cfg
+            #[doc = #struct_doc]
+            #(#variant_docs)*
+            #[derive(Debug)]
+            #helper_derive
+            #vis struct #struct_ident #body
+
+            #cfg
+            #impl_attributes
+            impl #root::convert::From<#struct_ident> for #ident {
+                fn from(#value: #struct_ident) -> Self {
+                    #ident::#variant_ident { #(#members: value.#members),* }
+                }
+            }
+        }
+    }
+}
+
+/// Return the doc comments of the given attributes.
+fn doc_attributes(attributes: &[Attribute]) -> Vec<&Attribute> {
+    attributes
+        .iter()
+        .filter(|a| a.path.is_ident("doc"))
+        .collect()
+}
+
+/// Error that might occur when the structs of the variants are created.
+#[derive(Debug)]
+pub enum VariantStructsError {
+    /// VARIANT_STRUCTS was set on a generic enum.
+    VariantStructsWithGenerics(Ident, Span),
+}
+
+impl VariantStructsError {
+    /// Return the span of the code which caused this error.
+    pub fn span(&self) -> Span {
+        match self {
+            VariantStructsWithGenerics(_, span) => *span
+        }
+    }
+}
+
+impl std::error::Error for VariantStructsError {}
+
+impl std::fmt::Display for VariantStructsError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            VariantStructsWithGenerics(ident, _) => write!(f, "The parameter '{}' cannot be used on the generic enum '{}'.", VARIANT_STRUCTS, ident)
+        }
+    }
+}
+
+impl From<VariantStructsError> for syn::Error {
+    fn from(e: VariantStructsError) -> Self {
+        syn::Error::new(e.span(), e)
+    }
+}
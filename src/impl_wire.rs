@@ -0,0 +1,284 @@
+use std::fmt::Formatter;
+
+use quote::quote;
+use syn::__private::{Span, TokenStream2};
+use syn::{Generics, Ident, Visibility};
+
+#[cfg(feature = "serde")]
+use crate::common::trait_impl_attributes;
+use crate::impl_wire::WireImplementationError::*;
+use crate::parameters::{Parameters, WIRE};
+
+/// Creates a serializable record '<name of the error>Wire' and the methods 'to_wire' and 'from_wire', which
+/// let an error and its chain of sources cross service boundaries. Requires the feature 'serde'.
+///
+/// The record holds the errno value as code (if ERRNO is set), the message and the record of the source.
+/// On the receiving side, the record itself is the reconstructed error: it displays the message and
+/// returns the record of the source in std::error::Error::source.
+pub struct WireImplementor<'a> {
+    ident: &'a Ident,
+    vis: &'a Visibility,
+    generics: &'a Generics,
+    parameters: &'a Parameters,
+}
+
+impl<'a> WireImplementor<'a> {
+    pub fn new(ident: &'a Ident, vis: &'a Visibility, generics: &'a Generics, parameters: &'a Parameters) -> Self {
+        WireImplementor { ident, vis, generics, parameters }
+    }
+
+    /// Create the record and the methods if WIRE is set.
+    ///
+    /// Fails if the error has no Display implementation, as the message is part of the record.
+    pub fn implement(self, display_implemented: bool, errno_implemented: bool) -> Result<TokenStream2, WireImplementationError> {
+        let span = match self.parameters.span_for_name(WIRE) {
+            Some(span) if self.parameters.bool_for_name(WIRE) => span,
+            _ => return Ok(quote! {})
+        };
+
+        if !display_implemented {
+            return Err(WireWithoutDisplay(self.ident.clone(), span));
+        }
+
+        let code = match errno_implemented {
+            true => quote! {Some(self.to_errno())},
+            false => quote! {None}
+        };
+
+        implement_wire(self.ident, self.vis, self.generics, code, span)
+    }
+}
+
+/// Create the record '<name of the error>Wire' and the methods of the error, which convert from and into it.
+#[cfg(feature = "serde")]
+fn implement_wire(ident: &Ident, vis: &Visibility, generics: &Generics, code: TokenStream2, _span: Span) -> Result<TokenStream2, WireImplementationError> {
+    let wire_ident = quote::format_ident!("{}Wire", ident);
+    let wire_doc = format!(" The serializable form of '{}' and its chain of sources, see '{}::to_wire'.", ident, ident);
+    let (impl_generics, type_generics, where_clause) = generics.split_for_impl();
+    let impl_attributes = trait_impl_attributes();
+
+    Ok(quote! {
+        #[doc = #wire_doc]
+        #[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+        #vis struct #wire_ident {
+            #[doc = " The errno value of the error, if it has one."]
+            pub code: Option<i32>,
+            #[doc = " The message of the error."]
+            pub message: String,
+            #[doc = " The record of the source of the error, if it has one."]
+            pub chain: Option<Box<#wire_ident>>,
+        }
+
+        impl #wire_ident {
+            fn from_source(error: &(dyn ::std::error::Error + 'static)) -> Self {
+                #wire_ident {
+                    code: None,
+                    message: error.to_string(),
+                    chain: error.source().map(|source| Box::new(Self::from_source(source))),
+                }
+            }
+        }
+
+        #impl_attributes
+        impl ::std::fmt::Display for #wire_ident {
+            fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+                f.write_str(&self.message)
+            }
+        }
+
+        #impl_attributes
+        impl ::std::error::Error for #wire_ident {
+            fn source(&self) -> ::std::option::Option<&(dyn ::std::error::Error + 'static)> {
+                self.chain.as_deref().map(|chain| chain as &(dyn ::std::error::Error + 'static))
+            }
+        }
+
+        impl #impl_generics #ident #type_generics #where_clause {
+            #[doc = " Convert the error and its chain of sources into a serializable record."]
+            pub fn to_wire(&self) -> #wire_ident {
+                #wire_ident {
+                    code: #code,
+                    message: self.to_string(),
+                    chain: ::std::error::Error::source(self).map(|source| Box::new(#wire_ident::from_source(source))),
+                }
+            }
+
+            #[doc = " Reconstruct a received record as an opaque error, which still provides the messages of the whole chain."]
+            pub fn from_wire(wire: #wire_ident) -> Box<dyn ::std::error::Error + Send + Sync> {
+                Box::new(wire)
+            }
+        }
+    })
+}
+
+/// Without the feature 'serde', WIRE cannot be used.
+#[cfg(not(feature = "serde"))]
+fn implement_wire(_ident: &Ident, _vis: &Visibility, _generics: &Generics, _code: TokenStream2, span: Span) -> Result<TokenStream2, WireImplementationError> {
+    Err(FeatureNotEnabled(span))
+}
+
+/// Error that might occur when the wire record is created.
+#[derive(Debug)]
+pub enum WireImplementationError {
+    /// The record should be created, but the error has no message.
+    WireWithoutDisplay(Ident, Span),
+    /// WIRE was used, but the feature 'serde' is not enabled.
+    #[allow(dead_code)]
+    FeatureNotEnabled(Span),
+}
+
+impl WireImplementationError {
+    /// Return the span of the code which caused this error.
+    pub fn span(&self) -> Span {
+        match self {
+            WireWithoutDisplay(_, span) => *span,
+            FeatureNotEnabled(span) => *span
+        }
+    }
+}
+
+impl std::error::Error for WireImplementationError {}
+
+impl std::fmt::Display for WireImplementationError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            WireWithoutDisplay(ident, _) => write!(f, "The parameter '{}' requires a message for '{}', as the message is sent.", WIRE, ident),
+            FeatureNotEnabled(_) => write!(f, "The parameter '{}' requires the feature 'serde' of error_generator.", WIRE)
+        }
+    }
+}
+
+impl From<WireImplementationError> for syn::Error {
+    fn from(e: WireImplementationError) -> Self {
+        syn::Error::new(e.span(), e)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::assert_struct_implementation_as_expected;
+
+    #[test]
+    #[should_panic(expected = "The parameter 'wire' requires a message for 'S', as the message is sent.")]
+    fn wire_without_message_should_panic() {
+        assert_struct_implementation_as_expected!(
+            item: {
+                #[error(wire)]
+                struct S;
+            }
+
+            expected: {
+                should panic
+            }
+        )
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn struct_wire() {
+        assert_struct_implementation_as_expected!(
+            item: {
+                #[error(message = "request failed", errno = 5, wire)]
+                pub struct S;
+            }
+
+            expected: {
+                #[derive(Debug)]
+                pub struct S;
+
+                #[automatically_derived]
+                #[allow(unused_qualifications, clippy::all)]
+                impl ::std::error::Error for S {}
+
+                #[automatically_derived]
+                #[allow(unused_qualifications, clippy::all)]
+                impl ::std::fmt::Display for S {
+                    fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+                        ::std::write!(f, "request failed")
+                    }
+                }
+
+                impl S {
+                    #[doc = " Return the errno value which describes this error."]
+                    pub fn to_errno(&self) -> i32 {
+                        5i32
+                    }
+
+                    #[doc = " Return the errno value which describes this error, the same as 'to_errno'."]
+                    pub fn errno(&self) -> i32 {
+                        self.to_errno()
+                    }
+                }
+
+                #[automatically_derived]
+                #[allow(unused_qualifications, clippy::all)]
+                impl ::std::convert::From<S> for i32 {
+                    fn from(e: S) -> i32 {
+                        e.to_errno()
+                    }
+                }
+
+                #[automatically_derived]
+                #[allow(unused_qualifications, clippy::all)]
+                impl ::std::convert::From<S> for ::std::io::Error {
+                    fn from(e: S) -> ::std::io::Error {
+                        ::std::io::Error::from_raw_os_error(e.to_errno())
+                    }
+                }
+
+                #[doc = " The serializable form of 'S' and its chain of sources, see 'S::to_wire'."]
+                #[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+                pub struct SWire {
+                    #[doc = " The errno value of the error, if it has one."]
+                    pub code: Option<i32>,
+                    #[doc = " The message of the error."]
+                    pub message: String,
+                    #[doc = " The record of the source of the error, if it has one."]
+                    pub chain: Option<Box<SWire>>,
+                }
+
+                impl SWire {
+                    fn from_source(error: &(dyn ::std::error::Error + 'static)) -> Self {
+                        SWire {
+                            code: None,
+                            message: error.to_string(),
+                            chain: error.source().map(|source| Box::new(Self::from_source(source))),
+                        }
+                    }
+                }
+
+                #[automatically_derived]
+                #[allow(unused_qualifications, clippy::all)]
+                impl ::std::fmt::Display for SWire {
+                    fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+                        f.write_str(&self.message)
+                    }
+                }
+
+                #[automatically_derived]
+                #[allow(unused_qualifications, clippy::all)]
+                impl ::std::error::Error for SWire {
+                    fn source(&self) -> ::std::option::Option<&(dyn ::std::error::Error + 'static)> {
+                        self.chain.as_deref().map(|chain| chain as &(dyn ::std::error::Error + 'static))
+                    }
+                }
+
+                impl S {
+                    #[doc = " Convert the error and its chain of sources into a serializable record."]
+                    pub fn to_wire(&self) -> SWire {
+                        SWire {
+                            code: Some(self.to_errno()),
+                            message: self.to_string(),
+                            chain: ::std::error::Error::source(self).map(|source| Box::new(SWire::from_source(source))),
+                        }
+                    }
+
+                    #[doc = " Reconstruct a received record as an opaque error, which still provides the messages of the whole chain."]
+                    pub fn from_wire(wire: SWire) -> Box<dyn ::std::error::Error + Send + Sync> {
+                        Box::new(wire)
+                    }
+                }
+            }
+        )
+    }
+}
@@ -0,0 +1,433 @@
+use std::fmt::Formatter;
+
+use quote::quote;
+use syn::{Ident, Index, ItemEnum, Member, Token, Type, Variant, WherePredicate};
+use syn::__private::{Span, TokenStream2};
+use syn::parse::{Parse, ParseStream, Parser};
+use syn::punctuated::Punctuated;
+
+use crate::common::{cfg_attributes, from_source_fields, single_source_field, tokens_to_readable_string};
+use crate::enum_error::VariantWithParams;
+use crate::impl_display::bounds::source_bounds;
+use crate::impl_wrap_map::WrapMapError::*;
+use crate::impl_includes::included_errors;
+use crate::parameters::{FORWARD, INCLUDES, MESSAGE, OTHER, Parameters, WRAP_MAP};
+
+/// The variants of an enum listed in WRAP_MAP, like 'wrap_map = "std::io::Error => Io, ParseIntError => Parse"'.
+///
+/// Every listed variant wraps exactly one source error. It gets a From implementation for the source type
+/// and returns the source in std::error::Error::source. If it has no message of its own, it displays the
+/// message of the source and returns the source of the source instead, so the message is not repeated in the chain.
+///
+/// The variants added for the errors listed in INCLUDES (see impl_includes) are part of the map as well.
+///
+/// The map also holds the variants marked with FORWARD, which wrap a single value as well. They display the
+/// message of the value and return its source in std::error::Error::source, so the value itself is skipped in
+/// the chain of sources. Unlike listed variants, they get no From implementation.
+///
+/// Finally, the map holds the variant marked with OTHER, a catch-all for unexpected errors, which usually holds a
+/// 'Box<dyn std::error::Error + Send + Sync>'. It gets a From implementation for the boxed error and a method 'wrap'
+/// for everything which converts into it and returns the boxed error in std::error::Error::source. Like the listed variants,
+/// it displays the message of the boxed error and returns its source if it has no message of its own.
+pub struct WrapMap<'a> {
+    item_enum: &'a ItemEnum,
+    entries: Vec<(&'a Variant, Member)>,
+    forwarded: Vec<(&'a Variant, Member, Span)>,
+    other: Option<(&'a Variant, Member)>,
+    /// The variants with a message of their own, which do not display the message of their source.
+    with_message: Vec<&'a Ident>,
+    span: Span,
+}
+
+impl<'a> WrapMap<'a> {
+    /// Parse WRAP_MAP and INCLUDES from the given enum parameters and FORWARD and OTHER from the given variant parameters.
+    /// The map is empty if none of these parameters is set.
+    ///
+    /// Fails if the map is not a comma separated list of 'Type => Variant' pairs, a variant does not exist,
+    /// is listed twice or its single source field does not have the listed type. Also fails if a variant marked
+    /// with FORWARD does not have exactly one field, has a message or is listed in the map, or if the variant marked
+    /// with OTHER is not the only one, has not exactly one field or is forwarded or listed as well.
+    pub fn parse(item_enum: &'a ItemEnum, enum_parameters: &Parameters, variants_with_parameters: &[VariantWithParams<'a>]) -> Result<Self, WrapMapError> {
+        let mut pairs = vec![];
+        let mut span = item_enum.ident.span();
+
+        if let (Some(map), Some(map_span)) = (enum_parameters.string_for_name(WRAP_MAP), enum_parameters.span_for_name(WRAP_MAP)) {
+            pairs.extend(Punctuated::<WrapPair, Token![,]>::parse_terminated
+                .parse_str(map)
+                .map_err(|_| InvalidWrapMap(map.to_string(), map_span))?);
+            span = map_span;
+        }
+
+        if let Some(includes_span) = enum_parameters.span_for_name(INCLUDES) {
+            // the list was already validated when the variants were added
+            let included_errors = included_errors(enum_parameters).unwrap_or_default();
+            pairs.extend(included_errors.into_iter().map(|(ty, variant)| WrapPair { ty, variant }));
+            span = includes_span;
+        }
+
+        let mut entries: Vec<(&Variant, Member)> = vec![];
+        for pair in pairs {
+            let variant = item_enum.variants
+                .iter()
+                .find(|v| v.ident == pair.variant)
+                .ok_or_else(|| UnknownVariant(item_enum.ident.clone(), pair.variant.clone(), span))?;
+
+            if entries.iter().any(|(v, _)| v.ident == variant.ident) {
+                return Err(DuplicateVariant(variant.ident.clone(), span));
+            }
+
+            let (index, source) = match from_source_fields(&variant.fields)[..] {
+                [source] => (variant.fields.iter().position(|f| std::ptr::eq(f, source)).unwrap(), source),
+                _ => return Err(NotExactlyOneField(variant.ident.clone(), span))
+            };
+
+            let listed_type = tokens_to_readable_string(&pair.ty);
+            let field_type = tokens_to_readable_string(&source.ty);
+            if listed_type != field_type {
+                return Err(TypeMismatch(variant.ident.clone(), listed_type, field_type, span));
+            }
+
+            let member = match &source.ident {
+                Some(ident) => Member::Named(ident.clone()),
+                None => Member::Unnamed(Index::from(index))
+            };
+            entries.push((variant, member))
+        }
+
+        let forwarded = Self::parse_forwarded(&entries, variants_with_parameters)?;
+        let other = Self::parse_other(&entries, &forwarded, variants_with_parameters)?;
+        let with_message = variants_with_parameters
+            .iter()
+            .filter(|(_, parameters)| parameters.as_ref().map(|p| p.has_parameter(MESSAGE)).unwrap_or(false))
+            .map(|(v, _)| &v.ident)
+            .collect();
+
+        Ok(WrapMap { item_enum, entries, forwarded, other, with_message, span })
+    }
+
+    /// Return the variant marked with OTHER, if any, together with the member of its field.
+    fn parse_other(entries: &[(&'a Variant, Member)], forwarded: &[(&'a Variant, Member, Span)], variants_with_parameters: &[VariantWithParams<'a>]) -> Result<Option<(&'a Variant, Member)>, WrapMapError> {
+        let mut other = None;
+        for (variant, parameters) in variants_with_parameters {
+            let span = match parameters {
+                Some(p) if p.bool_for_name(OTHER) => p.span_for_name(OTHER).unwrap(),
+                _ => continue
+            };
+
+            if other.is_some() {
+                return Err(DuplicateOther(variant.ident.clone(), span));
+            }
+
+            if entries.iter().any(|(v, _)| v.ident == variant.ident) || forwarded.iter().any(|(v, _, _)| v.ident == variant.ident) {
+                return Err(OtherAndWrapped(variant.ident.clone(), span));
+            }
+
+            let field = match variant.fields.iter().collect::<Vec<_>>()[..] {
+                [field] => field,
+                _ => return Err(OtherNotExactlyOneField(variant.ident.clone(), span))
+            };
+            let member = match &field.ident {
+                Some(ident) => Member::Named(ident.clone()),
+                None => Member::Unnamed(Index::from(0))
+            };
+            other = Some((*variant, member))
+        }
+
+        Ok(other)
+    }
+
+    /// Return the variants marked with FORWARD, together with the member of their single field and the span of the parameter.
+    fn parse_forwarded(entries: &[(&'a Variant, Member)], variants_with_parameters: &[VariantWithParams<'a>]) -> Result<Vec<(&'a Variant, Member, Span)>, WrapMapError> {
+        let mut forwarded = vec![];
+        for (variant, parameters) in variants_with_parameters {
+            let parameters = match parameters {
+                Some(p) if p.bool_for_name(FORWARD) => p,
+                _ => continue
+            };
+            let span = parameters.span_for_name(FORWARD).unwrap();
+
+            if parameters.has_parameter(MESSAGE) {
+                return Err(ForwardWithMessage(variant.ident.clone(), span));
+            }
+
+            if entries.iter().any(|(v, _)| v.ident == variant.ident) {
+                return Err(ForwardAndListed(variant.ident.clone(), span));
+            }
+
+            let (member, _) = single_source_field(&variant.fields).ok_or_else(|| ForwardNotExactlyOneField(variant.ident.clone(), span))?;
+            forwarded.push((*variant, member, span))
+        }
+
+        Ok(forwarded)
+    }
+
+    /// Return all listed variants.
+    pub fn variants(&self) -> Vec<&'a Variant> {
+        self.entries.iter().map(|(v, _)| *v).collect()
+    }
+
+    /// Return all variants marked with FORWARD.
+    pub fn forwarded_variants(&self) -> Vec<&'a Variant> {
+        self.forwarded.iter().map(|(v, _, _)| *v).collect()
+    }
+
+    /// Return the variant marked with OTHER, if any.
+    pub fn other_variant(&self) -> Option<&'a Variant> {
+        self.other.as_ref().map(|(v, _)| *v)
+    }
+
+    /// Return the message of the given variant if it is listed or marked with FORWARD or OTHER, which just displays the wrapped value.
+    pub fn message_for(&self, variant: &Variant) -> Option<(String, Span)> {
+        let listed = self.entries
+            .iter()
+            .find(|(v, _)| v.ident == variant.ident)
+            .map(|(_, member)| (member, self.span));
+        let forwarded = || self.forwarded
+            .iter()
+            .find(|(v, _, _)| v.ident == variant.ident)
+            .map(|(_, member, span)| (member, *span));
+        let other = || self.other
+            .as_ref()
+            .filter(|(v, _)| v.ident == variant.ident)
+            .map(|(_, member)| (member, variant.ident.span()));
+
+        listed.or_else(forwarded).or_else(other).map(|(member, span)| match member {
+            Member::Named(ident) => (format!("{{{}}}", ident), span),
+            Member::Unnamed(index) => (format!("{{_{}}}", index.index), span)
+        })
+    }
+
+    /// Return the message template of the given variant: its own message, the one of the wrapped source if it is listed
+    /// or marked (see message_for) or the one of the enum. Returns None if there is no message.
+    pub fn template_for(&self, variant: &Variant, variant_parameters: Option<&Parameters>, enum_parameters: &Parameters) -> Option<String> {
+        variant_parameters
+            .and_then(|p| p.string_for_name(MESSAGE))
+            .map(str::to_string)
+            .or_else(|| self.message_for(variant).map(|(message, _)| message))
+            .or_else(|| enum_parameters.string_for_name(MESSAGE).map(str::to_string))
+    }
+
+    /// Check if any variant is listed or marked with FORWARD or OTHER, so the enum can have a source.
+    pub fn has_source(&self) -> bool {
+        !self.entries.is_empty() || !self.forwarded.is_empty() || self.other.is_some()
+    }
+
+    /// Return the bounds the wrapped values of the listed and forwarded variants and the variant marked with OTHER
+    /// require if their type is generic (see bounds::source_bounds).
+    pub fn source_bounds(&self, root: &TokenStream2) -> Vec<WherePredicate> {
+        let members = self.entries
+            .iter()
+            .map(|(v, member)| (*v, member))
+            .chain(self.forwarded.iter().map(|(v, member, _)| (*v, member)))
+            .chain(self.other.iter().map(|(v, member)| (*v, member)));
+        let sources = members.filter_map(|(v, member)| v.fields
+            .iter()
+            .enumerate()
+            .find(|(i, f)| match (member, &f.ident) {
+                (Member::Named(name), Some(ident)) => name == ident,
+                (Member::Unnamed(index), None) => index.index as usize == *i,
+                _ => false
+            })
+            .map(|(_, f)| f));
+
+        source_bounds(sources, &self.item_enum.generics, root)
+    }
+
+    /// Create the method std::error::Error::source, which returns the wrapped source of the listed variants
+    /// and of the variant marked with OTHER, and the source of the wrapped value of the variants marked with FORWARD.
+    /// Listed variants and the variant marked with OTHER without a message of their own already display the message
+    /// of the wrapped source, so they return its source like the forwarded variants.
+    /// If no variant is listed or marked, the default implementation is kept.
+    pub fn implement_source(&self, root: &TokenStream2) -> TokenStream2 {
+        if !self.has_source() {
+            return quote! {};
+        }
+
+        let ident = &self.item_enum.ident;
+        let match_arms = self.entries
+            .iter()
+            .map(|(v, member)| {
+                let variant_ident = &v.ident;
+                let cfg = cfg_attributes(v);
+                match self.has_own_message(v) {
+                    true => quote! {#cfg #ident::#variant_ident { #member: e, .. } => #root::option::Option::Some(e)},
+                    false => quote! {#cfg #ident::#variant_ident { #member: e, .. } => #root::error::Error::source(e)}
+                }
+            })
+            .chain(self.forwarded.iter().map(|(v, member, _)| {
+                let variant_ident = &v.ident;
+                let cfg = cfg_attributes(v);
+                quote! {#cfg #ident::#variant_ident { #member: e, .. } => #root::error::Error::source(e)}
+            }))
+            .chain(self.other.iter().map(|(v, member)| {
+                let variant_ident = &v.ident;
+                let cfg = cfg_attributes(v);
+                match self.has_own_message(v) {
+                    true => quote! {#cfg #ident::#variant_ident { #member: e, .. } => #root::option::Option::Some(&**e)},
+                    false => quote! {#cfg #ident::#variant_ident { #member: e, .. } => #root::error::Error::source(&**e)}
+                }
+            }))
+            .collect::<Vec<_>>();
+        let default_match_arm = match match_arms.len() == self.item_enum.variants.len() {
+            true => quote! {},
+            false => quote! {_ => #root::option::Option::None}
+        };
+
+        quote! {
+            fn source(&self) -> #root::option::Option<&(dyn #root::error::Error + 'static)> {
+                match self {
+                    #(#match_arms,)*
+                    #default_match_arm
+                }
+            }
+        }
+    }
+
+    /// Check if the given variant has a message of its own instead of the one of its wrapped source.
+    fn has_own_message(&self, variant: &Variant) -> bool {
+        self.with_message.iter().any(|ident| **ident == variant.ident)
+    }
+
+    /// Create the method 'wrap', which creates the variant marked with OTHER from everything which converts into
+    /// the type of its field. If no variant is marked, return an empty token stream.
+    pub fn implement_wrap(&self, root: &TokenStream2) -> TokenStream2 {
+        let (variant, member) = match &self.other {
+            Some(other) => other,
+            None => return quote! {}
+        };
+
+        let ident = &self.item_enum.ident;
+        let variant_ident = &variant.ident;
+        let ty = &variant.fields.iter().next().unwrap().ty;
+        let doc = format!(" Wrap an unexpected error as '{}::{}', like 'result.map_err({}::wrap)?'.", ident, variant_ident, ident);
+        let (impl_generics, type_generics, where_clause) = self.item_enum.generics.split_for_impl();
+
+        quote! {
+            impl #impl_generics #ident #type_generics #where_clause {
+                #[doc = #doc]
+                pub fn wrap<__T: #root::convert::Into<#ty>>(error: __T) -> Self {
+                    #ident::#variant_ident { #member: #root::convert::Into::into(error) }
+                }
+            }
+        }
+    }
+
+    /// Create the method 'into_source', which returns the wrapped source of the listed variants as boxed error
+    /// and None for all other variants. If no variant is listed, return an empty token stream.
+    pub fn implement_into_source(&self, root: &TokenStream2) -> TokenStream2 {
+        if self.entries.is_empty() {
+            return quote! {};
+        }
+
+        let ident = &self.item_enum.ident;
+        let match_arms = self.entries
+            .iter()
+            .map(|(v, member)| {
+                let variant_ident = &v.ident;
+                let cfg = cfg_attributes(v);
+                quote! {#cfg #ident::#variant_ident { #member: e, .. } => Some(Box::new(e))}
+            })
+            .collect::<Vec<_>>();
+        let default_match_arm = match self.entries.len() == self.item_enum.variants.len() {
+            true => quote! {},
+            false => quote! {_ => None}
+        };
+
+        quote! {
+            #[doc = " Return the wrapped source of this error, if its variant wraps one."]
+            pub fn into_source(self) -> Option<Box<dyn #root::error::Error + Send + Sync>> {
+                match self {
+                    #(#match_arms,)*
+                    #default_match_arm
+                }
+            }
+        }
+    }
+}
+
+/// A single 'Type => Variant' pair of WRAP_MAP.
+struct WrapPair {
+    ty: Type,
+    variant: Ident,
+}
+
+impl Parse for WrapPair {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let ty = input.parse()?;
+        input.parse::<Token![=>]>()?;
+        let variant = input.parse()?;
+        Ok(WrapPair { ty, variant })
+    }
+}
+
+/// Error that might occur when WRAP_MAP is parsed.
+#[derive(Debug)]
+pub enum WrapMapError {
+    /// The map is not a comma separated list of 'Type => Variant' pairs.
+    InvalidWrapMap(String, Span),
+    /// A listed variant does not exist in the enum.
+    UnknownVariant(Ident, Ident, Span),
+    /// A variant was listed more than once.
+    DuplicateVariant(Ident, Span),
+    /// A listed variant does not have exactly one field which could be converted from.
+    NotExactlyOneField(Ident, Span),
+    /// The listed type does not match the type of the field of the variant.
+    TypeMismatch(Ident, String, String, Span),
+    /// A variant marked with FORWARD does not have exactly one field.
+    ForwardNotExactlyOneField(Ident, Span),
+    /// A variant marked with FORWARD has a message of its own.
+    ForwardWithMessage(Ident, Span),
+    /// A variant marked with FORWARD is listed in WRAP_MAP as well.
+    ForwardAndListed(Ident, Span),
+    /// More than one variant was marked with OTHER. Contains the second one.
+    DuplicateOther(Ident, Span),
+    /// The variant marked with OTHER does not have exactly one field.
+    OtherNotExactlyOneField(Ident, Span),
+    /// The variant marked with OTHER is marked with FORWARD or listed in WRAP_MAP as well.
+    OtherAndWrapped(Ident, Span),
+}
+
+impl WrapMapError {
+    /// Return the span of the code which caused this error.
+    pub fn span(&self) -> Span {
+        match self {
+            InvalidWrapMap(_, span) => *span,
+            UnknownVariant(_, _, span) => *span,
+            DuplicateVariant(_, span) => *span,
+            NotExactlyOneField(_, span) => *span,
+            TypeMismatch(_, _, _, span) => *span,
+            ForwardNotExactlyOneField(_, span) => *span,
+            ForwardWithMessage(_, span) => *span,
+            ForwardAndListed(_, span) => *span,
+            DuplicateOther(_, span) => *span,
+            OtherNotExactlyOneField(_, span) => *span,
+            OtherAndWrapped(_, span) => *span
+        }
+    }
+}
+
+impl std::error::Error for WrapMapError {}
+
+impl std::fmt::Display for WrapMapError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            InvalidWrapMap(map, _) => write!(f, "'{}' is not a comma separated list of 'Type => Variant' pairs.", map),
+            UnknownVariant(ident, variant, _) => write!(f, "The enum '{}' has no variant '{}' listed in '{}'.", ident, variant, WRAP_MAP),
+            DuplicateVariant(variant, _) => write!(f, "The variant '{}' is listed more than once in '{}'.", variant, WRAP_MAP),
+            NotExactlyOneField(variant, _) => write!(f, "The variant '{}' is listed in '{}', but does not have exactly one field.", variant, WRAP_MAP),
+            TypeMismatch(variant, listed_type, field_type, _) => write!(f, "The type '{}' listed in '{}' does not match the type '{}' of the field of variant '{}'.", listed_type, WRAP_MAP, field_type, variant),
+            ForwardNotExactlyOneField(variant, _) => write!(f, "The variant '{}' is marked with '{}', but does not have exactly one field which is not skipped.", variant, FORWARD),
+            ForwardWithMessage(variant, _) => write!(f, "The variant '{}' is marked with '{}', so it displays the wrapped value and cannot have a message.", variant, FORWARD),
+            ForwardAndListed(variant, _) => write!(f, "The variant '{}' is marked with '{}', so it cannot be listed in '{}'.", variant, FORWARD, WRAP_MAP),
+            DuplicateOther(variant, _) => write!(f, "Only one variant can be marked with '{}', but '{}' is the second one.", OTHER, variant),
+            OtherNotExactlyOneField(variant, _) => write!(f, "The variant '{}' is marked with '{}', so it must have exactly one field which holds the error.", variant, OTHER),
+            OtherAndWrapped(variant, _) => write!(f, "The variant '{}' is marked with '{}', so it cannot be marked with '{}' or listed in '{}'.", variant, OTHER, FORWARD, WRAP_MAP)
+        }
+    }
+}
+
+impl From<WrapMapError> for syn::Error {
+    fn from(e: WrapMapError) -> Self {
+        syn::Error::new(e.span(), e)
+    }
+}
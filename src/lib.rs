@@ -2,14 +2,58 @@ extern crate syn;
 
 use proc_macro::TokenStream;
 
-use syn::{AttributeArgs, ItemEnum, ItemStruct, parse, parse_macro_input};
+use quote::quote;
+use syn::{AttributeArgs, Error, Expr, ItemEnum, ItemMod, ItemStruct, parse, parse_macro_input};
+use syn::__private::TokenStream2;
 
 mod struct_error;
 mod enum_error;
+mod error_module;
+mod error_enum;
+mod bail;
 mod parameters;
 mod common;
+mod docs;
+mod debug_output;
 mod impl_from;
 mod impl_display;
+mod impl_debug;
+mod impl_assert_macro;
+mod impl_matcher_macro;
+mod impl_constructors;
+mod impl_predicates;
+mod impl_name;
+mod impl_getters;
+mod impl_into_inner;
+mod impl_kind;
+mod impl_variant_structs;
+mod impl_catalog;
+mod impl_aggregate;
+mod impl_chain;
+mod impl_boxed;
+mod impl_exit_code;
+mod impl_severity;
+mod impl_category;
+mod impl_errno;
+mod impl_io_kind;
+mod impl_retry_after;
+mod impl_retryable;
+mod impl_into_response;
+mod impl_grpc_status;
+mod impl_ffi;
+mod impl_serialize;
+mod impl_json;
+mod impl_wire;
+mod impl_wrap_map;
+mod impl_includes;
+mod impl_transparent;
+mod impl_duplicate_messages;
+mod impl_message_docs;
+mod impl_strict;
+mod validator;
+mod template;
+#[cfg(feature = "placeholder_resolvers")]
+mod placeholder_resolvers;
 #[cfg(test)]
 mod test_helper;
 
@@ -19,10 +63,31 @@ mod test_helper;
 /// - std::error::Error is implemented
 /// - std::fmt::Debug is implemented
 /// - std::fmt::Display is implemented
+///
 /// Also, it's possible to generate implementations for std::convert::From for structs and enum variants with a single field.
 ///
 /// The attribute is applicable for struct definitions and enum definitions. If it's used anywhere else,
-/// the attribute panics. The only exception are enum variants if the enum definition holds the error attribute.
+/// a compile error is emitted. The only exception are enum variants if the enum definition holds the error attribute.
+///
+/// Unknown parameters and parameters used at the wrong location (like 'no_from' on a struct)
+/// result in a compile error.
+///
+/// The parameters of an item, variant or field can be split into several error attributes, which are merged, like
+/// ```text
+/// #[error(message = "the request failed")]
+/// #[error(impl_from, exit_code = 3, severity = "warning")]
+/// ```
+/// Setting the same parameter in more than one of these attributes results in a compile error.
+///
+/// The attribute 'err' is a shorter alias of 'error' and can be used everywhere instead of it, like '#[err(message = "...")]'.
+/// Crates which re-export the macro under their own name, like 'pub use error_generator::error as app_error;', list the name
+/// in the environment variable 'ERROR_GENERATOR_ATTRIBUTES' (comma separated, like "app_error, api_error"), so
+/// '#[app_error(...)]' is recognized on variants and fields as well. Setting the variable in the [env] section of
+/// '.cargo/config.toml' shares the names with all crates of a workspace.
+///
+/// The variable is read while the macro is expanded, so it applies to every crate of a build alike. Every expansion contains
+/// an 'option_env!("ERROR_GENERATOR_ATTRIBUTES")', so cargo rebuilds the crate when the variable changes. Tools which
+/// expand macros on their own, like rust-analyzer, might need a restart to pick up the change.
 ///
 /// # structs
 /// ## general usage
@@ -52,6 +117,7 @@ mod test_helper;
 /// }
 ///
 /// impl std::convert::From for MyError {
+///     #[track_caller]
 ///     fn from(val: usize) {
 ///         MyError { faulty_value: val }
 ///     }
@@ -60,6 +126,25 @@ mod test_helper;
 ///
 /// Generics, lifetimes and any other attributes will be preserved.
 ///
+/// Fields with a generic type which are formatted by the message don't need bounds on the struct, the bound of
+/// the formatting trait is added to the Display implementation only, like
+/// ```text
+/// #[error(message = "invalid value {self.0}, expected {self.1:?}")]
+/// struct InvalidValue<T>(T, Vec<T>);
+///
+/// impl<T> std::fmt::Display for InvalidValue<T> where T: std::fmt::Display, Vec<T>: std::fmt::Debug { ... }
+/// ```
+/// std::error::Error is then implemented for every InvalidValue<T> which implements Debug and Display. Only placeholders
+/// which format a field directly are considered, so bounds for expressions like "{self.0.len()}" must be set on the struct.
+///
+/// Debug is not derived again if the struct already derives it in an attribute below the error attribute,
+/// like '#[derive(Clone, Debug)]'. Derives above the error attribute are not visible to the macro.
+///
+/// The generated trait implementations are marked with '#[automatically_derived]' and allow the lints
+/// 'unused_qualifications' and 'clippy::all', so crates with '#![deny(warnings)]' or strict clippy configs
+/// don't fail on generated code. Generated Debug implementations only allow the lints, as the compiler
+/// ignores field reads in automatically derived Debug implementations when it looks for unused fields.
+///
 /// ## the parameter 'message'
 /// The parameter 'message' is of type String. It is optional.
 ///
@@ -78,9 +163,67 @@ mod test_helper;
 /// ```
 ///
 /// The braces itself will be lost, so expressions with multiple statements must be contained in
-/// an additional pair, like "Complex: {{let mut i = 0; i += 1; i}}".
+/// an additional pair, like "Complex: { {let mut i = 0; i += 1; i} }".
+///
+/// Literal braces can be written by doubling them, like in the format! macro. For example
+/// ```text
+///  message = "{{\"line\": {self.line}}}"
+/// ```
+/// will print something like '{"line": 42}'. A single closing brace outside of an expression is an error.
+///
+/// An expression can be followed by a format spec after a colon, like in the format! macro. For example
+/// ```text
+///  message = "{self.name:>8}: {self.ratio:.2} ({self.flags:#x})"
+/// ```
+/// pads the name to 8 characters, prints the ratio with 2 decimals and the flags as hexadecimal number.
+/// Widths and precisions must be numbers. Colons of paths (like in "{std::u8::MAX}"), strings and char literals
+/// are no separators. Errors in messages report the byte offset of the problem, like "at byte 12".
+///
+/// Positional placeholders refer to the unnamed fields of tuple structs, so "{1}" is the same as "{self.1}".
+/// An index without such a field is a compile error.
+///
+/// The same expression can be used any number of times, it is only passed to write! once, like
+/// ```text
+///  message = "{self.0} ({0:>4})"
+/// ```
+/// for 'struct InvalidLine(usize)' becomes 'write!(f, "{0} ({0:>4})", self.0)'.
+///
+/// To access the error struct itself and its fields/methods, you can just use 'self'. Accessing a field which does not
+/// exist (like '{self.lien}' for the field 'line') or a field without 'self' (like '{line}') is reported as compile error
+/// at the message.
 ///
-/// To access the error struct itself and its fields/methods, you can just use 'self'.
+/// Some names are placeholders for metadata of your crate, which are resolved at compile time:
+/// - '{crate_name}': the name of the crate (CARGO_PKG_NAME)
+/// - '{crate_version}': the version of the crate (CARGO_PKG_VERSION)
+/// - '{build_profile}': "debug" if debug assertions are enabled, "release" otherwise
+///
+/// They take precedence over fields or variables with the same name.
+///
+/// ## the parameter 'message_const'
+/// The parameter 'message_const' is of type String. It is optional and can be used on structs and enum variants instead of 'message'.
+///
+/// It contains the path to a const or static '&str' with the message, so all messages can be kept in one reviewed module, like
+/// ```text
+/// mod messages {
+///     pub const READ_FAILED: &str = "reading {path} failed";
+/// }
+///
+/// #[error(message_const = "messages::READ_FAILED")]
+/// struct ReadError {
+///     path: String
+/// }
+/// ```
+/// The value of the const is unknown when the attribute expands, so its placeholders are filled at runtime. They can only name
+/// fields (like '{path}', '{self.path}', '{e.path}' or '{0}'), not arbitrary expressions. Every field which is not skipped must implement
+/// Display, fields with 'debug_placeholder' are formatted with Debug and redacted fields are displayed as '[REDACTED]'.
+/// Unknown placeholders are written unchanged.
+///
+/// ## the parameter 'fmt'
+/// The parameter 'fmt' is of type String. It is optional and can be used on structs and enums.
+///
+/// It contains the path to a function with the signature 'fn(&MyError, &mut std::fmt::Formatter<'_>) -> std::fmt::Result',
+/// which is called by the generated Display implementation. Use it if the message requires logic which doesn't fit into
+/// a single expression. 'fmt' cannot be combined with 'message' (also not with messages on enum variants).
 ///
 /// ## the parameter 'impl_from'
 /// The parameter 'impl_from' is of type bool. It is optional.
@@ -89,7 +232,119 @@ mod test_helper;
 ///
 /// When 'impl_from' is true, an implementation of From for the type of
 /// the single field of the struct will be created. If the struct has more
-/// or less than one field, the attribute panics.
+/// or less than one field, a compile error is emitted.
+///
+/// Setting 'impl_from = "into"' creates a generic implementation for every type which implements
+/// Into for the field type instead, so a field of type String can also be created from a &str with
+/// the ?-operator. This also works on enums and variants, but only if From is implemented for a single variant,
+/// as the generic implementation would conflict with every other one.
+///
+/// ## the parameter 'trace'
+/// The parameter 'trace' is of type bool. It is optional and can be used on structs and enums with a message.
+///
+/// When set, every generated From implementation and constructor (see 'constructors') emits a 'tracing::error!' event
+/// when the error is created. The event contains the message and the Debug representation of the error (so all field
+/// values). With 'trace = "warn"', 'tracing::warn!' is used instead. This way, errors are recorded where they occur and
+/// not only when they are reported. The using crate needs to depend on tracing.
+///
+/// ## the parameter 'log'
+/// The parameter 'log' is of type String. It is optional and can be used on structs, enums and enum variants with a message.
+///
+/// It contains a level of the log crate ("error", "warn", "info", "debug" or "trace"). When set, every generated From
+/// implementation and constructor (see 'constructors') logs the message with the macro of this level, like 'log::warn!',
+/// when the error is created. The level of a variant replaces the one of the enum. The using crate needs to depend on log.
+///
+/// ## the parameter 'label'
+/// The parameter 'label' is of type String. It is optional and can only be used on fields of structs.
+///
+/// If any field has a label, Debug is implemented manually instead of derived. The labels are printed
+/// next to the field names, like
+/// ```text
+/// #[error(message = "could not read {self.path}")]
+/// struct ReadError {
+///     #[error(label = "config file")]
+///     path: String
+/// }
+/// ```
+/// will be printed as 'ReadError { path ("config file"): "/etc/app" }'. Display is not affected.
+///
+/// ## the parameter 'skip'
+/// The parameter 'skip' is of type bool. It is optional and can be used on fields of structs and enum variants.
+///
+/// Skipped fields (like internal state or large buffers) are ignored by the generated code:
+/// - they are not bound in the match patterns of the Display implementation, so messages cannot use them
+/// - they are not counted when checking if From can be implemented. The From implementation
+///   initializes them with Default::default()
+///
+/// ## the parameter 'debug_placeholder'
+/// The parameter 'debug_placeholder' is of type bool. It is optional and can be used on fields of structs and enum variants.
+///
+/// Messages display their expressions with std::fmt::Display. A field marked with 'debug_placeholder' is displayed with
+/// std::fmt::Debug instead, when the message uses it directly (like '{self.payload}', '{payload}' or '{_0}'). This way,
+/// fields of foreign types which only implement Debug can appear in messages, like
+/// ```text
+/// #[error(message = "invalid payload {self.payload}")]
+/// struct InvalidPayload {
+///     #[error(debug_placeholder)]
+///     payload: Vec<u8>
+/// }
+/// ```
+///
+/// ## the parameter 'redact'
+/// The parameter 'redact' is of type bool. It is optional and can be used on fields of structs and enum variants.
+///
+/// Every expression in a message which uses a field marked with 'redact' is displayed as '[REDACTED]', so passwords,
+/// tokens or personal data don't leak into logs, like
+/// ```text
+/// #[error(message = "login of {self.user} with {self.password} failed")]
+/// struct LoginFailed {
+///     user: String,
+///     #[error(redact)]
+///     password: String
+/// }
+/// ```
+/// displays 'login of admin with [REDACTED] failed'. Method calls on the field (like '{self.password.len()}') are redacted as well.
+///
+/// ## the parameter 'redact_debug'
+/// The parameter 'redact_debug' is of type bool. It is optional and can be used on structs and enums.
+///
+/// By default, Debug still prints the values of redacted fields. With 'redact_debug', Debug is implemented manually
+/// and prints '[REDACTED]' for them, like 'LoginFailed { user: "admin", password: [REDACTED] }'.
+/// Note that 'runtime_verbosity' appends the Debug representation to the message, so use both parameters together.
+///
+/// ## the parameter 'debug'
+/// The parameter 'debug' is of type String. It is optional and can be used on structs and enums.
+///
+/// It changes how Debug is provided:
+/// - "off": Debug is neither derived nor implemented, so it can be written by hand
+/// - "display": Debug forwards to Display, so 'unwrap()' panics with the message instead of the fields. Requires a message.
+///
+/// Labels and 'redact_debug' have no effect on Debug then.
+///
+/// ## the parameter 'message_docs'
+/// The parameter 'message_docs' is of type bool. It is optional and can be used on structs and enums.
+///
+/// It adds the message template to the doc comment of the struct, so rustdoc shows what the error is displayed as, like
+/// ```text
+/// Displayed as: `could not read {self.path}`
+/// ```
+/// A struct with 'message_docs' requires a message, otherwise a compile error is emitted.
+///
+/// ## transparent structs
+/// Structs marked with '#[repr(transparent)]' wrap a single error, like
+/// ```text
+/// #[error]
+/// #[repr(transparent)]
+/// pub struct FfiError(std::io::Error);
+/// ```
+/// The repr is kept and no field is added, so the struct has the layout of the wrapped error and can be used in FFI.
+/// From is implemented for the wrapped error (also without 'impl_from') and the method 'into_inner' returns it again.
+/// Without a message, the struct displays the message of the wrapped error and returns its source, otherwise the wrapped
+/// error is the source. If the struct has not exactly one field which is not skipped, a compile error is emitted.
+///
+/// The wrapped error can be generic. Its bounds are added to the generated implementations only, so
+/// 'pub struct FfiError<E>(E);' gets 'where E: std::error::Error + 'static' on std::error::Error and Display.
+/// The same holds for generic values of variants listed in 'wrap_map' or marked with 'forward' or 'other'.
 ///
 /// # enums
 /// ## general usage
@@ -136,6 +391,18 @@ mod test_helper;
 /// }
 /// ```
 ///
+/// Variants can be conditional. Their cfg attributes are added to everything generated for them (like match arms,
+/// From implementations and constructors), so nothing refers to a variant which is not compiled, like
+/// ```text
+/// #[error(message = "Something went wrong")]
+/// enum MyError {
+///     #[cfg(feature = "io")]
+///     #[error(message = "I/O failed", impl_from)]
+///     Io(std::io::Error),
+///     Unknown
+/// }
+/// ```
+///
 /// ## the parameter 'message'
 /// The parameter 'message' is of type String. It is optional and can be used on enums and their variants.
 ///
@@ -154,6 +421,33 @@ mod test_helper;
 ///
 /// If the variant uses named fields, all names will be usable just by their name. When using tuple like variants,
 /// you can use the index of the field beginning with an underscore, like '_0' (as numbers aren't valid identifiers).
+/// Just like in struct messages, the fields can also be accessed on 'e', like '{e.path.display()}' or '{e.0 + 1}'.
+/// Positional placeholders like '{1}' are the unnamed field with this index, and an index without such a field is a compile error.
+/// Skipped fields are not bound, so accessing them is reported as a compile error.
+///
+/// Names which are no field of the variant (like '{lien}' for the field 'line') are reported as compile error at the message,
+/// which lists the valid names. Names starting with an uppercase letter (like consts), 'self', 'e' and the formatter 'f'
+/// are not checked.
+///
+/// ## the parameters 'prefix' and 'suffix'
+/// The parameters 'prefix' and 'suffix' are of type String. They are optional and can be used on enums.
+///
+/// They are written before and after the message of every variant, so a consistent prefix doesn't need to be repeated in every message, like
+/// ```text
+/// #[error(prefix = "config: ")]
+/// enum ConfigError {
+///     #[error(message = "the key {_0} is missing")]
+///     MissingKey(String)
+/// }
+/// ```
+/// which displays 'config: the key port is missing'. They require a message (or a custom formatter), otherwise a compile error is emitted.
+///
+/// ## the parameter 'message_docs'
+/// The parameter 'message_docs' is of type bool. It is optional and can be used on enums.
+///
+/// It adds the message template (with 'prefix' and 'suffix') to the doc comment of every variant with a message.
+/// The message of the enum is added to the doc comment of the enum, as it is displayed by all variants without a message of their own.
+/// At least the enum or one variant requires a message, otherwise a compile error is emitted.
 ///
 ///
 /// ## the parameter 'impl_from'
@@ -168,22 +462,947 @@ mod test_helper;
 /// When used on enums, error_generator tries to create From implementations for every variant of the enum.
 /// This only works if every variant has only one field.
 ///
+/// Variants which should not be converted from can be excluded with the parameter 'no_from', like
+/// ```text
+/// #[error(impl_from)]
+/// enum MyError {
+///     Io(std::io::Error),
+///     #[error(no_from)]
+///     Position(usize, usize)
+/// }
+/// ```
+///
 /// ### on variants
 /// When used on a variant, error_generator tries to implement From for the type of the variants single field.
 /// This fails if the variant has more or less than one field.
 ///
+/// Two variants must not be converted from the same type, unless they have different '#[cfg]' attributes, like
+/// 'cfg(feature = "a")' and 'cfg(not(feature = "a"))'. Whether such attributes exclude each other is not checked,
+/// so if both variants are compiled, the compiler reports the conflicting implementations.
+///
+/// ### generic variants
+/// From can't be implemented for a variant whose field has a type parameter as type, like 'Value(T)'.
+/// Instead, the concrete types to convert from can be listed with the variant parameter 'impl_from_for', like
+/// 'impl_from_for = "String, u8"'. This creates 'impl From<String> for MyError<String>' and so on.
+///
+/// ### multiple fields
+/// Structs and variants with multiple fields can still implement From if the source field is marked with the
+/// field parameter 'from'. All other fields are initialized with Default::default() or with the expression
+/// of their field parameter 'default', like
+/// ```text
+/// #[error(impl_from)]
+/// struct ParseError {
+///     #[error(from)]
+///     source: std::num::ParseIntError,
+///     line: usize,
+///     #[error(default = "String::from(\"unknown\")")]
+///     file: String
+/// }
+/// ```
+///
+/// ### caller locations
+/// Every generated From implementation is marked with #[track_caller]. A field of type
+/// '&'static std::panic::Location<'static>' is never converted from, but filled with the location
+/// of the code which called From (like a ?-operator), unless it has the field parameter 'default', like
+/// ```text
+/// #[error(message = "failed to read the config at {location}", impl_from)]
+/// struct ConfigError {
+///     source: std::io::Error,
+///     location: &'static std::panic::Location<'static>
+/// }
+/// ```
+/// The path of the type needs the segment 'panic' (like 'std::panic::Location' or 'panic::Location'), so other types
+/// named Location are treated like any other field.
+///
+/// ### phantom markers
+/// A field of type 'std::marker::PhantomData<T>' is never converted from, but filled with 'PhantomData', so generic
+/// errors with phantom markers can implement From for their single other field, like
+/// ```text
+/// #[error(message = "the value {self.0} is out of range", impl_from)]
+/// struct OutOfRange<Unit>(f64, std::marker::PhantomData<Unit>);
+/// ```
+/// Phantom markers are also not bound in the match arms of generated Display implementations and cannot be used in messages.
+///
+/// ### source types
+/// A field of type '&'static str' marked with the field parameter 'source_type' is never converted from, but filled
+/// with the name of the type the error was converted from. With 'impl_from = "into"', this is the name of the type
+/// which was passed to From. Together with a caller location, this tells which conversion created an error, like
+/// ```text
+/// #[error(message = "I/O failed")]
+/// enum MyError {
+///     #[error(impl_from)]
+///     Io {
+///         source: std::io::Error,
+///         #[error(source_type)]
+///         converted_from: &'static str,
+///         location: &'static std::panic::Location<'static>
+///     }
+/// }
+/// ```
+///
+/// ## the parameter 'deprecated_from'
+/// The parameter 'deprecated_from' is of type String. It is optional and can only be used on enum variants with a From implementation.
+///
+/// It contains a note, like 'deprecated_from = "use ParseFailed instead"', which is added to the documentation of the
+/// From implementation of the variant under the heading 'Deprecated'. This steers users away from legacy conversions
+/// without breaking them. The compiler does not allow '#[deprecated]' on trait implementations, so using the conversion
+/// does not emit a warning.
+///
+/// ## the parameter 'generate_from_tests'
+/// The parameter 'generate_from_tests' is of type bool. It is optional and can only be used on enums without generics.
+///
+/// When set, a '#[cfg(test)]' module is created, which contains a test for every From implementation of a variant.
+/// The test converts the default value of the source type and checks that the expected variant is created, which
+/// catches mixed up variants when the enum is refactored. Source types without a Default implementation are not checked.
+///
+/// The enum must be defined on module level, as the test module can't see items defined inside of functions.
+///
+/// ## the parameter 'no_from_docs'
+/// The parameter 'no_from_docs' is of type bool. It is optional and can be used on structs and enums.
+///
+/// Every generated From implementation is documented with an example of how the ?-operator can
+/// be used with it. Setting 'no_from_docs' omits this documentation.
+///
+/// ## the parameter 'auto_from_types'
+/// The parameter 'auto_from_types' is of type String. It is optional and can only be used on enums.
+///
+/// It contains a comma separated list of types, like "std::io::Error, std::num::ParseIntError". For every
+/// listed type, the variant with a single field of exactly this type is searched and std::convert::From is implemented
+/// for it. If a type matches no variant or multiple variants, a compile error is emitted.
+///
+/// ## the parameter 'wrap_map'
+/// The parameter 'wrap_map' is of type String. It is optional and can only be used on enums.
+///
+/// It contains a comma separated list of 'Type => Variant' pairs, which declares in one place which variant
+/// wraps which source error, like
+/// ```text
+/// #[error(wrap_map = "std::io::Error => Io, std::num::ParseIntError => Parse")]
+/// enum MyError {
+///     Io(std::io::Error),
+///     Parse { source: std::num::ParseIntError },
+///     #[error(message = "the config is empty")]
+///     Empty
+/// }
+/// ```
+/// Every listed variant must have a single field of exactly the listed type. For these variants
+/// - std::convert::From is implemented for the listed type,
+/// - std::error::Error::source returns the wrapped error
+/// - and, unless the variant has a message of its own, Display shows the message of the wrapped error.
+///
+/// Variants without a message of their own already show the message of the wrapped error, so std::error::Error::source returns
+/// the source of the wrapped error instead, like for the variants marked with 'forward'. This way, the message is not repeated
+/// in the chain of sources, like with 'format!("{:#}", error)'.
+///
+/// ## the parameter 'includes'
+/// The parameter 'includes' is of type String. It is optional and can only be used on enums.
+///
+/// It contains a comma separated list of error types, which are composed into the enum. For every
+/// listed type, a variant named after the last segment of its path is added, like
+/// ```text
+/// #[error(includes = "ParseError, io::IoErrorWrapper")]
+/// enum AppError {
+///     #[error(message = "the config is empty")]
+///     Empty
+/// }
+/// ```
+/// which adds the variants 'ParseError(ParseError)' and 'IoErrorWrapper(io::IoErrorWrapper)'. These variants behave
+/// like the ones listed in 'wrap_map': std::convert::From is implemented for the included type, std::error::Error::source
+/// returns the included error (or its source, see above) and Display shows its message. The enum must not already have a variant with the name
+/// of an included type.
+///
+/// ## the parameter 'forward'
+/// The parameter 'forward' is of type bool. It is optional and can only be used on enum variants.
+///
+/// A variant marked with 'forward' must have a single field, usually another detailed error, like
+/// ```text
+/// #[error(message = "the config could not be loaded")]
+/// enum ConfigError {
+///     #[error(forward)]
+///     Validation(ValidationError),
+///     Missing
+/// }
+/// ```
+/// Instead of a flat message of its own, the variant delegates to the wrapped value:
+/// - Display shows the message of the wrapped value
+/// - and std::error::Error::source returns the source of the wrapped value, so the chain of sources continues with it.
+///
+/// Unlike variants listed in 'wrap_map', no From implementation is created and the variant cannot have a message.
+///
+/// ## the parameter 'from_io'
+/// The parameter 'from_io' is of type String. It is optional and can only be used on enum variants.
+///
+/// It contains a comma separated list of std::io::ErrorKind names, or '_' for all kinds which are not listed. Instead of
+/// converting every std::io::Error into the same variant, the From implementation then matches on the kind, like
+/// ```text
+/// #[error(message = "the file could not be read")]
+/// enum ReadError {
+///     #[error(from_io = "NotFound")]
+///     NotFound(std::io::Error),
+///     #[error(from_io = "PermissionDenied, ReadOnlyFilesystem")]
+///     Denied { source: std::io::Error },
+///     #[error(from_io = "_")]
+///     Other(std::io::Error)
+/// }
+/// ```
+/// Every variant with 'from_io' must have a single field of type std::io::Error. Exactly one variant must be marked with '_'
+/// and every kind can only be listed once. No other variant may convert from std::io::Error.
+///
+/// ## the parameter 'other'
+/// The parameter 'other' is of type bool. It is optional and can only be used on enum variants.
+///
+/// It marks a single variant as catch-all for unexpected errors, which must have exactly one field, usually a boxed error, like
+/// ```text
+/// #[error]
+/// enum AppError {
+///     #[error(message = "the name is empty")]
+///     EmptyName,
+///     #[error(other)]
+///     Other(Box<dyn std::error::Error + Send + Sync>)
+/// }
+///
+/// fn parse(value: &str) -> Result<usize, AppError> {
+///     Ok(value.parse::<usize>().map_err(AppError::wrap)?)
+/// }
+/// ```
+/// For this variant
+/// - std::convert::From is implemented for the type of the field, so boxed errors can be propagated with the ?-operator,
+/// - the method 'wrap' creates the variant from everything which converts into the type of the field, like any error or a string,
+/// - std::error::Error::source returns the wrapped error
+/// - and, unless the variant has a message of its own, Display shows the message of the wrapped error. Like for the variants
+///   listed in 'wrap_map', std::error::Error::source returns the source of the wrapped error then.
+///
+/// A blanket From implementation for every error is not possible, as it would conflict with 'impl From<T> for T' of the
+/// standard library, so other errors are converted with 'wrap'.
+///
+/// ## the parameter 'assert_macro'
+/// The parameter 'assert_macro' is of type bool. It is optional and can only be used on enums.
+///
+/// When set, a companion macro 'assert_handles_all_<enum name in snake case>!' is created. It takes
+/// a list of variant names and fails to compile if the list does not cover every variant, like
+/// ```text
+/// #[error(assert_macro)]
+/// enum MyError {
+///     NotFound,
+///     Forbidden
+/// }
+///
+/// // fails to compile as soon as a new variant is added to MyError
+/// assert_handles_all_my_error!(NotFound, Forbidden);
+/// ```
+/// This helps to keep external dispatch tables in sync with the enum.
+///
+/// ## the parameter 'constructors'
+/// The parameter 'constructors' is of type bool. It is optional and can only be used on enums.
+///
+/// When set, a constructor function named after every variant in snake case is created, which takes
+/// the fields of the variant as arguments, like
+/// ```text
+/// #[error(message = "the config is invalid", constructors)]
+/// enum MyError {
+///     ParsingFailed { line: usize },
+///     Missing
+/// }
+///
+/// // MyError::ParsingFailed { line: 3 }
+/// let error = MyError::parsing_failed(3);
+/// ```
+/// Names which are keywords become raw identifiers, like 'MyError::r#type()' for the variant 'Type'. Variants named
+/// after keywords which cannot be raw identifiers (like 'Crate' or 'Super') are a compile error.
+///
+/// Caller locations (see the section about caller locations) are no arguments, but filled with the location
+/// of the code which called the constructor. Phantom markers are no arguments either, they are filled with 'PhantomData'.
+///
+/// Variants with arguments also get a lazy constructor '<variant name in snake case>_with', which takes closures computing
+/// the fields and returns a closure creating the variant. Expensive values are only computed on the error path, like
+/// ```text
+/// let line = lines.next().ok_or_else(MyError::parsing_failed_with(|| count_lines(&file)))?;
+/// ```
+/// Errors created with constructors are recorded like the ones created with From (see 'trace' and 'log').
+///
+/// ## the parameter 'predicates'
+/// The parameter 'predicates' is of type bool. It is optional and can only be used on enums.
+///
+/// When set, a predicate method named after every variant in snake case is created, like
+/// ```text
+/// #[error(message = "the config is invalid", predicates)]
+/// enum MyError {
+///     ParsingFailed { line: usize },
+///     Missing
+/// }
+///
+/// if error.is_parsing_failed() {
+///     // ...
+/// }
+/// ```
+///
+/// ## the parameter 'variant_name'
+/// The parameter 'variant_name' is of type bool. It is optional and can only be used on enums.
+///
+/// When set, a method 'name(&self) -> &'static str' is created, which returns the name of the variant, like
+/// "ReadFileFailed". Metrics and logs can use it as a stable label without formatting the whole message.
+///
+/// ## the parameter 'non_exhaustive'
+/// The parameter 'non_exhaustive' is of type bool. It is optional and can only be used on enums.
+///
+/// When set, the enum is marked with '#[non_exhaustive]', so variants can be added without breaking other crates.
+/// For enums marked like this (by the parameter or by hand), the match of Display gets a wildcard arm if every variant
+/// has a message and the method 'name' (see 'variant_name') returns "unknown" for variants it does not know, like
+/// ```text
+/// #[error(variant_name, non_exhaustive)]
+/// pub enum RequestError {
+///     #[error(message = "the request timed out")]
+///     Timeout
+/// }
+/// ```
+/// The wildcard arms are unreachable in the crate of the enum, so the lint 'unreachable_patterns' is allowed on them.
+///
+/// ## the parameter 'kind'
+/// The parameter 'kind' is of type bool. It is optional and can only be used on enums.
+///
+/// When set, the enum '<enum name>Kind' is created, which has the same variants as the error but no fields. It is returned by
+/// the method 'kind(&self)' and can also be created with From from a reference to the error. Its method 'message_template'
+/// returns the message of the variant with the placeholders unresolved, like
+/// ```text
+/// #[error(message = "something went wrong", kind)]
+/// enum MyError {
+///     #[error(message = "could not read {path}")]
+///     ReadFailed { path: String },
+///     Unknown
+/// }
+///
+/// // Some("could not read {path}")
+/// let template = MyErrorKind::from(&error).message_template();
+/// ```
+/// Metrics and documentation can work on the kind without holding or formatting the error.
+///
+/// ## the parameter 'variant_structs'
+/// The parameter 'variant_structs' is of type bool. It is optional and can only be used on enums, which are not generic.
+///
+/// When set, a struct named '<enum name><variant name>' is created for every variant, which has the same fields as the variant.
+/// The struct implements Debug (and the derives from 'helper_derive') and converts into the variant with std::convert::From, like
+/// ```text
+/// #[error(message = "the config is invalid", variant_structs)]
+/// pub enum ConfigError {
+///     Missing { key: String },
+///     OutOfRange(usize, usize)
+/// }
+///
+/// // generated: pub struct ConfigErrorMissing { pub key: String } and pub struct ConfigErrorOutOfRange(pub usize, pub usize);
+/// let error = ConfigError::from(ConfigErrorMissing { key: "port".to_string() });
+/// ```
+/// This way, the fields of a variant can be passed around and constructed as a type of their own.
+/// The structs and their fields have the visibility of the enum.
+///
+/// ## the parameter 'getters'
+/// The parameter 'getters' is of type bool. It is optional and can only be used on enums.
+///
+/// When set, an accessor method is created for every named field of the variants. It returns the field if the variant
+/// of the error has it and None otherwise, like
+/// ```text
+/// #[error(message = "the config is invalid", getters)]
+/// enum MyError {
+///     ParsingFailed { line: usize },
+///     Missing
+/// }
+///
+/// // Some(&3)
+/// let line = MyError::ParsingFailed { line: 3 }.line();
+/// ```
+/// Fields with the same name must have the same type in every variant, even in variants under mutually exclusive
+/// '#[cfg]' attributes, as the accessor has a single return type. A field must not have the name of a method
+/// created by another parameter (like 'name' with 'variant_name' or 'path' for a variant 'Path' with 'constructors'),
+/// as the method would be defined twice. This is a compile error at the field.
+///
+/// ## the parameter 'into_inner'
+/// The parameter 'into_inner' is of type bool. It is optional and can be used on structs and enums.
+///
+/// When set on a struct, a method 'into_inner(self)' is created, which returns the single field of the struct (skipped fields
+/// are not counted). If the struct has more or less than one field, a compile error is emitted.
+///
+/// When set on an enum, every variant with a single field gets a method 'into_<variant name in snake case>(self)', which returns
+/// the field or the error itself if it is another variant. If variants are listed in 'wrap_map', the method 'into_source(self)'
+/// returns their wrapped source as 'Box<dyn std::error::Error + Send + Sync>', like
+/// ```text
+/// #[error(wrap_map = "std::io::Error => Io", into_inner)]
+/// enum MyError {
+///     Io(std::io::Error),
+///     #[error(message = "the request timed out")]
+///     Timeout { seconds: u64 }
+/// }
+///
+/// // Ok(5)
+/// let seconds = MyError::Timeout { seconds: 5 }.into_timeout();
+/// // None
+/// let source = MyError::Timeout { seconds: 5 }.into_source();
+/// ```
+///
+/// ## the parameter 'matcher_macro'
+/// The parameter 'matcher_macro' is of type bool. It is optional and can only be used on enums.
+///
+/// When set, a companion macro 'assert_<enum name in snake case>!' is created for tests. It takes a Result and the
+/// pattern of a variant, panics if the result is Ok or holds another variant and binds the fields of the pattern, like
+/// ```text
+/// #[error(message = "request failed", matcher_macro)]
+/// enum MyError {
+///     NotFound { id: usize },
+///     Forbidden
+/// }
+///
+/// assert_my_error!(find_user(42), NotFound { id });
+/// assert_eq!(id, 42);
+/// ```
+/// The failure messages contain the message of the unexpected error (or its Debug representation if the enum has no message).
+///
+/// ## the method 'io_kind'
+/// If any variant has a field of type std::io::Error (written as 'std::io::Error' or 'io::Error'), a method
+/// 'io_kind(&self) -> Option<std::io::ErrorKind>' is created. It returns the kind of the wrapped error if the enum
+/// is such a variant and None otherwise, which saves the double match in retry or fallback logic.
+///
+/// ## the constant 'FROM_TYPES'
+/// Whenever std::convert::From implementations are generated for a struct or enum, an associated constant
+/// 'FROM_TYPES' is created, which lists the names of all types the error can be converted from, like
+/// ```text
+/// impl MyError {
+///     pub const FROM_TYPES: &'static [&'static str] = &["std::io::Error"];
+/// }
+/// ```
+///
+/// # runtime verbosity
+/// ## the parameter 'runtime_verbosity'
+/// The parameter 'runtime_verbosity' is of type bool. It is optional and can be used on structs and enums with a Display implementation.
+///
+/// When set, the methods 'MyError::set_error_verbosity(bool)' and 'MyError::error_verbosity()' are created. While the verbosity
+/// is enabled, the message is followed by the Debug representation of the error, like 'could not read /etc/app (ReadError { path: "/etc/app" })'.
+/// The flag is shared by the whole process, so operators can switch to verbose errors (for example from a config value
+/// or environment variable) without recompiling.
+///
+/// ## alternate formatting
+/// If an error has a source (like a variant listed in 'wrap_map'), the alternate flag appends the whole chain of sources to the message,
+/// so 'format!("{:#}", error)' prints something like 'loading the config failed: caused by: the port is invalid: caused by: invalid digit found in string'.
+/// Without the flag, only the message is printed. This way, the call site chooses between a rich and a terse message.
+/// Transparent structs without a message pass the flag to the wrapped error instead.
+///
+/// ## the parameter 'chain'
+/// The parameter 'chain' is of type bool. It is optional and can be used on structs and enums with a Display implementation.
+///
+/// When set, the method 'chain()' returns an iterator over the error and all of its sources, beginning with the error itself.
+/// The method 'render_chain()' renders their messages with every source on its own indented line, like
+/// ```text
+/// loading the config failed
+///   caused by: the port is invalid
+///     caused by: invalid digit found in string
+/// ```
+///
+/// # memoized messages
+/// ## the parameter 'memoize_display'
+/// The parameter 'memoize_display' is of type bool. It is optional and can be used on structs and enums with a Display implementation.
+///
+/// When set, a wrapper 'Memoized<name of the error>' is created, which renders the message only once and caches it.
+/// This helps if message expressions are expensive and the error is formatted repeatedly, like by logging layers.
+/// The wrapper is created with 'error.memoized()' and gives access to the error with 'error()' and 'into_inner()'.
+///
+/// # aggregated errors
+/// ## the parameter 'aggregate'
+/// The parameter 'aggregate' is of type bool. It is optional and can be used on structs and enums with a Display implementation.
+///
+/// When set, a wrapper '<name of the error>s' (like 'MyErrors' for 'MyError') is created, which holds multiple errors.
+/// It implements From<Vec<MyError>>, FromIterator, Extend, IntoIterator, Display (the messages separated by "; ") and Error.
+/// The method 'into_result' returns Ok if no error was collected, so validation pipelines can collect straight into the wrapper:
+/// ```text
+/// fn validate(users: &[User]) -> Result<(), MyErrors> {
+///     users.iter().filter_map(|u| check(u).err()).collect::<MyErrors>().into_result()
+/// }
+/// ```
+///
+/// ## the parameter 'helper_derive'
+/// The parameter 'helper_derive' is of type String. It is optional and can be used on structs and enums.
+///
+/// It contains a comma separated list of derives, like "Clone, serde::Serialize", which are added to the helper types
+/// generated next to the error (the memoize and aggregation wrappers and the variant structs). This way, they can be used like the other types of your application.
+///
+/// ## the parameter 'derives'
+/// The parameter 'derives' is of type String. It is optional and can be used on structs and enums.
+///
+/// It contains a comma separated list of derives for the error itself, like "Clone, PartialEq, Eq, Hash", which are added
+/// to the derive of Debug, like
+/// ```text
+/// #[error(message = "not found", derives = "Clone, PartialEq")]
+/// struct NotFound;
+///
+/// // generated: #[derive(Debug, Clone, PartialEq)] struct NotFound;
+/// ```
+/// Debug is always derived or implemented by the macro, so it must not be listed.
+///
+/// # error catalog
+/// ## the parameter 'catalog'
+/// The parameter 'catalog' is of type bool. It is optional and can only be used on enums.
+///
+/// When set, an associated constant 'CATALOG' is created, which describes every variant with an entry of the
+/// created type '<name of the enum>Info', like
+/// ```text
+/// #[error(message = "the command failed", catalog, exit_code = 1, severity = "error")]
+/// enum MyError {
+///     #[error(message = "invalid usage of {_0}", exit_code = 2, severity = "warning")]
+///     Usage(String),
+///     #[error(message = "the service is busy", retry_after = 5)]
+///     Busy,
+///     Other
+/// }
+///
+/// // MyErrorInfo { name: "Usage", message: Some("invalid usage of {_0}"), status: None, exit_code: Some(2), errno: None,
+/// //     severity: Some("warning"), retryable: false, retry_after: None }
+/// let usage = MyError::CATALOG[0];
+/// // MyErrorInfo { name: "Busy", message: Some("the service is busy"), status: None, exit_code: Some(1), errno: None,
+/// //     severity: Some("error"), retryable: true, retry_after: Some(Duration::from_secs(5)) }
+/// let busy = MyError::CATALOG[1];
+/// ```
+/// An entry holds the name of the variant, its message with the placeholders unresolved, its HTTP status (see 'status'),
+/// its exit code (see 'exit_code'), its errno value (see 'errno'), its severity (see 'severity'), if it is retryable
+/// (see 'retryable') and its retry delay (see 'retry_after'). A value is the one of the variant, the one of the enum
+/// or None if neither has one. The severity is the value of the parameter, like "warning", and not the variant of the
+/// severity enum, which is only created if some severity is set. 'retryable' is resolved like 'is_retryable', so it is
+/// false if neither the variant nor the enum is retryable. This way, a single constant lists all errors and their
+/// behavior for documentation tooling, support teams, runbooks and alerting rules.
+///
+/// # exit codes
+/// ## the parameter 'exit_code'
+/// The parameter 'exit_code' is an integer between 0 and 255. It is optional and can be used on structs, enums and enum variants.
+///
+/// When set, a method 'exit_code(&self) -> std::process::ExitCode' is created, so a binary can exit with a
+/// meaningful status, like
+/// ```text
+/// fn main() -> std::process::ExitCode {
+///     match run() {
+///         Ok(_) => std::process::ExitCode::SUCCESS,
+///         Err(e) => e.exit_code()
+///     }
+/// }
+/// ```
+/// Variants without an exit code use the exit code of the enum or ExitCode::FAILURE if the enum has none.
+///
+/// ## the parameter 'errno'
+/// The parameter 'errno' is an integer or the name of an errno constant, like "EINVAL". It is optional and can be used
+/// on structs, enums and enum variants.
+///
+/// When set, a method 'to_errno(&self) -> i32' (with the alias 'errno(&self) -> i32') and the conversions 'From<MyError> for i32'
+/// and 'From<MyError> for std::io::Error' (not with 'no_std') are created, so wrappers around POSIX APIs, like FUSE filesystems,
+/// can propagate conventional errno values.
+/// Unlike 'exit_code', there is no default: variants without an errno value use the one of the enum and it's a compile error
+/// if the enum has none.
+///
+/// A name is resolved as constant of the libc crate (like 'libc::EINVAL'), so the value is right on every platform.
+/// The using crate needs to depend on libc then.
+///
+/// # severity
+/// ## the parameter 'severity'
+/// The parameter 'severity' is one of "warning", "error" or "fatal". It is optional and can be used on structs, enums and enum variants.
+///
+/// When set, the enum 'MyErrorSeverity' with the variants Warning, Error and Fatal and a method
+/// 'severity(&self) -> MyErrorSeverity' are created, so monitoring code can route errors by their severity, like
+/// ```text
+/// #[error(message = "the job failed")]
+/// enum MyError {
+///     #[error(severity = "warning")]
+///     Retrying,
+///     #[error(severity = "fatal")]
+///     Corrupted
+/// }
+///
+/// match e.severity() {
+///     MyErrorSeverity::Fatal => page_on_call(&e),
+///     severity if severity >= MyErrorSeverity::Error => alert(&e),
+///     _ => log(&e)
+/// }
+/// ```
+/// The severities are ordered from Warning to Fatal. Variants without a severity use the severity of the enum or Error if the enum has none.
+/// With 'catalog', the entries list the severity of each variant as well.
+///
+/// ## the parameter 'category'
+/// The parameter 'category' is a string, like "io" or "validation". It is optional and can be used on structs, enums and enum variants.
+///
+/// When set, a method 'category(&self) -> &'static str' is created, so error dashboards can group errors by coarse categories
+/// defined next to their messages. Variants without a category use the one of the enum and it's a compile error if the enum has none.
+///
+/// # HTTP responses
+/// ## the parameter 'status'
+/// The parameter 'status' is an integer between 100 and 999. It is optional, can be used on structs, enums and enum variants
+/// and requires the feature 'axum'.
+///
+/// When set, axum::response::IntoResponse is implemented for the error. The response has the given status and the
+/// Display message as body, so the error needs a message. Variants without a status use the status of the enum or
+/// 500 (Internal Server Error) if the enum has none. The using crate needs to depend on axum.
+///
+/// ```text
+/// #[error(message = "request failed")]
+/// enum ApiError {
+///     #[error(status = 404, message = "user {_0} not found")]
+///     UserNotFound(String),
+///     Database(DbError)
+/// }
+/// ```
+///
+/// ## the parameter 'retry_after'
+/// The parameter 'retry_after' is an integer, the number of seconds after which a failed operation should be retried.
+/// It is optional and can be used on structs, enums and enum variants.
+///
+/// When set, a method 'retry_after(&self) -> Option<std::time::Duration>' is created. Variants without a value use the
+/// one of the enum or None if the enum has none. If the error also has a 'status', its response gets a Retry-After header
+/// whenever 'retry_after' returns a duration.
+///
+/// ## the parameter 'retryable'
+/// The parameter 'retryable' is of type bool. It is optional and can be used on structs, enums and enum variants.
+///
+/// When set, a method 'is_retryable(&self) -> bool' is created, so retry middleware can decide without knowing the
+/// concrete variants. Variants without a value are retryable if they have a 'retry_after', otherwise they use the value
+/// of the enum or false if the enum has none, like
+/// ```text
+/// #[error(message = "the request failed")]
+/// enum MyError {
+///     #[error(retryable)]
+///     Timeout,
+///     #[error(retry_after = 5)]
+///     Busy,
+///     InvalidRequest
+/// }
+/// ```
+/// where Timeout and Busy are retryable, but InvalidRequest is not.
+///
+/// # gRPC status
+/// ## the parameter 'grpc_code'
+/// The parameter 'grpc_code' is of type String. It is optional, can be used on structs, enums and enum variants
+/// and requires the feature 'tonic'.
+///
+/// It contains the name of a tonic::Code in snake_case, like "not_found". When set, From<MyError> is implemented
+/// for tonic::Status, which carries the code and the Display message, so the error needs a message. Variants without
+/// a code use the code of the enum or 'internal' if the enum has none. This way, gRPC handlers can just use the ?-operator.
+/// The using crate needs to depend on tonic.
+///
+/// # C APIs
+/// ## the parameter 'ffi'
+/// The parameter 'ffi' is of type bool. It is optional, can only be used on enums without generics
+/// and requires the feature 'ffi'.
+///
+/// When set, the glue to expose the error through a C API is created, like for
+/// ```text
+/// #[error(message = "the operation failed", ffi)]
+/// pub enum MyError {
+///     NotFound,
+///     Other(u8)
+/// }
+/// ```
+/// - the enum 'MyErrorCode' with 'repr(C)', which mirrors the variants with the codes 1, 2, 3... (0 is left for success)
+/// - the function 'my_error_code(&MyError) -> u32', which returns the code of an error
+/// - the function 'my_error_message(&MyError, buf, len) -> usize', which writes the message of an error as nul-terminated
+///   string into a buffer. Like snprintf, it returns the length of the whole message, so a C caller can retry with a bigger buffer.
+///
+/// The functions are 'extern "C"' and not mangled, so a header generator like cbindgen picks them up. The error needs a message.
+///
+/// # serialization
+/// ## the parameter 'serialize'
+/// The parameter 'serialize' is of type bool. It is optional, can be used on structs and enums with a message
+/// and requires the feature 'serde'.
+///
+/// When set, serde::Serialize is implemented for the error. It is serialized as a struct with the fields
+/// - 'error': the name of the struct or enum variant
+/// - 'code': the errno value of the error, only if the parameter 'errno' is used
+/// - 'message': the Display message
+///
+/// The using crate needs to depend on serde.
+///
+/// ## the parameter 'json'
+/// The parameter 'json' is of type bool. It is optional and can be used on structs and enums with a message.
+///
+/// When set, a method 'to_json(&self) -> String' is created, which renders the error as a single line JSON object with
+/// the same fields as the Serialize implementation, like '{"error": "NotFound", "message": "user 42 not found"}'.
+/// This is meant for log pipelines which only capture lines of stdout and does not require serde.
+///
+/// ## the parameter 'wire'
+/// The parameter 'wire' is of type bool. It is optional, can be used on structs and enums with a message
+/// and requires the feature 'serde'.
+///
+/// When set, a serializable record '<name of the error>Wire' is created, which holds the errno value as 'code'
+/// (only if the parameter 'errno' is used), the 'message' and the record of the source as 'chain'. The error gets
+/// the methods
+/// - 'to_wire(&self)', which converts the error and its whole chain of sources into the record
+/// - 'from_wire(wire)', which turns a received record into a Box<dyn std::error::Error + Send + Sync>
+///
+/// The record implements std::error::Error itself, so the reconstructed chain can be inspected with 'source' like the
+/// original one. The using crate needs to depend on serde with the feature 'derive'.
+///
+/// # no_std
+/// ## the parameter 'no_std'
+/// The parameter 'no_std' is of type bool. It is optional and can be used on structs and enums.
+///
+/// When set, the generated implementations use 'core' instead of 'std', like 'core::error::Error'
+/// and 'core::fmt::Display', so the error can be used in crates without the standard library.
+///
+/// ## the parameter 'alloc'
+/// The parameter 'alloc' is of type bool. It is optional and can be used on structs and enums.
+///
+/// When set, a method 'boxed' is created, which turns the error into an 'alloc::boxed::Box<dyn core::error::Error>'.
+/// The using crate needs to declare 'extern crate alloc;'. Converting errors into the boxed trait object
+/// with the ?-operator already works with alloc alone, so no additional From implementation is generated.
+///
+/// ## the parameter 'dyn_error'
+/// The parameter 'dyn_error' is of type bool. It is optional and can be used on structs and enums.
+///
+/// When set, the method 'boxed(self)' turns the error into a 'Box<dyn std::error::Error + Send + Sync + 'static>' and
+/// the method 'as_dyn_error(&self)' borrows it as '&(dyn std::error::Error + 'static)', which saves the conversions
+/// when errors are handed to APIs working with trait objects. Together with 'alloc', 'boxed' returns an 'alloc::boxed::Box'.
+/// With 'no_std' but without 'alloc', only 'as_dyn_error' is created.
+///
+/// ## the parameter 'render_into'
+/// The parameter 'render_into' is of type bool. It is optional, can be used on structs and enums with a message
+/// and requires the feature 'embedded'.
+///
+/// When set, the error gets a method 'render_into<const N: usize>(&self, buf: &mut heapless::String<N>)'. It writes the
+/// message into the fixed capacity string without allocating and fails with core::fmt::Error if the message does not fit.
+/// In this case, 'buf' contains the part of the message which was written before the capacity was exceeded, so clear it
+/// before reusing it. The using crate needs to depend on heapless.
+///
+/// ```text
+/// #[error(message = "the port {self.0} is invalid", render_into)]
+/// struct PortError(u16);
+///
+/// let mut buf = heapless::String::<32>::new();
+/// PortError(8080).render_into(&mut buf)?; // buf == "the port 8080 is invalid"
+/// ```
+///
+/// ## the parameter 'defmt'
+/// The parameter 'defmt' is of type bool. It is optional, can be used on structs and enums with a message
+/// and requires the feature 'defmt'.
+///
+/// When set, defmt::Format is implemented with the same message templates as Display, so the error can be logged with
+/// defmt on targets where core::fmt is too big. The messages are written with defmt::write, which keeps the
+/// formatting on the host, so every placeholder needs to implement defmt::Format. 'prefix' and 'suffix' are added to the
+/// messages. As defmt needs the messages at compile time, it cannot be used together with 'fmt' and 'message_const'.
+/// The using crate needs to depend on defmt.
+///
+/// # modules
+/// ## general usage
+/// Add the attribute to a module to turn every struct and enum in it into an error, like
+/// ```text
+/// #[error(message = "the storage failed", no_std)]
+/// mod storage_errors {
+///     #[error(message = "could not read {self.path}")]
+///     pub struct ReadFailed {
+///         pub path: String
+///     }
+///
+///     pub enum WriteError {
+///         #[error(message = "the disk is full")]
+///         DiskFull,
+///         Unknown
+///     }
+/// }
+/// ```
+/// The parameters of the module are the defaults of all structs and enums in it. Every item gets the defaults which can be
+/// used on it (so 'assert_macro' only applies to enums), unless it sets the parameter in its own error attribute.
+/// The default message is not added to enums whose variants all have a message of their own.
+/// Other items, like functions and nested modules, are kept as they are. The module needs a body, as the macro cannot see
+/// the items of a module in another file.
+///
+/// # error_enum
+/// ## general usage
+/// The macro 'error_enum' defines an error enum in a compact form, like
+/// ```text
+/// error_enum! {
+///     /// Errors of the config loader.
+///     pub ConfigError: "the config could not be loaded" {
+///         NotFound(String): "missing {0}",
+///         InvalidLine { line: usize }: "invalid line {line}",
+///         Io(std::io::Error) from,
+///         Unknown
+///     }
+/// }
+/// ```
+/// which is the same as
+/// ```text
+/// /// Errors of the config loader.
+/// #[error(message = "the config could not be loaded")]
+/// pub enum ConfigError {
+///     #[error(message = "missing {0}")]
+///     NotFound(String),
+///     #[error(message = "invalid line {line}")]
+///     InvalidLine { line: usize },
+///     #[error(impl_from)]
+///     Io(std::io::Error),
+///     Unknown
+/// }
+/// ```
+/// The message of the enum and of every variant follows after a colon, 'from' after a variant implements From for it.
+/// Further parameters can be set with error attributes on the enum and the variants, which are merged with the generated ones.
+///
+/// # bail and ensure
+/// The macros 'bail' and 'ensure' return errors early, like
+/// ```text
+/// fn line(lines: &[String], line: usize) -> Result<&String, MyError> {
+///     ensure!(line < lines.len(), MyError::InvalidLine { line });
+///     if lines[line].is_empty() {
+///         bail!(MyError::EmptyLine { line })
+///     }
+///     Ok(&lines[line])
+/// }
+/// ```
+/// The error is converted with From into the error type of the function, so an error can be returned from a function
+/// which returns an enum with a variant for it (see 'impl_from'). Both macros also work in no_std crates.
+///
+/// # duplicate messages
+/// ## the feature 'message_registry'
+/// With the feature 'message_registry', every message is recorded in a registry file, which is shared by all crates of
+/// a workspace. When two different errors or variants of the same crate have the same message, a warning is emitted at
+/// the second one, as this usually is a copy-paste error which makes logs ambiguous. Messages without any text besides
+/// placeholders (like "{_0}") are not checked.
+///
+/// The registry is the file in the environment variable 'ERROR_GENERATOR_MESSAGE_REGISTRY' or 'error_generator_messages.tsv'
+/// in the target directory, which is taken from 'CARGO_TARGET_DIR' or derived from 'OUT_DIR'. Without any of them (for
+/// example in a crate without a build script), no messages are recorded. Set 'ERROR_GENERATOR_MESSAGE_REGISTRY' to an
+/// absolute path in '.cargo/config.toml' to use the registry in every crate.
+///
+/// Every expansion replaces the messages which were recorded for the expanded error before, so renamed variants and changed
+/// messages are forgotten. Items are identified by name only, so errors with the same name in different modules are considered
+/// the same. Delete the registry to forget messages of removed or renamed errors.
+///
+/// # unused fields
+/// ## the parameter 'strict'
+/// The parameter 'strict' is of type bool. It is optional and can be used on structs and enums.
+///
+/// When set, a warning is emitted for every message which uses none of the fields of its struct or variant, as such
+/// a message is often stale, like
+/// ```text
+/// #[error(message = "the request failed", strict)]
+/// enum RequestError {
+///     #[error(message = "the request timed out")]
+///     Timeout { secs: u64 }
+/// }
+/// ```
+/// Any use of 'self' or 'e' counts as use of the fields. Skipped fields, caller locations and fields with 'source_type'
+/// don't need to be used. Variants without a message of their own, variants from 'wrap_map' and structs with a source
+/// are not checked. Without 'strict', no warnings are emitted.
+///
+/// # placeholder resolvers
+/// ## the feature 'placeholder_resolvers'
+/// With the feature 'placeholder_resolvers', a placeholder can name a resolver after a colon, which formats its value, like
+/// ```text
+/// #[error(message = "the request of {user:masked} timed out after {secs:duration}")]
+/// struct TimeoutError {
+///     user: String,
+///     secs: u64
+/// }
+/// ```
+/// The resolvers are registered in the environment variable 'ERROR_GENERATOR_RESOLVERS' as comma separated 'name = path' pairs,
+/// like "duration = crate::fmt::duration, masked = crate::fmt::mask". Every resolver is a function which takes a reference
+/// to the value and returns something which implements Display, so "{secs:duration}" becomes 'crate::fmt::duration(&(secs))'.
+/// Setting the variable in the [env] section of '.cargo/config.toml' shares the resolvers with all crates of a workspace.
+/// Using a resolver which is not registered is a compile error.
+/// A format spec can follow the resolver, like "{secs:duration:>8}". Resolver names which are format specs themselves
+/// (like 'e' or 'x') are read as format specs.
+///
+/// # localized messages
+/// ## the parameter 'msg_key'
+/// The parameter 'msg_key' is of type string. It is optional, requires the feature 'i18n' and can be used on structs and enum variants
+/// which have a message.
+///
+/// The message is looked up with the key at runtime, like in a Fluent bundle. If the lookup fails, the inline message is displayed instead, like
+/// ```text
+/// #[error(message = "reading {self.path} failed", msg_key = "errors-read-file")]
+/// struct ReadFileError {
+///     path: String
+/// }
+/// ```
+/// The lookup function is registered in the environment variable 'ERROR_GENERATOR_I18N' as a path, like "crate::i18n::lookup".
+/// It has the signature 'fn(&str, &[(&str, &dyn Display)]) -> Option<String>' and gets the key together with the fields as named arguments
+/// (tuple fields are named by their index). For Fluent, the function can turn the arguments into FluentArgs and format the message of the key:
+/// ```text
+/// pub fn lookup(key: &str, args: &[(&str, &dyn Display)]) -> Option<String> {
+///     let mut fluent_args = FluentArgs::new();
+///     args.iter().for_each(|(name, value)| fluent_args.set(*name, value.to_string()));
+///     BUNDLE.with(|bundle| Some(bundle.format_pattern(bundle.get_message(key)?.value()?, Some(&fluent_args), &mut vec![]).into_owned()))
+/// }
+/// ```
+/// Every field which is not skipped must implement Display, fields with 'debug_placeholder' are formatted with Debug and redacted fields
+/// are passed as '[REDACTED]'.
+///
 /// # Important
-/// error_generator will not check if the expressions in your Display messages are correct OR your chosen items for the From implementation interfere with other code.
+/// error_generator checks the placeholders of your Display messages before generating code: named placeholders must refer
+/// to fields (like '{self.line}' or, in variants, '{line}'), positional placeholders like '{1}' must refer to an unnamed
+/// field which exists and is neither skipped nor a phantom marker, and format specs must be valid. These mistakes are
+/// reported at the message.
+///
+/// Everything else is left to rustc: whether the expressions in your placeholders type check (like method calls or
+/// the formatting traits of the fields) and whether your chosen items for the From implementation interfere with other code.
 /// This might lead to strange compiler errors due to wrong implementations.
+///
+/// To see what was generated, add the parameter 'debug_output' (of type bool, on structs and enums). The generated code is then
+/// printed with indentation while building, like
+/// ```text
+/// note: code generated by error_generator for 'MyError':
+/// #[derive(Debug)]
+/// struct MyError {
+///     faulty_value: usize,
+/// }
+/// ...
+/// ```
 #[proc_macro_attribute]
 pub fn error(attributes: TokenStream, item: TokenStream) -> TokenStream {
-    if let Ok(item_struct) = parse::<ItemStruct>(item.clone()) {
-        return struct_error::implement(parse_macro_input!(attributes as AttributeArgs), item_struct).into();
-    }
+    let result = if let Ok(item_struct) = parse::<ItemStruct>(item.clone()) {
+        struct_error::implement(parse_macro_input!(attributes as AttributeArgs), item_struct)
+    } else if let Ok(item_enum) = parse::<ItemEnum>(item.clone()) {
+        enum_error::implement(parse_macro_input!(attributes as AttributeArgs), item_enum)
+    } else if let Ok(item_mod) = parse::<ItemMod>(item.clone()) {
+        error_module::implement(parse_macro_input!(attributes as AttributeArgs), item_mod)
+    } else {
+        Err(Error::new_spanned(TokenStream2::from(item), "The error attribute is only allowed on structs, enums, enum variants and modules."))
+    };
 
-    if let Ok(item_enum) = parse::<ItemEnum>(item) {
-        return enum_error::implement(parse_macro_input!(attributes as AttributeArgs), item_enum).into();
-    }
+    let tracking = common::track_attribute_names();
+    result
+        .map(|expansion| quote! {#expansion #tracking})
+        .unwrap_or_else(Error::into_compile_error)
+        .into()
+}
 
-    panic!("The error attribute is only allowed on structs, enums and enum variants.")
-}
\ No newline at end of file
+/// Shorter alias of the attribute 'error', which works the same. Variants and fields can be marked with '#[err(...)]' as well.
+#[proc_macro_attribute]
+pub fn err(attributes: TokenStream, item: TokenStream) -> TokenStream {
+    error(attributes, item)
+}
+
+/// Define an error enum in a compact form, like
+/// ```text
+/// error_enum! {
+///     pub MyError: "Something went wrong" {
+///         NotFound(String): "missing {0}",
+///         Io(std::io::Error) from,
+///         Unknown
+///     }
+/// }
+/// ```
+/// See the section 'error_enum' of the attribute 'error' for details.
+#[proc_macro]
+pub fn error_enum(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as error_enum::ErrorEnumInput);
+    error_enum::implement(input).unwrap_or_else(Error::into_compile_error).into()
+}
+
+/// Return the given error from the current function, converted with From into the error type of the function, like
+/// ```text
+/// bail!(MyError::NotFound { path })
+/// ```
+/// which is the same as 'return Err(From::from(MyError::NotFound { path }))'.
+#[proc_macro]
+pub fn bail(input: TokenStream) -> TokenStream {
+    let error = parse_macro_input!(input as Expr);
+    bail::implement_bail(&error).into()
+}
+
+/// Return the given error from the current function (like bail) if the given condition does not hold, like
+/// ```text
+/// ensure!(line < lines.len(), MyError::InvalidLine { line })
+/// ```
+/// The error is only created if the condition does not hold.
+#[proc_macro]
+pub fn ensure(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as bail::EnsureInput);
+    bail::implement_ensure(&input).into()
+}
@@ -1,19 +1,105 @@
 use std::collections::HashMap;
-use std::ops::Deref;
+use std::collections::hash_map::Keys;
+use std::convert::TryFrom;
 
-use syn::{Attribute, AttributeArgs, Lit, Meta, NestedMeta};
+use syn::{Attribute, AttributeArgs, Error, Lit, Meta, NestedMeta};
+use syn::__private::Span;
 use syn::Lit::*;
 use syn::Meta::*;
+use syn::spanned::Spanned;
 
 use crate::common::*;
 
-pub const ERROR_ATTRIBUTE: &'static str = "error";
-pub const MESSAGE: &'static str = "message";
-pub const IMPL_FROM: &'static str = "impl_from";
+pub const ERROR_ATTRIBUTE: &str = "error";
+/// Shorter name of ERROR_ATTRIBUTE, which can be used instead of it everywhere.
+pub const ERROR_ATTRIBUTE_ALIAS: &str = "err";
+pub const MESSAGE: &str = "message";
+pub const IMPL_FROM: &str = "impl_from";
+/// Alternative value of IMPL_FROM, which implements From for every type that converts Into the field type.
+pub const IMPL_FROM_INTO: &str = "into";
+pub const NO_FROM: &str = "no_from";
+pub const ASSERT_MACRO: &str = "assert_macro";
+pub const AUTO_FROM_TYPES: &str = "auto_from_types";
+pub const IMPL_FROM_FOR: &str = "impl_from_for";
+pub const NO_FROM_DOCS: &str = "no_from_docs";
+pub const LABEL: &str = "label";
+pub const FMT: &str = "fmt";
+pub const SKIP: &str = "skip";
+pub const FROM: &str = "from";
+pub const DEFAULT: &str = "default";
+pub const NO_STD: &str = "no_std";
+pub const ALLOC: &str = "alloc";
+pub const EXIT_CODE: &str = "exit_code";
+pub const MEMOIZE_DISPLAY: &str = "memoize_display";
+pub const ERRNO: &str = "errno";
+pub const STATUS: &str = "status";
+pub const HELPER_DERIVE: &str = "helper_derive";
+pub const DERIVES: &str = "derives";
+pub const DEBUG: &str = "debug";
+pub const GRPC_CODE: &str = "grpc_code";
+pub const RUNTIME_VERBOSITY: &str = "runtime_verbosity";
+pub const SERIALIZE: &str = "serialize";
+pub const JSON: &str = "json";
+pub const MATCHER_MACRO: &str = "matcher_macro";
+pub const AGGREGATE: &str = "aggregate";
+pub const TRACE: &str = "trace";
+pub const TRACE_WARN: &str = "warn";
+pub const LOG: &str = "log";
+pub const WRAP_MAP: &str = "wrap_map";
+pub const SOURCE_TYPE: &str = "source_type";
+pub const WIRE: &str = "wire";
+pub const CONSTRUCTORS: &str = "constructors";
+pub const CATALOG: &str = "catalog";
+pub const GENERATE_FROM_TESTS: &str = "generate_from_tests";
+pub const PREDICATES: &str = "predicates";
+pub const RETRY_AFTER: &str = "retry_after";
+pub const VARIANT_NAME: &str = "variant_name";
+pub const GETTERS: &str = "getters";
+pub const DEBUG_PLACEHOLDER: &str = "debug_placeholder";
+pub const INTO_INNER: &str = "into_inner";
+pub const KIND: &str = "kind";
+pub const DYN_ERROR: &str = "dyn_error";
+pub const DEPRECATED_FROM: &str = "deprecated_from";
+pub const INCLUDES: &str = "includes";
+pub const FORWARD: &str = "forward";
+pub const FROM_IO: &str = "from_io";
+pub const FROM_IO_FALLBACK: &str = "_";
+pub const OTHER: &str = "other";
+pub const VARIANT_STRUCTS: &str = "variant_structs";
+pub const REDACT: &str = "redact";
+pub const REDACT_DEBUG: &str = "redact_debug";
+pub const MSG_KEY: &str = "msg_key";
+pub const MESSAGE_CONST: &str = "message_const";
+pub const PREFIX: &str = "prefix";
+pub const SUFFIX: &str = "suffix";
+pub const CHAIN: &str = "chain";
+pub const SEVERITY: &str = "severity";
+pub const CATEGORY: &str = "category";
+pub const RETRYABLE: &str = "retryable";
+pub const FFI: &str = "ffi";
+pub const DEFMT: &str = "defmt";
+pub const MESSAGE_DOCS: &str = "message_docs";
+pub const DEBUG_OUTPUT: &str = "debug_output";
+pub const STRICT: &str = "strict";
+pub const NON_EXHAUSTIVE: &str = "non_exhaustive";
+pub const RENDER_INTO: &str = "render_into";
+
+/// Parameters which only accept string literals.
+const STRING_PARAMETERS: &[&str] = &[MESSAGE, AUTO_FROM_TYPES, LABEL, FMT, DEFAULT, IMPL_FROM_FOR, HELPER_DERIVE, GRPC_CODE, LOG, WRAP_MAP, DEPRECATED_FROM, INCLUDES, FROM_IO, MSG_KEY, MESSAGE_CONST, PREFIX, SUFFIX, SEVERITY, CATEGORY, DERIVES, DEBUG];
+/// Parameters which only accept boolean literals (or no value at all, which means true).
+const BOOL_PARAMETERS: &[&str] = &[IMPL_FROM, NO_FROM, ASSERT_MACRO, NO_FROM_DOCS, SKIP, NO_STD, ALLOC, FROM, MEMOIZE_DISPLAY, RUNTIME_VERBOSITY, SERIALIZE, JSON, MATCHER_MACRO, AGGREGATE, TRACE, SOURCE_TYPE, WIRE, CONSTRUCTORS, CATALOG, GENERATE_FROM_TESTS, PREDICATES, VARIANT_NAME, GETTERS, DEBUG_PLACEHOLDER, INTO_INNER, KIND, DYN_ERROR, FORWARD, OTHER, VARIANT_STRUCTS, REDACT, REDACT_DEBUG, CHAIN, RETRYABLE, FFI, DEFMT, MESSAGE_DOCS, DEBUG_OUTPUT, STRICT, NON_EXHAUSTIVE, RENDER_INTO];
+/// Parameters which only accept unsigned integer literals.
+const INTEGER_PARAMETERS: &[&str] = &[EXIT_CODE, ERRNO, STATUS, RETRY_AFTER];
+/// Integer parameters which also accept any string value as an alternative, like the name of a constant.
+const INTEGER_PARAMETERS_WITH_STRING_ALTERNATIVE: &[&str] = &[ERRNO];
+/// Boolean parameters which also accept some string values as an alternative setting.
+/// Such a string value also enables the parameter.
+const BOOL_PARAMETER_ALTERNATIVES: &[(&str, &str)] = &[(IMPL_FROM, IMPL_FROM_INTO), (TRACE, TRACE_WARN)];
 
 /// Representation of attributes as key value pairs with names as key and primitives as values.
+/// The span of every parameter is kept to point compile errors to the right location.
 pub struct Parameters {
-    values: HashMap<String, LitValue>,
+    values: HashMap<String, (LitValue, Span)>,
 }
 
 impl Parameters {
@@ -23,12 +109,12 @@ impl Parameters {
     /// struct AnError;
     ///
     /// (only list-like attributes like above are valid)
-    pub fn from_attribute(attribute: &Attribute) -> Self {
-        let meta = attribute.parse_meta().unwrap();
+    pub fn from_attribute(attribute: &Attribute) -> syn::Result<Self> {
+        let meta = attribute.parse_meta()?;
 
         match meta {
             syn::Meta::List(list) => Self::from_nested_metas(list.nested),
-            _ => panic!(r#"Expected list-like attribute, like [error(param0 = "foo", param1 = false)]"#)
+            _ => Err(Error::new(attribute.span(), r#"Expected list-like attribute, like #[error(param0 = "foo", param1 = false)]"#))
         }
     }
 
@@ -38,51 +124,108 @@ impl Parameters {
     /// #[error( <-- list-like Attribute
     ///     message = "AHHH", impl_from <-- AttributeArgs
     /// )]
-    pub fn from_attribute_args(args: AttributeArgs) -> Self {
+    pub fn from_attribute_args(args: AttributeArgs) -> syn::Result<Self> {
         Self::from_nested_metas(args)
     }
 
-    fn from_nested_metas<I>(nested_metas: I) -> Self
+    /// Add the parameters of the given attribute to these parameters, like the ones of the second attribute in
+    ///
+    /// #[error(message = "AHHHH")]
+    /// #[error(impl_from)] <--
+    /// struct AnError;
+    ///
+    /// Fails if the attribute is invalid or sets a parameter which is already set.
+    pub fn merge_attribute(mut self, attribute: &Attribute) -> syn::Result<Self> {
+        let mut other = Self::from_attribute(attribute)?.values.into_iter().collect::<Vec<_>>();
+        other.sort_by(|(a, _), (b, _)| a.cmp(b));
+
+        for (name, (value, span)) in other {
+            if self.values.contains_key(&name) {
+                return Err(Error::new(span, format!("The parameter '{}' is set in more than one error attribute", name)));
+            }
+            self.values.insert(name, (value, span));
+        }
+
+        Ok(self)
+    }
+
+    fn from_nested_metas<I>(nested_metas: I) -> syn::Result<Self>
         where I: IntoIterator<Item=NestedMeta> {
         let values = nested_metas
             .into_iter()
-            .filter_map(|nested| match nested {
-                syn::NestedMeta::Meta(meta) => Some(meta),
-                syn::NestedMeta::Lit(_) => panic!("Unexpected literal in meta list")
+            .map(|nested| match nested {
+                syn::NestedMeta::Meta(meta) => Self::meta_to_name_value(meta),
+                syn::NestedMeta::Lit(lit) => Err(Error::new(lit.span(), "Unexpected literal in meta list"))
             })
-            .map(Self::meta_to_name_value)
-            .collect();
+            .collect::<syn::Result<_>>()?;
 
-        Parameters { values }
+        Ok(Parameters { values })
     }
 
     /// Parse meta to name value tuples. The value of a NameValue is the
     /// value of the corresponding literal. The value of a path is always true.
-    /// Meta lists are ignored.
-    fn meta_to_name_value(meta: Meta) -> (String, LitValue) {
-        match meta {
-            NameValue(name_value) => {
-                let name = path_to_name(&name_value.path);
-                let value = LitValue::from(&name_value.lit);
-                (name, value)
-            }
-            Path(path) => {
-                let name = path_to_name(&path);
-                let value = LitValue::Boolean(true);
-                (name, value)
-            }
-            List(_) => panic!("Unexpected meta list")
+    /// Meta lists are not allowed.
+    fn meta_to_name_value(meta: Meta) -> syn::Result<(String, (LitValue, Span))> {
+        let span = meta.span();
+        let (name, value) = match meta {
+            NameValue(name_value) => (path_to_name(&name_value.path)?, LitValue::try_from(&name_value.lit)?),
+            Path(path) => (path_to_name(&path)?, LitValue::Boolean(true)),
+            List(list) => return Err(Error::new(list.span(), "Unexpected meta list"))
+        };
+
+        Self::check_value_type(&name, &value, span)?;
+        Ok((name, (value, span)))
+    }
+
+    /// Check if the value of a known parameter has the expected type.
+    /// Unknown parameters are not checked here.
+    fn check_value_type(name: &str, value: &LitValue, span: Span) -> syn::Result<()> {
+        match value {
+            LitValue::String(s) if BOOL_PARAMETER_ALTERNATIVES.contains(&(name, s.as_str())) => Ok(()),
+            LitValue::String(_) if BOOL_PARAMETERS.contains(&name) => Err(Error::new(span, format!("The parameter '{}' expects a boolean value", name))),
+            LitValue::Integer(_) if BOOL_PARAMETERS.contains(&name) => Err(Error::new(span, format!("The parameter '{}' expects a boolean value", name))),
+            LitValue::Boolean(_) | LitValue::Integer(_) if STRING_PARAMETERS.contains(&name) => Err(Error::new(span, format!("The parameter '{}' expects a string value", name))),
+            LitValue::String(_) if INTEGER_PARAMETERS_WITH_STRING_ALTERNATIVE.contains(&name) => Ok(()),
+            LitValue::String(_) | LitValue::Boolean(_) if INTEGER_PARAMETERS.contains(&name) => Err(Error::new(span, format!("The parameter '{}' expects an integer value", name))),
+            _ => Ok(())
         }
     }
 
     /// Not setting this value and setting it to 'false' means the same, so
     /// returning an Option<bool> is pointless here
     pub fn bool_for_name(&self, name: &str) -> bool {
-        self.values.get(name).map(LitValue::bool_value).unwrap_or(false)
+        self.values.get(name).and_then(|(v, _)| v.bool_value()).unwrap_or(false)
+    }
+
+    /// Check if the parameter with the given name is set to the given string value.
+    /// Unlike string_for_name, this also works for boolean parameters with string alternatives.
+    pub fn has_string_value(&self, name: &str, value: &str) -> bool {
+        match self.values.get(name) {
+            Some((LitValue::String(s), _)) => s == value,
+            _ => false
+        }
+    }
+
+    /// Return the string value of the parameter with the given name, if it is set to a string.
+    /// Unlike string_for_name, this also works for integer parameters with a string alternative.
+    pub fn string_alternative_for_name(&self, name: &str) -> Option<&str> {
+        match self.values.get(name) {
+            Some((LitValue::String(s), _)) => Some(s),
+            _ => None
+        }
     }
 
-    pub fn string_for_name(&self, name: &str) -> Option<String> {
-        self.values.get(name).map(LitValue::string_value)
+    pub fn string_for_name(&self, name: &str) -> Option<&str> {
+        self.values.get(name).and_then(|(v, _)| v.string_value())
+    }
+
+    pub fn integer_for_name(&self, name: &str) -> Option<u64> {
+        self.values.get(name).and_then(|(v, _)| v.integer_value())
+    }
+
+    /// Return the span of the parameter with the given name, if it is set.
+    pub fn span_for_name(&self, name: &str) -> Option<Span> {
+        self.values.get(name).map(|(_, span)| *span)
     }
 
     /// Return how many parameters are set.
@@ -96,34 +239,20 @@ impl Parameters {
     }
 
     /// Return an iterator over the names of this Parameters.
-    pub fn name_iter(&self) -> ParameterIter {
+    pub fn name_iter(&self) -> ParameterIter<'_> {
         self.into_iter()
     }
 }
 
 pub struct ParameterIter<'a> {
-    index: usize,
-    keys: Vec<&'a str>,
-}
-
-impl<'a> ParameterIter<'a> {
-    fn new(parameters: &'a Parameters) -> Self {
-        ParameterIter {
-            index: 0,
-            keys: parameters.values.keys().map(|key| key.deref()).collect(),
-        }
-    }
+    keys: Keys<'a, String, (LitValue, Span)>,
 }
 
 impl<'a> Iterator for ParameterIter<'a> {
     type Item = &'a str;
 
     fn next(&mut self) -> Option<Self::Item> {
-        if self.index == self.keys.len() { return None; }
-
-        let result = self.keys[self.index];
-        self.index += 1;
-        Some(result)
+        self.keys.next().map(String::as_str)
     }
 }
 
@@ -132,40 +261,57 @@ impl<'a> IntoIterator for &'a Parameters {
     type IntoIter = ParameterIter<'a>;
 
     fn into_iter(self) -> Self::IntoIter {
-        ParameterIter::new(self)
+        ParameterIter { keys: self.values.keys() }
     }
 }
 
 /// syn::Lit describes a literal from a token stream.
 /// This is not very handy to use, for example when creating a literal value like 'true'.
 /// This LitValue enum fixes this issue by ignoring the token stream part and only wrapping the literal value.
+///
+/// The accessors return None on a type mismatch. The types of known parameters are already checked on creation
+/// (see Parameters::check_value_type), so the compile error points to the parameter instead.
 enum LitValue {
     String(String),
     Boolean(bool),
+    Integer(u64),
 }
 
 impl LitValue {
-    pub fn bool_value(&self) -> bool {
-        if let LitValue::Boolean(b) = self {
-            return *b;
+    /// Return the boolean value. Strings can only be set for boolean parameters as alternative settings
+    /// (see BOOL_PARAMETER_ALTERNATIVES), which enable the parameter, so they count as true.
+    pub fn bool_value(&self) -> Option<bool> {
+        match self {
+            LitValue::Boolean(b) => Some(*b),
+            LitValue::String(_) => Some(true),
+            LitValue::Integer(_) => None
         }
-        panic!("Expected boolean")
     }
 
-    pub fn string_value(&self) -> String {
-        if let LitValue::String(s) = self {
-            return s.clone();
+    pub fn string_value(&self) -> Option<&str> {
+        match self {
+            LitValue::String(s) => Some(s),
+            _ => None
+        }
+    }
+
+    pub fn integer_value(&self) -> Option<u64> {
+        match self {
+            LitValue::Integer(i) => Some(*i),
+            _ => None
         }
-        panic!("Expected string")
     }
 }
 
-impl From<&Lit> for LitValue {
-    fn from(lit: &syn::Lit) -> Self {
+impl TryFrom<&Lit> for LitValue {
+    type Error = Error;
+
+    fn try_from(lit: &syn::Lit) -> syn::Result<Self> {
         match lit {
-            Str(lit_str) => LitValue::String(lit_str.value()),
-            Bool(lit_bool) => LitValue::Boolean(lit_bool.value),
-            _ => panic!("Unexpected literal value")
+            Str(lit_str) => Ok(LitValue::String(lit_str.value())),
+            Bool(lit_bool) => Ok(LitValue::Boolean(lit_bool.value)),
+            Int(lit_int) => Ok(LitValue::Integer(lit_int.base10_parse()?)),
+            _ => Err(Error::new(lit.span(), "Unexpected literal value, expected a string, a boolean or an unsigned integer"))
         }
     }
 }
@@ -174,6 +320,7 @@ impl From<&Lit> for LitValue {
 mod parameters_tests {
     use std::collections::HashMap;
 
+    use syn::__private::Span;
     use syn::Attribute;
 
     use crate::parameters::{LitValue, Parameters};
@@ -181,24 +328,86 @@ mod parameters_tests {
     #[test]
     fn from_attribute_works() {
         let attribute: Attribute = syn::parse_quote!(#[foo(bar = true)]);
-        let parameters = Parameters::from_attribute(&attribute);
+        let parameters = Parameters::from_attribute(&attribute).unwrap();
         assert_eq!(parameters.size(), 1);
-        assert_eq!(parameters.has_parameter("bar"), true);
-        assert_eq!(parameters.bool_for_name("bar"), true);
+        assert!(parameters.has_parameter("bar"));
+        assert!(parameters.bool_for_name("bar"));
     }
 
     #[test]
-    #[should_panic]
     fn from_attribute_path_like_fails() {
         let attribute: Attribute = syn::parse_quote!(#[foo]);
-        Parameters::from_attribute(&attribute);
+        assert!(Parameters::from_attribute(&attribute).is_err());
     }
 
     #[test]
-    #[should_panic]
     fn from_attribute_key_value_like_fails() {
         let attribute: Attribute = syn::parse_quote!(#[foo = true]);
-        Parameters::from_attribute(&attribute);
+        assert!(Parameters::from_attribute(&attribute).is_err());
+    }
+
+    #[test]
+    fn from_attribute_wrong_value_type_fails() {
+        let attribute: Attribute = syn::parse_quote!(#[error(message = true)]);
+        let error = Parameters::from_attribute(&attribute).err().unwrap();
+        assert_eq!(error.to_string(), "The parameter 'message' expects a string value");
+    }
+
+    #[test]
+    fn from_attribute_bool_alternative_works() {
+        let attribute: Attribute = syn::parse_quote!(#[error(impl_from = "into")]);
+        let parameters = Parameters::from_attribute(&attribute).unwrap();
+        assert!(parameters.bool_for_name("impl_from"));
+        assert!(parameters.has_string_value("impl_from", "into"));
+
+        let attribute: Attribute = syn::parse_quote!(#[error(impl_from = "onto")]);
+        assert!(Parameters::from_attribute(&attribute).is_err());
+    }
+
+    #[test]
+    fn from_attribute_integer_works() {
+        let attribute: Attribute = syn::parse_quote!(#[error(exit_code = 42)]);
+        let parameters = Parameters::from_attribute(&attribute).unwrap();
+        assert_eq!(parameters.integer_for_name("exit_code"), Some(42));
+
+        let attribute: Attribute = syn::parse_quote!(#[error(exit_code = "42")]);
+        let error = Parameters::from_attribute(&attribute).err().unwrap();
+        assert_eq!(error.to_string(), "The parameter 'exit_code' expects an integer value");
+    }
+
+    #[test]
+    fn mismatching_value_types_are_none() {
+        let attribute: Attribute = syn::parse_quote!(#[error(foo = "bar", baz = 42, errno = "EINVAL")]);
+        let parameters = Parameters::from_attribute(&attribute).unwrap();
+        assert_eq!(parameters.integer_for_name("foo"), None);
+        assert_eq!(parameters.string_for_name("baz"), None);
+        assert!(!parameters.bool_for_name("baz"));
+        assert_eq!(parameters.integer_for_name("errno"), None);
+        assert_eq!(parameters.string_alternative_for_name("errno"), Some("EINVAL"));
+    }
+
+    #[test]
+    fn from_attribute_unexpected_literal_fails() {
+        let attribute: Attribute = syn::parse_quote!(#[error(impl_from = 42)]);
+        assert!(Parameters::from_attribute(&attribute).is_err());
+
+        let attribute: Attribute = syn::parse_quote!(#[error(impl_from = 4.2)]);
+        assert!(Parameters::from_attribute(&attribute).is_err());
+    }
+
+    #[test]
+    fn merge_attribute_works() {
+        let first: Attribute = syn::parse_quote!(#[error(message = "failed")]);
+        let second: Attribute = syn::parse_quote!(#[error(impl_from, exit_code = 3)]);
+        let parameters = Parameters::from_attribute(&first).unwrap().merge_attribute(&second).unwrap();
+        assert_eq!(parameters.size(), 3);
+        assert_eq!(parameters.string_for_name("message"), Some("failed"));
+        assert!(parameters.bool_for_name("impl_from"));
+        assert_eq!(parameters.integer_for_name("exit_code"), Some(3));
+
+        let third: Attribute = syn::parse_quote!(#[error(message = "failed again")]);
+        let error = parameters.merge_attribute(&third).err().unwrap();
+        assert_eq!(error.to_string(), "The parameter 'message' is set in more than one error attribute");
     }
 
     #[test]
@@ -210,9 +419,9 @@ mod parameters_tests {
     #[test]
     fn has_parameter_works() {
         let parameters = create_example_parameters();
-        assert_eq!(parameters.has_parameter("foo"), true);
-        assert_eq!(parameters.has_parameter("baz"), true);
-        assert_eq!(parameters.has_parameter("oof"), false);
+        assert!(parameters.has_parameter("foo"));
+        assert!(parameters.has_parameter("baz"));
+        assert!(!parameters.has_parameter("oof"));
     }
 
     #[test]
@@ -226,8 +435,8 @@ mod parameters_tests {
 
     fn create_example_parameters() -> Parameters {
         let mut values = HashMap::new();
-        values.insert("foo".to_string(), LitValue::String("bar".to_string()));
-        values.insert("baz".to_string(), LitValue::Boolean(true));
+        values.insert("foo".to_string(), (LitValue::String("bar".to_string()), Span::call_site()));
+        values.insert("baz".to_string(), (LitValue::Boolean(true), Span::call_site()));
         Parameters { values }
     }
 }
\ No newline at end of file
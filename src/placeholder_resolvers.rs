@@ -0,0 +1,105 @@
+use quote::quote;
+use syn::__private::TokenStream2;
+use syn::{Ident, Path};
+
+use crate::template::TemplateError;
+use crate::template::TemplateError::*;
+
+/// The environment variable which contains the registered resolvers, like "duration = crate::fmt::duration, masked = crate::fmt::mask".
+/// Set it for a whole workspace with the [env] section of '.cargo/config.toml'.
+const REGISTRY_VARIABLE: &str = "ERROR_GENERATOR_RESOLVERS";
+
+/// Resolve a placeholder with a resolver, like "{secs:duration}", into a call of the registered function
+/// with a reference to the value, like 'crate::fmt::duration(&(secs))'. The function must return something
+/// which implements Display. Requires the feature 'placeholder_resolvers'.
+///
+/// Returns None if the placeholder has no resolver. Fails if the resolver is not registered in ERROR_GENERATOR_RESOLVERS
+/// or the registry is invalid. The given offset of the placeholder in the message is reported if its expression is invalid.
+pub fn resolve(placeholder: &str, offset: usize) -> Result<Option<TokenStream2>, TemplateError> {
+    resolve_with_registry(placeholder, offset, &std::env::var(REGISTRY_VARIABLE).unwrap_or_default())
+}
+
+fn resolve_with_registry(placeholder: &str, offset: usize, registry: &str) -> Result<Option<TokenStream2>, TemplateError> {
+    let (expression, name) = match split_resolver(placeholder) {
+        Some(split) => split,
+        None => return Ok(None)
+    };
+
+    let path = parse_registry(registry)?
+        .into_iter()
+        .find(|(n, _)| n == name)
+        .map(|(_, path)| path)
+        .ok_or_else(|| InvalidResolver(format!("The placeholder resolver '{}' is not registered in '{}'.", name, REGISTRY_VARIABLE)))?;
+    let expression = expression
+        .parse::<TokenStream2>()
+        .map_err(|_| InvalidExpression(expression.to_string(), offset))?;
+
+    Ok(Some(quote! {#path(&(#expression))}))
+}
+
+/// Split a placeholder at its last single colon into the expression and the name of the resolver, like "secs:duration".
+/// Colons of paths (like "a::b") are no separators and the name must be an identifier.
+fn split_resolver(placeholder: &str) -> Option<(&str, &str)> {
+    let index = placeholder.rfind(':')?;
+    let (expression, name) = (&placeholder[..index], placeholder[index + 1..].trim());
+
+    if expression.ends_with(':') || syn::parse_str::<Ident>(name).is_err() {
+        return None;
+    }
+
+    Some((expression.trim(), name))
+}
+
+/// Parse the registry, a comma separated list of 'name = path' pairs.
+fn parse_registry(registry: &str) -> Result<Vec<(String, Path)>, TemplateError> {
+    registry
+        .split(',')
+        .filter(|entry| !entry.trim().is_empty())
+        .map(|entry| {
+            let (name, path) = entry.split_once('=')
+                .ok_or_else(|| InvalidResolver(format!("'{}' in '{}' is no 'name = path' pair.", entry.trim(), REGISTRY_VARIABLE)))?;
+            let path = syn::parse_str::<Path>(path.trim())
+                .map_err(|_| InvalidResolver(format!("'{}' in '{}' is not a valid path to a function.", path.trim(), REGISTRY_VARIABLE)))?;
+            Ok((name.trim().to_string(), path))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::placeholder_resolvers::{resolve_with_registry, split_resolver};
+    use crate::template::TemplateError;
+
+    const REGISTRY: &str = "duration = crate::fmt::duration, masked=mask";
+
+    #[test]
+    fn split_resolver_works() {
+        assert_eq!(split_resolver("secs:duration"), Some(("secs", "duration")));
+        assert_eq!(split_resolver("self.user : masked"), Some(("self.user", "masked")));
+        assert_eq!(split_resolver("std::u8::MAX"), None);
+        assert_eq!(split_resolver("secs"), None)
+    }
+
+    #[test]
+    fn resolve_works() {
+        let resolved = resolve_with_registry("self.secs:duration", 0, REGISTRY).unwrap().unwrap();
+        assert_eq!(resolved.to_string(), "crate :: fmt :: duration (& (self . secs))");
+
+        let resolved = resolve_with_registry("user:masked", 0, REGISTRY).unwrap().unwrap();
+        assert_eq!(resolved.to_string(), "mask (& (user))");
+
+        assert!(resolve_with_registry("std::u8::MAX", 0, REGISTRY).unwrap().is_none())
+    }
+
+    #[test]
+    fn resolve_unknown_resolver_fails() {
+        let error = resolve_with_registry("secs:unknown", 0, REGISTRY).unwrap_err();
+        assert!(matches!(error, TemplateError::InvalidResolver(e) if e == "The placeholder resolver 'unknown' is not registered in 'ERROR_GENERATOR_RESOLVERS'."))
+    }
+
+    #[test]
+    fn resolve_invalid_registry_fails() {
+        let error = resolve_with_registry("secs:duration", 0, "duration").unwrap_err();
+        assert!(matches!(error, TemplateError::InvalidResolver(e) if e == "'duration' in 'ERROR_GENERATOR_RESOLVERS' is no 'name = path' pair."))
+    }
+}
@@ -1,10 +1,38 @@
 use quote::quote;
-use syn::{AttributeArgs, ItemStruct};
+use syn::{AttributeArgs, ItemStruct, WherePredicate};
 use syn::__private::TokenStream2;
 
+use crate::common::{derives, helper_derive, merge_error_attributes, remove_field_attributes, std_root, to_fields_with_parameters, trait_impl_attributes};
+use crate::debug_output::print_debug_output;
+use crate::impl_aggregate::AggregateImplementor;
+use crate::impl_chain::ChainImplementor;
+use crate::impl_boxed::BoxedImplementor;
+use crate::impl_errno::ErrnoImplementor;
+use crate::impl_exit_code::ExitCodeImplementor;
+use crate::impl_severity::SeverityImplementor;
+use crate::impl_category::CategoryImplementor;
+use crate::impl_grpc_status::GrpcStatusImplementor;
+use crate::impl_into_response::IntoResponseImplementor;
+use crate::impl_retry_after::RetryAfterImplementor;
+use crate::impl_retryable::RetryableImplementor;
+use crate::impl_json::JsonImplementor;
+use crate::impl_wire::WireImplementor;
+use crate::impl_transparent::TransparentImplementor;
+use crate::impl_into_inner::IntoInnerImplementor;
+use crate::impl_duplicate_messages::{DuplicateMessagesImplementor, NamedMessage};
+use crate::impl_message_docs::add_message_docs_to_struct;
+use crate::impl_strict::StrictImplementor;
+use crate::impl_serialize::SerializeImplementor;
+use crate::impl_debug::structs::StructDebugImplementor;
+use crate::impl_display::memoize::MemoizeImplementor;
+use crate::impl_display::render_into::RenderIntoImplementor;
+use crate::impl_display::implement_display;
+use crate::impl_display::bounds::{error_bounds, with_bounds};
 use crate::impl_display::structs::StructDisplayImplementor;
+use crate::impl_from::FromContext;
 use crate::impl_from::structs::StructFromImplementer;
-use crate::parameters::Parameters;
+use crate::parameters::{MESSAGE, Parameters};
+use crate::validator::{ParameterLocation, Validator};
 
 /// Generate the implementations for a given struct to be a fully qualified and
 /// usable error. This means
@@ -12,24 +40,115 @@ use crate::parameters::Parameters;
 /// - std::error::Error is implemented
 /// - std::fmt::Debug and Display are implemented
 /// - std::convert::From is implemented (if possible) to allow the usage of the ?-operator
-pub fn implement(attr_args: AttributeArgs, item_struct: ItemStruct) -> TokenStream2 {
-    let parameters = Parameters::from_attribute_args(attr_args);
+///
+/// Fails if the parameters are invalid or an implementation cannot be created.
+pub fn implement(attr_args: AttributeArgs, mut item_struct: ItemStruct) -> syn::Result<TokenStream2> {
+    let parameters = merge_error_attributes(Parameters::from_attribute_args(attr_args)?, &mut item_struct.attrs)?;
+    Validator::new(ParameterLocation::Struct).validate(&parameters)?;
+
+    let fields_with_parameters = to_fields_with_parameters(&item_struct.fields)?;
+    let helper_derive = helper_derive(&parameters)?;
+
+    let debug_implementor = StructDebugImplementor::new(&item_struct, &parameters, &fields_with_parameters);
+    let debug_derive = debug_implementor.implement_derive(&derives(&parameters)?)?;
+    let transparent_implementor = TransparentImplementor::new(&item_struct, &parameters);
+    let has_source = transparent_implementor.wrapped_member()?.is_some();
+    let source_bounds = transparent_implementor.source_bounds()?;
+    let struct_display_implementation = StructDisplayImplementor::new(&item_struct, &parameters, has_source).implement()?;
+    let message_forwarded = struct_display_implementation.is_empty() && has_source;
+    let display_implementation = match message_forwarded {
+        // the formatter is passed to the wrapped error, which appends its sources by itself with the alternate flag
+        true => implement_display(&item_struct.ident, &with_bounds(&item_struct.generics, &source_bounds), &parameters, transparent_implementor.implement_display_body()?, false),
+        false => struct_display_implementation
+    };
+    let debug_implementation = debug_implementor.implement(!display_implementation.is_empty())?;
+    let defmt_implementation = StructDisplayImplementor::new(&item_struct, &parameters, has_source).implement_defmt()?;
+    let memoize_implementation = MemoizeImplementor::new(&item_struct.ident, &item_struct.vis, &item_struct.generics, &parameters, &helper_derive).implement(!display_implementation.is_empty())?;
+    let aggregate_implementation = AggregateImplementor::new(&item_struct.ident, &item_struct.vis, &item_struct.generics, &parameters, &helper_derive).implement(!display_implementation.is_empty())?;
+    let render_into_implementation = RenderIntoImplementor::new(&item_struct.ident, &item_struct.generics, &parameters).implement(!display_implementation.is_empty())?;
+    let chain_implementation = ChainImplementor::new(&item_struct.ident, &item_struct.generics, &parameters).implement(!display_implementation.is_empty())?;
+    let from_context = FromContext::new(&item_struct.ident, &parameters, !display_implementation.is_empty())?;
+    let from_implementation = StructFromImplementer::new(&item_struct, &parameters, &from_context).implement()?;
+    let error_implementation = implement_error(&item_struct, &parameters, &source_bounds, transparent_implementor.implement_source(message_forwarded)?);
+    let into_inner_implementation = IntoInnerImplementor::new(&parameters).implement_for_struct(&item_struct)?;
+    let boxed_implementation = BoxedImplementor::new(&item_struct.ident, &item_struct.generics, &parameters).implement();
+    let exit_code_implementation = ExitCodeImplementor::new(&item_struct.ident, &item_struct.generics, &parameters).implement_for_struct()?;
+    let severity_implementation = SeverityImplementor::new(&item_struct.ident, &item_struct.vis, &item_struct.generics, &parameters).implement_for_struct()?;
+    let category_implementation = CategoryImplementor::new(&item_struct.ident, &item_struct.generics, &parameters).implement_for_struct()?;
+    let errno_implementation = ErrnoImplementor::new(&item_struct.ident, &item_struct.generics, &parameters).implement_for_struct()?;
+    let retry_after_implementation = RetryAfterImplementor::new(&item_struct.ident, &item_struct.generics, &parameters).implement_for_struct();
+    let retryable_implementation = RetryableImplementor::new(&item_struct.ident, &item_struct.generics, &parameters).implement_for_struct();
+    let into_response_implementation = IntoResponseImplementor::new(&item_struct.ident, &item_struct.generics, &parameters).implement_for_struct(!display_implementation.is_empty(), !retry_after_implementation.is_empty())?;
+    let grpc_status_implementation = GrpcStatusImplementor::new(&item_struct.ident, &item_struct.generics, &parameters).implement_for_struct(!display_implementation.is_empty())?;
+    let serialize_implementation = SerializeImplementor::new(&item_struct.ident, &item_struct.generics, &parameters).implement_for_struct(!display_implementation.is_empty(), !errno_implementation.is_empty())?;
+    let json_implementation = JsonImplementor::new(&item_struct.ident, &item_struct.generics, &parameters).implement_for_struct(!display_implementation.is_empty(), !errno_implementation.is_empty())?;
+    let wire_implementation = WireImplementor::new(&item_struct.ident, &item_struct.vis, &item_struct.generics, &parameters).implement(!display_implementation.is_empty(), !errno_implementation.is_empty())?;
+    let duplicate_message_warnings = DuplicateMessagesImplementor::new(item_struct.ident.to_string(), struct_messages(&item_struct, &parameters)).implement();
+    let unused_fields_warning = StrictImplementor::new(&parameters).implement_for_struct(&item_struct, has_source);
+
+    add_message_docs_to_struct(&mut item_struct, &parameters)?;
+    remove_field_attributes(&mut item_struct.fields);
+
+    let expansion = quote! {
+        #debug_derive #item_struct
+        #error_implementation
+        #debug_implementation
+        #display_implementation
+        #defmt_implementation
+        #memoize_implementation
+        #render_into_implementation
+        #aggregate_implementation
+
+        #chain_implementation
+        #from_implementation
+        #into_inner_implementation
+        #boxed_implementation
+        #exit_code_implementation
+        #severity_implementation
+        #category_implementation
+        #errno_implementation
+        #retry_after_implementation
+        #retryable_implementation
+
+        #into_response_implementation
+        #grpc_status_implementation
+        #serialize_implementation
+        #json_implementation
+
+        #wire_implementation
+
+        #duplicate_message_warnings
+        #unused_fields_warning
+    };
+
+    print_debug_output(&item_struct.ident, &parameters, &expansion);
+    Ok(expansion)
+}
 
+/// Return the message of the struct (if it has one) for the detection of duplicate messages.
+fn struct_messages(item_struct: &ItemStruct, parameters: &Parameters) -> Vec<NamedMessage> {
+    match (parameters.string_for_name(MESSAGE), parameters.span_for_name(MESSAGE)) {
+        (Some(message), Some(span)) => vec![(item_struct.ident.to_string(), message.to_string(), span)],
+        _ => vec![]
+    }
+}
+
+/// Implement std::error::Error, with the given implementation of 'source' (or the default one if it is empty).
+/// Generic structs get the bounds of Error (see bounds::error_bounds) and of a generic source (see bounds::source_bounds).
+fn implement_error(item_struct: &ItemStruct, parameters: &Parameters, source_bounds: &[WherePredicate], source: TokenStream2) -> TokenStream2 {
     let ident = &item_struct.ident;
-    let generics = &item_struct.generics;
+    let root = std_root(parameters);
+    let bounds = error_bounds(&item_struct.generics, &root).into_iter().chain(source_bounds.iter().cloned()).collect::<Vec<_>>();
+    let generics = with_bounds(&item_struct.generics, &bounds);
     let (impl_generics, type_generics, where_clause) = generics.split_for_impl();
 
-    let display_implementation = StructDisplayImplementor::new(&item_struct, &parameters).implement();
-    let from_implementation = match StructFromImplementer::new(&item_struct, &parameters).implement() {
-        Ok(implementation) => implementation,
-        Err(e) => panic!("{}", e)
-    };
+    let impl_attributes = trait_impl_attributes();
 
     quote! {
-        #[derive(Debug)] #item_struct
-        impl #impl_generics std::error::Error for #ident #type_generics #where_clause {}
-        #display_implementation
-        #from_implementation
+        #impl_attributes
+        impl #impl_generics #root::error::Error for #ident #type_generics #where_clause {
+            #source
+        }
     }
 }
 
@@ -53,7 +172,9 @@ mod tests {
                     foo: usize
                 }
 
-                impl std::error::Error for S {}
+                #[automatically_derived]
+                #[allow(unused_qualifications, clippy::all)]
+                impl ::std::error::Error for S {}
             }
         )
     }
@@ -72,7 +193,9 @@ mod tests {
                 struct S {
                 }
 
-                impl std::error::Error for S {}
+                #[automatically_derived]
+                #[allow(unused_qualifications, clippy::all)]
+                impl ::std::error::Error for S {}
             }
         )
     }
@@ -89,7 +212,9 @@ mod tests {
                 #[derive(Debug)]
                 struct S (usize);
 
-                impl std::error::Error for S {}
+                #[automatically_derived]
+                #[allow(unused_qualifications, clippy::all)]
+                impl ::std::error::Error for S {}
             }
         )
     }
@@ -106,7 +231,9 @@ mod tests {
                 #[derive(Debug)]
                 struct S ();
 
-                impl std::error::Error for S {}
+                #[automatically_derived]
+                #[allow(unused_qualifications, clippy::all)]
+                impl ::std::error::Error for S {}
             }
         )
     }
@@ -123,7 +250,9 @@ mod tests {
                 #[derive(Debug)]
                 struct S;
 
-                impl std::error::Error for S {}
+                #[automatically_derived]
+                #[allow(unused_qualifications, clippy::all)]
+                impl ::std::error::Error for S {}
             }
         )
     }
@@ -142,7 +271,55 @@ mod tests {
                 #[derive(Clone)]
                 struct S;
 
-                impl std::error::Error for S {}
+                #[automatically_derived]
+                #[allow(unused_qualifications, clippy::all)]
+                impl ::std::error::Error for S {}
+            }
+        )
+    }
+
+    #[test]
+    fn multiple_error_attributes() {
+        assert_struct_implementation_as_expected!(
+            item: {
+                #[error(message = "{self.0}")]
+                #[derive(Clone)]
+                #[error(no_std)]
+                struct S(usize);
+            }
+
+            expected: {
+                #[derive(Debug)]
+                #[derive(Clone)]
+                struct S(usize);
+
+                #[automatically_derived]
+                #[allow(unused_qualifications, clippy::all)]
+                impl ::core::error::Error for S {}
+
+                #[automatically_derived]
+                #[allow(unused_qualifications, clippy::all)]
+                impl ::core::fmt::Display for S {
+                    fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+                        ::core::write!(f, "{}", self.0)
+                    }
+                }
+            }
+        )
+    }
+
+    #[test]
+    #[should_panic(expected = "The parameter 'message' is set in more than one error attribute")]
+    fn parameter_in_multiple_error_attributes_should_panic() {
+        assert_struct_implementation_as_expected!(
+            item: {
+                #[error(message = "first")]
+                #[error(message = "second")]
+                struct S;
+            }
+
+            expected: {
+                should panic
             }
         )
     }
@@ -159,7 +336,9 @@ mod tests {
                 #[derive(Debug)]
                 struct S<'a> (&'a usize);
 
-                impl<'a> std::error::Error for S<'a> {}
+                #[automatically_derived]
+                #[allow(unused_qualifications, clippy::all)]
+                impl<'a> ::std::error::Error for S<'a> {}
             }
         )
     }
@@ -176,7 +355,36 @@ mod tests {
                 #[derive(Debug)]
                 struct S<A: Clone, B> (A, B) where B: Clone;
 
-                impl<A: Clone, B> std::error::Error for S<A, B> where B: Clone {}
+                #[automatically_derived]
+                #[allow(unused_qualifications, clippy::all)]
+                impl<A: Clone, B> ::std::error::Error for S<A, B> where B: Clone, Self: ::std::fmt::Debug + ::std::fmt::Display {}
+            }
+        )
+    }
+
+    #[test]
+    fn display_bounds_of_generic_fields() {
+        assert_struct_implementation_as_expected!(
+            item: {
+                #[error(message = "{self.0} at {self.1:x}, count: {self.2}")]
+                struct S<A, B> (A, B, usize) where B: Clone;
+            }
+
+            expected: {
+                #[derive(Debug)]
+                struct S<A, B> (A, B, usize) where B: Clone;
+
+                #[automatically_derived]
+                #[allow(unused_qualifications, clippy::all)]
+                impl<A, B> ::std::error::Error for S<A, B> where B: Clone, Self: ::std::fmt::Debug + ::std::fmt::Display {}
+
+                #[automatically_derived]
+                #[allow(unused_qualifications, clippy::all)]
+                impl<A, B> ::std::fmt::Display for S<A, B> where B: Clone, A: ::std::fmt::Display, B: ::std::fmt::LowerHex {
+                    fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+                        ::std::write!(f, "{} at {:x}, count: {}", self.0, self.1, self.2)
+                    }
+                }
             }
         )
     }
@@ -193,7 +401,9 @@ mod tests {
                 #[derive(Debug)]
                 struct S<const C: usize> (C);
 
-                impl<const C: usize> std::error::Error for S<C> {}
+                #[automatically_derived]
+                #[allow(unused_qualifications, clippy::all)]
+                impl<const C: usize> ::std::error::Error for S<C> {}
             }
         )
     }
@@ -214,13 +424,216 @@ mod tests {
                     foo: usize
                 }
 
-                impl std::error::Error for S {}
-
-                impl std::convert::From<usize> for S {
+                #[automatically_derived]
+                #[allow(unused_qualifications, clippy::all)]
+                impl ::std::error::Error for S {}
+
+                #[doc = " Converts a `usize` into a `S`, which allows using the ?-operator like"]
+                #[doc = ""]
+                #[doc = " ```ignore"]
+                #[doc = " fn example(result: Result<(), usize>) -> Result<(), S> {"]
+                #[doc = "     result?;"]
+                #[doc = "     Ok(())"]
+                #[doc = " }"]
+                #[doc = " ```"]
+                #[automatically_derived]
+                #[allow(unused_qualifications, clippy::all)]
+                impl ::std::convert::From<usize> for S {
+                    #[track_caller]
                     fn from(val: usize) -> Self {
                         S{ foo : val }
                     }
                 }
+
+                impl S {
+                    #[doc = "The types this error can be created from with std::convert::From (and therefore the ?-operator)."]
+                    pub const FROM_TYPES: &'static [&'static str] = &["usize"];
+                }
+            }
+        )
+    }
+
+    #[test]
+    fn unnamed_impl_from_no_docs() {
+        assert_struct_implementation_as_expected!(
+            item: {
+                #[error(impl_from, no_from_docs)]
+                struct S (usize);
+            }
+
+            expected: {
+                #[derive(Debug)]
+                struct S (usize);
+
+                #[automatically_derived]
+                #[allow(unused_qualifications, clippy::all)]
+                impl ::std::error::Error for S {}
+
+                #[automatically_derived]
+                #[allow(unused_qualifications, clippy::all)]
+                impl ::std::convert::From<usize> for S {
+                    #[track_caller]
+                    fn from(val: usize) -> Self {
+                        S(val)
+                    }
+                }
+
+                impl S {
+                    #[doc = "The types this error can be created from with std::convert::From (and therefore the ?-operator)."]
+                    pub const FROM_TYPES: &'static [&'static str] = &["usize"];
+                }
+            }
+        )
+    }
+
+    #[test]
+    fn impl_from_with_location() {
+        assert_struct_implementation_as_expected!(
+            item: {
+                #[error(impl_from, no_from_docs)]
+                struct S {
+                    value: usize,
+                    location: &'static std::panic::Location<'static>
+                }
+            }
+
+            expected: {
+                #[derive(Debug)]
+                struct S {
+                    value: usize,
+                    location: &'static std::panic::Location<'static>
+                }
+
+                #[automatically_derived]
+                #[allow(unused_qualifications, clippy::all)]
+                impl ::std::error::Error for S {}
+
+                #[automatically_derived]
+                #[allow(unused_qualifications, clippy::all)]
+                impl ::std::convert::From<usize> for S {
+                    #[track_caller]
+                    fn from(val: usize) -> Self {
+                        S { value: val, location: ::std::panic::Location::caller() }
+                    }
+                }
+
+                impl S {
+                    #[doc = "The types this error can be created from with std::convert::From (and therefore the ?-operator)."]
+                    pub const FROM_TYPES: &'static [&'static str] = &["usize"];
+                }
+            }
+        )
+    }
+
+    #[test]
+    fn impl_from_with_phantom_data() {
+        assert_struct_implementation_as_expected!(
+            item: {
+                #[error(impl_from, no_from_docs)]
+                struct S<T>(usize, std::marker::PhantomData<T>);
+            }
+
+            expected: {
+                #[derive(Debug)]
+                struct S<T>(usize, std::marker::PhantomData<T>);
+
+                #[automatically_derived]
+                #[allow(unused_qualifications, clippy::all)]
+                impl<T> ::std::error::Error for S<T> where Self: ::std::fmt::Debug + ::std::fmt::Display {}
+
+                #[automatically_derived]
+                #[allow(unused_qualifications, clippy::all)]
+                impl<T> ::std::convert::From<usize> for S<T> {
+                    #[track_caller]
+                    fn from(val: usize) -> Self {
+                        S(val, ::std::marker::PhantomData)
+                    }
+                }
+
+                impl<T> S<T> {
+                    #[doc = "The types this error can be created from with std::convert::From (and therefore the ?-operator)."]
+                    pub const FROM_TYPES: &'static [&'static str] = &["usize"];
+                }
+            }
+        )
+    }
+
+    #[test]
+    fn named_impl_from_into() {
+        assert_struct_implementation_as_expected!(
+            item: {
+                #[error(impl_from = "into", no_from_docs)]
+                struct S<A> {
+                    val: String,
+                    #[error(skip)]
+                    a: A
+                }
+            }
+
+            expected: {
+                #[derive(Debug)]
+                struct S<A> {
+                    val: String,
+                    a: A
+                }
+
+                #[automatically_derived]
+                #[allow(unused_qualifications, clippy::all)]
+                impl<A> ::std::error::Error for S<A> where Self: ::std::fmt::Debug + ::std::fmt::Display {}
+
+                #[automatically_derived]
+                #[allow(unused_qualifications, clippy::all)]
+                impl<A, __T: ::std::convert::Into<String> > ::std::convert::From<__T> for S<A> {
+                    #[track_caller]
+                    fn from(val: __T) -> Self {
+                        S { val: ::std::convert::Into::into(val), a: ::std::default::Default::default() }
+                    }
+                }
+
+                impl<A> S<A> {
+                    #[doc = "The types this error can be created from with std::convert::From (and therefore the ?-operator)."]
+                    pub const FROM_TYPES: &'static [&'static str] = &["String"];
+                }
+            }
+        )
+    }
+
+    #[test]
+    fn named_impl_from_skipped_field() {
+        assert_struct_implementation_as_expected!(
+            item: {
+                #[error(impl_from, no_from_docs)]
+                struct S {
+                    val: usize,
+                    #[error(skip)]
+                    buffer: Vec<u8>
+                }
+            }
+
+            expected: {
+                #[derive(Debug)]
+                struct S {
+                    val: usize,
+                    buffer: Vec<u8>
+                }
+
+                #[automatically_derived]
+                #[allow(unused_qualifications, clippy::all)]
+                impl ::std::error::Error for S {}
+
+                #[automatically_derived]
+                #[allow(unused_qualifications, clippy::all)]
+                impl ::std::convert::From<usize> for S {
+                    #[track_caller]
+                    fn from(val: usize) -> Self {
+                        S { val: val, buffer: ::std::default::Default::default() }
+                    }
+                }
+
+                impl S {
+                    #[doc = "The types this error can be created from with std::convert::From (and therefore the ?-operator)."]
+                    pub const FROM_TYPES: &'static [&'static str] = &["usize"];
+                }
             }
         )
     }
@@ -267,13 +680,31 @@ mod tests {
                 #[derive(Debug)]
                 struct S (usize);
 
-                impl std::error::Error for S {}
-
-                impl std::convert::From<usize> for S {
+                #[automatically_derived]
+                #[allow(unused_qualifications, clippy::all)]
+                impl ::std::error::Error for S {}
+
+                #[doc = " Converts a `usize` into a `S`, which allows using the ?-operator like"]
+                #[doc = ""]
+                #[doc = " ```ignore"]
+                #[doc = " fn example(result: Result<(), usize>) -> Result<(), S> {"]
+                #[doc = "     result?;"]
+                #[doc = "     Ok(())"]
+                #[doc = " }"]
+                #[doc = " ```"]
+                #[automatically_derived]
+                #[allow(unused_qualifications, clippy::all)]
+                impl ::std::convert::From<usize> for S {
+                    #[track_caller]
                     fn from(val: usize) -> Self {
                         S(val)
                     }
                 }
+
+                impl S {
+                    #[doc = "The types this error can be created from with std::convert::From (and therefore the ?-operator)."]
+                    pub const FROM_TYPES: &'static [&'static str] = &["usize"];
+                }
             }
         )
     }
@@ -339,11 +770,15 @@ mod tests {
                     foo: usize
                 }
 
-                impl std::error::Error for S {}
+                #[automatically_derived]
+                #[allow(unused_qualifications, clippy::all)]
+                impl ::std::error::Error for S {}
 
-                impl std::fmt::Display for S {
-                    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-                        write!(f, "My foo value: {}", self.foo)
+                #[automatically_derived]
+                #[allow(unused_qualifications, clippy::all)]
+                impl ::std::fmt::Display for S {
+                    fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+                        ::std::write!(f, "My foo value: {}", self.foo)
                     }
                 }
             }
@@ -362,11 +797,15 @@ mod tests {
                 #[derive(Debug)]
                 struct S (usize);
 
-                impl std::error::Error for S {}
+                #[automatically_derived]
+                #[allow(unused_qualifications, clippy::all)]
+                impl ::std::error::Error for S {}
 
-                impl std::fmt::Display for S {
-                    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-                        write!(f, "My single value: {}", self.0)
+                #[automatically_derived]
+                #[allow(unused_qualifications, clippy::all)]
+                impl ::std::fmt::Display for S {
+                    fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+                        ::std::write!(f, "My single value: {}", self.0)
                     }
                 }
             }
@@ -385,11 +824,15 @@ mod tests {
                 #[derive(Debug)]
                 struct S;
 
-                impl std::error::Error for S {}
+                #[automatically_derived]
+                #[allow(unused_qualifications, clippy::all)]
+                impl ::std::error::Error for S {}
 
-                impl std::fmt::Display for S {
-                    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-                        write!(f, "Something went wrong")
+                #[automatically_derived]
+                #[allow(unused_qualifications, clippy::all)]
+                impl ::std::fmt::Display for S {
+                    fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+                        ::std::write!(f, "Something went wrong")
                     }
                 }
             }
@@ -397,32 +840,108 @@ mod tests {
     }
 
     #[test]
-    fn named_impl_from_and_display() {
+    #[should_panic(expected = "Unknown parameter 'mesage'. Valid parameters on structs are: ")]
+    fn unknown_parameter_should_panic() {
         assert_struct_implementation_as_expected!(
             item: {
-                #[error(message = "My foo value: {self.foo}", impl_from)]
+                #[error(mesage = "typo")]
+                struct S;
+            }
+
+            expected: {
+                should not work
+            }
+        )
+    }
+
+    #[test]
+    #[should_panic(expected = "The message contains an expression at byte 14 which is never closed.")]
+    fn unclosed_expression_in_display_should_panic() {
+        assert_struct_implementation_as_expected!(
+            item: {
+                #[error(message = "My foo value: {self.foo")]
                 struct S {
                     foo: usize
                 }
             }
 
             expected: {
-                #[derive(Debug)]
+                should not work
+            }
+        )
+    }
+
+    #[test]
+    #[should_panic(expected = "'self.lien' in the message of 'S' is no field. Valid names are: self.line, self.path.")]
+    fn unknown_field_in_display_should_panic() {
+        assert_struct_implementation_as_expected!(
+            item: {
+                #[error(message = "{self.path} failed in line {self.lien}")]
                 struct S {
-                    foo: usize
+                    line: usize,
+                    path: String
                 }
+            }
 
-                impl std::error::Error for S {}
+            expected: {
+                should not work
+            }
+        )
+    }
 
-                impl std::fmt::Display for S {
-                    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-                        write!(f, "My foo value: {}", self.foo)
-                    }
+    #[test]
+    #[should_panic(expected = "'line' in the message of 'S' is no variable, use '{self.line}' to access the field.")]
+    fn field_without_self_in_display_should_panic() {
+        assert_struct_implementation_as_expected!(
+            item: {
+                #[error(message = "failed in line {line}")]
+                struct S {
+                    line: usize
                 }
+            }
 
-                impl std::convert::From<usize> for S {
-                    fn from(val: usize) -> Self {
-                        S{ foo : val }
+            expected: {
+                should not work
+            }
+        )
+    }
+
+    #[test]
+    #[should_panic(expected = "'{1}' in the message of 'S' is out of range, as 'S' has 1 unnamed field.")]
+    fn positional_placeholder_out_of_range_in_display_should_panic() {
+        assert_struct_implementation_as_expected!(
+            item: {
+                #[error(message = "{0} and {1}")]
+                struct S(usize);
+            }
+
+            expected: {
+                should not work
+            }
+        )
+    }
+
+    #[test]
+    fn custom_formatter_display() {
+        assert_struct_implementation_as_expected!(
+            item: {
+                #[error(fmt = "my_module::format_s")]
+                struct S;
+            }
+
+            expected: {
+                #[derive(Debug)]
+                struct S;
+
+                #[automatically_derived]
+                #[allow(unused_qualifications, clippy::all)]
+                impl ::std::error::Error for S {}
+
+                #[automatically_derived]
+                #[allow(unused_qualifications, clippy::all)]
+                impl ::std::fmt::Display for S {
+                    fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+                        my_module::format_s(self, f)
                     }
                 }
             }
@@ -430,34 +949,1179 @@ mod tests {
     }
 
     #[test]
-    fn unnamed_impl_from_and_display() {
+    #[should_panic(expected = "'S' has both a message and a custom formatter function. Choose only one.")]
+    fn custom_formatter_and_message_should_panic() {
         assert_struct_implementation_as_expected!(
             item: {
-                #[error(message = "My single value: {self.0}", impl_from)]
-                struct S (usize);
+                #[error(message = "foo", fmt = "my_module::format_s")]
+                struct S;
+            }
+
+            expected: {
+                should not work
+            }
+        )
+    }
+
+    #[test]
+    fn no_std_alloc() {
+        assert_struct_implementation_as_expected!(
+            item: {
+                #[error(message = "{self.0}", no_std, alloc)]
+                struct S(usize);
             }
 
             expected: {
                 #[derive(Debug)]
-                struct S (usize);
+                struct S(usize);
 
-                impl std::error::Error for S {}
+                #[automatically_derived]
+                #[allow(unused_qualifications, clippy::all)]
+                impl ::core::error::Error for S {}
 
-                impl std::fmt::Display for S {
-                    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-                        write!(f, "My single value: {}", self.0)
+                #[automatically_derived]
+                #[allow(unused_qualifications, clippy::all)]
+                impl ::core::fmt::Display for S {
+                    fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+                        ::core::write!(f, "{}", self.0)
                     }
                 }
 
-                impl std::convert::From<usize> for S {
-                    fn from(val: usize) -> Self {
-                        S(val)
+                impl S {
+                    #[doc = " Box this error as a trait object, like 'alloc::boxed::Box::<dyn core::error::Error>::from' would."]
+                    pub fn boxed(self) -> alloc::boxed::Box<dyn ::core::error::Error> where Self: 'static {
+                        alloc::boxed::Box::new(self)
                     }
                 }
             }
         )
     }
 
+    #[test]
+    fn dyn_error() {
+        assert_struct_implementation_as_expected!(
+            item: {
+                #[error(dyn_error)]
+                struct S;
+            }
+
+            expected: {
+                #[derive(Debug)]
+                struct S;
+
+                #[automatically_derived]
+                #[allow(unused_qualifications, clippy::all)]
+                impl ::std::error::Error for S {}
+
+                impl S {
+                    #[doc = " Box this error as a trait object which can be sent between threads."]
+                    pub fn boxed(self) -> ::std::boxed::Box<dyn ::std::error::Error + Send + Sync + 'static> where Self: Send + Sync + 'static {
+                        ::std::boxed::Box::new(self)
+                    }
+
+                    #[doc = " Borrow this error as a trait object."]
+                    pub fn as_dyn_error(&self) -> &(dyn ::std::error::Error + 'static) where Self: 'static {
+                        self
+                    }
+                }
+            }
+        )
+    }
+
+    #[test]
+    fn no_std_dyn_error() {
+        assert_struct_implementation_as_expected!(
+            item: {
+                #[error(no_std, dyn_error)]
+                struct S;
+            }
+
+            expected: {
+                #[derive(Debug)]
+                struct S;
+
+                #[automatically_derived]
+                #[allow(unused_qualifications, clippy::all)]
+                impl ::core::error::Error for S {}
+
+                impl S {
+                    #[doc = " Borrow this error as a trait object."]
+                    pub fn as_dyn_error(&self) -> &(dyn ::core::error::Error + 'static) where Self: 'static {
+                        self
+                    }
+                }
+            }
+        )
+    }
+
+    #[test]
+    #[should_panic(expected = "The exit code 256 is out of range. Exit codes must be between 0 and 255.")]
+    fn exit_code_out_of_range_should_panic() {
+        assert_struct_implementation_as_expected!(
+            item: {
+                #[error(exit_code = 256)]
+                struct S;
+            }
+
+            expected: {
+                should panic
+            }
+        )
+    }
+
+    #[test]
+    #[should_panic(expected = "The severity 'critical' is unknown. Possible severities are: warning, error, fatal")]
+    fn unknown_severity_should_panic() {
+        assert_struct_implementation_as_expected!(
+            item: {
+                #[error(severity = "critical")]
+                struct S;
+            }
+
+            expected: {
+                should panic
+            }
+        )
+    }
+
+    #[test]
+    #[should_panic(expected = "'einval' is not the name of an errno constant, like \"EINVAL\".")]
+    fn invalid_errno_name_should_panic() {
+        assert_struct_implementation_as_expected!(
+            item: {
+                #[error(errno = "einval")]
+                struct S;
+            }
+
+            expected: {
+                should panic
+            }
+        )
+    }
+
+    #[test]
+    fn memoize_display() {
+        assert_struct_implementation_as_expected!(
+            item: {
+                #[error(message = "expensive", memoize_display)]
+                pub struct S;
+            }
+
+            expected: {
+                #[derive(Debug)]
+                pub struct S;
+
+                #[automatically_derived]
+                #[allow(unused_qualifications, clippy::all)]
+                impl ::std::error::Error for S {}
+
+                #[automatically_derived]
+                #[allow(unused_qualifications, clippy::all)]
+                impl ::std::fmt::Display for S {
+                    fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+                        ::std::write!(f, "expensive")
+                    }
+                }
+
+                #[doc = " Wrapper around 'S' which renders its message only once."]
+                #[derive(Debug)]
+                pub struct MemoizedS {
+                    error: S,
+                    message: ::std::sync::OnceLock<::std::string::String>,
+                }
+
+                impl S {
+                    #[doc = " Wrap this error, so its message is only rendered once."]
+                    pub fn memoized(self) -> MemoizedS {
+                        MemoizedS { error: self, message: ::std::sync::OnceLock::new() }
+                    }
+                }
+
+                impl MemoizedS {
+                    #[doc = " Return the wrapped error."]
+                    pub fn error(&self) -> &S {
+                        &self.error
+                    }
+
+                    #[doc = " Return the wrapped error and discard the cached message."]
+                    pub fn into_inner(self) -> S {
+                        self.error
+                    }
+                }
+
+                #[automatically_derived]
+                #[allow(unused_qualifications, clippy::all)]
+                impl ::std::fmt::Display for MemoizedS {
+                    fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+                        f.write_str(self.message.get_or_init(|| ::std::string::ToString::to_string(&self.error)))
+                    }
+                }
+
+                #[automatically_derived]
+                #[allow(unused_qualifications, clippy::all)]
+                impl ::std::error::Error for MemoizedS {
+                    fn source(&self) -> ::std::option::Option<&(dyn ::std::error::Error + 'static)> {
+                        ::std::error::Error::source(&self.error)
+                    }
+                }
+            }
+        )
+    }
+
+    #[test]
+    #[should_panic(expected = "The parameter 'json' requires a message for 'S', as it is rendered into the JSON object.")]
+    fn json_without_message_should_panic() {
+        assert_struct_implementation_as_expected!(
+            item: {
+                #[error(json)]
+                struct S;
+            }
+
+            expected: {
+                should panic
+            }
+        )
+    }
+
+    #[test]
+    fn trace() {
+        assert_struct_implementation_as_expected!(
+            item: {
+                #[error(message = "failed", impl_from, trace = "warn")]
+                struct S(usize);
+            }
+
+            expected: {
+                #[derive(Debug)]
+                struct S(usize);
+
+                #[automatically_derived]
+                #[allow(unused_qualifications, clippy::all)]
+                impl ::std::error::Error for S {}
+
+                #[automatically_derived]
+                #[allow(unused_qualifications, clippy::all)]
+                impl ::std::fmt::Display for S {
+                    fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+                        ::std::write!(f, "failed")
+                    }
+                }
+
+                #[doc = " Converts a `usize` into a `S`, which allows using the ?-operator like"]
+                #[doc = ""]
+                #[doc = " ```ignore"]
+                #[doc = " fn example(result: Result<(), usize>) -> Result<(), S> {"]
+                #[doc = "     result?;"]
+                #[doc = "     Ok(())"]
+                #[doc = " }"]
+                #[doc = " ```"]
+                #[automatically_derived]
+                #[allow(unused_qualifications, clippy::all)]
+                impl ::std::convert::From<usize> for S {
+                    #[track_caller]
+                    fn from(val: usize) -> Self {
+                        let error = S(val);
+                        tracing::warn!(error = ?error, "{}", error);
+                        error
+                    }
+                }
+
+                impl S {
+                    #[doc = "The types this error can be created from with std::convert::From (and therefore the ?-operator)."]
+                    pub const FROM_TYPES: &'static [&'static str] = &["usize"];
+                }
+            }
+        )
+    }
+
+    #[test]
+    #[should_panic(expected = "'warning' is not a level of the log crate. Possible levels are: error, warn, info, debug, trace")]
+    fn invalid_log_level_should_panic() {
+        assert_struct_implementation_as_expected!(
+            item: {
+                #[error(message = "failed", impl_from, log = "warning")]
+                struct S(usize);
+            }
+
+            expected: {
+                should panic
+            }
+        )
+    }
+
+    #[test]
+    #[should_panic(expected = "The parameter 'trace' requires a message for 'S', as it is recorded in the tracing event.")]
+    fn trace_without_message_should_panic() {
+        assert_struct_implementation_as_expected!(
+            item: {
+                #[error(impl_from, trace)]
+                struct S(usize);
+            }
+
+            expected: {
+                should panic
+            }
+        )
+    }
+
+    #[test]
+    #[should_panic(expected = "The parameter 'aggregate' requires a message for 'S', as the wrapper displays the messages of all errors.")]
+    fn aggregate_without_message_should_panic() {
+        assert_struct_implementation_as_expected!(
+            item: {
+                #[error(aggregate)]
+                struct S;
+            }
+
+            expected: {
+                should panic
+            }
+        )
+    }
+
+    #[test]
+    #[should_panic(expected = "The parameter 'chain' requires a message for 'S', as the chain is rendered from the messages.")]
+    fn chain_without_message_should_panic() {
+        assert_struct_implementation_as_expected!(
+            item: {
+                #[error(chain)]
+                struct S;
+            }
+
+            expected: {
+                should panic
+            }
+        )
+    }
+
+    #[test]
+    fn runtime_verbosity() {
+        assert_struct_implementation_as_expected!(
+            item: {
+                #[error(message = "failed", runtime_verbosity)]
+                struct S;
+            }
+
+            expected: {
+                #[derive(Debug)]
+                struct S;
+
+                #[automatically_derived]
+                #[allow(unused_qualifications, clippy::all)]
+                impl ::std::error::Error for S {}
+
+                #[automatically_derived]
+                #[allow(unused_qualifications, clippy::all)]
+                impl ::std::fmt::Display for S {
+                    fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+                        (::std::write!(f, "failed"))?;
+
+                        match Self::error_verbosity() {
+                            true => ::std::write!(f, " ({:?})", self),
+                            false => ::std::result::Result::Ok(())
+                        }
+                    }
+                }
+
+                impl S {
+                    #[doc(hidden)]
+                    fn __verbosity_flag() -> &'static ::std::sync::atomic::AtomicBool {
+                        static VERBOSITY: ::std::sync::atomic::AtomicBool = ::std::sync::atomic::AtomicBool::new(false);
+                        &VERBOSITY
+                    }
+
+                    #[doc = " Enable or disable the verbose messages of this error type for the whole process."]
+                    pub fn set_error_verbosity(verbose: bool) {
+                        Self::__verbosity_flag().store(verbose, ::std::sync::atomic::Ordering::Relaxed)
+                    }
+
+                    #[doc = " Return if the verbose messages of this error type are enabled."]
+                    pub fn error_verbosity() -> bool {
+                        Self::__verbosity_flag().load(::std::sync::atomic::Ordering::Relaxed)
+                    }
+                }
+            }
+        )
+    }
+
+    #[test]
+    fn memoize_display_helper_derive() {
+        assert_struct_implementation_as_expected!(
+            item: {
+                #[error(message = "expensive", memoize_display, helper_derive = "Clone, serde::Serialize")]
+                struct S;
+            }
+
+            expected: {
+                #[derive(Debug)]
+                struct S;
+
+                #[automatically_derived]
+                #[allow(unused_qualifications, clippy::all)]
+                impl ::std::error::Error for S {}
+
+                #[automatically_derived]
+                #[allow(unused_qualifications, clippy::all)]
+                impl ::std::fmt::Display for S {
+                    fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+                        ::std::write!(f, "expensive")
+                    }
+                }
+
+                #[doc = " Wrapper around 'S' which renders its message only once."]
+                #[derive(Debug)]
+                #[derive(Clone, serde::Serialize)]
+                struct MemoizedS {
+                    error: S,
+                    message: ::std::sync::OnceLock<::std::string::String>,
+                }
+
+                impl S {
+                    #[doc = " Wrap this error, so its message is only rendered once."]
+                    pub fn memoized(self) -> MemoizedS {
+                        MemoizedS { error: self, message: ::std::sync::OnceLock::new() }
+                    }
+                }
+
+                impl MemoizedS {
+                    #[doc = " Return the wrapped error."]
+                    pub fn error(&self) -> &S {
+                        &self.error
+                    }
+
+                    #[doc = " Return the wrapped error and discard the cached message."]
+                    pub fn into_inner(self) -> S {
+                        self.error
+                    }
+                }
+
+                #[automatically_derived]
+                #[allow(unused_qualifications, clippy::all)]
+                impl ::std::fmt::Display for MemoizedS {
+                    fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+                        f.write_str(self.message.get_or_init(|| ::std::string::ToString::to_string(&self.error)))
+                    }
+                }
+
+                #[automatically_derived]
+                #[allow(unused_qualifications, clippy::all)]
+                impl ::std::error::Error for MemoizedS {
+                    fn source(&self) -> ::std::option::Option<&(dyn ::std::error::Error + 'static)> {
+                        ::std::error::Error::source(&self.error)
+                    }
+                }
+            }
+        )
+    }
+
+    #[test]
+    #[should_panic(expected = "The value of 'helper_derive' must be a comma separated list of paths, like \"Clone, serde::Serialize\".")]
+    fn invalid_helper_derive_should_panic() {
+        assert_struct_implementation_as_expected!(
+            item: {
+                #[error(message = "expensive", memoize_display, helper_derive = "Clone +")]
+                struct S;
+            }
+
+            expected: {
+                should panic
+            }
+        )
+    }
+
+    #[test]
+    fn derives() {
+        assert_struct_implementation_as_expected!(
+            item: {
+                #[error(message = "failed", derives = "Clone, PartialEq, Eq, std::hash::Hash")]
+                struct S;
+            }
+
+            expected: {
+                #[derive(Debug, Clone, PartialEq, Eq, std::hash::Hash)]
+                struct S;
+
+                #[automatically_derived]
+                #[allow(unused_qualifications, clippy::all)]
+                impl ::std::error::Error for S {}
+
+                #[automatically_derived]
+                #[allow(unused_qualifications, clippy::all)]
+                impl ::std::fmt::Display for S {
+                    fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+                        ::std::write!(f, "failed")
+                    }
+                }
+            }
+        )
+    }
+
+    #[test]
+    fn existing_debug_derive() {
+        assert_struct_implementation_as_expected!(
+            item: {
+                #[derive(Clone, std::fmt::Debug)]
+                #[error(message = "failed", derives = "PartialEq")]
+                struct S;
+            }
+
+            expected: {
+                #[derive(PartialEq)]
+                #[derive(Clone, std::fmt::Debug)]
+                struct S;
+
+                #[automatically_derived]
+                #[allow(unused_qualifications, clippy::all)]
+                impl ::std::error::Error for S {}
+
+                #[automatically_derived]
+                #[allow(unused_qualifications, clippy::all)]
+                impl ::std::fmt::Display for S {
+                    fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+                        ::std::write!(f, "failed")
+                    }
+                }
+            }
+        )
+    }
+
+    #[test]
+    #[should_panic(expected = "'S' derives Debug, but Debug is implemented by error_generator to print labels, redacted fields or the message. Please remove the derive of Debug or set 'debug = \"off\"'.")]
+    fn existing_debug_derive_with_manual_debug_should_panic() {
+        assert_struct_implementation_as_expected!(
+            item: {
+                #[error(message = "failed")]
+                #[derive(Debug)]
+                struct S {
+                    #[error(label = "config file")]
+                    path: String
+                }
+            }
+
+            expected: {
+                should panic
+            }
+        )
+    }
+
+    #[test]
+    fn debug_off() {
+        assert_struct_implementation_as_expected!(
+            item: {
+                #[error(message = "failed", debug = "off", derives = "Clone")]
+                struct S {
+                    #[error(label = "config file")]
+                    path: String
+                }
+            }
+
+            expected: {
+                #[derive(Clone)]
+                struct S {
+                    path: String
+                }
+
+                #[automatically_derived]
+                #[allow(unused_qualifications, clippy::all)]
+                impl ::std::error::Error for S {}
+
+                #[automatically_derived]
+                #[allow(unused_qualifications, clippy::all)]
+                impl ::std::fmt::Display for S {
+                    fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+                        ::std::write!(f, "failed")
+                    }
+                }
+            }
+        )
+    }
+
+    #[test]
+    fn debug_display() {
+        assert_struct_implementation_as_expected!(
+            item: {
+                #[error(message = "{self.0} failed", debug = "display")]
+                struct S<T: std::fmt::Display>(T);
+            }
+
+            expected: {
+                struct S<T: std::fmt::Display>(T);
+
+                #[automatically_derived]
+                #[allow(unused_qualifications, clippy::all)]
+                impl<T: std::fmt::Display> ::std::error::Error for S<T> where Self: ::std::fmt::Debug + ::std::fmt::Display {}
+                #[allow(unused_qualifications, clippy::all)]
+                impl<T: std::fmt::Display> ::std::fmt::Debug for S<T> {
+                    fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+                        ::std::fmt::Display::fmt(self, f)
+                    }
+                }
+
+                #[automatically_derived]
+                #[allow(unused_qualifications, clippy::all)]
+                impl<T: std::fmt::Display> ::std::fmt::Display for S<T> where T: ::std::fmt::Display {
+                    fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+                        ::std::write!(f, "{} failed", self.0)
+                    }
+                }
+            }
+        )
+    }
+
+    #[test]
+    #[should_panic(expected = "'debug = \"display\"' requires a message for 'S', as Debug prints the message.")]
+    fn debug_display_without_message_should_panic() {
+        assert_struct_implementation_as_expected!(
+            item: {
+                #[error(debug = "display")]
+                struct S;
+            }
+
+            expected: {
+                should panic
+            }
+        )
+    }
+
+    #[test]
+    #[should_panic(expected = "The value 'pretty' of 'debug' is unknown. Possible values are: off, display")]
+    fn unknown_debug_should_panic() {
+        assert_struct_implementation_as_expected!(
+            item: {
+                #[error(message = "failed", debug = "pretty")]
+                struct S;
+            }
+
+            expected: {
+                should panic
+            }
+        )
+    }
+
+    #[test]
+    #[should_panic(expected = "Debug is derived or implemented automatically and must not be listed in 'derives'.")]
+    fn debug_in_derives_should_panic() {
+        assert_struct_implementation_as_expected!(
+            item: {
+                #[error(message = "failed", derives = "Clone, Debug")]
+                struct S;
+            }
+
+            expected: {
+                should panic
+            }
+        )
+    }
+
+    #[test]
+    #[should_panic(expected = "'S' cannot memoize its message, as it has neither a message nor a custom formatter.")]
+    fn memoize_display_without_message_should_panic() {
+        assert_struct_implementation_as_expected!(
+            item: {
+                #[error(memoize_display)]
+                struct S;
+            }
+
+            expected: {
+                should panic
+            }
+        )
+    }
+
+    #[test]
+    fn labelled_fields_debug() {
+        assert_struct_implementation_as_expected!(
+            item: {
+                #[error]
+                struct S<T> {
+                    #[error(label = "config file")]
+                    path: T,
+                    line: usize
+                }
+            }
+
+            expected: {
+                struct S<T> {
+                    path: T,
+                    line: usize
+                }
+
+                #[automatically_derived]
+                #[allow(unused_qualifications, clippy::all)]
+                impl<T> ::std::error::Error for S<T> where Self: ::std::fmt::Debug + ::std::fmt::Display {}
+                #[allow(unused_qualifications, clippy::all)]
+                impl<T> ::std::fmt::Debug for S<T> where T: ::std::fmt::Debug {
+                    fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+                        f.debug_struct("S")
+                            .field("path (\"config file\")", &self.path)
+                            .field("line", &self.line)
+                            .finish()
+                    }
+                }
+            }
+        )
+    }
+
+    #[test]
+    fn debug_placeholder() {
+        assert_struct_implementation_as_expected!(
+            item: {
+                #[error(message = "invalid payload {self.payload} in {self.file}")]
+                struct S {
+                    #[error(debug_placeholder)]
+                    payload: Vec<u8>,
+                    file: String
+                }
+            }
+
+            expected: {
+                #[derive(Debug)]
+                struct S {
+                    payload: Vec<u8>,
+                    file: String
+                }
+
+                #[automatically_derived]
+                #[allow(unused_qualifications, clippy::all)]
+                impl ::std::error::Error for S {}
+
+                #[automatically_derived]
+                #[allow(unused_qualifications, clippy::all)]
+                impl ::std::fmt::Display for S {
+                    fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+                        ::std::write!(f, "invalid payload {:?} in {}", self.payload, self.file)
+                    }
+                }
+            }
+        )
+    }
+
+    #[test]
+    fn redacted_fields() {
+        assert_struct_implementation_as_expected!(
+            item: {
+                #[error(message = "login of {self.user} with {self.password} failed", redact_debug)]
+                struct S {
+                    user: String,
+                    #[error(redact)]
+                    password: String
+                }
+            }
+
+            expected: {
+                struct S {
+                    user: String,
+                    password: String
+                }
+
+                #[automatically_derived]
+                #[allow(unused_qualifications, clippy::all)]
+                impl ::std::error::Error for S {}
+                #[allow(unused_qualifications, clippy::all)]
+                impl ::std::fmt::Debug for S {
+                    fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+                        f.debug_struct("S")
+                            .field("user", &self.user)
+                            .field("password", &::std::format_args!("[REDACTED]"))
+                            .finish()
+                    }
+                }
+
+                #[automatically_derived]
+                #[allow(unused_qualifications, clippy::all)]
+                impl ::std::fmt::Display for S {
+                    fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+                        ::std::write!(f, "login of {} with [REDACTED] failed", self.user)
+                    }
+                }
+            }
+        )
+    }
+
+    #[cfg(not(feature = "i18n"))]
+    #[test]
+    #[should_panic(expected = "The parameter 'msg_key' requires the feature 'i18n' of error_generator.")]
+    fn msg_key_without_feature_should_panic() {
+        assert_struct_implementation_as_expected!(
+            item: {
+                #[error(message = "read of {self.path} failed", msg_key = "errors-read-file")]
+                struct S {
+                    path: String
+                }
+            }
+
+            expected: {
+                should panic
+            }
+        )
+    }
+
+    #[test]
+    fn transparent() {
+        assert_struct_implementation_as_expected!(
+            item: {
+                #[error]
+                #[repr(transparent)]
+                pub struct S(std::io::Error);
+            }
+
+            expected: {
+                #[derive(Debug)]
+                #[repr(transparent)]
+                pub struct S(std::io::Error);
+
+                #[automatically_derived]
+                #[allow(unused_qualifications, clippy::all)]
+                impl ::std::error::Error for S {
+                    fn source(&self) -> ::std::option::Option<&(dyn ::std::error::Error + 'static)> {
+                        ::std::error::Error::source(&self.0)
+                    }
+                }
+
+                #[automatically_derived]
+                #[allow(unused_qualifications, clippy::all)]
+                impl ::std::fmt::Display for S {
+                    fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+                        ::std::fmt::Display::fmt(&self.0, f)
+                    }
+                }
+
+                #[doc = " Converts a `std::io::Error` into a `S`, which allows using the ?-operator like"]
+                #[doc = ""]
+                #[doc = " ```ignore"]
+                #[doc = " fn example(result: Result<(), std::io::Error>) -> Result<(), S> {"]
+                #[doc = "     result?;"]
+                #[doc = "     Ok(())"]
+                #[doc = " }"]
+                #[doc = " ```"]
+                #[automatically_derived]
+                #[allow(unused_qualifications, clippy::all)]
+                impl ::std::convert::From<std::io::Error> for S {
+                    #[track_caller]
+                    fn from(val: std::io::Error) -> Self {
+                        S(val)
+                    }
+                }
+
+                impl S {
+                    #[doc = "The types this error can be created from with std::convert::From (and therefore the ?-operator)."]
+                    pub const FROM_TYPES: &'static [&'static str] = &["std::io::Error"];
+                }
+
+                impl S {
+                    #[doc = " Return the wrapped error."]
+                    pub fn into_inner(self) -> std::io::Error {
+                        self.0
+                    }
+                }
+            }
+        )
+    }
+
+    #[test]
+    fn transparent_with_message() {
+        assert_struct_implementation_as_expected!(
+            item: {
+                #[error(message = "I/O failed", no_from_docs)]
+                #[repr(transparent)]
+                struct S {
+                    inner: std::io::Error
+                }
+            }
+
+            expected: {
+                #[derive(Debug)]
+                #[repr(transparent)]
+                struct S {
+                    inner: std::io::Error
+                }
+
+                #[automatically_derived]
+                #[allow(unused_qualifications, clippy::all)]
+                impl ::std::error::Error for S {
+                    fn source(&self) -> ::std::option::Option<&(dyn ::std::error::Error + 'static)> {
+                        ::std::option::Option::Some(&self.inner)
+                    }
+                }
+
+                #[automatically_derived]
+                #[allow(unused_qualifications, clippy::all)]
+                impl ::std::fmt::Display for S {
+                    fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+                        {
+                            (::std::write!(f, "I/O failed"))?;
+
+                            if f.alternate() {
+                                let mut source = ::std::error::Error::source(self);
+                                while let ::std::option::Option::Some(error) = source {
+                                    ::std::write!(f, ": caused by: {}", error)?;
+                                    source = ::std::error::Error::source(error);
+                                }
+                            }
+
+                            ::std::result::Result::Ok(())
+                        }
+                    }
+                }
+
+                #[automatically_derived]
+                #[allow(unused_qualifications, clippy::all)]
+                impl ::std::convert::From<std::io::Error> for S {
+                    #[track_caller]
+                    fn from(val: std::io::Error) -> Self {
+                        S {
+                            inner: val
+                        }
+                    }
+                }
+
+                impl S {
+                    #[doc = "The types this error can be created from with std::convert::From (and therefore the ?-operator)."]
+                    pub const FROM_TYPES: &'static [&'static str] = &["std::io::Error"];
+                }
+
+                impl S {
+                    #[doc = " Return the wrapped error."]
+                    pub fn into_inner(self) -> std::io::Error {
+                        self.inner
+                    }
+                }
+            }
+        )
+    }
+
+    #[test]
+    fn transparent_generic_source() {
+        assert_struct_implementation_as_expected!(
+            item: {
+                #[error(message = "I/O failed", no_from_docs)]
+                #[repr(transparent)]
+                struct S<E>(E);
+            }
+
+            expected: {
+                #[derive(Debug)]
+                #[repr(transparent)]
+                struct S<E>(E);
+
+                #[automatically_derived]
+                #[allow(unused_qualifications, clippy::all)]
+                impl<E> ::std::error::Error for S<E> where Self: ::std::fmt::Debug + ::std::fmt::Display, E: ::std::error::Error + 'static {
+                    fn source(&self) -> ::std::option::Option<&(dyn ::std::error::Error + 'static)> {
+                        ::std::option::Option::Some(&self.0)
+                    }
+                }
+
+                #[automatically_derived]
+                #[allow(unused_qualifications, clippy::all)]
+                impl<E> ::std::fmt::Display for S<E> where E: ::std::error::Error + 'static {
+                    fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+                        {
+                            (::std::write!(f, "I/O failed"))?;
+
+                            if f.alternate() {
+                                let mut source = ::std::error::Error::source(self);
+                                while let ::std::option::Option::Some(error) = source {
+                                    ::std::write!(f, ": caused by: {}", error)?;
+                                    source = ::std::error::Error::source(error);
+                                }
+                            }
+
+                            ::std::result::Result::Ok(())
+                        }
+                    }
+                }
+
+                #[automatically_derived]
+                #[allow(unused_qualifications, clippy::all)]
+                impl<E> ::std::convert::From<E> for S<E> {
+                    #[track_caller]
+                    fn from(val: E) -> Self {
+                        S(val)
+                    }
+                }
+
+                impl<E> S<E> {
+                    #[doc = "The types this error can be created from with std::convert::From (and therefore the ?-operator)."]
+                    pub const FROM_TYPES: &'static [&'static str] = &["E"];
+                }
+
+                impl<E> S<E> {
+                    #[doc = " Return the wrapped error."]
+                    pub fn into_inner(self) -> E {
+                        self.0
+                    }
+                }
+            }
+        )
+    }
+
+    #[test]
+    #[should_panic(expected = "The struct 'S' is marked with '#[repr(transparent)]', so it must have exactly one field which is not skipped.")]
+    fn transparent_two_fields_should_panic() {
+        assert_struct_implementation_as_expected!(
+            item: {
+                #[error]
+                #[repr(transparent)]
+                struct S(std::io::Error, String);
+            }
+
+            expected: {
+                should panic
+            }
+        )
+    }
+
+    #[test]
+    fn into_inner() {
+        assert_struct_implementation_as_expected!(
+            item: {
+                #[error(into_inner)]
+                struct S {
+                    inner: std::io::Error,
+                    #[error(skip)]
+                    attempts: usize
+                }
+            }
+
+            expected: {
+                #[derive(Debug)]
+                struct S {
+                    inner: std::io::Error,
+                    attempts: usize
+                }
+
+                #[automatically_derived]
+                #[allow(unused_qualifications, clippy::all)]
+                impl ::std::error::Error for S {}
+
+                impl S {
+                    #[doc = " Return the wrapped error."]
+                    pub fn into_inner(self) -> std::io::Error {
+                        self.inner
+                    }
+                }
+            }
+        )
+    }
+
+    #[test]
+    #[should_panic(expected = "The parameter 'into_inner' requires the struct 'S' to have exactly one field which is not skipped.")]
+    fn into_inner_two_fields_should_panic() {
+        assert_struct_implementation_as_expected!(
+            item: {
+                #[error(into_inner)]
+                struct S(std::io::Error, String);
+            }
+
+            expected: {
+                should panic
+            }
+        )
+    }
+
+    #[test]
+    fn named_impl_from_and_display() {
+        assert_struct_implementation_as_expected!(
+            item: {
+                #[error(message = "My foo value: {self.foo}", impl_from)]
+                struct S {
+                    foo: usize
+                }
+            }
+
+            expected: {
+                #[derive(Debug)]
+                struct S {
+                    foo: usize
+                }
+
+                #[automatically_derived]
+                #[allow(unused_qualifications, clippy::all)]
+                impl ::std::error::Error for S {}
+
+                #[automatically_derived]
+                #[allow(unused_qualifications, clippy::all)]
+                impl ::std::fmt::Display for S {
+                    fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+                        ::std::write!(f, "My foo value: {}", self.foo)
+                    }
+                }
+
+                #[doc = " Converts a `usize` into a `S`, which allows using the ?-operator like"]
+                #[doc = ""]
+                #[doc = " ```ignore"]
+                #[doc = " fn example(result: Result<(), usize>) -> Result<(), S> {"]
+                #[doc = "     result?;"]
+                #[doc = "     Ok(())"]
+                #[doc = " }"]
+                #[doc = " ```"]
+                #[automatically_derived]
+                #[allow(unused_qualifications, clippy::all)]
+                impl ::std::convert::From<usize> for S {
+                    #[track_caller]
+                    fn from(val: usize) -> Self {
+                        S{ foo : val }
+                    }
+                }
+
+                impl S {
+                    #[doc = "The types this error can be created from with std::convert::From (and therefore the ?-operator)."]
+                    pub const FROM_TYPES: &'static [&'static str] = &["usize"];
+                }
+            }
+        )
+    }
+
+    #[test]
+    fn unnamed_impl_from_and_display() {
+        assert_struct_implementation_as_expected!(
+            item: {
+                #[error(message = "My single value: {self.0}", impl_from)]
+                struct S (usize);
+            }
+
+            expected: {
+                #[derive(Debug)]
+                struct S (usize);
+
+                #[automatically_derived]
+                #[allow(unused_qualifications, clippy::all)]
+                impl ::std::error::Error for S {}
+
+                #[automatically_derived]
+                #[allow(unused_qualifications, clippy::all)]
+                impl ::std::fmt::Display for S {
+                    fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+                        ::std::write!(f, "My single value: {}", self.0)
+                    }
+                }
+
+                #[doc = " Converts a `usize` into a `S`, which allows using the ?-operator like"]
+                #[doc = ""]
+                #[doc = " ```ignore"]
+                #[doc = " fn example(result: Result<(), usize>) -> Result<(), S> {"]
+                #[doc = "     result?;"]
+                #[doc = "     Ok(())"]
+                #[doc = " }"]
+                #[doc = " ```"]
+                #[automatically_derived]
+                #[allow(unused_qualifications, clippy::all)]
+                impl ::std::convert::From<usize> for S {
+                    #[track_caller]
+                    fn from(val: usize) -> Self {
+                        S(val)
+                    }
+                }
+
+                impl S {
+                    #[doc = "The types this error can be created from with std::convert::From (and therefore the ?-operator)."]
+                    pub const FROM_TYPES: &'static [&'static str] = &["usize"];
+                }
+            }
+        )
+    }
+
     /// Assert that the generated code for a given struct is as expected.
     ///
     /// Generates the code and compares the token streams (as strings) with
@@ -470,13 +2134,15 @@ mod tests {
                 let error_attribute_index = item_struct.attrs
                     .iter()
                     .enumerate()
-                    .find(|(_, a)|crate::common::attribute_is_error(a))
+                    .find(|(_, a)|$crate::common::attribute_is_error(a))
                     .expect("One attribute should be 'error'").0;
 
-                let attribute_args = crate::test_helper::extract_attribute_args(item_struct.attrs.remove(error_attribute_index));
-                let implementation_ts = crate::struct_error::implement(attribute_args, item_struct).to_string();
+                let attribute_args = $crate::test_helper::extract_attribute_args(item_struct.attrs.remove(error_attribute_index));
+                let implementation_ts = $crate::struct_error::implement(attribute_args, item_struct)
+                    .unwrap_or_else(|e| panic!("{}", e))
+                    .to_string();
                 let expected = quote::quote!($($expected)*).to_string();
-                crate::test_helper::assert_tokens_are_equal(implementation_ts, expected)
+                $crate::test_helper::assert_tokens_are_equal(implementation_ts, expected)
             }
         };
     }
@@ -0,0 +1,683 @@
+use std::fmt::Formatter;
+use std::iter::Peekable;
+use std::str::CharIndices;
+
+use proc_macro2::{Group, TokenTree};
+use quote::{quote, ToTokens};
+use syn::__private::TokenStream2;
+use syn::{Expr, Ident, Index};
+
+use crate::common::{REDACTED, tokens_to_readable_string};
+use crate::template::Segment::*;
+use crate::template::TemplateError::*;
+
+/// A parsed message template, like "Value {e.value()} is invalid".
+///
+/// The template consists of literal text and placeholders in braces. Outside of placeholders,
+/// doubled braces ("{{" and "}}") are escapes for literal braces, just like in the format! macro.
+/// A single closing brace outside of a placeholder is an error.
+///
+/// A placeholder is an expression with an optional format spec after the last colon which is not part of a path,
+/// like "{self.value:>8}" or "{e.ratio:.2}" (see is_format_spec). Braces, parentheses and brackets inside of placeholders
+/// are balanced, so blocks like "{if b {1} else {2}}" are possible. String and char literals in placeholders are skipped,
+/// so braces and colons in them (like in "{s.trim_matches('}')}") are no syntax.
+///
+/// Some names are placeholders for metadata of the crate using the error, which are resolved
+/// at compile time (see metadata_expression). With the feature 'placeholder_resolvers', placeholders
+/// can name a resolver function, like "{secs:duration}" (see placeholder_resolvers::resolve).
+#[derive(Debug)]
+pub struct Template {
+    segments: Vec<Segment>,
+}
+
+/// A part of a template.
+#[derive(Debug)]
+pub enum Segment {
+    /// Plain text, with escapes already resolved.
+    Literal(String),
+    /// An expression which is evaluated and displayed at this position.
+    Placeholder(Placeholder),
+}
+
+/// An expression in braces, displayed with the format spec after its colon (if any).
+#[derive(Debug)]
+pub struct Placeholder {
+    expression: TokenStream2,
+    /// The format spec without the colon, like ">8" or ".2?". Empty if the placeholder has none.
+    spec: String,
+}
+
+/// The formatting traits a format spec can end with, like '?' for Debug or 'x' for LowerHex.
+const FORMAT_TRAITS: &[&str] = &["?", "x?", "X?", "x", "X", "o", "b", "e", "E"];
+
+impl Template {
+    /// Parse the given message into a template.
+    ///
+    /// Fails if a placeholder is never closed, a closing brace closes no placeholder, a format spec is invalid or an
+    /// expression cannot be parsed into tokens. The errors contain the byte offset of the problem in the message.
+    pub fn parse(message: &str) -> Result<Self, TemplateError> {
+        let mut parser = Parser { message, chars: message.char_indices().peekable() };
+        let mut segments = vec![];
+        let mut literal = String::new();
+
+        while let Some((offset, c)) = parser.chars.next() {
+            match c {
+                '{' | '}' if parser.next_is(c) => {
+                    parser.chars.next();
+                    literal.push(c)
+                }
+                '{' => {
+                    if !literal.is_empty() {
+                        segments.push(Literal(std::mem::take(&mut literal)))
+                    }
+
+                    segments.push(Segment::Placeholder(parser.parse_placeholder(offset)?))
+                }
+                '}' => return Err(UnmatchedClosingBrace(offset)),
+                c => literal.push(c)
+            }
+        }
+
+        if !literal.is_empty() {
+            segments.push(Literal(literal))
+        }
+
+        Ok(Template { segments })
+    }
+
+    /// Return the expression for a metadata placeholder, if the given name is one:
+    /// - crate_name: the name of the crate (CARGO_PKG_NAME)
+    /// - crate_version: the version of the crate (CARGO_PKG_VERSION)
+    /// - build_profile: "debug" if debug assertions are enabled, "release" otherwise
+    fn metadata_expression(name: &str) -> Option<TokenStream2> {
+        match name {
+            "crate_name" => Some(quote! {::core::env!("CARGO_PKG_NAME")}),
+            "crate_version" => Some(quote! {::core::env!("CARGO_PKG_VERSION")}),
+            "build_profile" => Some(quote! {if ::core::cfg!(debug_assertions) { "debug" } else { "release" }}),
+            _ => None
+        }
+    }
+
+    /// Return the format string for this template, where every placeholder is replaced by
+    /// "{}" (or "{:spec}" with its format spec) and literal braces are escaped again. The given expressions
+    /// (written as readable strings, like "self.payload", see common::tokens_to_readable_string) are formatted
+    /// with Debug instead, unless their format spec already selects a formatting trait.
+    ///
+    /// If an expression is used more than once, every placeholder gets the index of its expression in the
+    /// arguments (see arguments), like "{0} {1} {0:?}" for "{line} {file} {line:?}".
+    pub fn format_string(&self, debug_expressions: &[String]) -> String {
+        let arguments = self.arguments();
+        let has_repetitions = arguments.len() < self.expressions().count();
+
+        self.segments
+            .iter()
+            .map(|s| match s {
+                Literal(text) => text.replace('{', "{{").replace('}', "}}"),
+                Segment::Placeholder(p) => {
+                    let has_trait = FORMAT_TRAITS.iter().any(|t| p.spec.ends_with(t));
+                    let is_debug = !has_trait && !debug_expressions.is_empty() && debug_expressions.contains(&tokens_to_readable_string(&p.expression));
+                    let index = match has_repetitions {
+                        true => arguments.iter().position(|a| a.to_string() == p.expression.to_string()).unwrap().to_string(),
+                        false => String::new()
+                    };
+                    match (p.spec.as_str(), is_debug) {
+                        ("", false) => format!("{{{}}}", index),
+                        (spec, false) => format!("{{{}:{}}}", index, spec),
+                        (spec, true) => format!("{{{}:{}?}}", index, spec)
+                    }
+                }
+            })
+            .collect()
+    }
+
+    /// Return the expressions which are passed to the format string in order, where repeated expressions
+    /// (like "line" in "{line} {line:?}") are only contained once, so they are evaluated once.
+    pub fn arguments(&self) -> Vec<&TokenStream2> {
+        let mut arguments: Vec<&TokenStream2> = vec![];
+
+        for expression in self.expressions() {
+            if !arguments.iter().any(|a| a.to_string() == expression.to_string()) {
+                arguments.push(expression)
+            }
+        }
+
+        arguments
+    }
+
+    /// Return the expression of every placeholder together with the name of the formatting trait its format spec selects,
+    /// like "LowerHex" for "{e.id:x}". Placeholders without a trait in their spec are formatted with "Display".
+    pub fn formatting_traits(&self) -> Vec<(&TokenStream2, &'static str)> {
+        self.segments
+            .iter()
+            .filter_map(|s| match s {
+                Literal(_) => None,
+                Segment::Placeholder(p) => Some((&p.expression, formatting_trait(&p.spec)))
+            })
+            .collect()
+    }
+
+    /// Return the members accessed on the variable with the given name, like 'val' for "{e.val.len()}"
+    /// and the name 'e'. Only chains of field accesses and method calls are checked.
+    pub fn members_accessed_on(&self, name: &str) -> Vec<String> {
+        self.expressions()
+            .filter_map(|e| syn::parse2::<Expr>(e.clone()).ok())
+            .filter_map(|e| Self::member_accessed_on(&e, name))
+            .collect()
+    }
+
+    fn member_accessed_on(expr: &Expr, name: &str) -> Option<String> {
+        match expr {
+            Expr::Field(field) => match &*field.base {
+                Expr::Path(path) if path.path.is_ident(name) => Some(field.member.to_token_stream().to_string()),
+                base => Self::member_accessed_on(base, name)
+            },
+            Expr::MethodCall(call) => Self::member_accessed_on(&call.receiver, name),
+            Expr::Reference(reference) => Self::member_accessed_on(&reference.expr, name),
+            Expr::Paren(paren) => Self::member_accessed_on(&paren.expr, name),
+            _ => None
+        }
+    }
+
+    /// Return the variables the expressions are based on, like 'path' for "{path.display()}" or 'self' for "{self.line}".
+    /// Only chains of field accesses and method calls are checked, so calls (like "{format(path)}") are not included.
+    pub fn variables(&self) -> Vec<String> {
+        self.expressions()
+            .filter_map(|e| syn::parse2::<Expr>(e.clone()).ok())
+            .filter_map(|e| Self::variable_of(&e))
+            .collect()
+    }
+
+    fn variable_of(expr: &Expr) -> Option<String> {
+        match expr {
+            Expr::Path(path) if path.qself.is_none() => path.path.get_ident().map(Ident::to_string),
+            Expr::Field(field) => Self::variable_of(&field.base),
+            Expr::MethodCall(call) => Self::variable_of(&call.receiver),
+            Expr::Reference(reference) => Self::variable_of(&reference.expr),
+            Expr::Paren(paren) => Self::variable_of(&paren.expr),
+            _ => None
+        }
+    }
+
+    /// Return the indices of all positional placeholders, like 1 for "{1}" or "{1:>8}".
+    pub fn positional_indices(&self) -> Vec<usize> {
+        self.expressions()
+            .filter_map(Self::positional_index)
+            .collect()
+    }
+
+    /// Replace every positional placeholder with an access of its index on the variable with the given name,
+    /// like "{1}" with "{e.1}", so it is handled like other accesses of unnamed fields.
+    pub fn resolve_positional_placeholders(&mut self, name: &str) {
+        for segment in self.segments.iter_mut() {
+            if let Segment::Placeholder(p) = segment {
+                if let Some(index) = Self::positional_index(&p.expression) {
+                    let span = p.expression.clone().into_iter().next().unwrap().span();
+                    let variable = Ident::new(name, span);
+                    let index = Index { index: index as u32, span };
+                    p.expression = quote! {#variable.#index}
+                }
+            }
+        }
+    }
+
+    /// Return the index of the given expression, if it is a positional placeholder (an integer literal without suffix).
+    fn positional_index(expression: &TokenStream2) -> Option<usize> {
+        let mut tokens = expression.clone().into_iter();
+        match (tokens.next(), tokens.next()) {
+            (Some(TokenTree::Literal(literal)), None) => match syn::Lit::new(literal) {
+                syn::Lit::Int(int) if int.suffix().is_empty() => int.base10_parse().ok(),
+                _ => None
+            },
+            _ => None
+        }
+    }
+
+    /// Replace every access of a listed member on the variable with the given name by the listed replacement,
+    /// like "e.path.display()" by "path.display()" for the member "path" and the name "e".
+    /// Method calls (like "e.path()") and members which are not listed are kept.
+    pub fn replace_member_accesses(&mut self, name: &str, replacements: &[(String, TokenStream2)]) {
+        if replacements.is_empty() {
+            return;
+        }
+
+        for segment in self.segments.iter_mut() {
+            if let Segment::Placeholder(p) = segment {
+                p.expression = Self::replace_member_accesses_in(std::mem::take(&mut p.expression), name, replacements)
+            }
+        }
+    }
+
+    fn replace_member_accesses_in(expression: TokenStream2, name: &str, replacements: &[(String, TokenStream2)]) -> TokenStream2 {
+        let tokens = expression.into_iter().collect::<Vec<_>>();
+        let mut result = TokenStream2::new();
+        let mut i = 0;
+
+        while i < tokens.len() {
+            if let Some(replacement) = Self::member_replacement(&tokens, i, name, replacements) {
+                result.extend(replacement.clone());
+                i += 3;
+                continue;
+            }
+
+            match &tokens[i] {
+                TokenTree::Group(group) => {
+                    let mut replaced = Group::new(group.delimiter(), Self::replace_member_accesses_in(group.stream(), name, replacements));
+                    replaced.set_span(group.span());
+                    result.extend(Some(TokenTree::Group(replaced)))
+                }
+                token => result.extend(Some(token.clone()))
+            }
+            i += 1
+        }
+
+        result
+    }
+
+    /// Return the replacement if the tokens at the given index access a listed member on the variable with the given name.
+    fn member_replacement<'r>(tokens: &[TokenTree], i: usize, name: &str, replacements: &'r [(String, TokenStream2)]) -> Option<&'r TokenStream2> {
+        let is_variable = Self::is_variable(tokens, i, name);
+        let is_method_call = matches!(tokens.get(i + 3), Some(TokenTree::Group(g)) if g.delimiter() == proc_macro2::Delimiter::Parenthesis);
+        if !is_variable || !is_punct(tokens.get(i + 1), '.') || is_method_call {
+            return None;
+        }
+
+        let member = match tokens.get(i + 2)? {
+            TokenTree::Ident(ident) => ident.to_string(),
+            TokenTree::Literal(literal) => literal.to_string(),
+            _ => return None
+        };
+
+        replacements
+            .iter()
+            .find(|(m, _)| *m == member)
+            .map(|(_, replacement)| replacement)
+    }
+
+    /// Replace every expression which uses one of the given accesses with the literal text REDACTED.
+    /// An access is a variable with an optional member, like 'password' for the binding of a field
+    /// or 'self' and "password" for "self.password". Method calls on an access are also replaced.
+    pub fn redact(&mut self, accesses: &[(String, Option<String>)]) {
+        if accesses.is_empty() {
+            return;
+        }
+
+        for segment in self.segments.iter_mut() {
+            if matches!(segment, Segment::Placeholder(p) if Self::uses_any_access(p.expression.clone(), accesses)) {
+                *segment = Literal(REDACTED.to_string())
+            }
+        }
+    }
+
+    /// Check if any expression of this template uses one of the given accesses (see redact).
+    pub fn uses_any(&self, accesses: &[(String, Option<String>)]) -> bool {
+        self.expressions().any(|e| Self::uses_any_access(e.clone(), accesses))
+    }
+
+    fn uses_any_access(expression: TokenStream2, accesses: &[(String, Option<String>)]) -> bool {
+        let tokens = expression.into_iter().collect::<Vec<_>>();
+
+        (0..tokens.len()).any(|i| match &tokens[i] {
+            TokenTree::Group(group) => Self::uses_any_access(group.stream(), accesses),
+            _ => accesses.iter().any(|(name, member)| Self::is_variable(&tokens, i, name) && match member {
+                Some(member) => is_punct(tokens.get(i + 1), '.') && matches!(tokens.get(i + 2), Some(t) if t.to_string() == *member),
+                None => true
+            })
+        })
+    }
+
+    /// Check if the token at the given index is the variable with the given name and not a member or path segment.
+    fn is_variable(tokens: &[TokenTree], i: usize, name: &str) -> bool {
+        matches!(&tokens[i], TokenTree::Ident(ident) if ident == name)
+            && !(i > 0 && (is_punct(tokens.get(i - 1), '.') || is_punct(tokens.get(i - 1), ':')))
+    }
+
+    /// Check if this template contains any text besides its expressions (whitespace is not counted).
+    #[cfg(feature = "message_registry")]
+    pub fn has_literal_text(&self) -> bool {
+        self.segments
+            .iter()
+            .any(|s| matches!(s, Literal(text) if !text.trim().is_empty()))
+    }
+
+    /// Return all expressions of this template in order.
+    pub fn expressions(&self) -> impl Iterator<Item=&TokenStream2> {
+        self.segments
+            .iter()
+            .filter_map(|s| match s {
+                Literal(_) => None,
+                Segment::Placeholder(p) => Some(&p.expression)
+            })
+    }
+}
+
+fn is_punct(token: Option<&TokenTree>, c: char) -> bool {
+    matches!(token, Some(TokenTree::Punct(p)) if p.as_char() == c)
+}
+
+/// The state of Template::parse: the message and the characters (with their byte offsets) which are not parsed yet.
+struct Parser<'m> {
+    message: &'m str,
+    chars: Peekable<CharIndices<'m>>,
+}
+
+impl Parser<'_> {
+    /// Parse a placeholder until the brace matching the already consumed opening brace at the given offset.
+    fn parse_placeholder(&mut self, open: usize) -> Result<Placeholder, TemplateError> {
+        let mut braces = 1;
+        let mut nesting = 0;
+        let mut spec_colon = None;
+
+        while let Some((offset, c)) = self.chars.next() {
+            match c {
+                '"' => self.skip_string(open)?,
+                '\'' => self.skip_char(),
+                '{' => {
+                    braces += 1;
+                    nesting += 1
+                }
+                '}' if braces == 1 => return self.create_placeholder(open, offset, spec_colon),
+                '}' => {
+                    braces -= 1;
+                    nesting -= 1
+                }
+                '(' | '[' => nesting += 1,
+                ')' | ']' => nesting -= 1,
+                ':' if self.next_is(':') => {
+                    self.chars.next();
+                }
+                ':' if nesting == 0 => spec_colon = Some(offset),
+                _ => {}
+            }
+        }
+
+        Err(UnclosedExpression(open))
+    }
+
+    /// Create the placeholder between the braces at the given offsets. The text after the colon at the given offset
+    /// is its format spec, unless it is the name of a placeholder resolver (like "duration" in "{secs:duration}").
+    fn create_placeholder(&self, open: usize, close: usize, spec_colon: Option<usize>) -> Result<Placeholder, TemplateError> {
+        let (expression, spec) = match spec_colon {
+            Some(colon) => {
+                let spec = &self.message[colon + 1..close];
+                match (is_format_spec(spec), syn::parse_str::<Ident>(spec.trim()).is_ok()) {
+                    (true, _) => (&self.message[open + 1..colon], spec),
+                    (false, true) => (&self.message[open + 1..close], ""),
+                    (false, false) => return Err(InvalidFormatSpec(spec.to_string(), colon + 1))
+                }
+            }
+            None => (&self.message[open + 1..close], "")
+        };
+
+        Ok(Placeholder { expression: Self::parse_expression(expression, open)?, spec: spec.to_string() })
+    }
+
+    /// Parse the expression of the placeholder which starts at the given offset into tokens.
+    fn parse_expression(expression: &str, open: usize) -> Result<TokenStream2, TemplateError> {
+        if let Some(metadata) = Template::metadata_expression(expression.trim()) {
+            return Ok(metadata);
+        }
+
+        #[cfg(feature = "placeholder_resolvers")]
+        if let Some(resolved) = crate::placeholder_resolvers::resolve(expression.trim(), open)? {
+            return Ok(resolved);
+        }
+
+        expression
+            .parse::<TokenStream2>()
+            .map_err(|_| InvalidExpression(expression.to_string(), open))
+    }
+
+    /// Skip a string literal in the placeholder starting at the given offset until its unescaped closing quote.
+    fn skip_string(&mut self, open: usize) -> Result<(), TemplateError> {
+        while let Some((_, c)) = self.chars.next() {
+            match c {
+                '\\' => {
+                    self.chars.next();
+                }
+                '"' => return Ok(()),
+                _ => {}
+            }
+        }
+
+        Err(UnclosedExpression(open))
+    }
+
+    /// Skip a char literal, like '}' or '\''. Lifetimes (like 'a) are kept, as they are no literals.
+    fn skip_char(&mut self) {
+        let mut ahead = self.chars.clone().map(|(_, c)| c);
+        let length = match (ahead.next(), ahead.next()) {
+            (Some('\\'), _) => 2 + self.chars.clone().skip(2).take_while(|(_, c)| *c != '\'').count(),
+            (Some(_), Some('\'')) => 2,
+            _ => 0
+        };
+
+        for _ in 0..length {
+            self.chars.next();
+        }
+    }
+
+    fn next_is(&mut self, c: char) -> bool {
+        matches!(self.chars.peek(), Some((_, next)) if *next == c)
+    }
+}
+
+/// Check if the given text is a format spec of the format! macro without named or positional arguments, like ">8", "+.2e"
+/// or "#x?": '[[fill]align][sign]['#']['0'][width]['.' precision][trait]'.
+fn is_format_spec(spec: &str) -> bool {
+    let is_align = |c: char| matches!(c, '<' | '^' | '>');
+    let mut chars = spec.chars();
+    let mut rest = match (chars.next(), chars.next()) {
+        (Some(_), Some(c)) if is_align(c) => chars.as_str(),
+        (Some(c), _) if is_align(c) => &spec[1..],
+        _ => spec
+    };
+
+    rest = rest.strip_prefix(|c| c == '+' || c == '-').unwrap_or(rest);
+    rest = rest.strip_prefix('#').unwrap_or(rest);
+    rest = rest.trim_start_matches(|c: char| c.is_ascii_digit());
+
+    if let Some(precision) = rest.strip_prefix('.') {
+        rest = precision.trim_start_matches(|c: char| c.is_ascii_digit());
+        if rest.len() == precision.len() {
+            return false;
+        }
+    }
+
+    rest.is_empty() || FORMAT_TRAITS.contains(&rest)
+}
+
+/// Return the name of the formatting trait the given format spec selects, like "Debug" for ">8?".
+fn formatting_trait(spec: &str) -> &'static str {
+    let format_trait = FORMAT_TRAITS
+        .iter()
+        .find(|t| spec.ends_with(*t))
+        .copied()
+        .unwrap_or_default();
+
+    match format_trait {
+        "?" | "x?" | "X?" => "Debug",
+        "x" => "LowerHex",
+        "X" => "UpperHex",
+        "o" => "Octal",
+        "b" => "Binary",
+        "e" => "LowerExp",
+        "E" => "UpperExp",
+        _ => "Display"
+    }
+}
+
+/// Error that occurs if a message cannot be parsed into a template.
+#[derive(Debug)]
+pub enum TemplateError {
+    /// The opening brace of a placeholder at the contained offset has no matching closing brace.
+    UnclosedExpression(usize),
+    /// A closing brace at the contained offset closes no placeholder and is not escaped.
+    UnmatchedClosingBrace(usize),
+    /// The expression of the placeholder at the contained offset is no valid token stream.
+    InvalidExpression(String, usize),
+    /// The text after the colon at the contained offset is no valid format spec.
+    InvalidFormatSpec(String, usize),
+    /// A resolver of a placeholder is not registered or the registry is invalid, with a description of the problem.
+    #[cfg(feature = "placeholder_resolvers")]
+    InvalidResolver(String),
+}
+
+impl std::error::Error for TemplateError {}
+
+impl std::fmt::Display for TemplateError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            UnclosedExpression(offset) => write!(f, "The message contains an expression at byte {} which is never closed. Use '{{{{' to write a literal brace.", offset),
+            UnmatchedClosingBrace(offset) => write!(f, "The message contains a closing brace at byte {} which closes no expression. Use '}}}}' to write a literal brace.", offset),
+            InvalidExpression(e, offset) => write!(f, "The expression '{}' at byte {} in the message is invalid.", e, offset),
+            InvalidFormatSpec(spec, offset) => write!(f, "'{}' at byte {} in the message is no valid format spec, like '>8' or '.2'.", spec, offset),
+            #[cfg(feature = "placeholder_resolvers")]
+            InvalidResolver(description) => write!(f, "{}", description)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use quote::quote;
+
+    use crate::template::{Segment, Template, TemplateError};
+
+    #[test]
+    fn parse_works() {
+        let template = Template::parse("value: {e.value()}!").unwrap();
+        let segments = &template.segments;
+
+        assert_eq!(segments.len(), 3);
+        assert!(matches!(&segments[0], Segment::Literal(l) if l == "value: "));
+        assert!(matches!(&segments[1], Segment::Placeholder(p) if p.expression.to_string() == "e . value ()" && p.spec.is_empty()));
+        assert!(matches!(&segments[2], Segment::Literal(l) if l == "!"));
+    }
+
+    #[test]
+    fn parse_nested_braces_works() {
+        let template = Template::parse("{if b {1} else {2}}").unwrap();
+        assert_eq!(template.expressions().count(), 1);
+        assert_eq!(template.format_string(&[]), "{}")
+    }
+
+    #[test]
+    fn format_string_with_debug_expressions_works() {
+        let template = Template::parse("{payload} and {self.payload} and {other}").unwrap();
+        assert_eq!(template.format_string(&["payload".to_string(), "self.payload".to_string()]), "{:?} and {:?} and {}")
+    }
+
+    #[test]
+    fn formatting_traits_works() {
+        let template = Template::parse("{a} {b:?} {c:>8x?} {d:#x} {e:X} {f:.2e} {g:>8}").unwrap();
+        let traits = template.formatting_traits().into_iter().map(|(_, t)| t).collect::<Vec<_>>();
+        assert_eq!(traits, vec!["Display", "Debug", "Debug", "LowerHex", "UpperHex", "LowerExp", "Display"])
+    }
+
+    #[test]
+    fn format_string_escapes_braces() {
+        let template = Template::parse("{{literal}} {x}").unwrap();
+        assert!(matches!(&template.segments[0], Segment::Literal(l) if l == "{literal} "));
+        assert_eq!(template.format_string(&[]), "{{literal}} {}")
+    }
+
+    #[test]
+    fn parse_metadata_placeholders_works() {
+        let template = Template::parse("v{crate_version} ({ build_profile })").unwrap();
+        let expressions = template.expressions().map(|e| e.to_string()).collect::<Vec<_>>();
+        assert_eq!(expressions[0], r#":: core :: env ! ("CARGO_PKG_VERSION")"#);
+        assert!(expressions[1].starts_with("if :: core :: cfg ! (debug_assertions)"))
+    }
+
+    #[test]
+    fn replace_member_accesses_works() {
+        let mut template = Template::parse("{e.path.display()} {e.0} {(e.line + 1)} {e.line()} {x.e.line} {e.other}").unwrap();
+        template.replace_member_accesses("e", &[("path".to_string(), quote!(path)), ("0".to_string(), quote!(_0)), ("line".to_string(), quote!(line))]);
+
+        let expressions = template.expressions().map(|e| e.to_string()).collect::<Vec<_>>();
+        assert_eq!(expressions, vec!["path . display ()", "_0", "(line + 1)", "e . line ()", "x . e . line", "e . other"])
+    }
+
+    #[test]
+    fn redact_works() {
+        let mut template = Template::parse("{self.password} {self.password.len()} {self.user} {password} {x.password}").unwrap();
+        template.redact(&[("self".to_string(), Some("password".to_string())), ("password".to_string(), None)]);
+
+        let expressions = template.expressions().map(|e| e.to_string()).collect::<Vec<_>>();
+        assert_eq!(expressions, vec!["self . user", "x . password"]);
+        assert_eq!(template.format_string(&[]), "[REDACTED] [REDACTED] {} [REDACTED] {}")
+    }
+
+    #[test]
+    fn members_accessed_on_works() {
+        let template = Template::parse("{e.val} {e.0.len()} {&e.other} {self.val} {val}").unwrap();
+        assert_eq!(template.members_accessed_on("e"), vec!["val", "0", "other"])
+    }
+
+    #[test]
+    fn variables_works() {
+        let template = Template::parse("{path.display()} {&self.line} {(e.0)} {format(x)} {MAX} {std::u8::MAX} {1 + y}").unwrap();
+        assert_eq!(template.variables(), vec!["path", "self", "e", "MAX"])
+    }
+
+    #[test]
+    fn resolve_positional_placeholders_works() {
+        let mut template = Template::parse("{0} {1:>4} {2u8} {0.5} {e.1}").unwrap();
+        assert_eq!(template.positional_indices(), vec![0, 1]);
+
+        template.resolve_positional_placeholders("e");
+        let expressions = template.expressions().map(|e| e.to_string()).collect::<Vec<_>>();
+        assert_eq!(expressions, vec!["e . 0", "e . 1", "2u8", "0.5", "e . 1"]);
+        assert_eq!(template.format_string(&[]), "{0} {1:>4} {2} {3} {1}")
+    }
+
+    #[test]
+    fn repeated_expressions_are_arguments_once() {
+        let template = Template::parse("{line} {file} {line:?} {line}").unwrap();
+        let arguments = template.arguments().into_iter().map(|e| e.to_string()).collect::<Vec<_>>();
+        assert_eq!(arguments, vec!["line", "file"]);
+        assert_eq!(template.format_string(&["file".to_string()]), "{0} {1:?} {0:?} {0}")
+    }
+
+    #[test]
+    fn parse_unclosed_expression_fails() {
+        assert!(matches!(Template::parse("value: {e.foo()"), Err(TemplateError::UnclosedExpression(7))));
+        assert!(matches!(Template::parse(r#"{e.trim_matches("}")"#), Err(TemplateError::UnclosedExpression(0))))
+    }
+
+    #[test]
+    fn parse_invalid_expression_fails() {
+        assert!(matches!(Template::parse("a {e.foo(}"), Err(TemplateError::InvalidExpression(e, 2)) if e == "e.foo("))
+    }
+
+    #[test]
+    fn parse_unmatched_closing_brace_fails() {
+        assert!(matches!(Template::parse("{{a}} b}"), Err(TemplateError::UnmatchedClosingBrace(7))))
+    }
+
+    #[test]
+    fn parse_format_specs_works() {
+        let template = Template::parse("{self.value:>8} {e.ratio:.2} {x:#x?} {y:*^+#010.3e} {z:}").unwrap();
+        assert_eq!(template.expressions().map(|e| e.to_string()).collect::<Vec<_>>(), vec!["self . value", "e . ratio", "x", "y", "z"]);
+        assert_eq!(template.format_string(&[]), "{:>8} {:.2} {:#x?} {:*^+#010.3e} {}")
+    }
+
+    #[test]
+    fn parse_colons_without_format_spec_works() {
+        let template = Template::parse(r#"{std::u8::MAX} {<u8 as Default>::default()} {m[&a]} {f(b':')} {s.split(":").count()} {s.trim_matches('}')}"#).unwrap();
+        assert_eq!(template.expressions().count(), 6);
+        assert_eq!(template.format_string(&[]), "{} {} {} {} {} {}")
+    }
+
+    #[test]
+    fn format_string_with_debug_expressions_and_specs_works() {
+        let template = Template::parse("{payload:>8} {self.payload:x} {other:5}").unwrap();
+        assert_eq!(template.format_string(&["payload".to_string(), "self.payload".to_string()]), "{:>8?} {:x} {:5}")
+    }
+
+    #[test]
+    fn parse_invalid_format_spec_fails() {
+        assert!(matches!(Template::parse("{e.ratio:.}"), Err(TemplateError::InvalidFormatSpec(s, 9)) if s == "."));
+        assert!(matches!(Template::parse("{e.ratio:>8 }"), Err(TemplateError::InvalidFormatSpec(s, 9)) if s == ">8 "))
+    }
+}
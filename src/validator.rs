@@ -0,0 +1,172 @@
+use std::fmt::Formatter;
+
+use syn::__private::Span;
+
+use crate::parameters::*;
+use crate::validator::ParameterLocation::*;
+use crate::validator::ValidationError::*;
+
+/// Parameters which can be used on structs.
+const STRUCT_PARAMETERS: &[&str] = &[MESSAGE, IMPL_FROM, NO_FROM_DOCS, FMT, NO_STD, ALLOC, EXIT_CODE, MEMOIZE_DISPLAY, ERRNO, STATUS, HELPER_DERIVE, GRPC_CODE, RUNTIME_VERBOSITY, SERIALIZE, JSON, AGGREGATE, TRACE, LOG, WIRE, RETRY_AFTER, INTO_INNER, DYN_ERROR, REDACT_DEBUG, MSG_KEY, MESSAGE_CONST, CHAIN, SEVERITY, CATEGORY, RETRYABLE, DEFMT, DERIVES, DEBUG, MESSAGE_DOCS, DEBUG_OUTPUT, STRICT, RENDER_INTO];
+/// Parameters which can be used on enums.
+const ENUM_PARAMETERS: &[&str] = &[MESSAGE, IMPL_FROM, ASSERT_MACRO, AUTO_FROM_TYPES, NO_FROM_DOCS, FMT, NO_STD, ALLOC, EXIT_CODE, MEMOIZE_DISPLAY, ERRNO, STATUS, HELPER_DERIVE, GRPC_CODE, RUNTIME_VERBOSITY, SERIALIZE, JSON, MATCHER_MACRO, AGGREGATE, TRACE, LOG, WRAP_MAP, WIRE, CONSTRUCTORS, CATALOG, GENERATE_FROM_TESTS, PREDICATES, RETRY_AFTER, VARIANT_NAME, GETTERS, INTO_INNER, KIND, DYN_ERROR, INCLUDES, VARIANT_STRUCTS, REDACT_DEBUG, PREFIX, SUFFIX, CHAIN, SEVERITY, CATEGORY, RETRYABLE, FFI, DEFMT, DERIVES, DEBUG, MESSAGE_DOCS, DEBUG_OUTPUT, STRICT, NON_EXHAUSTIVE, RENDER_INTO];
+/// Parameters which can be used on enum variants.
+const VARIANT_PARAMETERS: &[&str] = &[MESSAGE, IMPL_FROM, NO_FROM, IMPL_FROM_FOR, EXIT_CODE, ERRNO, STATUS, GRPC_CODE, LOG, RETRY_AFTER, DEPRECATED_FROM, FORWARD, FROM_IO, OTHER, MSG_KEY, MESSAGE_CONST, SEVERITY, CATEGORY, RETRYABLE];
+/// Parameters which can be used on fields of structs.
+const FIELD_PARAMETERS: &[&str] = &[LABEL, SKIP, FROM, DEFAULT, SOURCE_TYPE, DEBUG_PLACEHOLDER, REDACT];
+
+/// The places where the error attribute (and therefore parameters) can be used.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum ParameterLocation {
+    Struct,
+    Enum,
+    Variant,
+    Field,
+    /// Modules, whose parameters are the defaults of all structs and enums in them.
+    Module,
+}
+
+impl ParameterLocation {
+    fn allowed_parameters(&self) -> Vec<&'static str> {
+        match self {
+            Struct => STRUCT_PARAMETERS.to_vec(),
+            Enum => ENUM_PARAMETERS.to_vec(),
+            Variant => VARIANT_PARAMETERS.to_vec(),
+            Field => FIELD_PARAMETERS.to_vec(),
+            Module => {
+                let mut parameters = ENUM_PARAMETERS.to_vec();
+                parameters.extend(STRUCT_PARAMETERS.iter().filter(|p| !ENUM_PARAMETERS.contains(p)));
+                parameters
+            }
+        }
+    }
+
+    /// Check if the parameter with the given name can be used at this location.
+    pub fn allows(&self, name: &str) -> bool {
+        self.allowed_parameters().contains(&name)
+    }
+}
+
+impl std::fmt::Display for ParameterLocation {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Struct => write!(f, "structs"),
+            Enum => write!(f, "enums"),
+            Variant => write!(f, "enum variants"),
+            Field => write!(f, "fields"),
+            Module => write!(f, "modules")
+        }
+    }
+}
+
+/// Checks if the parameters of an error attribute are known and
+/// allowed at the location of the attribute.
+pub struct Validator {
+    location: ParameterLocation,
+}
+
+impl Validator {
+    pub fn new(location: ParameterLocation) -> Self {
+        Validator { location }
+    }
+
+    /// Validate the given parameters. The parameters are checked in alphabetical
+    /// order, so the first invalid one is reported.
+    pub fn validate(&self, parameters: &Parameters) -> Result<(), ValidationError> {
+        let mut names = parameters.name_iter().collect::<Vec<_>>();
+        names.sort_unstable();
+
+        for name in names {
+            let span = parameters.span_for_name(name).unwrap_or_else(Span::call_site);
+            self.validate_parameter(name, span)?;
+        }
+
+        Ok(())
+    }
+
+    fn validate_parameter(&self, name: &str, span: Span) -> Result<(), ValidationError> {
+        if self.location.allows(name) {
+            return Ok(());
+        }
+
+        match Self::is_known(name) {
+            true => Err(MisplacedParameter(name.to_string(), self.location, span)),
+            false => Err(UnknownParameter(name.to_string(), self.location, span))
+        }
+    }
+
+    fn is_known(name: &str) -> bool {
+        [Struct, Enum, Variant, Field]
+            .iter()
+            .any(|l| l.allows(name))
+    }
+}
+
+/// Error that occurs if a parameter is unknown or used at the wrong location.
+#[derive(Debug)]
+pub enum ValidationError {
+    /// The parameter does not exist at all (probably a typo).
+    UnknownParameter(String, ParameterLocation, Span),
+    /// The parameter exists, but is not allowed at this location.
+    MisplacedParameter(String, ParameterLocation, Span),
+}
+
+impl ValidationError {
+    /// Return the span of the parameter which caused this error.
+    pub fn span(&self) -> Span {
+        match self {
+            UnknownParameter(_, _, span) => *span,
+            MisplacedParameter(_, _, span) => *span
+        }
+    }
+}
+
+impl std::error::Error for ValidationError {}
+
+impl std::fmt::Display for ValidationError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            UnknownParameter(name, location, _) => write!(f, "Unknown parameter '{}'. Valid parameters on {} are: {}", name, location, location.allowed_parameters().join(", ")),
+            MisplacedParameter(name, location, _) => write!(f, "The parameter '{}' is not allowed on {}.", name, location)
+        }
+    }
+}
+
+impl From<ValidationError> for syn::Error {
+    fn from(e: ValidationError) -> Self {
+        syn::Error::new(e.span(), e)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use syn::{Attribute, parse_quote};
+
+    use crate::parameters::Parameters;
+    use crate::validator::{ParameterLocation, Validator};
+
+    #[test]
+    fn validate_works() {
+        let attribute: Attribute = parse_quote!(#[error(message = "foo", impl_from)]);
+        assert!(validate(attribute, ParameterLocation::Struct).is_ok())
+    }
+
+    #[test]
+    fn validate_unknown_parameter_fails() {
+        let attribute: Attribute = parse_quote!(#[error(mesage = "foo")]);
+        let error = validate(attribute, ParameterLocation::Enum).err().unwrap();
+        assert!(error.to_string().starts_with("Unknown parameter 'mesage'. Valid parameters on enums are: message, impl_from"))
+    }
+
+    #[test]
+    fn validate_misplaced_parameter_fails() {
+        let attribute: Attribute = parse_quote!(#[error(no_from)]);
+        let error = validate(attribute, ParameterLocation::Struct).err().unwrap();
+        assert_eq!(error.to_string(), "The parameter 'no_from' is not allowed on structs.")
+    }
+
+    fn validate(attribute: Attribute, location: ParameterLocation) -> Result<(), super::ValidationError> {
+        let parameters = Parameters::from_attribute(&attribute).unwrap();
+        Validator::new(location).validate(&parameters)
+    }
+}
@@ -1,3 +1,5 @@
+extern crate alloc;
+
 use std::error::Error;
 use std::fmt::{Debug, Display, Formatter};
 
@@ -36,6 +38,7 @@ fn tuple_works() {
 #[test]
 fn tuple_multiple_works() {
     #[error(message = "tuple like multiple {self.0} {self.2}")]
+    #[allow(dead_code)]
     struct S(usize, usize, usize);
 
     check_error_implementation_works(S(41, 42, 43), "tuple like multiple 41 43")
@@ -69,6 +72,21 @@ fn generics_where_clause_works() {
     check_error_implementation_works(S(42), "generics 42")
 }
 
+#[test]
+fn generics_display_bounds_are_inferred() {
+    #[error(message = "generics {self.0}")]
+    struct S<T>(T);
+
+    #[error]
+    enum E<T> {
+        #[error(message = "value {_0:?}")]
+        Value(T),
+    }
+
+    check_error_implementation_works(S(42), "generics 42");
+    check_error_implementation_works(E::Value("foo"), "value \"foo\"")
+}
+
 #[test]
 fn lifetimes_works() {
     #[error(message = "lifetimes {self.0}")]
@@ -109,13 +127,33 @@ fn implement_from_for_unnamed_works() {
 
 #[test]
 fn complex_message_expressions_works() {
-    // #[error(message = "condition: { if e.i > 42 {\"more than 42\"} else {\"less than 42\"} }")]
-    #[derive(Eq, PartialEq)]
+    #[error(message = "condition: { if self.i > 42 {\"more than 42\"} else {\"less than 42\"} }")]
     struct S {
-        i: usize,
-        j: usize,
-        k: usize
+        i: usize
+    }
+
+    check_error_implementation_works(S { i: 43 }, "condition: more than 42");
+    check_error_implementation_works(S { i: 41 }, "condition: less than 42")
+}
+
+#[test]
+fn escaped_braces_works() {
+    #[error(message = "{{\"value\": {self.0}}}")]
+    struct S(usize);
+
+    check_error_implementation_works(S(42), "{\"value\": 42}")
+}
+
+#[test]
+fn format_specs_work() {
+    #[error(message = "{self.name:>6}|{self.ratio:.2}|{self.flags:#06x}|{self.name:?}|{self.name.trim_matches('}')}")]
+    struct S {
+        name: &'static str,
+        ratio: f64,
+        flags: u8
     }
+
+    check_error_implementation_works(S { name: "io}", ratio: 0.5, flags: 10 }, "   io}|0.50|0x000a|\"io}\"|io")
 }
 
 #[test]
@@ -196,6 +234,22 @@ fn enum_check_positional_message_works() {
     check_error_implementation_works(E::Foo(42, 420.5), "Float: 420.5, Int: 42");
 }
 
+#[test]
+fn positional_placeholders_work() {
+    #[error(message = "{1} after {0:>3}")]
+    struct S(usize, &'static str);
+
+    #[error]
+    #[allow(dead_code)]
+    enum E {
+        #[error(message = "Float: {1}, Int: {0}, Secret: {2}")]
+        Foo(usize, f32, #[error(redact)] String)
+    }
+
+    check_error_implementation_works(S(7, "seconds"), "seconds after   7");
+    check_error_implementation_works(E::Foo(42, 420.5, "token".to_string()), "Float: 420.5, Int: 42, Secret: [REDACTED]");
+}
+
 #[test]
 fn enum_check_generics_and_lifetimes_works() {
     #[error]
@@ -233,23 +287,1492 @@ fn check_global_impl_from_works() {
     check_from_implementation_works("42", b);
 }
 
-/// Check if the given value is a fully qualified Error.
-/// It implements all necessary traits if it is a valid parameter for this function.
-/// Also its Display-implementation should create the expected message.
-fn check_error_implementation_works<E>(e: E, expected_message: &str)
-    where E: Error + Debug + Display
-{
-    assert_eq!(e.to_string(), expected_message);
+#[test]
+fn from_types_works() {
+    #[error(message = "Error", impl_from)]
+    #[allow(dead_code)]
+    enum E {
+        A(usize),
+        B(&'static str),
+    }
+
+    assert_eq!(E::FROM_TYPES, &["usize", "&'static str"]);
 }
 
-/// If this method call does not produce a compile error, From was correctly
-/// implemented for the given type f. Also checks if the expected value
-/// was produced.
-fn check_from_implementation_works<E, F>(from_value: F, expected: E)
-    where E: Error + Debug + Display + From<F> + Eq {
-    let fun: fn(F) -> Result<(), E> = |f| {
-        Err(f)?;
-        Ok(())
-    };
-    assert_eq!(expected, fun(from_value).err().unwrap());
-}
\ No newline at end of file
+#[test]
+fn assert_macro_works() {
+    #[error(message = "Error", assert_macro)]
+    #[allow(dead_code)]
+    enum MyError {
+        A,
+        B(usize),
+        C { val: usize },
+    }
+
+    assert_handles_all_my_error!(A, B, C);
+    check_error_implementation_works(MyError::C { val: 42 }, "Error");
+}
+
+#[test]
+fn labelled_debug_works() {
+    #[error(message = "could not read {self.path}")]
+    struct S {
+        #[error(label = "config file")]
+        path: &'static str,
+        line: usize,
+    }
+
+    let s = S { path: "/etc/app", line: 42 };
+    assert_eq!(format!("{:?}", s), r#"S { path ("config file"): "/etc/app", line: 42 }"#);
+    check_error_implementation_works(s, "could not read /etc/app")
+}
+
+#[test]
+fn custom_formatter_works() {
+    fn format_e(e: &E, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match e {
+            E::Values(values) => write!(f, "values: {}", values.iter().map(|v| v.to_string()).collect::<Vec<_>>().join(", "))
+        }
+    }
+
+    #[error(fmt = "format_e")]
+    enum E {
+        Values(Vec<usize>)
+    }
+
+    check_error_implementation_works(E::Values(vec![1, 2, 3]), "values: 1, 2, 3")
+}
+
+#[test]
+fn skipped_fields_work() {
+    #[allow(dead_code)]
+    #[error(impl_from)]
+    enum E {
+        #[error(message = "code {code}")]
+        Named { code: usize, #[error(skip)] buffer: Vec<u8> }
+    }
+
+    let e = E::from(42);
+    assert!(matches!(&e, E::Named { buffer, .. } if buffer.is_empty()));
+    check_error_implementation_works(e, "code 42")
+}
+
+#[test]
+fn no_std_alloc_works() {
+    #[error(message = "no_std error {self.0}", no_std, alloc)]
+    struct S(usize);
+
+    let boxed: alloc::boxed::Box<dyn core::error::Error> = S(42).boxed();
+    assert_eq!(boxed.to_string(), "no_std error 42")
+}
+
+#[test]
+fn impl_from_into_works() {
+    #[error]
+    enum E {
+        #[error(message = "{_0}", impl_from = "into")]
+        Message(String),
+    }
+
+    fn fail() -> Result<(), E> {
+        Err("static message")?
+    }
+
+    check_error_implementation_works(fail().unwrap_err(), "static message")
+}
+
+#[test]
+fn impl_from_multiple_fields_works() {
+    #[error(message = "{self.source} in line {self.line} of {self.file}", impl_from)]
+    struct S {
+        #[error(from)]
+        source: std::num::ParseIntError,
+        line: usize,
+        #[error(default = "String::from(\"unknown\")")]
+        file: String
+    }
+
+    fn parse() -> Result<usize, S> {
+        Ok("x".parse::<usize>()?)
+    }
+
+    check_error_implementation_works(parse().unwrap_err(), "invalid digit found in string in line 0 of unknown")
+}
+
+#[test]
+fn impl_from_for_works() {
+    #[error]
+    enum E<T: Debug + Display> {
+        #[error(message = "value {_0}", impl_from_for = "String, u8")]
+        Value(T),
+    }
+
+    check_error_implementation_works(E::from(String::from("foo")), "value foo");
+    check_error_implementation_works(E::from(42u8), "value 42")
+}
+
+#[test]
+fn exit_code_works() {
+    #[allow(dead_code)]
+    #[error(message = "failed")]
+    enum E {
+        #[error(exit_code = 66)]
+        NoInput,
+        Other
+    }
+
+    assert_eq!(E::NoInput.exit_code(), std::process::ExitCode::from(66));
+    assert_eq!(E::Other.exit_code(), std::process::ExitCode::FAILURE)
+}
+
+#[test]
+fn severity_works() {
+    #[allow(dead_code)]
+    #[error(message = "failed", severity = "warning")]
+    enum E {
+        #[error(severity = "fatal")]
+        Corrupted,
+        Retrying(usize)
+    }
+
+    #[error(message = "failed", severity = "error")]
+    struct S;
+
+    assert_eq!(E::Corrupted.severity(), ESeverity::Fatal);
+    assert_eq!(E::Retrying(1).severity(), ESeverity::Warning);
+    assert!(E::Corrupted.severity() > E::Retrying(1).severity());
+    assert_eq!(S.severity(), SSeverity::Error)
+}
+
+#[test]
+fn category_works() {
+    #[allow(dead_code)]
+    #[error(message = "failed", category = "io")]
+    enum E {
+        #[error(category = "validation")]
+        InvalidInput(String),
+        NotFound
+    }
+
+    assert_eq!(E::InvalidInput(String::new()).category(), "validation");
+    assert_eq!(E::NotFound.category(), "io")
+}
+
+#[test]
+fn memoize_display_works() {
+    use std::cell::Cell;
+
+    #[error(message = "rendered {self.renders.replace(self.renders.get() + 1)} times before", memoize_display)]
+    struct S {
+        renders: Cell<usize>
+    }
+
+    let memoized = S { renders: Cell::new(0) }.memoized();
+    check_error_implementation_works(&memoized, "rendered 0 times before");
+    check_error_implementation_works(&memoized, "rendered 0 times before");
+    assert_eq!(memoized.error().renders.get(), 1)
+}
+
+#[test]
+fn errno_works() {
+    #[error(message = "failed", errno = 5)]
+    struct S;
+
+    assert_eq!(S.to_errno(), 5);
+    assert_eq!(S.errno(), 5);
+    assert_eq!(i32::from(S), 5);
+    assert_eq!(std::io::Error::from(S).raw_os_error(), Some(5))
+}
+
+#[test]
+fn errno_names_work() {
+    /// Stands in for the libc crate, which is where the names of errno constants are resolved.
+    mod libc {
+        pub const EINVAL: i32 = 22;
+    }
+
+    #[error(message = "failed")]
+    enum E {
+        #[error(errno = "EINVAL")]
+        InvalidArgument,
+        #[error(errno = 5)]
+        Io
+    }
+
+    assert_eq!(E::InvalidArgument.to_errno(), 22);
+    assert_eq!(E::Io.to_errno(), 5)
+}
+
+#[test]
+fn metadata_placeholders_work() {
+    #[error(message = "{crate_name} v{crate_version} ({build_profile}) failed")]
+    struct S;
+
+    let profile = if cfg!(debug_assertions) { "debug" } else { "release" };
+    assert_eq!(S.to_string(), format!("error_generator v{} ({}) failed", env!("CARGO_PKG_VERSION"), profile))
+}
+
+#[test]
+fn helper_derive_works() {
+    #[error(message = "expensive", memoize_display, helper_derive = "Clone")]
+    #[derive(Clone)]
+    struct S;
+
+    let memoized = S.memoized();
+    assert_eq!(memoized.clone().to_string(), memoized.to_string())
+}
+
+#[test]
+fn derives_works() {
+    #[error(message = "not found", derives = "Clone, PartialEq, Eq, Hash")]
+    enum E {
+        NotFound(u8)
+    }
+
+    let mut errors = std::collections::HashSet::new();
+    errors.insert(E::NotFound(1));
+    assert!(errors.contains(&E::NotFound(1).clone()));
+    assert_eq!(format!("{:?}", E::NotFound(1)), "NotFound(1)")
+}
+
+#[test]
+fn existing_debug_derive_works() {
+    #[error(message = "derived")]
+    #[derive(Clone, Debug)]
+    struct S;
+
+    assert_eq!(format!("{:?}", S.clone()), "S")
+}
+
+#[test]
+fn debug_modes_work() {
+    #[error(debug = "display")]
+    enum E {
+        #[error(message = "user {_0} not found")]
+        NotFound(u32)
+    }
+
+    #[error(message = "failed", debug = "off")]
+    struct S;
+
+    impl std::fmt::Debug for S {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            f.write_str("hand-written")
+        }
+    }
+
+    assert_eq!(format!("{:?}", E::NotFound(42)), "user 42 not found");
+    assert_eq!(format!("{:?}", S), "hand-written")
+}
+
+#[test]
+fn runtime_verbosity_works() {
+    #[error(message = "could not read {self.path}", runtime_verbosity)]
+    #[allow(dead_code)]
+    struct S {
+        path: &'static str,
+        attempts: usize,
+    }
+
+    let s = S { path: "/etc/app", attempts: 3 };
+    assert_eq!(s.to_string(), "could not read /etc/app");
+
+    S::set_error_verbosity(true);
+    assert_eq!(s.to_string(), r#"could not read /etc/app (S { path: "/etc/app", attempts: 3 })"#);
+    S::set_error_verbosity(false)
+}
+
+#[test]
+fn json_works() {
+    #[error(message = "request failed", json, errno = 5)]
+    enum E {
+        #[error(message = "user \"{_0}\" not found", errno = 2)]
+        NotFound(String),
+        Internal
+    }
+
+    assert_eq!(E::NotFound("bob".to_string()).to_json(), r#"{"error": "NotFound", "code": 2, "message": "user \"bob\" not found"}"#);
+    assert_eq!(E::Internal.to_json(), r#"{"error": "Internal", "code": 5, "message": "request failed"}"#)
+}
+
+#[test]
+fn matcher_macro_works() {
+    #[error(message = "request failed", matcher_macro)]
+    #[allow(dead_code)]
+    enum MyError {
+        NotFound { id: usize },
+        Forbidden(String),
+        Other
+    }
+
+    let result: Result<(), MyError> = Err(MyError::NotFound { id: 42 });
+    assert_my_error!(result, NotFound { id });
+    assert_eq!(id, 42);
+
+    let result: Result<(), MyError> = Err(MyError::Forbidden("admin".to_string()));
+    assert_my_error!(result, Forbidden(role));
+    assert_eq!(role, "admin");
+
+    let panic = std::panic::catch_unwind(|| {
+        let result: Result<(), MyError> = Err(MyError::Other);
+        assert_my_error!(result, Forbidden(_));
+    });
+    let message = panic.unwrap_err().downcast::<String>().unwrap();
+    assert_eq!(*message, "expected MyError::Forbidden, but got: request failed")
+}
+
+#[test]
+fn io_kind_works() {
+    use std::io;
+
+    #[error(message = "request failed")]
+    #[allow(dead_code)]
+    enum E {
+        Read(std::io::Error),
+        Write { path: String, source: io::Error },
+        Other
+    }
+
+    let read = E::Read(io::Error::new(io::ErrorKind::NotFound, "missing"));
+    let write = E::Write { path: "/tmp".to_string(), source: io::Error::new(io::ErrorKind::PermissionDenied, "denied") };
+    assert_eq!(read.io_kind(), Some(io::ErrorKind::NotFound));
+    assert_eq!(write.io_kind(), Some(io::ErrorKind::PermissionDenied));
+    assert_eq!(E::Other.io_kind(), None)
+}
+
+#[test]
+fn caller_location_works() {
+    #[error(message = "invalid number at line {self.location.line()}", impl_from)]
+    #[allow(dead_code)]
+    struct E {
+        source: std::num::ParseIntError,
+        location: &'static std::panic::Location<'static>
+    }
+
+    fn parse(s: &str) -> Result<usize, E> {
+        Ok(s.parse::<usize>()?)
+    }
+
+    let line = line!() - 3;
+    let error = parse("x").unwrap_err();
+    assert_eq!(error.location.file(), file!());
+    assert_eq!(error.location.line(), line);
+    assert_eq!(error.to_string(), format!("invalid number at line {}", line))
+}
+
+#[test]
+fn source_type_works() {
+    #[error]
+    #[allow(dead_code)]
+    enum E {
+        #[error(message = "converting '{text}' from {converted_from} failed at {location}", impl_from = "into")]
+        Text {
+            text: String,
+            #[error(source_type)]
+            converted_from: &'static str,
+            location: &'static std::panic::Location<'static>
+        }
+    }
+
+    let error = E::from("text");
+    let line = line!() - 1;
+    assert!(error.to_string().starts_with(&format!("converting 'text' from &str failed at {}:{}:", file!(), line)))
+}
+
+#[test]
+fn phantom_data_works() {
+    use std::marker::PhantomData;
+
+    #[derive(Debug)]
+    struct Meters;
+
+    #[error(constructors)]
+    enum E<U> {
+        #[error(message = "{_0} is out of range", impl_from)]
+        OutOfRange(f64, PhantomData<U>),
+        #[error(message = "{value} is negative")]
+        Negative { value: f64, unit: PhantomData<U> }
+    }
+
+    let error: E<Meters> = E::from(3.5);
+    assert!(matches!(error, E::OutOfRange(_, PhantomData)));
+    assert_eq!(error.to_string(), "3.5 is out of range");
+    assert_eq!(E::<Meters>::negative(-1.0).to_string(), "-1 is negative");
+    assert_eq!(E::<Meters>::out_of_range_with(|| 7.0)().to_string(), "7 is out of range")
+}
+
+#[test]
+fn non_exhaustive_works() {
+    #[error(variant_name, non_exhaustive)]
+    enum E {
+        #[error(message = "the request timed out")]
+        Timeout,
+        #[error(message = "the response is invalid")]
+        InvalidResponse
+    }
+
+    #[error(variant_name)]
+    #[non_exhaustive]
+    enum F {
+        #[error(message = "the request timed out")]
+        Timeout
+    }
+
+    assert_eq!(E::Timeout.name(), "Timeout");
+    assert_eq!(E::InvalidResponse.to_string(), "the response is invalid");
+    assert_eq!(F::Timeout.name(), "Timeout");
+    check_error_implementation_works(F::Timeout, "the request timed out")
+}
+
+#[test]
+fn cfg_attributes_of_variants_work() {
+    #[error(variant_name, constructors, predicates, kind)]
+    enum E {
+        #[cfg(any())]
+        #[error(message = "parsing failed", impl_from)]
+        Parse(std::num::ParseIntError),
+        #[cfg(not(any()))]
+        #[error(message = "the request timed out")]
+        Timeout
+    }
+
+    assert_eq!(E::timeout().name(), "Timeout");
+    assert!(E::Timeout.is_timeout());
+    assert_eq!(E::Timeout.kind(), EKind::Timeout);
+    check_error_implementation_works(E::Timeout, "the request timed out")
+}
+
+#[test]
+fn large_tuple_variants_work() {
+    #[error]
+    enum E {
+        #[error(message = "{_0} {_25} {_26} {e.27}")]
+        Large(u8, u8, u8, u8, u8, u8, u8, u8, u8, u8, u8, u8, u8, u8, u8, u8, u8, u8, u8, u8, u8, u8, u8, u8, u8, u8, u8, u8)
+    }
+
+    let error = E::Large(0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17, 18, 19, 20, 21, 22, 23, 24, 25, 26, 27);
+    check_error_implementation_works(error, "0 25 26 27")
+}
+
+#[test]
+fn repeated_placeholders_work() {
+    #[error(message = "{0} is invalid, {self.0:?} is not a number")]
+    struct InvalidNumber(String);
+
+    #[error]
+    enum E {
+        #[error(message = "line {line} of {file} (line {e.line:>3}, {line:?})")]
+        Invalid { line: usize, file: String },
+        #[error(message = "{_0}{0}{e.0}")]
+        Repeated(u8)
+    }
+
+    check_error_implementation_works(InvalidNumber("x".to_string()), r#"x is invalid, "x" is not a number"#);
+    check_error_implementation_works(E::Invalid { line: 7, file: "a.txt".to_string() }, "line 7 of a.txt (line   7, 7)");
+    check_error_implementation_works(E::Repeated(1), "111")
+}
+
+#[test]
+fn constructors_work() {
+    #[error(constructors)]
+    #[allow(dead_code)]
+    enum E {
+        #[error(message = "parsing failed in line {line}")]
+        ParsingFailed {
+            line: usize,
+            #[error(skip)]
+            location: &'static std::panic::Location<'static>
+        },
+        #[error(message = "{_0} is invalid")]
+        Invalid(String)
+    }
+
+    let error = E::parsing_failed(3);
+    assert_eq!(error.to_string(), "parsing failed in line 3");
+    assert!(matches!(error, E::ParsingFailed { line: 3, location } if location.file() == file!()));
+    assert_eq!(E::invalid("x".to_string()).to_string(), "x is invalid");
+
+    let mut computed = false;
+    assert!(Some(1).ok_or_else(E::invalid_with(|| { computed = true; "x".to_string() })).is_ok());
+    assert!(!computed);
+
+    let error = None::<usize>.ok_or_else(E::parsing_failed_with(|| 4)).unwrap_err();
+    assert!(matches!(error, E::ParsingFailed { line: 4, location } if location.file() == file!()))
+}
+
+#[test]
+fn constructors_of_keyword_variants_work() {
+    #[error(constructors)]
+    enum E {
+        #[error(message = "unexpected type {_0}")]
+        Type(String),
+        #[error(message = "no match")]
+        Match
+    }
+
+    assert_eq!(E::r#type("u8".to_string()).to_string(), "unexpected type u8");
+    assert_eq!(E::type_with(|| "u8".to_string())().to_string(), "unexpected type u8");
+    assert_eq!(E::r#match().to_string(), "no match")
+}
+
+#[test]
+fn predicates_work() {
+    #[error(message = "the config is invalid", predicates)]
+    #[allow(dead_code)]
+    enum E {
+        ParsingFailed { line: usize },
+        Missing
+    }
+
+    assert!(E::ParsingFailed { line: 1 }.is_parsing_failed());
+    assert!(!E::ParsingFailed { line: 1 }.is_missing());
+    assert!(E::Missing.is_missing())
+}
+
+#[test]
+fn retry_after_works() {
+    use std::time::Duration;
+
+    #[error(message = "the service is not available")]
+    enum E {
+        #[error(retry_after = 5)]
+        Busy,
+        Down
+    }
+
+    assert_eq!(E::Busy.retry_after(), Some(Duration::from_secs(5)));
+    assert_eq!(E::Down.retry_after(), None)
+}
+
+#[test]
+fn retryable_works() {
+    #[error(message = "the request failed")]
+    enum E {
+        #[error(retryable)]
+        Timeout,
+        #[error(retry_after = 5)]
+        Busy,
+        InvalidRequest
+    }
+
+    #[error(message = "the connection was reset", retryable)]
+    struct S;
+
+    assert!(E::Timeout.is_retryable());
+    assert!(E::Busy.is_retryable());
+    assert!(!E::InvalidRequest.is_retryable());
+    assert!(S.is_retryable())
+}
+
+#[test]
+fn variant_name_works() {
+    #[error(message = "reading failed", variant_name)]
+    #[allow(dead_code)]
+    enum E {
+        ReadFileFailed(String),
+        Unit
+    }
+
+    assert_eq!(E::ReadFileFailed("config.toml".to_string()).name(), "ReadFileFailed");
+    assert_eq!(E::Unit.name(), "Unit")
+}
+
+#[test]
+fn getters_work() {
+    #[error(message = "the config is invalid", getters)]
+    enum E {
+        ParsingFailed { line: usize, file: String },
+        Missing { file: String }
+    }
+
+    let parsing_failed = E::ParsingFailed { line: 3, file: "config.toml".to_string() };
+    assert_eq!(parsing_failed.line(), Some(&3));
+    assert_eq!(parsing_failed.file().map(String::as_str), Some("config.toml"));
+
+    let missing = E::Missing { file: "config.toml".to_string() };
+    assert_eq!(missing.line(), None);
+    assert_eq!(missing.file().map(String::as_str), Some("config.toml"))
+}
+
+#[test]
+fn debug_placeholder_works() {
+    #[allow(dead_code)]
+    #[derive(Debug)]
+    struct Payload(u8);
+
+    #[error(message = "invalid payload {self.payload}")]
+    struct S {
+        #[error(debug_placeholder)]
+        payload: Payload
+    }
+
+    #[error]
+    enum E {
+        #[error(message = "invalid payload {_0}")]
+        Invalid(#[error(debug_placeholder)] Payload)
+    }
+
+    check_error_implementation_works(S { payload: Payload(1) }, "invalid payload Payload(1)");
+    check_error_implementation_works(E::Invalid(Payload(2)), "invalid payload Payload(2)")
+}
+
+#[test]
+fn redact_works() {
+    #[allow(dead_code)]
+    #[error(message = "login of {self.user} with {self.password} failed")]
+    struct S {
+        user: String,
+        #[error(redact)]
+        password: String
+    }
+
+    #[allow(dead_code)]
+    #[error(redact_debug)]
+    enum E {
+        #[error(message = "invalid token {e.token}")]
+        InvalidToken {
+            #[error(redact)]
+            token: String
+        }
+    }
+
+    let s = S { user: "admin".to_string(), password: "hunter2".to_string() };
+    assert_eq!(format!("{:?}", s), r#"S { user: "admin", password: "hunter2" }"#);
+    check_error_implementation_works(s, "login of admin with [REDACTED] failed");
+
+    let e = E::InvalidToken { token: "secret".to_string() };
+    assert_eq!(format!("{:?}", e), "InvalidToken { token: [REDACTED] }");
+    check_error_implementation_works(e, "invalid token [REDACTED]")
+}
+
+#[test]
+fn message_const_works() {
+    mod messages {
+        pub const READ_FAILED: &str = "reading {path} failed in line {self.line} {{sic}}";
+        pub static MISSING: &str = "{0} is missing, {unknown} is unknown";
+    }
+
+    #[error(message_const = "messages::READ_FAILED")]
+    struct S {
+        path: String,
+        line: usize
+    }
+
+    #[error]
+    enum E {
+        #[error(message_const = "messages::MISSING")]
+        Missing(String),
+        #[error(message = "invalid")]
+        Invalid
+    }
+
+    check_error_implementation_works(S { path: "config.toml".to_string(), line: 3 }, "reading config.toml failed in line 3 {sic}");
+    check_error_implementation_works(E::Missing("key".to_string()), "key is missing, {unknown} is unknown");
+    check_error_implementation_works(E::Invalid, "invalid")
+}
+
+#[test]
+fn prefix_and_suffix_work() {
+    #[error(prefix = "config: ", suffix = " (see the manual)")]
+    enum E {
+        #[error(message = "the key {_0} is missing")]
+        MissingKey(String),
+        #[error(message = "the file is empty")]
+        Empty
+    }
+
+    check_error_implementation_works(E::MissingKey("port".to_string()), "config: the key port is missing (see the manual)");
+    check_error_implementation_works(E::Empty, "config: the file is empty (see the manual)")
+}
+
+#[test]
+fn alternate_display_prints_source_chain() {
+    #[error(wrap_map = "std::num::ParseIntError => Parse")]
+    enum ConfigError {
+        #[error(message = "the port is invalid")]
+        Parse(std::num::ParseIntError)
+    }
+
+    #[error(wrap_map = "ConfigError => Config")]
+    enum AppError {
+        #[error(message = "loading the config failed")]
+        Config(ConfigError)
+    }
+
+    let error = AppError::from(ConfigError::from("x".parse::<u16>().unwrap_err()));
+    assert_eq!(format!("{}", error), "loading the config failed");
+    assert_eq!(format!("{:#}", error), "loading the config failed: caused by: the port is invalid: caused by: invalid digit found in string")
+}
+
+#[test]
+fn transparent_works() {
+    #[error]
+    #[repr(transparent)]
+    struct S(std::num::ParseIntError);
+
+    let inner = "x".parse::<u8>().unwrap_err();
+    let message = inner.to_string();
+    let s = S::from(inner.clone());
+
+    assert_eq!(std::mem::size_of::<S>(), std::mem::size_of::<std::num::ParseIntError>());
+    assert!(std::error::Error::source(&s).is_none());
+    assert_eq!(s.into_inner(), inner);
+    check_error_implementation_works(S::from(inner), &message)
+}
+
+#[test]
+fn generic_sources_get_bounds() {
+    use std::error::Error;
+
+    #[error(message = "reading failed")]
+    #[repr(transparent)]
+    struct ReadError<E>(E);
+
+    #[error(message = "the request failed")]
+    enum RequestError<E> {
+        #[error(forward)]
+        Inner(E),
+        Timeout
+    }
+
+    let inner = "x".parse::<u8>().unwrap_err();
+    let read_error = ReadError::from(inner.clone());
+    assert_eq!(read_error.source().unwrap().to_string(), inner.to_string());
+    assert_eq!(format!("{:#}", read_error), format!("reading failed: caused by: {}", inner));
+    check_error_implementation_works(read_error, "reading failed");
+
+    let request_error = RequestError::Inner(ReadError::from(inner.clone()));
+    assert_eq!(request_error.source().unwrap().to_string(), inner.to_string());
+    check_error_implementation_works(request_error, "reading failed");
+    check_error_implementation_works(RequestError::<ReadError<std::num::ParseIntError>>::Timeout, "the request failed")
+}
+
+#[test]
+fn into_inner_works() {
+    #[error(message = "request failed", into_inner)]
+    struct S {
+        inner: std::num::ParseIntError
+    }
+
+    #[error(wrap_map = "std::num::ParseIntError => Parse", into_inner)]
+    enum E {
+        Parse(std::num::ParseIntError),
+        #[error(message = "the request timed out after {seconds} seconds")]
+        Timeout { seconds: u64 }
+    }
+
+    let inner = "x".parse::<u8>().unwrap_err();
+    assert_eq!(S { inner: inner.clone() }.into_inner(), inner);
+
+    assert_eq!(E::Parse(inner.clone()).into_parse().ok(), Some(inner.clone()));
+    assert_eq!(E::Timeout { seconds: 5 }.into_timeout().ok(), Some(5));
+    assert!(E::Timeout { seconds: 5 }.into_parse().is_err());
+
+    let source = E::Parse(inner.clone()).into_source().unwrap();
+    assert_eq!(source.downcast_ref::<std::num::ParseIntError>(), Some(&inner));
+    assert!(E::Timeout { seconds: 5 }.into_source().is_none())
+}
+
+#[test]
+fn kind_works() {
+    #[error(message = "something went wrong", kind)]
+    enum E {
+        #[error(message = "could not read {path}")]
+        ReadFailed { path: String },
+        Unknown
+    }
+
+    let e = E::ReadFailed { path: "/etc/app".to_string() };
+    assert_eq!(e.kind(), EKind::ReadFailed);
+    assert_eq!(EKind::from(&e), EKind::ReadFailed);
+    assert_eq!(EKind::from(&e).message_template(), Some("could not read {path}"));
+    assert_eq!(E::Unknown.kind().message_template(), Some("something went wrong"))
+}
+
+#[test]
+fn dyn_error_works() {
+    #[error(message = "the request failed", dyn_error)]
+    struct S;
+
+    #[error(dyn_error)]
+    enum E {
+        #[error(message = "the request timed out")]
+        Timeout
+    }
+
+    assert_eq!(S.as_dyn_error().to_string(), "the request failed");
+    let boxed: Box<dyn Error + Send + Sync> = S.boxed();
+    assert!(boxed.downcast_ref::<S>().is_some());
+
+    assert_eq!(E::Timeout.as_dyn_error().to_string(), "the request timed out");
+    std::thread::spawn(move || assert_eq!(E::Timeout.boxed().to_string(), "the request timed out")).join().unwrap()
+}
+
+#[test]
+fn deprecated_from_works() {
+    #[error]
+    enum E {
+        #[error(message = "parsing failed", impl_from, deprecated_from = "use E::ParseFailed instead")]
+        Legacy(std::num::ParseIntError),
+        #[error(message = "could not parse line {line}")]
+        ParseFailed { line: usize }
+    }
+
+    let e: E = "x".parse::<u8>().unwrap_err().into();
+    assert!(matches!(e, E::Legacy(_)));
+    check_error_implementation_works(E::ParseFailed { line: 3 }, "could not parse line 3")
+}
+
+#[test]
+fn catalog_works() {
+    #[error(message = "the command failed", catalog, exit_code = 1, severity = "error")]
+    #[allow(dead_code)]
+    enum E {
+        #[error(message = "invalid usage of {_0}", exit_code = 2, severity = "warning")]
+        Usage(String),
+        #[error(message = "the service is busy", retry_after = 5)]
+        Busy,
+        Other
+    }
+
+    assert_eq!(E::CATALOG.len(), 3);
+    assert_eq!(E::CATALOG[0], EInfo { name: "Usage", message: Some("invalid usage of {_0}"), status: None, exit_code: Some(2), errno: None, severity: Some("warning"), retryable: false, retry_after: None });
+    assert!(E::CATALOG[1].retryable);
+    assert_eq!(E::CATALOG[1].retry_after, Some(std::time::Duration::from_secs(5)));
+    assert_eq!(E::CATALOG[2].message, Some("the command failed"));
+    assert_eq!(E::CATALOG[2].name, "Other");
+    assert_eq!(E::CATALOG[2].exit_code, Some(1));
+    assert_eq!(E::CATALOG[2].severity, Some("error"))
+}
+
+#[test]
+fn catalog_and_from_types_skip_configured_out_variants() {
+    #[error(message = "conversion failed", catalog, impl_from)]
+    #[allow(dead_code)]
+    enum E {
+        Parse(std::num::ParseIntError),
+        #[cfg(any())]
+        Fmt(std::fmt::Error),
+        Utf8(std::str::Utf8Error)
+    }
+
+    assert_eq!(E::CATALOG.iter().map(|info| info.name).collect::<Vec<_>>(), vec!["Parse", "Utf8"]);
+    assert_eq!(E::FROM_TYPES, &["std::num::ParseIntError", "std::str::Utf8Error"])
+}
+
+#[test]
+fn impl_from_allows_the_same_type_in_exclusive_configurations() {
+    #[error(message = "conversion failed", impl_from)]
+    #[allow(dead_code)]
+    enum E {
+        #[cfg(debug_assertions)]
+        Debug(std::num::ParseIntError),
+        #[cfg(not(debug_assertions))]
+        Release(std::num::ParseIntError)
+    }
+
+    let error = E::from("x".parse::<u8>().unwrap_err());
+    assert_eq!(E::FROM_TYPES, &["std::num::ParseIntError"]);
+    assert_eq!(error.to_string(), "conversion failed")
+}
+
+/// The test module generated by 'generate_from_tests' runs with the integration tests. It needs to be
+/// defined on module level, as the module can't see items inside of functions.
+#[error(message = "conversion failed", impl_from, generate_from_tests)]
+#[allow(dead_code)]
+enum GeneratedFromTestsError {
+    Parse(std::num::ParseIntError),
+    Text(String),
+    Number { value: usize }
+}
+
+#[test]
+fn wrap_map_works() {
+    use std::error::Error;
+
+    #[error(wrap_map = "std::num::ParseIntError => Parse, std::fmt::Error => Fmt")]
+    enum E {
+        Parse(std::num::ParseIntError),
+        #[error(message = "formatting failed")]
+        Fmt { source: std::fmt::Error },
+        #[error(message = "something else went wrong")]
+        Other
+    }
+
+    let parse_error = "x".parse::<usize>().unwrap_err();
+    let parse = E::from(parse_error.clone());
+    assert_eq!(parse.to_string(), parse_error.to_string());
+    assert_eq!(format!("{:#}", parse), parse_error.to_string());
+    assert!(parse.source().is_none());
+
+    let fmt = E::from(std::fmt::Error);
+    assert_eq!(fmt.to_string(), "formatting failed");
+    assert_eq!(format!("{:#}", fmt), format!("formatting failed: caused by: {}", std::fmt::Error));
+    assert_eq!(fmt.source().unwrap().to_string(), std::fmt::Error.to_string());
+
+    assert_eq!(E::Other.to_string(), "something else went wrong");
+    assert!(E::Other.source().is_none())
+}
+
+#[test]
+fn includes_works() {
+    use std::error::Error;
+
+    #[error(message = "could not parse the number {self.0}")]
+    struct ParseError(String);
+
+    #[error(message = "could not read the file {self.path}")]
+    struct ReadError {
+        path: String
+    }
+
+    #[error(includes = "ParseError, ReadError")]
+    enum AppError {
+        #[error(message = "the configuration is empty")]
+        Empty
+    }
+
+    let parse = AppError::from(ParseError("x".to_string()));
+    assert!(matches!(parse, AppError::ParseError(_)));
+    assert_eq!(parse.to_string(), "could not parse the number x");
+    assert!(parse.source().is_none());
+
+    let read: AppError = ReadError { path: "config.toml".to_string() }.into();
+    assert_eq!(read.to_string(), "could not read the file config.toml");
+
+    assert_eq!(AppError::Empty.to_string(), "the configuration is empty")
+}
+
+#[test]
+fn forward_works() {
+    use std::error::Error;
+
+    #[error(wrap_map = "std::num::ParseIntError => Parse")]
+    enum ValidationError {
+        Parse(std::num::ParseIntError),
+        #[error(message = "the value {_0} is too large")]
+        TooLarge(usize)
+    }
+
+    #[error(message = "the config could not be loaded")]
+    enum ConfigError {
+        #[error(forward)]
+        Validation(ValidationError),
+        Missing
+    }
+
+    let too_large = ConfigError::Validation(ValidationError::TooLarge(42));
+    assert_eq!(too_large.to_string(), "the value 42 is too large");
+    assert!(too_large.source().is_none());
+
+    let parse_error = "x".parse::<usize>().unwrap_err();
+    let parse = ConfigError::Validation(ValidationError::from(parse_error.clone()));
+    assert_eq!(parse.to_string(), parse_error.to_string());
+    assert_eq!(format!("{:#}", parse), parse_error.to_string());
+    assert!(parse.source().is_none());
+
+    assert_eq!(ConfigError::Missing.to_string(), "the config could not be loaded")
+}
+
+#[test]
+fn from_io_works() {
+    use std::io::ErrorKind;
+
+    #[error(message = "the file could not be read")]
+    enum ReadError {
+        #[error(from_io = "NotFound")]
+        NotFound(std::io::Error),
+        #[error(from_io = "PermissionDenied")]
+        PermissionDenied { source: std::io::Error },
+        #[error(from_io = "_")]
+        Other(std::io::Error)
+    }
+
+    fn read(kind: ErrorKind) -> Result<(), ReadError> {
+        Err(std::io::Error::from(kind))?;
+        Ok(())
+    }
+
+    assert!(matches!(read(ErrorKind::NotFound), Err(ReadError::NotFound(_))));
+    assert!(matches!(read(ErrorKind::PermissionDenied), Err(ReadError::PermissionDenied { .. })));
+    assert!(matches!(read(ErrorKind::TimedOut), Err(ReadError::Other(_))));
+    assert_eq!(read(ErrorKind::TimedOut).unwrap_err().io_kind(), Some(ErrorKind::TimedOut));
+    assert_eq!(ReadError::FROM_TYPES, &["std::io::Error"])
+}
+
+#[test]
+fn other_works() {
+    use std::error::Error;
+
+    #[error]
+    enum AppError {
+        #[error(message = "the name is empty")]
+        EmptyName,
+        #[error(other)]
+        Other(Box<dyn Error + Send + Sync>)
+    }
+
+    fn parse(value: &str) -> Result<usize, AppError> {
+        let parsed = value.parse::<usize>().map_err(AppError::wrap)?;
+        Ok(parsed)
+    }
+
+    let error = parse("x").unwrap_err();
+    let parse_error = "x".parse::<usize>().unwrap_err();
+    assert!(matches!(error, AppError::Other(_)));
+    assert_eq!(error.to_string(), parse_error.to_string());
+    assert_eq!(format!("{:#}", error), parse_error.to_string());
+    assert!(error.source().is_none());
+
+    let boxed: Box<dyn Error + Send + Sync> = "unexpected".into();
+    assert_eq!(AppError::from(boxed).to_string(), "unexpected");
+    assert_eq!(AppError::wrap("unexpected").to_string(), "unexpected");
+    assert!(AppError::EmptyName.source().is_none())
+}
+
+#[test]
+fn variant_structs_works() {
+    #[error(message = "the config is invalid", variant_structs)]
+    #[derive(PartialEq)]
+    enum ConfigError {
+        Missing { key: String },
+        OutOfRange(usize, usize),
+        Empty
+    }
+
+    fn missing(key: &str) -> ConfigErrorMissing {
+        ConfigErrorMissing { key: key.to_string() }
+    }
+
+    assert_eq!(ConfigError::from(missing("port")), ConfigError::Missing { key: "port".to_string() });
+    assert_eq!(ConfigError::from(ConfigErrorOutOfRange(1, 2)), ConfigError::OutOfRange(1, 2));
+
+    let empty: ConfigError = ConfigErrorEmpty.into();
+    assert_eq!(empty, ConfigError::Empty)
+}
+
+#[test]
+fn field_access_on_e_works() {
+    use std::path::PathBuf;
+
+    #[error(message = "could not read {e.path.display()} ({e.size} bytes)")]
+    struct ReadError {
+        path: PathBuf,
+        size: usize
+    }
+
+    #[error]
+    enum ConfigError {
+        #[error(message = "could not open {e.path.display()} in line {e.line + 1}")]
+        Open { path: PathBuf, line: usize },
+        #[error(message = "the value {e.0} is {e.1.to_uppercase()}")]
+        Invalid(usize, String)
+    }
+
+    let read = ReadError { path: PathBuf::from("config.toml"), size: 12 };
+    assert_eq!(read.to_string(), "could not read config.toml (12 bytes)");
+
+    let open = ConfigError::Open { path: PathBuf::from("config.toml"), line: 2 };
+    assert_eq!(open.to_string(), "could not open config.toml in line 3");
+    assert_eq!(ConfigError::Invalid(42, "invalid".to_string()).to_string(), "the value 42 is INVALID")
+}
+
+#[test]
+fn chain_works() {
+    #[error(wrap_map = "std::num::ParseIntError => Parse")]
+    enum ConfigError {
+        #[error(message = "the port is invalid")]
+        Parse(std::num::ParseIntError)
+    }
+
+    #[error(wrap_map = "ConfigError => Config", chain)]
+    enum AppError {
+        #[error(message = "loading the config failed")]
+        Config(ConfigError)
+    }
+
+    let error = AppError::from(ConfigError::from("x".parse::<u16>().unwrap_err()));
+    let messages = error.chain().map(|e| e.to_string()).collect::<Vec<_>>();
+    assert_eq!(messages, vec!["loading the config failed", "the port is invalid", "invalid digit found in string"]);
+    assert_eq!(error.render_chain(), "loading the config failed\n  caused by: the port is invalid\n    caused by: invalid digit found in string")
+}
+
+#[cfg(feature = "embedded")]
+#[test]
+fn render_into_works() {
+    #[error(message = "the port {self.0} is invalid", render_into)]
+    struct PortError(u16);
+
+    #[error(render_into)]
+    enum ConfigError {
+        #[error(message = "the config is missing")]
+        Missing
+    }
+
+    let mut buf = heapless::String::<32>::new();
+    PortError(8080).render_into(&mut buf).unwrap();
+    assert_eq!(buf, "the port 8080 is invalid");
+
+    let mut buf = heapless::String::<32>::new();
+    ConfigError::Missing.render_into(&mut buf).unwrap();
+    assert_eq!(buf, "the config is missing");
+
+    let mut buf = heapless::String::<12>::new();
+    assert!(PortError(8080).render_into(&mut buf).is_err());
+    assert_eq!(buf, "the port ")
+}
+
+#[test]
+fn aggregate_works() {
+    #[error(message = "invalid value {self.0}", aggregate)]
+    struct ValidationError(usize);
+
+    let errors = vec![1, 20, 3, 40].into_iter()
+        .filter(|v| *v > 10)
+        .map(ValidationError)
+        .collect::<ValidationErrors>();
+    assert_eq!(errors.len(), 2);
+    check_error_implementation_works(errors.into_result().unwrap_err(), "invalid value 20; invalid value 40");
+
+    let mut errors = ValidationErrors::from(vec![]);
+    assert!(errors.is_empty());
+    errors.extend(vec![ValidationError(1)]);
+    assert_eq!(errors.into_iter().map(|e| e.0).collect::<Vec<_>>(), vec![1]);
+    assert!(ValidationErrors::new().into_result().is_ok())
+}
+
+/// Stand-in for the tracing crate, which records the messages of all events.
+mod tracing {
+    use std::cell::RefCell;
+
+    thread_local! {
+        pub static EVENTS: RefCell<Vec<String>> = const { RefCell::new(Vec::new()) };
+    }
+
+    macro_rules! error {
+        (error = ?$error:expr, $($message:tt)*) => {
+            $crate::tracing::EVENTS.with(|events| events.borrow_mut().push(format!("{:?}: {}", $error, format!($($message)*))))
+        };
+    }
+
+    pub(crate) use error;
+}
+
+#[test]
+fn trace_works() {
+    #[error(impl_from, trace)]
+    enum E {
+        #[error(message = "invalid value {_0}")]
+        Value(usize)
+    }
+
+    fn parse(value: usize) -> Result<(), E> {
+        Err(value)?
+    }
+
+    assert!(parse(42).is_err());
+    tracing::EVENTS.with(|events| assert_eq!(*events.borrow(), vec!["Value(42): invalid value 42".to_string()]))
+}
+
+#[test]
+fn trace_works_for_constructors() {
+    #[error(constructors, trace)]
+    enum E {
+        #[error(message = "invalid value {_0}")]
+        Value(usize)
+    }
+
+    let _ = E::value(42);
+    let _ = E::value_with(|| 7)();
+    tracing::EVENTS.with(|events| assert_eq!(*events.borrow(), vec!["Value(42): invalid value 42".to_string(), "Value(7): invalid value 7".to_string()]))
+}
+
+/// Stand-in for the log crate, which records all messages with their level.
+mod log {
+    use std::cell::RefCell;
+
+    thread_local! {
+        pub static MESSAGES: RefCell<Vec<String>> = const { RefCell::new(Vec::new()) };
+    }
+
+    macro_rules! log_warn {
+        ($($message:tt)*) => {
+            $crate::log::MESSAGES.with(|messages| messages.borrow_mut().push(format!("WARN {}", format!($($message)*))))
+        };
+    }
+
+    macro_rules! log_info {
+        ($($message:tt)*) => {
+            $crate::log::MESSAGES.with(|messages| messages.borrow_mut().push(format!("INFO {}", format!($($message)*))))
+        };
+    }
+
+    pub(crate) use {log_info as info, log_warn as warn};
+}
+
+#[test]
+fn log_works() {
+    #[error(impl_from, log = "warn")]
+    enum E {
+        #[error(message = "invalid value {_0}")]
+        Value(usize),
+        #[error(message = "invalid name {_0}", log = "info")]
+        Name(String)
+    }
+
+    let _ = E::from(42);
+    let _ = E::from("bob".to_string());
+    log::MESSAGES.with(|messages| assert_eq!(*messages.borrow(), vec!["WARN invalid value 42".to_string(), "INFO invalid name bob".to_string()]))
+}
+
+#[test]
+fn log_works_for_constructors() {
+    #[error(constructors, log = "warn")]
+    enum E {
+        #[error(message = "invalid value {_0}")]
+        Value(usize),
+        #[error(message = "invalid name {_0}", log = "info")]
+        Name(String)
+    }
+
+    let _ = E::value(42);
+    let _ = E::name_with(|| "bob".to_string())();
+    log::MESSAGES.with(|messages| assert_eq!(*messages.borrow(), vec!["WARN invalid value 42".to_string(), "INFO invalid name bob".to_string()]))
+}
+
+/// Check if the given value is a fully qualified Error.
+/// It implements all necessary traits if it is a valid parameter for this function.
+/// Also its Display-implementation should create the expected message.
+fn check_error_implementation_works<E>(e: E, expected_message: &str)
+    where E: Error + Debug + Display
+{
+    assert_eq!(e.to_string(), expected_message);
+}
+
+/// If this method call does not produce a compile error, From was correctly
+/// implemented for the given type f. Also checks if the expected value
+/// was produced.
+fn check_from_implementation_works<E, F>(from_value: F, expected: E)
+    where E: Error + Debug + Display + From<F> + Eq {
+    let fun: fn(F) -> Result<(), E> = |f| {
+        Err(f)?;
+        Ok(())
+    };
+    assert_eq!(expected, fun(from_value).err().unwrap());
+}
+/// The generated code must not depend on the prelude or on the name 'std' in scope.
+mod hygiene {
+    #![no_implicit_prelude]
+
+    use ::error_generator::error;
+
+    #[allow(dead_code)]
+    mod std {}
+
+    #[error(message = "could not parse {self.0}", impl_from)]
+    pub struct ParseError(::std::string::String);
+
+    #[error(message = "the operation failed")]
+    #[allow(dead_code)]
+    pub enum OperationError {
+        #[error(message = "reading {_0} failed")]
+        Read(::std::io::Error),
+        #[error(impl_from)]
+        Parse(ParseError),
+        Unit,
+        #[error(message = "timeout after {secs} seconds")]
+        Timeout { secs: u64 }
+    }
+
+    #[test]
+    fn generated_paths_are_hygienic() {
+        let parse_error: ParseError = ::std::convert::From::from(::std::string::String::new());
+        let error: OperationError = ::std::convert::From::from(parse_error);
+        ::std::assert_eq!(::std::string::ToString::to_string(&error), "the operation failed");
+        ::std::assert!(::std::error::Error::source(&error).is_none());
+        ::std::assert_eq!(::std::format!("{:?}", OperationError::Unit), "Unit")
+    }
+}
+
+mod strict_lints {
+    #![deny(warnings, unused_qualifications, clippy::all, clippy::pedantic)]
+
+    use error_generator::error;
+
+    #[error(message = "the value {self.value} is invalid", impl_from)]
+    pub struct InvalidValue {
+        value: usize
+    }
+
+    #[error(message = "the request failed", kind, errno = 5)]
+    pub enum RequestError {
+        #[error(message = "the value of {_0} is invalid", impl_from)]
+        Invalid(InvalidValue),
+        #[error(errno = 110)]
+        Timeout
+    }
+
+    #[test]
+    fn generated_code_passes_strict_lints() {
+        let error = RequestError::from(InvalidValue::from(3));
+        assert_eq!(error.to_string(), "the value of the value 3 is invalid is invalid");
+        assert!(std::error::Error::source(&error).is_none());
+        assert_eq!(i32::from(RequestError::Timeout), 110);
+    }
+}
+
+#[test]
+#[allow(deprecated)]
+fn strict_works() {
+    #[error(message = "the request failed", strict)]
+    enum RequestError {
+        #[error(message = "the request timed out after {secs} seconds")]
+        Timeout { secs: u64 },
+        #[error(message = "the request was cancelled")]
+        Cancelled { reason: String },
+        Unknown
+    }
+
+    check_error_implementation_works(RequestError::Timeout { secs: 5 }, "the request timed out after 5 seconds");
+    check_error_implementation_works(RequestError::Cancelled { reason: "shutdown".to_string() }, "the request was cancelled");
+    check_error_implementation_works(RequestError::Unknown, "the request failed")
+}
+
+#[test]
+fn multiple_error_attributes_work() {
+    #[error(message = "the login failed")]
+    #[derive(Clone, PartialEq, Eq)]
+    #[error(no_std)]
+    enum LoginError {
+        #[error(message = "the login of {user} with {password} failed")]
+        InvalidPassword {
+            user: String,
+            #[error(debug_placeholder)]
+            #[error(redact)]
+            password: String
+        },
+        #[error(message = "the login timed out after {_0} seconds")]
+        #[error(impl_from)]
+        Timeout(u64),
+        Unknown
+    }
+
+    check_error_implementation_works(LoginError::InvalidPassword { user: "admin".to_string(), password: "secret".to_string() }, "the login of admin with [REDACTED] failed");
+    check_from_implementation_works(5u64, LoginError::Timeout(5));
+    check_error_implementation_works(LoginError::Unknown, "the login failed")
+}
+
+#[test]
+fn err_alias_works() {
+    use error_generator::err;
+
+    #[err(message = "the request failed with {self.code}")]
+    #[err(impl_from)]
+    struct RequestFailed {
+        #[err(debug_placeholder)]
+        code: u16
+    }
+
+    #[err(message = "the connection failed")]
+    enum ConnectionError {
+        #[err(message = "the request failed with {code}")]
+        Request { code: u16 },
+        #[error(message = "the connection timed out")]
+        Timeout,
+        Unknown
+    }
+
+    check_error_implementation_works(RequestFailed::from(404), "the request failed with 404");
+    check_error_implementation_works(ConnectionError::Request { code: 500 }, "the request failed with 500");
+    check_error_implementation_works(ConnectionError::Timeout, "the connection timed out");
+    check_error_implementation_works(ConnectionError::Unknown, "the connection failed")
+}
+
+#[error(message = "the storage failed", dyn_error)]
+mod storage_errors {
+    #[error(message = "could not read {self.0}", impl_from)]
+    #[derive(PartialEq, Eq)]
+    pub struct ReadFailed(pub String);
+
+    #[error(dyn_error = false)]
+    pub enum WriteError {
+        #[error(message = "the disk is full")]
+        DiskFull,
+        Unknown
+    }
+
+    pub enum DeleteError {
+        #[error(message = "the file is locked")]
+        Locked
+    }
+
+    pub fn read(path: &str) -> Result<(), ReadFailed> {
+        Err(path.to_string())?
+    }
+
+    #[test]
+    fn error_module_works() {
+        crate::check_error_implementation_works(read("config.toml").unwrap_err(), "could not read config.toml");
+        crate::check_from_implementation_works("data".to_string(), ReadFailed("data".to_string()));
+        crate::check_error_implementation_works(WriteError::DiskFull, "the disk is full");
+        crate::check_error_implementation_works(WriteError::Unknown, "the storage failed");
+        crate::check_error_implementation_works(DeleteError::Locked, "the file is locked");
+        assert_eq!(DeleteError::Locked.as_dyn_error().to_string(), "the file is locked")
+    }
+}
+
+#[test]
+fn error_enum_works() {
+    error_generator::error_enum! {
+        #[derive(PartialEq)]
+        ConfigError: "the config could not be loaded" {
+            NotFound(String): "missing {0}",
+            InvalidLine { line: usize }: "invalid line {line}",
+            #[error(message = "invalid number: {_0}")]
+            Parse(std::num::ParseIntError) from,
+            Unknown
+        }
+    }
+
+    fn parse(value: &str) -> Result<usize, ConfigError> {
+        Ok(value.parse::<usize>()?)
+    }
+
+    check_error_implementation_works(ConfigError::NotFound("config.toml".to_string()), "missing config.toml");
+    check_error_implementation_works(ConfigError::InvalidLine { line: 3 }, "invalid line 3");
+    check_error_implementation_works(parse("x").unwrap_err(), "invalid number: invalid digit found in string");
+    check_error_implementation_works(ConfigError::Unknown, "the config could not be loaded")
+}
+
+#[test]
+fn bail_and_ensure_work() {
+    use error_generator::{bail, ensure};
+
+    #[error(message = "line {self.line} is empty")]
+    #[derive(PartialEq)]
+    struct EmptyLine {
+        line: usize
+    }
+
+    #[error]
+    #[derive(PartialEq)]
+    enum LinesError {
+        #[error(message = "there is no line {line}")]
+        InvalidLine { line: usize },
+        #[error(message = "{_0}", impl_from)]
+        Empty(EmptyLine)
+    }
+
+    fn line(lines: &[&'static str], line: usize) -> Result<&'static str, LinesError> {
+        ensure!(line < lines.len(), LinesError::InvalidLine { line });
+        if lines[line].is_empty() {
+            bail!(EmptyLine { line })
+        }
+        Ok(lines[line])
+    }
+
+    let lines = ["first", ""];
+    assert_eq!(line(&lines, 0), Ok("first"));
+    check_error_implementation_works(line(&lines, 1).unwrap_err(), "line 1 is empty");
+    check_error_implementation_works(line(&lines, 2).unwrap_err(), "there is no line 2")
+}
@@ -0,0 +1,9 @@
+/// Compile every file in tests/ui against error_generator and compare the diagnostics with the .stderr file next to it.
+/// This locks down the wording and the spans of the compile errors of the macro.
+///
+/// Run the test with 'TRYBUILD=overwrite' to write the .stderr files after the diagnostics changed on purpose.
+#[test]
+fn ui() {
+    let cases = trybuild::TestCases::new();
+    cases.compile_fail("tests/ui/*.rs");
+}
@@ -0,0 +1,10 @@
+use error_generator::error;
+
+#[error(message = "login failed", redact_debug)]
+#[derive(Debug)]
+struct LoginFailed {
+    #[error(redact)]
+    password: String
+}
+
+fn main() {}
@@ -0,0 +1,11 @@
+use error_generator::error;
+
+#[error(message = "the request failed")]
+enum RequestError {
+    #[error(impl_from)]
+    Read(std::io::Error),
+    #[error(impl_from)]
+    Write(std::io::Error)
+}
+
+fn main() {}
@@ -0,0 +1,9 @@
+use error_generator::error;
+
+#[error(message = "the request failed", impl_from)]
+enum RequestError {
+    Read(std::io::Error),
+    Write(std::io::Error)
+}
+
+fn main() {}
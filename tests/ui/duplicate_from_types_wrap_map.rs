@@ -0,0 +1,10 @@
+use error_generator::error;
+
+#[error(wrap_map = "std::io::Error => Io")]
+enum RequestError {
+    Io(std::io::Error),
+    #[error(message = "reading failed", impl_from)]
+    Read(std::io::Error)
+}
+
+fn main() {}
@@ -0,0 +1,9 @@
+use error_generator::error;
+
+#[error(message = "the request failed", impl_from)]
+enum RequestError {
+    Io(std::io::Error),
+    Timeout
+}
+
+fn main() {}
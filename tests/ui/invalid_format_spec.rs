@@ -0,0 +1,6 @@
+use error_generator::error;
+
+#[error(message = "the ratio {self.0:.} is invalid")]
+struct InvalidRatio(f64);
+
+fn main() {}
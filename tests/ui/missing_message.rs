@@ -0,0 +1,11 @@
+use error_generator::error;
+
+#[error(prefix = "config: ")]
+enum ConfigError {
+    MissingKey(String)
+}
+
+#[error(json)]
+struct InvalidValue;
+
+fn main() {}
@@ -0,0 +1,6 @@
+use error_generator::error;
+
+#[error(message = "failed")]
+fn fail() {}
+
+fn main() {}
@@ -0,0 +1,7 @@
+use error_generator::error;
+
+#[error(message = "the request failed")]
+#[error(impl_from, message = "the request timed out")]
+struct RequestError(u64);
+
+fn main() {}
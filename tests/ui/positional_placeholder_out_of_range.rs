@@ -0,0 +1,9 @@
+use error_generator::error;
+
+#[error]
+enum ParseError {
+    #[error(message = "parsing failed at {0}:{3}")]
+    Syntax(usize, usize),
+}
+
+fn main() {}
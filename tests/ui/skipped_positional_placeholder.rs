@@ -0,0 +1,9 @@
+use error_generator::error;
+
+#[error]
+enum MyError {
+    #[error(message = "x {1}")]
+    Skipped(u8, #[error(skip)] u8),
+}
+
+fn main() {}
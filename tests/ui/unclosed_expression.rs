@@ -0,0 +1,6 @@
+use error_generator::error;
+
+#[error(message = "the value {self.0 is invalid")]
+struct InvalidValue(usize);
+
+fn main() {}
@@ -0,0 +1,9 @@
+use error_generator::error;
+
+#[error]
+enum ParseError {
+    #[error(message = "parsing failed in line {lien}")]
+    Syntax { line: usize, column: usize },
+}
+
+fn main() {}
@@ -0,0 +1,6 @@
+use error_generator::error;
+
+#[error(mesage = "the value is invalid")]
+struct InvalidValue;
+
+fn main() {}
@@ -0,0 +1,9 @@
+use error_generator::error;
+
+#[error(message = "the request failed")]
+enum RequestError {
+    #[error(impl_frm)]
+    Io(std::io::Error)
+}
+
+fn main() {}
@@ -0,0 +1,6 @@
+use error_generator::error;
+
+#[error(message = 42)]
+struct InvalidValue;
+
+fn main() {}